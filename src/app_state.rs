@@ -0,0 +1,266 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::{
+    input::common_conditions::input_just_pressed, prelude::*, window::WindowCloseRequested,
+};
+use bevy_rapier2d::plugin::RapierConfiguration;
+
+use crate::{
+    battlefield::{RestartEvent, RoundWinner},
+    cli::LaunchOptions,
+    utils::GameAssets,
+};
+
+// Constants {{{
+
+/// Time in seconds counted down before each round starts.
+const COUNTDOWN_SECS: f32 = 3.0;
+const PAUSE_KEY: KeyCode = KeyCode::Escape;
+const QUIT_KEY: KeyCode = KeyCode::F10;
+/// Time in seconds the session summary is shown for before the game exits.
+const SHUTDOWN_SECS: f32 = 4.0;
+const STATE_TEXT_FONT_SIZE: f32 = 96.0;
+const STATE_TEXT_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+
+// }}}
+
+pub struct AppStatePlugin;
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AppState>()
+            .add_systems(Startup, install_signal_handlers)
+            .add_systems(PostStartup, finish_loading)
+            .add_systems(OnEnter(AppState::Menu), skip_menu.run_if(menu_skipped))
+            .add_systems(OnEnter(AppState::Countdown), (freeze, start_countdown))
+            .add_systems(OnExit(AppState::Countdown), unfreeze)
+            .add_systems(OnEnter(AppState::Paused), (freeze, show_paused_text))
+            .add_systems(OnExit(AppState::Paused), unfreeze)
+            .add_systems(OnEnter(AppState::GameOver), freeze)
+            .add_systems(OnExit(AppState::GameOver), unfreeze)
+            .add_systems(OnEnter(AppState::Editor), freeze)
+            .add_systems(OnExit(AppState::Editor), unfreeze)
+            .add_systems(OnEnter(AppState::ShuttingDown), (freeze, start_shutdown))
+            .add_systems(OnExit(AppState::Countdown), despawn_state_text)
+            .add_systems(OnExit(AppState::Paused), despawn_state_text)
+            .add_systems(
+                Update,
+                (
+                    count_down.run_if(in_state(AppState::Countdown)),
+                    toggle_pause.run_if(
+                        input_just_pressed(PAUSE_KEY).and_then(
+                            in_state(AppState::Playing).or_else(in_state(AppState::Paused)),
+                        ),
+                    ),
+                    end_round.run_if(
+                        in_state(AppState::Playing).and_then(resource_changed::<RoundWinner>),
+                    ),
+                    restart.run_if(
+                        on_event::<RestartEvent>().and_then(
+                            in_state(AppState::Playing)
+                                .or_else(in_state(AppState::Paused))
+                                .or_else(in_state(AppState::GameOver)),
+                        ),
+                    ),
+                    request_shutdown.run_if(
+                        termination_requested
+                            .or_else(on_event::<WindowCloseRequested>())
+                            .or_else(input_just_pressed(QUIT_KEY)),
+                    ),
+                    shut_down.run_if(in_state(AppState::ShuttingDown)),
+                ),
+            );
+    }
+}
+
+/// Where the game is at, from launch through each round.
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    /// Setting up at launch. The initial state is entered before the startup systems run, so the
+    /// menu waits for them here to have the assets to show itself with.
+    #[default]
+    Loading,
+    /// Picking the settings for the first round.
+    Menu,
+    /// Laying out a custom map in the map editor, opened from the main menu. The arena is frozen
+    /// underneath.
+    Editor,
+    /// Counting down to the start of a round. The arena is set up but frozen, so spectators get a
+    /// look at it before anything moves.
+    Countdown,
+    /// The round is being played.
+    Playing,
+    /// The round is on hold until it's unpaused.
+    Paused,
+    /// The round has been won, and the arena is frozen until the next one starts on a
+    /// [`RestartEvent`].
+    GameOver,
+    /// Showing the session summary on the way out. The game exits once it times out.
+    ShuttingDown,
+}
+impl AppState {
+    /// The state to go to when the pause key or button is pressed.
+    pub fn toggle_pause(self) -> Self {
+        match self {
+            AppState::Playing => AppState::Paused,
+            AppState::Paused => AppState::Playing,
+            other => other,
+        }
+    }
+}
+
+/// Set when the process is asked to terminate, e.g. with Ctrl+C in the terminal it was started from,
+/// until [`termination_requested`] takes it.
+static TERMINATION_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Resource)]
+struct Countdown(Timer);
+#[derive(Resource)]
+struct ShutdownTimer(Timer);
+/// The big text in the middle of the screen during the countdown and while paused.
+#[derive(Component)]
+struct StateText;
+
+fn spawn_state_text(commands: &mut Commands, assets: &GameAssets, text: impl Into<String>) {
+    commands.spawn((
+        StateText,
+        Name::new("State Text"),
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(1),
+            ..TextBundle::from_section(
+                text,
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: STATE_TEXT_FONT_SIZE,
+                    color: STATE_TEXT_COLOR,
+                },
+            )
+        },
+    ));
+}
+fn despawn_state_text(mut commands: Commands, texts: Query<Entity, With<StateText>>) {
+    for entity in &texts {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Catch SIGINT and SIGTERM instead of dying on the spot, so the game goes out through the session
+/// summary and saves what it has to on the way, the same as when the window is closed. Other
+/// platforms keep their default handling.
+#[cfg(unix)]
+fn install_signal_handlers() {
+    extern "C" fn handle_signal(_: libc::c_int) {
+        TERMINATION_REQUESTED.store(true, Ordering::Relaxed);
+    }
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: The handler does nothing but store to an atomic, which is async-signal-safe.
+        unsafe {
+            libc::signal(
+                signal,
+                handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            )
+        };
+    }
+}
+#[cfg(not(unix))]
+fn install_signal_handlers() {}
+fn termination_requested() -> bool {
+    TERMINATION_REQUESTED.swap(false, Ordering::Relaxed)
+}
+fn finish_loading(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Menu);
+}
+/// Whether to start the first round with the settings from launch instead of showing the main
+/// menu. Nobody's there to pick anything when running headless, in attract mode or replaying a
+/// demo, and a demo being recorded has to start the same way it'll be replayed.
+fn menu_skipped(options: Res<LaunchOptions>) -> bool {
+    options.skip_menu || options.headless || options.attract || options.demo.is_some()
+}
+fn skip_menu(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Countdown);
+}
+fn start_countdown(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.insert_resource(Countdown(Timer::from_seconds(
+        COUNTDOWN_SECS,
+        TimerMode::Once,
+    )));
+    spawn_state_text(&mut commands, &assets, "");
+}
+/// Count down in real time, since the virtual clock is frozen along with the arena.
+fn count_down(
+    mut countdown: ResMut<Countdown>,
+    time: Res<Time<Real>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut texts: Query<&mut Text, With<StateText>>,
+) {
+    countdown.0.tick(time.delta());
+    if countdown.0.finished() {
+        next_state.set(AppState::Playing);
+        return;
+    }
+    let secs_left = countdown.0.remaining_secs().ceil();
+    for mut text in &mut texts {
+        text.sections[0].value = format!("{}", secs_left);
+    }
+}
+fn toggle_pause(state: Res<State<AppState>>, mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(state.get().toggle_pause());
+}
+/// Freeze the simulation, physics included, so the round picks up exactly where it left off.
+fn freeze(mut time: ResMut<Time<Virtual>>, mut rapier: ResMut<RapierConfiguration>) {
+    time.pause();
+    rapier.physics_pipeline_active = false;
+}
+fn show_paused_text(mut commands: Commands, assets: Res<GameAssets>) {
+    spawn_state_text(&mut commands, &assets, "Paused");
+}
+fn unfreeze(mut time: ResMut<Time<Virtual>>, mut rapier: ResMut<RapierConfiguration>) {
+    time.unpause();
+    rapier.physics_pipeline_active = true;
+}
+fn end_round(winner: Res<RoundWinner>, mut next_state: ResMut<NextState<AppState>>) {
+    if winner.0.is_some() {
+        next_state.set(AppState::GameOver);
+    }
+}
+/// Count down to the new round. Restarts from anywhere but a round, e.g. from the console while the
+/// map editor or the session summary is up, don't pull the game out of there.
+fn restart(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Countdown);
+}
+/// Head out through the session summary. Asking again while it's up, e.g. by closing the window a
+/// second time or pressing Ctrl+C again, exits right away.
+fn request_shutdown(
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if *state.get() == AppState::ShuttingDown {
+        exit.send(AppExit::Success);
+    } else {
+        next_state.set(AppState::ShuttingDown);
+    }
+}
+fn start_shutdown(mut commands: Commands) {
+    commands.insert_resource(ShutdownTimer(Timer::from_seconds(
+        SHUTDOWN_SECS,
+        TimerMode::Once,
+    )));
+}
+/// Exit once the summary has been up for long enough. Everything that has to be saved on the way
+/// out, like a demo being recorded, is saved on [`AppExit`], and the stats are saved as they
+/// change.
+fn shut_down(
+    mut timer: ResMut<ShutdownTimer>,
+    time: Res<Time<Real>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        exit.send(AppExit::Success);
+    }
+}
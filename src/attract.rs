@@ -0,0 +1,87 @@
+//! An unattended mode for running the game as a background screen, started with `--attract`. A
+//! new round starts by itself a few seconds after each one ends, moving on to the next preset with
+//! a random lineup each time.
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    app_state::AppState,
+    cli::LaunchOptions,
+    config::{GameConfig, GamePreset, ValidateConfig},
+    game_command::GameCommand,
+    utils::{GameRng, Participant, ParticipantMap, Roster},
+};
+
+// Constants {{{
+
+/// Time in seconds, in real time, the end of a round stays up before the next one starts.
+const ATTRACT_RESTART_SECS: f32 = 8.0;
+
+// }}}
+
+pub struct AttractPlugin;
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().resource::<LaunchOptions>().attract {
+            return;
+        }
+        app.add_systems(OnEnter(AppState::GameOver), start_timer)
+            .add_systems(
+                Update,
+                start_next_round
+                    .before(ValidateConfig)
+                    .run_if(in_state(AppState::GameOver)),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct AttractTimer(Timer);
+
+fn start_timer(mut commands: Commands) {
+    commands.insert_resource(AttractTimer(Timer::from_seconds(
+        ATTRACT_RESTART_SECS,
+        TimerMode::Once,
+    )));
+}
+/// Set up the next preset with a random lineup and start it once the timer runs out. Presets that
+/// need someone at the keyboard are skipped, and the speed ramp is always on so no round drags on
+/// for too long.
+fn start_next_round(
+    mut timer: ResMut<AttractTimer>,
+    mut preset: ResMut<GamePreset>,
+    (mut config, options): (ResMut<GameConfig>, Res<LaunchOptions>),
+    mut roster: ResMut<Roster>,
+    mut rng: ResMut<GameRng>,
+    mut events: EventWriter<GameCommand>,
+    real_time: Res<Time<Real>>,
+) {
+    if !timer.0.tick(real_time.delta()).just_finished() {
+        return;
+    }
+    let unattended = GamePreset::ALL
+        .into_iter()
+        .filter(|&x| {
+            !Participant::ALL
+                .into_iter()
+                .any(|p| GameConfig::from_preset(x).control.human[p])
+        })
+        .collect::<Vec<_>>();
+    let next = unattended
+        .iter()
+        .position(|&x| x == *preset)
+        .map_or(0, |i| (i + 1) % unattended.len());
+    *preset = unattended[next];
+    *config = GameConfig::from_preset(*preset);
+    options.override_config(&mut config);
+    config.speed_ramp.enabled = true;
+
+    let count = rng.0.gen_range(2..=Participant::ALL.len());
+    let mut new_roster = Roster(ParticipantMap::splat(false));
+    for &participant in Participant::ALL.choose_multiple(&mut rng.0, count) {
+        new_roster[participant] = true;
+    }
+    *roster = new_roster;
+    events.send(GameCommand::Restart);
+}
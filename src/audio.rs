@@ -0,0 +1,318 @@
+//! Audio stingers played when a marble lands in a trigger zone, along with the background music and
+//! the participants' themes, loaded from sound packs in the mod directory.
+//!
+//! A sound pack is a directory under `mods/sound_packs/` with a `pack.ron` manifest:
+//!
+//! ```ron
+//! (
+//!     default: Some("zone.ogg"),
+//!     stingers: {
+//!         "Multiply": "multiply.ogg",
+//!         "Multiply(4)": "multiply_big.ogg",
+//!         "ChargedShot": "charged_shot.ogg",
+//!     },
+//!     music: Some("music.ogg"),
+//!     themes: {
+//!         "A": "theme_a.ogg",
+//!         "B": "theme_b.ogg",
+//!     },
+//!     victory: Some("victory.ogg"),
+//!     streak: Some("streak.ogg"),
+//! )
+//! ```
+//!
+//! Stingers are keyed by the trigger zone as written in the config, so zones added later work
+//! without any changes here. A zone falls back from its exact key to its name without the
+//! parameter, then to the pack's default, and stays silent if none of them are set. Sound files are
+//! relative to the pack directory. Changes to the manifest or the sound files are picked up while
+//! the game is running.
+//!
+//! The music loops for as long as the game runs. Each participant's theme plays when they eliminate
+//! someone, and the victory jingle when a round is won, with the music ducked under both. See
+//! [`music`]. The streak sound calls out a participant's capture streak from their turret.
+//!
+//! With `audio.spatial` on, the stingers and themes are panned toward where they happen on screen,
+//! so the left panel is heard on the left, and get quieter the further they are from the middle.
+//! The music, the hum and the victory jingle are heard the same from everywhere.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    audio::{DefaultSpatialScale, SpatialScale, Volume},
+    ecs::system::EntityCommands,
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use serde::Deserialize;
+
+use crate::{
+    battlefield::{BattlefieldGeometry, MatchViewCamera, StreakEvent},
+    config::{GameConfig, ValidateConfig},
+    panel_plugin::{TriggerEvent, TriggerType},
+    utils::{modified_time, Matches, Participant},
+};
+
+mod charge_hum;
+mod music;
+
+// Constants {{{
+
+const SOUND_PACK_DIR: &str = "mods/sound_packs";
+const SOUND_PACK_MANIFEST: &str = "pack.ron";
+/// Time in seconds between checks for changes to the sound pack on disk.
+const SOUND_PACK_POLL_INTERVAL: f32 = 1.0;
+/// The distance between the listener's ears, the same as the narrowest the view gets, so a sound at
+/// either edge of the screen is heard only from that side.
+const LISTENER_EAR_GAP: f32 = 1280.0;
+/// Sounds within half a screen of an ear play at full volume on that side, and fall off with the
+/// square of the distance past that.
+const SPATIAL_SCALE: f32 = 2.0 / LISTENER_EAR_GAP;
+
+// }}}
+
+pub struct SoundPackPlugin;
+impl Plugin for SoundPackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((charge_hum::ChargeHumPlugin, music::MusicPlugin))
+            .init_resource::<SoundPack>()
+            .insert_resource(DefaultSpatialScale(SpatialScale::new_2d(SPATIAL_SCALE)))
+            .add_systems(PostStartup, add_listener)
+            .add_systems(
+                Update,
+                (
+                    reload_sound_pack.after(ValidateConfig).run_if(
+                        on_timer(Duration::from_secs_f32(SOUND_PACK_POLL_INTERVAL))
+                            .or_else(resource_changed::<GameConfig>),
+                    ),
+                    play_stingers
+                        .after(reload_sound_pack)
+                        .run_if(on_event::<TriggerEvent>()),
+                    play_streak_callouts
+                        .after(reload_sound_pack)
+                        .run_if(on_event::<StreakEvent>()),
+                ),
+            );
+    }
+}
+
+/// The `pack.ron` manifest of a sound pack.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SoundPackManifest {
+    /// The stinger for zones that don't have one of their own.
+    default: Option<PathBuf>,
+    stingers: HashMap<String, PathBuf>,
+    /// Looped in the background.
+    music: Option<PathBuf>,
+    /// The short theme of each participant, keyed by their letter.
+    themes: HashMap<String, PathBuf>,
+    /// Played for whoever wins the round.
+    victory: Option<PathBuf>,
+    /// Played when someone's capture streak earns a bonus.
+    streak: Option<PathBuf>,
+}
+/// The sound pack currently in use.
+#[derive(Resource, Default)]
+struct SoundPack {
+    /// The name of the loaded pack.
+    name: String,
+    default: Option<Handle<AudioSource>>,
+    stingers: HashMap<String, Handle<AudioSource>>,
+    music: Option<Handle<AudioSource>>,
+    themes: HashMap<String, Handle<AudioSource>>,
+    victory: Option<Handle<AudioSource>>,
+    streak: Option<Handle<AudioSource>>,
+    /// The files the pack was loaded from and when they were last modified, to tell when it needs
+    /// to be reloaded.
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+impl SoundPack {
+    fn load(name: &str, sources: &mut Assets<AudioSource>) -> Result<Self, String> {
+        let dir = Path::new(SOUND_PACK_DIR).join(name);
+        let manifest_path = dir.join(SOUND_PACK_MANIFEST);
+        let mut modified = vec![(manifest_path.clone(), modified_time(&manifest_path))];
+        let manifest: SoundPackManifest = fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e))
+            .and_then(|x| {
+                ron::from_str(&x).map_err(|e| format!("{}: {}", manifest_path.display(), e))
+            })?;
+        let mut load = |file: &Path| -> Result<Handle<AudioSource>, String> {
+            let path = dir.join(file);
+            modified.push((path.clone(), modified_time(&path)));
+            let bytes = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            Ok(sources.add(AudioSource {
+                bytes: Arc::from(bytes),
+            }))
+        };
+        let default = manifest.default.as_deref().map(&mut load).transpose()?;
+        let stingers = manifest
+            .stingers
+            .iter()
+            .map(|(key, file)| Ok((key.clone(), load(file)?)))
+            .collect::<Result<_, String>>()?;
+        let music = manifest.music.as_deref().map(&mut load).transpose()?;
+        let themes = manifest
+            .themes
+            .iter()
+            .map(|(key, file)| Ok((key.clone(), load(file)?)))
+            .collect::<Result<_, String>>()?;
+        let victory = manifest.victory.as_deref().map(&mut load).transpose()?;
+        let streak = manifest.streak.as_deref().map(&mut load).transpose()?;
+        Ok(Self {
+            name: name.to_string(),
+            default,
+            stingers,
+            music,
+            themes,
+            victory,
+            streak,
+            modified,
+        })
+    }
+    fn is_outdated(&self, name: &str) -> bool {
+        self.name != name
+            || self
+                .modified
+                .iter()
+                .any(|(path, time)| modified_time(path) != *time)
+    }
+    /// Find the stinger for `trigger_type`, falling back from the exact zone to the kind of zone to
+    /// the pack's default.
+    fn stinger(&self, trigger_type: TriggerType) -> Option<&Handle<AudioSource>> {
+        let key = format!("{:?}", trigger_type);
+        let kind = key.split('(').next().unwrap_or(&key);
+        self.stingers
+            .get(&key)
+            .or_else(|| self.stingers.get(kind))
+            .or(self.default.as_ref())
+    }
+    fn theme(&self, participant: Participant) -> Option<&Handle<AudioSource>> {
+        self.themes.get(&format!("{:?}", participant))
+    }
+}
+
+fn reload_sound_pack(
+    mut pack: ResMut<SoundPack>,
+    mut sources: ResMut<Assets<AudioSource>>,
+    config: Res<GameConfig>,
+) {
+    let name = &config.audio.sound_pack;
+    if !pack.is_outdated(name) {
+        return;
+    }
+    match SoundPack::load(name, &mut sources) {
+        Ok(new_pack) => {
+            info!("Loaded sound pack {}.", name);
+            *pack = new_pack;
+        }
+        Err(e) => {
+            let manifest_path = Path::new(SOUND_PACK_DIR)
+                .join(name)
+                .join(SOUND_PACK_MANIFEST);
+            // Playing without a sound pack is perfectly normal.
+            if manifest_path.exists() {
+                warn!("Failed to load sound pack {}: {}", name, e);
+            } else {
+                info!("No sound pack found at {}.", manifest_path.display());
+            }
+            // Keep track of the manifest anyway, so this isn't retried until it changes.
+            *pack = SoundPack {
+                name: name.clone(),
+                modified: vec![(manifest_path.clone(), modified_time(&manifest_path))],
+                ..default()
+            };
+        }
+    }
+}
+/// Hear the sounds from the camera, so they're placed by where they are on screen.
+fn add_listener(
+    mut commands: Commands,
+    cameras: Query<Entity, (With<Camera2d>, Without<MatchViewCamera>)>,
+) {
+    for camera in &cameras {
+        commands
+            .entity(camera)
+            .insert(SpatialListener::new(LISTENER_EAR_GAP));
+    }
+}
+/// Play `source` once at `volume`, panned toward `position` in the world if it's given and spatial
+/// audio is on.
+fn play_sound<'a>(
+    commands: &'a mut Commands,
+    source: &Handle<AudioSource>,
+    volume: f32,
+    position: Option<Vec2>,
+    config: &GameConfig,
+) -> EntityCommands<'a> {
+    let settings = PlaybackSettings::DESPAWN.with_volume(Volume::new(volume));
+    match position.filter(|_| config.audio.spatial) {
+        Some(position) => commands.spawn((
+            AudioBundle {
+                source: source.clone(),
+                settings: settings.with_spatial(true),
+            },
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        )),
+        None => commands.spawn(AudioBundle {
+            source: source.clone(),
+            settings,
+        }),
+    }
+}
+/// Play the stinger for each trigger, from the marble that landed in the zone, or from the turret
+/// if it didn't come from the panels. Each match is heard as if it were the only one, from where the
+/// marble is relative to its own battlefield.
+fn play_stingers(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    pack: Res<SoundPack>,
+    config: Res<GameConfig>,
+    (geometry, matches): (Res<BattlefieldGeometry>, Res<Matches>),
+    balls: Query<&GlobalTransform>,
+) {
+    for event in events.read() {
+        let Some(source) = pack.stinger(event.trigger_type) else {
+            continue;
+        };
+        let position = event
+            .ball
+            .and_then(|x| balls.get(x).ok())
+            .map_or(geometry.turret_position(event.participant), |x| {
+                x.translation().xy() - matches[event.participant].world_offset()
+            });
+        play_sound(
+            &mut commands,
+            source,
+            config.audio.volume,
+            Some(position),
+            &config,
+        );
+    }
+}
+/// Call out each capture streak bonus from the turret that earned it.
+fn play_streak_callouts(
+    mut commands: Commands,
+    mut events: EventReader<StreakEvent>,
+    pack: Res<SoundPack>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+) {
+    for event in events.read() {
+        let Some(source) = &pack.streak else {
+            continue;
+        };
+        play_sound(
+            &mut commands,
+            source,
+            config.audio.volume,
+            Some(geometry.turret_position(event.participant)),
+            &config,
+        );
+    }
+}
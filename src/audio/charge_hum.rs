@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    app_state::AppState,
+    battlefield::ShotFiredEvent,
+    config::GameConfig,
+    stats::{RoundStats, StatsAggregator},
+    utils::Participant,
+};
+
+// Constants {{{
+
+/// Frequency in hertz of the hum at the lowest charge.
+const HUM_BASE_FREQUENCY: f32 = 110.0;
+/// Length in seconds of the tone that's looped. A whole number of periods at the base frequency, so
+/// the loop doesn't click.
+const HUM_LOOP_SECS: f32 = 1.0;
+/// How many octaves the hum climbs from a charge of 1 up to [`HUM_MAX_CHARGE_LOG10`].
+const HUM_OCTAVES: f32 = 2.0;
+/// The charge, as a power of 10, where the hum stops climbing.
+const HUM_MAX_CHARGE_LOG10: f32 = 12.0;
+/// Volume at the lowest charge, as a fraction of the volume at the top.
+const HUM_MIN_INTENSITY: f32 = 0.3;
+/// Time in seconds the hum cuts out for when the leading turret releases its charge.
+const HUM_CUT_SECS: f32 = 0.4;
+
+// }}}
+
+/// A quiet hum that rises in pitch and volume with the charge held by the leading turret, and cuts
+/// out for a moment when that turret releases it.
+pub struct ChargeHumPlugin;
+impl Plugin for ChargeHumPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HumCut>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, update_hum.after(StatsAggregator));
+    }
+}
+
+#[derive(Component)]
+struct ChargeHum;
+/// Counts down the silence after a release, along with who was leading before it.
+#[derive(Resource, Default)]
+struct HumCut {
+    timer: Option<Timer>,
+    leader: Option<Participant>,
+}
+
+fn setup(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    commands.spawn((
+        ChargeHum,
+        Name::new("Charge Hum"),
+        PitchBundle {
+            source: pitches.add(Pitch::new(
+                HUM_BASE_FREQUENCY,
+                Duration::from_secs_f32(HUM_LOOP_SECS),
+            )),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::ZERO),
+        },
+    ));
+}
+fn update_hum(
+    stats: Res<RoundStats>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+    time: Res<Time<Real>>,
+    mut cut: ResMut<HumCut>,
+    mut shots: EventReader<ShotFiredEvent>,
+    sinks: Query<&AudioSink, With<ChargeHum>>,
+) {
+    let leader = stats.leading_charge();
+    if shots
+        .read()
+        .any(|event| Some(event.participant) == cut.leader)
+    {
+        cut.timer = Some(Timer::from_seconds(HUM_CUT_SECS, TimerMode::Once));
+    }
+    cut.leader = leader.map(|(participant, _)| participant);
+    let is_cut = match &mut cut.timer {
+        Some(timer) => !timer.tick(time.delta()).finished(),
+        None => false,
+    };
+
+    let Ok(sink) = sinks.get_single() else {
+        return;
+    };
+    let charge = match leader {
+        Some((_, charge)) if *state.get() == AppState::Playing && !is_cut => charge,
+        _ => {
+            sink.set_volume(0.0);
+            return;
+        }
+    };
+    // The charge grows exponentially, so the hum follows its order of magnitude.
+    let t = ((charge as f32).log10() / HUM_MAX_CHARGE_LOG10).clamp(0.0, 1.0);
+    sink.set_speed(2f32.powf(t * HUM_OCTAVES));
+    sink.set_volume(
+        config.audio.charge_hum_volume * (HUM_MIN_INTENSITY + (1.0 - HUM_MIN_INTENSITY) * t),
+    );
+}
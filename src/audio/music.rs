@@ -0,0 +1,123 @@
+use bevy::{audio::Volume, prelude::*};
+
+use super::{play_sound, SoundPack};
+use crate::{
+    battlefield::{BattlefieldGeometry, EliminationEvent, RoundWinner},
+    config::GameConfig,
+};
+
+// Constants {{{
+
+/// Time in seconds the music takes to fade all the way down when a theme starts, or back up once
+/// it's over.
+const DUCK_FADE_SECS: f32 = 0.25;
+
+// }}}
+
+/// The background music of the sound pack, with the participants' themes and the victory jingle
+/// played over it.
+pub struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_music.run_if(resource_changed::<SoundPack>),
+                play_themes.run_if(on_event::<EliminationEvent>()),
+                play_victory.run_if(resource_changed::<RoundWinner>),
+                duck_music,
+            )
+                .chain()
+                .after(super::reload_sound_pack),
+        );
+    }
+}
+
+/// The looping music, with how loud it currently is relative to the configured volume.
+#[derive(Component)]
+struct Music {
+    level: f32,
+}
+/// A theme or jingle that the music ducks under while it plays. It despawns once it's over.
+#[derive(Component)]
+struct Theme;
+
+/// Start over with the music of the sound pack that was just loaded, if it has any.
+fn start_music(
+    mut commands: Commands,
+    pack: Res<SoundPack>,
+    config: Res<GameConfig>,
+    music: Query<Entity, With<Music>>,
+) {
+    for entity in &music {
+        commands.entity(entity).despawn();
+    }
+    let Some(source) = &pack.music else {
+        return;
+    };
+    commands.spawn((
+        Music { level: 1.0 },
+        Name::new("Music"),
+        AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new(config.audio.music_volume)),
+        },
+    ));
+}
+/// Play the theme of whoever made each elimination, from the turret they took out.
+fn play_themes(
+    mut commands: Commands,
+    mut events: EventReader<EliminationEvent>,
+    pack: Res<SoundPack>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+) {
+    for event in events.read() {
+        if let Some(source) = event.eliminated_by.and_then(|x| pack.theme(x)) {
+            let position = geometry.turret_position(event.participant);
+            play_sound(
+                &mut commands,
+                source,
+                config.audio.theme_volume,
+                Some(position),
+                &config,
+            )
+            .insert((Theme, Name::new("Theme")));
+        }
+    }
+}
+fn play_victory(
+    mut commands: Commands,
+    winner: Res<RoundWinner>,
+    pack: Res<SoundPack>,
+    config: Res<GameConfig>,
+) {
+    if let (Some(_), Some(source)) = (winner.0, &pack.victory) {
+        play_sound(
+            &mut commands,
+            source,
+            config.audio.theme_volume,
+            None,
+            &config,
+        )
+        .insert((Theme, Name::new("Theme")));
+    }
+}
+/// Fade the music down while any theme is playing, and back up after.
+fn duck_music(
+    config: Res<GameConfig>,
+    time: Res<Time<Real>>,
+    themes: Query<(), With<Theme>>,
+    mut music: Query<(&mut Music, &AudioSink)>,
+) {
+    let target = if themes.is_empty() {
+        1.0
+    } else {
+        config.audio.music_ducking
+    };
+    let step = time.delta_seconds() / DUCK_FADE_SECS;
+    for (mut music, sink) in &mut music {
+        music.level += (target - music.level).clamp(-step, step);
+        sink.set_volume(config.audio.music_volume * music.level);
+    }
+}
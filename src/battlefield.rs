@@ -1,26 +1,105 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use std::{
-    collections::VecDeque,
-    f32::consts::{FRAC_PI_2, PI},
+    collections::{HashMap, VecDeque},
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI},
 };
 
-use bevy::{color::palettes::css, prelude::*, sprite::Mesh2dHandle, time::Stopwatch};
+use bevy::{
+    color::palettes::css,
+    prelude::*,
+    sprite::Mesh2dHandle,
+    tasks::{ComputeTaskPool, ParallelSlice},
+    time::Stopwatch,
+};
 use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 use crate::{
+    app_state::AppState,
+    cli::LaunchOptions,
     collision_groups::{self, all_new_bullets_except},
-    panel_plugin::{TriggerEvent, TriggerType},
+    config::{
+        FortificationConfig, GameConfig, GamePreset, TileConversionConfig, TileConversionMode,
+        TileHitPointsConfig, TileShape, ValidateConfig,
+    },
+    draft::{DraftedUpgrades, Upgrade},
+    mutators::{ActiveMutators, Mutator},
+    occlusion::WindowShownEvent,
+    panel_plugin::{TriggerEvent, TriggerType, WorkerBallNumber},
+    stats::PersistentStats,
     utils::{
-        BallColor, EffectPropertiesExt, Participant, ParticipantMap, TileColor, TileHitEffect,
+        Backlog, BallColor, DisplayName, EffectPropertiesExt, GameAssets, GameRng, MatchId,
+        Matches, Participant, ParticipantMap, PerMatch, Roster, RoundScoped, TileColor,
     },
 };
 
+mod aim_path;
+mod arena;
+mod battle_royale;
+mod black_hole;
+mod capture_streak;
+mod comeback;
+mod debris;
+mod emotes;
+mod gamble;
+mod geometry;
+mod hazard_bar;
+mod interest;
+mod king_of_the_hill;
+mod laser;
+mod manual_control;
+mod map_balance;
+mod map_editor;
+mod map_export;
+mod map_file;
+mod map_generator;
+mod match_view;
+mod neutral_tile;
+mod shrink;
+mod siphon;
+mod snapshot;
+mod speed_ramp;
+mod speed_zone;
+mod stalemate;
+mod streak_bonus;
+mod supply_drop;
+mod swap;
+mod teleporter;
+mod territory_elimination;
+mod tile_chunks;
+mod trail;
+mod trigger_pulse;
+mod wall;
+mod win_condition;
+mod wrap;
+
+pub use geometry::BattlefieldGeometry;
+pub use manual_control::aim_keys;
+pub use map_balance::MapBalance;
+use map_file::LayoutCell;
+pub use map_file::{MapFile, MapFiles};
+use map_generator::GeneratedMap;
+pub use match_view::MatchViewCamera;
+pub use snapshot::GameSnapshot;
+pub use streak_bonus::StreakEvent;
+pub use win_condition::{
+    LastTeamStanding, LastTurretStanding, MatchDecidedEvent, MatchWinners, RoundWinner,
+    ScoreTarget, Territory, Timed, WinCondition, WinConditions,
+};
+
 // Constants {{{
 
 const TILE_COUNT: usize = 100;
 const TILE_DIMENSION: f32 = BATTLEFIELD_HALF_WIDTH / TILE_COUNT as f32;
+/// The distance between rows of hexagon tiles, `sqrt(3) / 2` times the width of a tile.
+const HEX_ROW_HEIGHT: f32 = TILE_DIMENSION * 0.866_025_4;
+const HEX_ROW_COUNT: usize = (BATTLEFIELD_HALF_WIDTH * 2.0 / HEX_ROW_HEIGHT) as usize + 1;
+/// How much bigger hexagon tiles are drawn than they are.
+const HEX_TILE_OVERLAP: f32 = 1.05;
+/// The number of splash impacts each task works out the tiles for at a time.
+const SPLASH_REGION_CHUNK_SIZE: usize = 16;
 pub const BATTLEFIELD_HALF_WIDTH: f32 = 360.0;
 const BATTLEFIELD_BOUNDARY_HALF_WIDTH: f32 = 50.0;
 
@@ -34,25 +113,30 @@ const TURRET_HEAD_THICNESS: f32 = 3.0;
 const TURRET_HEAD_LENGTH: f32 = 50.0;
 const TURRET_ROTATION_SPEED: f32 = 0.75;
 
+/// How much darker a fortified tile gets once it's been hit.
+const CRACKED_TILE_DARKEN: f32 = 0.1;
+/// How much brighter the sparks off a bullet on a capture streak are.
+const ON_FIRE_SPARK_LIGHTEN: f32 = 0.25;
+
 const MULTI_SHOT_CHARGE_OFFSET: u64 = 8;
 
-/// The width of a rectangular area at the corner where the `NEW_BULLET` tag will not be dropped.
+/// The width of a square area around the turret where the `NEW_BULLET` tag will not be dropped.
 const NEW_BULLET_PHASE_RANGE: f32 = 2.0 * (BATTLEFIELD_HALF_WIDTH - TURRET_POSITION);
 const BULLET_TEXT_COLOR: Color = Color::BLACK;
 const BULLET_TEXT_FONT_SIZE_ASPECT: f32 = 0.5;
 const BULLET_MINIMUM_TEXT_SIZE: f32 = 8.0;
 const BULLET_SIZE_FACTOR: f32 = 2.0;
 const BULLET_DENSITY_FACTOR: f32 = 5.0;
-const BULLET_RESTITUTION_COEFFICIENT: f32 = 0.75;
 const CHARGED_SHOT_BULLET_SPEED: f32 = 250.0;
 const BURST_SHOT_BULLET_SPEED: f32 = 500.0;
-/// Time in seconds the turret will stop firing for after firing a charged shot.
-const CHARGED_SHOT_COOLDOWN: f32 = 0.5;
 
 // Z-index
 const TILE_Z: f32 = -1.0;
 const BULLET_BALL_Z: f32 = -1.0;
 const BULLET_TEXT_Z: f32 = 3.0;
+/// In front of everything else on the battlefield.
+const MATCH_BANNER_Z: f32 = 10.0;
+const MATCH_BANNER_FONT_SIZE: f32 = 72.0;
 // Turret head is a child of turret, which inherits the z position as well, so the local z of the
 // head needs to be negative to put it behind the main turret.
 const TURRET_HEAD_Z: f32 = -1.0;
@@ -63,38 +147,97 @@ const TURRET_PLATFORM_Z: f32 = -1.0;
 pub struct BattlefieldPlugin;
 impl Plugin for BattlefieldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<EliminationEvent>()
-            .add_event::<RestartEvent>()
-            .add_systems(Startup, setup)
-            .add_systems(
-                Update,
-                (
-                    rotate_turret,
-                    handle_bullet_tile_collision,
-                    handle_bullet_turret_collision
-                        .run_if(game_is_going)
-                        .after(handle_bullet_tile_collision),
-                    handle_trigger_events
-                        .after(handle_bullet_turret_collision)
-                        .run_if(on_event::<TriggerEvent>().or_else(on_event::<RestartEvent>())),
-                    update_charge_level.after(handle_trigger_events),
-                    update_charge_ball.after(update_charge_level),
-                    handle_elimination
-                        .run_if(on_event::<EliminationEvent>())
-                        .after(update_charge_level),
-                    cleanup_particle_emitters.before(handle_bullet_tile_collision),
-                    restart.run_if(on_event::<RestartEvent>()),
-                ),
-            )
-            .add_systems(
-                FixedUpdate,
-                (
-                    update_bullets_solver_groups.before(fire_shots),
-                    fire_shots
-                        .run_if(game_is_going)
-                        .after(handle_trigger_events),
-                ),
-            );
+        app.add_plugins((
+            (
+                aim_path::AimPathPlugin,
+                battle_royale::BattleRoyalePlugin,
+                black_hole::BlackHolePlugin,
+                capture_streak::CaptureStreakPlugin,
+                comeback::ComebackPlugin,
+                debris::DebrisPlugin,
+                emotes::EmotesPlugin,
+                gamble::GamblePlugin,
+                geometry::GeometryPlugin,
+                hazard_bar::HazardBarPlugin,
+                interest::InterestPlugin,
+                king_of_the_hill::KingOfTheHillPlugin,
+                laser::LaserPlugin,
+                manual_control::ManualControlPlugin,
+                map_editor::MapEditorPlugin,
+            ),
+            (
+                map_export::MapExportPlugin,
+                map_file::MapFilePlugin,
+                map_generator::MapGeneratorPlugin,
+                match_view::MatchViewPlugin,
+                neutral_tile::NeutralTilePlugin,
+                shrink::ShrinkPlugin,
+                siphon::SiphonPlugin,
+                snapshot::SnapshotPlugin,
+            ),
+            (
+                speed_ramp::SpeedRampPlugin,
+                speed_zone::SpeedZonePlugin,
+                stalemate::StalematePlugin,
+                streak_bonus::StreakBonusPlugin,
+                supply_drop::SupplyDropPlugin,
+                swap::SwapPlugin,
+                teleporter::TeleporterPlugin,
+                territory_elimination::TerritoryEliminationPlugin,
+                tile_chunks::TileChunksPlugin,
+                trail::TrailPlugin,
+                trigger_pulse::TriggerPulsePlugin,
+                wall::WallPlugin,
+                wrap::WrapPlugin,
+                win_condition::WinConditionPlugin,
+            ),
+        ))
+        .add_event::<EliminationEvent>()
+        .add_event::<TileConvertedEvent>()
+        .add_event::<ShotFiredEvent>()
+        .add_event::<ChargeGeneratedEvent>()
+        .add_event::<RestartEvent>()
+        .init_resource::<Backlog<TileHitEffect>>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                rotate_turret,
+                handle_bullet_tile_collision,
+                handle_bullet_turret_collision
+                    .run_if(in_state(AppState::Playing))
+                    .after(handle_bullet_tile_collision),
+                handle_trigger_events
+                    .after(handle_bullet_turret_collision)
+                    .run_if(on_event::<TriggerEvent>().or_else(on_event::<RestartEvent>())),
+                update_charge_level.after(handle_trigger_events),
+                update_charge_ball.after(update_charge_level),
+                handle_elimination
+                    .run_if(on_event::<EliminationEvent>())
+                    .after(update_charge_level),
+                spawn_tile_hit_effects.after(handle_bullet_tile_collision),
+                cleanup_particle_emitters.before(handle_bullet_tile_collision),
+                reset_particle_emitters
+                    .before(cleanup_particle_emitters)
+                    .run_if(on_event::<WindowShownEvent>()),
+                apply_physics_config
+                    .after(ValidateConfig)
+                    .run_if(resource_changed::<GameConfig>),
+                restart.run_if(on_event::<RestartEvent>()),
+                settle_match
+                    .after(handle_elimination)
+                    .run_if(on_event::<MatchDecidedEvent>()),
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                update_bullets_solver_groups.before(fire_shots),
+                fire_shots
+                    .run_if(in_state(AppState::Playing))
+                    .after(handle_trigger_events),
+            ),
+        );
     }
 }
 
@@ -119,23 +262,86 @@ impl EffectInstanceManager {
         self.pool.append(&mut self.dispatched);
     }
 }
-#[derive(Event, Default)]
-pub struct RestartEvent;
+/// Event sent to set a match up again for a new round.
+#[derive(Event)]
+pub struct RestartEvent {
+    pub match_id: MatchId,
+}
+impl RestartEvent {
+    /// The matches to set up, for systems that run both at startup and on a restart. There are no
+    /// events at startup, when every running match needs setting up.
+    pub fn matches(
+        events: &mut EventReader<Self>,
+        roster: &Roster,
+        matches: &Matches,
+    ) -> Vec<MatchId> {
+        if events.is_empty() {
+            return matches.running(roster);
+        }
+        events.read().map(|x| x.match_id).collect()
+    }
+}
 #[derive(Event)]
 pub struct EliminationEvent {
+    /// The match the eliminated turret was playing in.
+    pub match_id: MatchId,
     pub participant: Participant,
+    /// Whoever's bullet last hit the eliminated turret, if any did.
+    pub eliminated_by: Option<Participant>,
 }
 impl EliminationEvent {
-    fn new(participant: Participant) -> Self {
-        Self { participant }
+    fn new(
+        match_id: MatchId,
+        participant: Participant,
+        eliminated_by: Option<Participant>,
+    ) -> Self {
+        Self {
+            match_id,
+            participant,
+            eliminated_by,
+        }
     }
 }
-#[derive(Resource)]
+/// The number of participants left in a match, kept for each match in a [`PerMatch`].
+#[derive(Debug, Clone, Copy)]
 pub struct SurvivorCount(pub u8);
-impl Default for SurvivorCount {
-    fn default() -> Self {
-        Self(4)
-    }
+/// The number of the current round, starting from 1.
+#[derive(Resource)]
+pub struct RoundNumber(pub u32);
+/// Event sent when a tile changes hands.
+#[derive(Event, Clone)]
+pub struct TileConvertedEvent {
+    pub match_id: MatchId,
+    pub from: Participant,
+    pub to: Participant,
+    /// The bullet that converted the tile.
+    pub bullet: Entity,
+}
+/// Event sent when a turret fires a bullet.
+#[derive(Event, Clone)]
+pub struct ShotFiredEvent {
+    pub participant: Participant,
+    pub charge: u64,
+}
+/// Event sent when a worker ball landing in a trigger zone changes its turret's charge.
+#[derive(Event, Clone)]
+pub struct ChargeGeneratedEvent {
+    /// The participant whose turret's charge changed.
+    pub participant: Participant,
+    pub ball: Entity,
+    /// The participant the worker ball belongs to, which isn't the one whose charge changed when
+    /// the reverse mutator sends it to a rival.
+    pub owner: Participant,
+    pub number: usize,
+    /// The charge a multiply or gamble zone gained, or 0 if it was lost, or the zone spent it or
+    /// didn't multiply it.
+    pub generated: u64,
+}
+/// A particle effect for a bullet hitting a tile, waiting in a [`Backlog`] to be started.
+struct TileHitEffect {
+    position: Vec3,
+    color: Color,
+    velocity: Vec2,
 }
 #[derive(Component, Clone, Copy)]
 struct BattlefieldRoot;
@@ -143,13 +349,43 @@ struct BattlefieldRoot;
 struct TileRoot;
 /// Marker to mark this entity as a tile.
 #[derive(Component, Clone, Copy)]
-struct Tile;
+pub struct Tile;
+/// How much it takes to convert a tile.
+#[derive(Component, Clone, Copy)]
+struct TileDurability {
+    /// The number of hits the tile can take before it's converted.
+    hits: u8,
+    /// Whether the tile is close enough to its owner's turret to be fortified.
+    fortified: bool,
+}
+impl TileDurability {
+    /// Fortified tiles near the owner's turret, at `turret`, take more hits to convert than the
+    /// rest, and so do tiles deep in the owner's territory with tile hit points on.
+    fn new(
+        position: Vec2,
+        config: &FortificationConfig,
+        hit_points: &TileHitPointsConfig,
+        turret: Vec2,
+    ) -> Self {
+        let distance = position.distance(turret);
+        let fortified = distance < config.radius;
+        let hits = match fortified {
+            true => config.hits.max(1),
+            false => 1,
+        };
+        Self {
+            hits: hits.max(hit_points.hits_at(distance)),
+            fortified,
+        }
+    }
+}
 /// Component bundle for each of the individual tiles on the battle field.
 #[derive(Bundle)]
 struct TileBundle {
     /// Markers to mark this entity as a tile, a sensor collider, and a trigger for collision
     /// events.
-    markers: (Tile, Sensor),
+    markers: (Tile, Sensor, RoundScoped),
+    match_id: MatchId,
     /// Bevy rendering component used to display the tile.
     sprite_bundle: SpriteBundle,
     /// Rapier collider component. We'll mark this as sensor and won't add a rigidbody to this
@@ -158,32 +394,343 @@ struct TileBundle {
     collision_groups: CollisionGroups,
     /// The game participant that owns this tile.
     owner: Participant,
+    durability: TileDurability,
     name: Name,
 }
 impl TileBundle {
-    fn new(owner: Participant, color: Color, x: f32, y: f32) -> Self {
+    fn new(
+        match_id: MatchId,
+        owner: Participant,
+        color: Color,
+        x: f32,
+        y: f32,
+        style: &TileStyle,
+        fortification: &FortificationConfig,
+        hit_points: &TileHitPointsConfig,
+        turret: Vec2,
+    ) -> Self {
         Self {
-            markers: (Tile, Sensor),
+            markers: (Tile, Sensor, RoundScoped),
+            match_id,
             sprite_bundle: SpriteBundle {
-                transform: Transform {
-                    translation: Vec3::new(x, y, TILE_Z),
-                    scale: Vec3::new(TILE_DIMENSION, TILE_DIMENSION, 1.0),
-                    rotation: Quat::IDENTITY,
+                transform: Transform::from_xyz(x, y, TILE_Z),
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(style.size),
+                    ..default()
                 },
-                sprite: Sprite { color, ..default() },
+                texture: style.texture.clone(),
                 ..default()
             },
-            collider: Collider::cuboid(0.5, 0.5),
+            collider: style.collider.clone(),
             collision_groups: CollisionGroups::new(
                 collision_groups::tile(owner),
                 collision_groups::all_bullets_except(owner)
                     | collision_groups::all_new_bullets_except(owner),
             ),
             owner,
+            durability: TileDurability::new(Vec2::new(x, y), fortification, hit_points, turret),
             name: Name::new("Tile"),
         }
     }
 }
+/// The parts of a tile that only depend on its shape, worked out once for the whole battlefield.
+struct TileStyle {
+    texture: Handle<Image>,
+    size: Vec2,
+    collider: Collider,
+}
+impl TileStyle {
+    fn new(shape: TileShape, assets: &GameAssets) -> Self {
+        match shape {
+            TileShape::Square => Self {
+                texture: default(),
+                size: Vec2::splat(TILE_DIMENSION),
+                collider: Collider::cuboid(TILE_DIMENSION * 0.5, TILE_DIMENSION * 0.5),
+            },
+            TileShape::Hexagon => {
+                let radius = TILE_DIMENSION / 3f32.sqrt();
+                let corners = (0..6)
+                    .map(|i| Vec2::from_angle(PI / 6.0 + i as f32 * PI / 3.0) * radius)
+                    .collect();
+                Self {
+                    texture: assets.hex_tile.clone(),
+                    // The sprites overlap a little, so the background doesn't show through the
+                    // antialiased edges.
+                    size: Vec2::new(TILE_DIMENSION, radius * 2.0) * HEX_TILE_OVERLAP,
+                    collider: Collider::convex_polyline(corners)
+                        .expect("a hexagon should be a valid convex polygon"),
+                }
+            }
+        }
+    }
+}
+/// Lookup from grid cells to tile entities, so tiles in an area can be found without going through
+/// every tile. Cells outside the arena are left as [`Entity::PLACEHOLDER`] and skipped.
+///
+/// Cells are indexed by column and row, counting from the bottom left. With
+/// [`TileShape::Hexagon`], every other row is shifted right by half a tile and has one less
+/// column, and the rows are packed closer together so the hexagons' points interlock.
+#[derive(Resource, Default)]
+struct TileGrid {
+    shape: TileShape,
+    tiles: Vec<Entity>,
+}
+impl TileGrid {
+    /// The number of columns in the grid.
+    const SIZE: usize = TILE_COUNT * 2;
+    fn new(shape: TileShape) -> Self {
+        let mut grid = Self {
+            shape,
+            tiles: Vec::new(),
+        };
+        grid.tiles = vec![Entity::PLACEHOLDER; Self::SIZE * grid.rows()];
+        grid
+    }
+    fn rows(&self) -> usize {
+        match self.shape {
+            TileShape::Square => Self::SIZE,
+            TileShape::Hexagon => HEX_ROW_COUNT,
+        }
+    }
+    fn columns(&self, y: usize) -> usize {
+        Self::SIZE - (self.row_offset(y) > 0.0) as usize
+    }
+    fn row_height(&self) -> f32 {
+        match self.shape {
+            TileShape::Square => TILE_DIMENSION,
+            TileShape::Hexagon => HEX_ROW_HEIGHT,
+        }
+    }
+    /// How far the cells in row `y` are shifted right, in tiles.
+    fn row_offset(&self, y: usize) -> f32 {
+        match self.shape {
+            TileShape::Hexagon if y % 2 == 1 => 0.5,
+            _ => 0.0,
+        }
+    }
+    fn get(&self, x: usize, y: usize) -> Entity {
+        self.tiles[y * Self::SIZE + x]
+    }
+    fn set(&mut self, x: usize, y: usize, tile: Entity) {
+        self.tiles[y * Self::SIZE + x] = tile;
+    }
+    /// The cell whose center is closest to `position`.
+    fn cell(&self, position: Vec2) -> Option<(usize, usize)> {
+        let row = ((position.y + BATTLEFIELD_HALF_WIDTH) / self.row_height()).floor();
+        if !row.is_finite() {
+            return None;
+        }
+        let row = row as i64;
+        // The points of a hexagon reach into the rows above and below, so the closest cell can be
+        // in either of those too.
+        let rows = match self.shape {
+            TileShape::Square => row..=row,
+            TileShape::Hexagon => row - 1..=row + 1,
+        };
+        rows.filter_map(|y| {
+            let y = usize::try_from(y).ok().filter(|&y| y < self.rows())?;
+            let x = ((position.x + BATTLEFIELD_HALF_WIDTH) / TILE_DIMENSION - self.row_offset(y))
+                .floor();
+            (0.0..self.columns(y) as f32)
+                .contains(&x)
+                .then_some((x as usize, y))
+        })
+        .min_by(|&(ax, ay), &(bx, by)| {
+            let a = self.cell_center(ax, ay).distance_squared(position);
+            let b = self.cell_center(bx, by).distance_squared(position);
+            a.total_cmp(&b)
+        })
+    }
+    /// The cells next to the one at `x`, `y`, four of them with square tiles and six with
+    /// hexagons. Directions leading off the grid give `None`.
+    fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = Option<(usize, usize)>> + '_ {
+        let sides = match self.shape {
+            TileShape::Square => 4,
+            TileShape::Hexagon => 6,
+        };
+        let center = self.cell_center(x, y);
+        (0..sides).map(move |i| {
+            let direction = Vec2::from_angle(i as f32 * 2.0 * PI / sides as f32);
+            self.cell(center + direction * TILE_DIMENSION)
+        })
+    }
+    fn cell_center(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(
+            (x as f32 + 0.5 + self.row_offset(y)) * TILE_DIMENSION,
+            (y as f32 + 0.5) * self.row_height(),
+        ) - BATTLEFIELD_HALF_WIDTH
+    }
+    /// The tiles crossed by the line from `start` to `end` in order, along with their centers. This
+    /// visits at most `3 * SIZE` cells no matter how long the line is.
+    fn tiles_on_line(&self, start: Vec2, end: Vec2) -> Vec<(Entity, Vec2)> {
+        if self.tiles.is_empty() {
+            return Vec::new();
+        }
+        match self.shape {
+            TileShape::Square => self.squares_on_line(start, end),
+            TileShape::Hexagon => self.hexagons_on_line(start, end),
+        }
+    }
+    /// Walk the grid one cell boundary at a time, which visits at most `2 * SIZE` cells.
+    fn squares_on_line(&self, start: Vec2, end: Vec2) -> Vec<(Entity, Vec2)> {
+        let to_grid = |position: Vec2| {
+            let max = BATTLEFIELD_HALF_WIDTH - TILE_DIMENSION * 0.01;
+            (position.clamp(Vec2::splat(-max), Vec2::splat(max)) + BATTLEFIELD_HALF_WIDTH)
+                / TILE_DIMENSION
+        };
+        let (start, end) = (to_grid(start), to_grid(end));
+        let delta = end - start;
+        // Unlike `signum`, this leaves the axes the line doesn't move along at 0.
+        let direction = Vec2::select(delta.cmpne(Vec2::ZERO), delta.signum(), Vec2::ZERO);
+        // Distance along the line, as a fraction of its length, between two cell boundaries, and
+        // to the next boundary.
+        let t_delta = 1.0 / delta.abs();
+        let next_boundary = start.floor() + direction.max(Vec2::ZERO);
+        let mut t_max = Vec2::select(
+            delta.cmpne(Vec2::ZERO),
+            (next_boundary - start) / delta,
+            Vec2::INFINITY,
+        );
+        let mut cell = start.floor().as_ivec2();
+        let last = end.floor().as_ivec2();
+        let mut tiles = Vec::new();
+        for _ in 0..=Self::SIZE * 2 {
+            let (x, y) = (cell.x as usize, cell.y as usize);
+            if self.get(x, y) != Entity::PLACEHOLDER {
+                tiles.push((self.get(x, y), self.cell_center(x, y)));
+            }
+            if cell == last {
+                break;
+            }
+            if t_max.x < t_max.y {
+                cell.x += direction.x as i32;
+                t_max.x += t_delta.x;
+            } else {
+                cell.y += direction.y as i32;
+                t_max.y += t_delta.y;
+            }
+            if !(0..Self::SIZE as i32).contains(&cell.x)
+                || !(0..Self::SIZE as i32).contains(&cell.y)
+            {
+                break;
+            }
+        }
+        tiles
+    }
+    /// Walk the hexagons in cube coordinates, stepping across whichever side the line leaves each
+    /// one through, which visits at most `2 * SIZE` cells. Cells off the grid, like the half cells
+    /// at the ends of the shifted rows, are walked through but not returned.
+    fn hexagons_on_line(&self, start: Vec2, end: Vec2) -> Vec<(Entity, Vec2)> {
+        // The neighbor across each side, starting from the right and going counterclockwise.
+        const DIRECTIONS: [IVec3; 6] = [
+            IVec3::new(1, 0, -1),
+            IVec3::new(0, 1, -1),
+            IVec3::new(-1, 1, 0),
+            IVec3::new(-1, 0, 1),
+            IVec3::new(0, -1, 1),
+            IVec3::new(1, -1, 0),
+        ];
+        let max = Vec2::splat(BATTLEFIELD_HALF_WIDTH - TILE_DIMENSION * 0.01);
+        let (start, end) = (start.clamp(-max, max), end.clamp(-max, max));
+        let delta = end - start;
+        let mut cube = hex_cube(start);
+        let mut tiles = Vec::new();
+        for _ in 0..=Self::SIZE * 2 {
+            let center = hex_center(cube);
+            if let Some((x, y)) = self.hex_cell(cube) {
+                if self.get(x, y) != Entity::PLACEHOLDER {
+                    tiles.push((self.get(x, y), center));
+                }
+            }
+            // Distance along the line, as a fraction of its length, to where it leaves the hexagon
+            // through each side it's heading toward. Every side is half a tile from the center.
+            let exit = (0..6)
+                .filter_map(|i| {
+                    let normal = Vec2::from_angle(i as f32 * PI / 3.0);
+                    let speed = delta.dot(normal);
+                    (speed > 0.0).then(|| {
+                        let t = (TILE_DIMENSION * 0.5 - (start - center).dot(normal)) / speed;
+                        (t, i)
+                    })
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            match exit {
+                Some((t, i)) if t < 1.0 => cube += DIRECTIONS[i],
+                _ => break,
+            }
+        }
+        tiles
+    }
+    /// The cell at `cube`, if it's on the grid. Cube coordinates number the rows the same way, but
+    /// their columns lean right going up, by half a tile every row.
+    fn hex_cell(&self, cube: IVec3) -> Option<(usize, usize)> {
+        let y = usize::try_from(cube.y).ok().filter(|&y| y < self.rows())?;
+        let x = usize::try_from(cube.x + cube.y / 2)
+            .ok()
+            .filter(|&x| x < self.columns(y))?;
+        Some((x, y))
+    }
+    /// The tiles whose centers are within `radius` of `center`, closest first.
+    fn tiles_in_radius(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+        // The cells from `low` to `high` that are on the grid, given in cells from the first
+        // center.
+        let span = |low: f32, high: f32, count: usize| {
+            let (low, high) = (low.ceil().max(0.0), high.floor().min(count as f32 - 1.0));
+            (low <= high).then_some(low as usize..=high as usize)
+        };
+        let row = |y: f32| (y + BATTLEFIELD_HALF_WIDTH) / self.row_height() - 0.5;
+        let Some(rows) = span(row(center.y - radius), row(center.y + radius), self.rows()) else {
+            return Vec::new();
+        };
+        let mut tiles = Vec::new();
+        for y in rows {
+            let column =
+                |x: f32| (x + BATTLEFIELD_HALF_WIDTH) / TILE_DIMENSION - 0.5 - self.row_offset(y);
+            let Some(columns) = span(
+                column(center.x - radius),
+                column(center.x + radius),
+                self.columns(y),
+            ) else {
+                continue;
+            };
+            for x in columns {
+                let distance = self.cell_center(x, y).distance(center);
+                if distance <= radius && self.get(x, y) != Entity::PLACEHOLDER {
+                    tiles.push((distance, self.get(x, y)));
+                }
+            }
+        }
+        tiles.sort_by(|a, b| a.0.total_cmp(&b.0));
+        tiles.into_iter().map(|(_, tile)| tile).collect()
+    }
+}
+/// The hexagon `position` is in, in cube coordinates, whether or not it's on the grid.
+fn hex_cube(position: Vec2) -> IVec3 {
+    let r = (position.y + BATTLEFIELD_HALF_WIDTH) / HEX_ROW_HEIGHT - 0.5;
+    let q = (position.x + BATTLEFIELD_HALF_WIDTH) / TILE_DIMENSION - 0.5 - r * 0.5;
+    let cube = Vec3::new(q, r, -q - r);
+    let rounded = cube.round();
+    // Rounding each coordinate on its own can leave them not adding up to 0, in which case the
+    // one that was rounded the furthest is worked out from the other two.
+    let error = (rounded - cube).abs();
+    let mut cube = rounded.as_ivec3();
+    if error.x > error.y && error.x > error.z {
+        cube.x = -cube.y - cube.z;
+    } else if error.y > error.z {
+        cube.y = -cube.x - cube.z;
+    } else {
+        cube.z = -cube.x - cube.y;
+    }
+    cube
+}
+/// The center of the hexagon at `cube`, whether or not it's on the grid.
+fn hex_center(cube: IVec3) -> Vec2 {
+    Vec2::new(
+        (cube.x as f32 + cube.y as f32 * 0.5 + 0.5) * TILE_DIMENSION,
+        (cube.y as f32 + 0.5) * HEX_ROW_HEIGHT,
+    ) - BATTLEFIELD_HALF_WIDTH
+}
 #[derive(Resource, Default, Clone)]
 struct TurretStopwatch(Stopwatch);
 impl TurretStopwatch {
@@ -193,8 +740,9 @@ impl TurretStopwatch {
 }
 #[derive(Component, Deref, Clone, Copy)]
 struct ChargeBallLink(Entity);
+/// The charge held by a turret or carried by a bullet.
 #[derive(Debug, Component, Clone, Copy)]
-struct Charge {
+pub struct Charge {
     value: u64,
     level: u64,
 }
@@ -207,10 +755,13 @@ impl Default for Charge {
     }
 }
 impl Charge {
+    pub fn value(&self) -> u64 {
+        self.value
+    }
     fn calculate_level(value: u64) -> u64 {
         (value as f64).log2().ceil() as u64 + 1
     }
-    fn from_value(value: u64) -> Self {
+    pub fn from_value(value: u64) -> Self {
         let mut v = Self { value, level: 1 };
         v.update_level();
         v
@@ -258,10 +809,12 @@ impl ChargeBallBundle {
         }
     }
 }
-#[derive(Resource, Deref)]
-struct BulletMesh(Mesh2dHandle);
 #[derive(Clone, Copy, Component)]
 struct Bullet;
+/// Marker for the text put over a match's battlefield once it's been won, while the other matches
+/// play on.
+#[derive(Clone, Copy, Component)]
+struct MatchBanner;
 #[derive(Clone, Copy, Component)]
 struct NewBullet;
 /// Component bundle for the bullets that the turrets fire.
@@ -276,8 +829,11 @@ struct BulletBundle {
         Restitution,
         LockedAxes,
         ActiveEvents,
+        RoundScoped,
     ),
+    match_id: MatchId,
     charge: Charge,
+    shot_type: ShotType,
     link: ChargeBallLink,
     /// Rapier collider component.
     collider: Collider,
@@ -285,9 +841,12 @@ struct BulletBundle {
     solver_groups: SolverGroups,
     collider_scale: ColliderScale,
     velocity: Velocity,
+    /// Used by hazards to push the bullet around.
+    external_force: ExternalForce,
     /// Rapier rigidbody component, used by the physics engine to move the entity.
     rigidbody: RigidBody,
     mass: ColliderMassProperties,
+    read_mass: ReadMassProperties,
     /// The game participant that owns this bullet.
     owner: Participant,
     text_bundle: Text2dBundle,
@@ -295,18 +854,24 @@ struct BulletBundle {
 }
 impl BulletBundle {
     fn new(
+        match_id: MatchId,
         owner: Participant,
         position: Vec2,
         ball: Entity,
         charge: Charge,
+        shot_type: ShotType,
         firing_angle: f32,
         bullet_speed: f32,
+        font: Handle<Font>,
+        restitution: f32,
     ) -> Self {
         let direction = Vec2::from_angle(firing_angle);
         Self {
+            match_id,
             owner,
             name: Name::new("Bullet"),
             charge,
+            shot_type,
             link: ChargeBallLink(ball),
             markers: (
                 Bullet,
@@ -317,11 +882,12 @@ impl BulletBundle {
                     combine_rule: CoefficientCombineRule::Min,
                 },
                 Restitution {
-                    coefficient: BULLET_RESTITUTION_COEFFICIENT,
+                    coefficient: restitution,
                     combine_rule: CoefficientCombineRule::Max,
                 },
                 LockedAxes::ROTATION_LOCKED,
                 ActiveEvents::COLLISION_EVENTS,
+                RoundScoped,
             ),
             collider: Collider::ball(1.0),
             collision_groups: CollisionGroups::new(
@@ -329,7 +895,9 @@ impl BulletBundle {
                 collision_groups::BATTLEFIELD_ROOT
                     | collision_groups::ALL_BULLETS
                     | collision_groups::ALL_NEW_BULLETS
-                    | collision_groups::ALL_TURRETS
+                    | collision_groups::TURRETS
+                    | collision_groups::HAZARDS
+                    | collision_groups::PICKUPS
                     | collision_groups::all_tiles_except(owner),
             ),
             solver_groups: SolverGroups::new(
@@ -340,14 +908,16 @@ impl BulletBundle {
             ),
             collider_scale: ColliderScale::Absolute(Vect::splat(1.0)),
             velocity: Velocity::linear(direction * bullet_speed),
+            external_force: ExternalForce::default(),
             rigidbody: RigidBody::Dynamic,
             mass: ColliderMassProperties::Density(1.0),
+            read_mass: ReadMassProperties::default(),
             text_bundle: Text2dBundle {
                 transform: Transform::from_translation(position.extend(BULLET_TEXT_Z)),
                 text: Text::from_section(
                     "",
                     TextStyle {
-                        font: Default::default(),
+                        font,
                         font_size: BULLET_SIZE_FACTOR,
                         color: BULLET_TEXT_COLOR,
                     },
@@ -357,15 +927,24 @@ impl BulletBundle {
         }
     }
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Component)]
 enum ShotType {
     Charged,
     Multi,
 }
+impl ShotType {
+    fn conversion_mode(self, config: &TileConversionConfig) -> TileConversionMode {
+        match self {
+            Self::Charged => config.charged_shot,
+            Self::Multi => config.burst_shot,
+        }
+    }
+}
 #[derive(Component)]
-struct Turret {
+pub struct Turret {
     firing_queue: VecDeque<(ShotType, Charge)>,
     last_hit_timestamp: f32,
+    last_hit_by: Option<Participant>,
     last_charged_shot_timestamp: f32,
 }
 impl Default for Turret {
@@ -373,7 +952,8 @@ impl Default for Turret {
         Self {
             firing_queue: VecDeque::new(),
             last_hit_timestamp: -TURRET_BOOST_COOLDOWN,
-            last_charged_shot_timestamp: -CHARGED_SHOT_COOLDOWN,
+            last_hit_by: None,
+            last_charged_shot_timestamp: f32::NEG_INFINITY,
         }
     }
 }
@@ -390,12 +970,23 @@ struct TurretBundle {
     collision_groups: CollisionGroups,
     collider_scale: ColliderScale,
     active_events: ActiveEvents,
+    round_scoped: RoundScoped,
+    match_id: MatchId,
     name: Name,
 }
 impl TurretBundle {
-    fn new(owner: Participant, x: f32, y: f32, ball: Entity, platform: Entity) -> Self {
+    fn new(
+        match_id: MatchId,
+        owner: Participant,
+        position: Vec2,
+        ball: Entity,
+        platform: Entity,
+        font: Handle<Font>,
+    ) -> Self {
         Self {
             owner,
+            round_scoped: RoundScoped,
+            match_id,
             name: Name::new(format!("Turret: {}", owner)),
             firing_queue: Turret::default(),
             charge: Charge::default(),
@@ -404,17 +995,17 @@ impl TurretBundle {
             rb: RigidBody::Fixed,
             collider: Collider::ball(1.0),
             collision_groups: CollisionGroups::new(
-                collision_groups::turret(owner),
+                collision_groups::TURRETS,
                 collision_groups::ALL_BULLETS | collision_groups::all_new_bullets_except(owner),
             ),
             collider_scale: ColliderScale::Absolute(Vect::splat(1.0)),
             active_events: ActiveEvents::COLLISION_EVENTS,
             text_bundle: Text2dBundle {
-                transform: Transform::from_xyz(x, y, BULLET_TEXT_Z),
+                transform: Transform::from_translation(position.extend(BULLET_TEXT_Z)),
                 text: Text::from_section(
                     "",
                     TextStyle {
-                        font: Default::default(),
+                        font,
                         font_size: BULLET_SIZE_FACTOR,
                         color: BULLET_TEXT_COLOR,
                     },
@@ -435,15 +1026,12 @@ struct TurretBarrelBundle {
     name: Name,
 }
 impl TurretBarrelBundle {
-    fn new() -> Self {
+    fn new(color: Color) -> Self {
         Self {
             marker: TurretBarrel,
             name: Name::new("Turret Barrel"),
             sprite_bundle: SpriteBundle {
-                sprite: Sprite {
-                    color: TURRET_HEAD_COLOR,
-                    ..default()
-                },
+                sprite: Sprite { color, ..default() },
                 transform: Transform {
                     translation: Vec3::new(TURRET_HEAD_LENGTH / 2.0, 0.0, TURRET_HEAD_Z),
                     scale: Vec3::new(TURRET_HEAD_LENGTH, TURRET_HEAD_THICNESS, 1.0),
@@ -482,59 +1070,105 @@ impl TurretPlatformBundle {
 
 fn setup(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
     colors: Res<ParticipantMap<TileColor>>,
-    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    stats: Res<PersistentStats>,
+    assets: Res<GameAssets>,
+    roster: Res<Roster>,
+    matches: Res<Matches>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    options: Res<LaunchOptions>,
+    upgrades: Res<DraftedUpgrades>,
+    map: Res<GeneratedMap>,
+    mut time: ResMut<Time<Virtual>>,
 ) {
+    time.set_relative_speed(options.time_scale);
     commands.insert_resource(EffectInstanceManager::default());
     commands.insert_resource(TurretStopwatch::default());
-    commands.insert_resource(SurvivorCount::default());
-    const OFFSET: f32 = BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH;
-    let horizontal_cuboid = Collider::cuboid(
-        BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
-        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
-    );
-    let vertical_cuboid = Collider::cuboid(
-        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
-        BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
-    );
-    let collider = Collider::compound(vec![
-        (Vect::new(OFFSET, 0.0), 0.0, vertical_cuboid.clone()),
-        (Vect::new(-OFFSET, 0.0), 0.0, vertical_cuboid.clone()),
-        (Vect::new(0.0, OFFSET), 0.0, horizontal_cuboid.clone()),
-        (Vect::new(0.0, -OFFSET), 0.0, horizontal_cuboid.clone()),
-    ]);
+    commands.insert_resource(RoundNumber(1));
+    let mut survivor_counts = PerMatch::default();
+    let mut grids = PerMatch::default();
+    let mut turrets = PerMatch::default();
+    // Each match gets a battlefield of its own, with the rest laid out around it.
+    for match_id in matches.running(&roster) {
+        let roster = matches.roster(&roster, match_id);
+        survivor_counts.insert(match_id, SurvivorCount(roster.count()));
+        let (root, tile_root) = spawn_battlefield_root(&mut commands, match_id, &geometry);
+        grids.insert(
+            match_id,
+            setup_tiles(
+                &mut commands,
+                match_id,
+                tile_root,
+                &colors,
+                &geometry,
+                &config,
+                &upgrades,
+                &assets,
+                &map,
+            ),
+        );
+        turrets.insert(
+            match_id,
+            setup_turrets(
+                &mut commands,
+                match_id,
+                root,
+                &assets,
+                &roster,
+                &stats,
+                &ball_colors,
+                &geometry,
+            ),
+        );
+    }
+    commands.insert_resource(survivor_counts);
+    commands.insert_resource(grids);
+    commands.insert_resource(turrets);
+}
+/// Spawn the root of `match_id`'s battlefield, which the tiles and turrets go under, and the root
+/// of its tiles.
+fn spawn_battlefield_root(
+    commands: &mut Commands,
+    match_id: MatchId,
+    geometry: &BattlefieldGeometry,
+) -> (Entity, Entity) {
     let root = commands
         .spawn((
             Name::new("Battlefield Root"),
             BattlefieldRoot,
+            match_id,
             RigidBody::Fixed,
             CollisionGroups::new(
                 collision_groups::BATTLEFIELD_ROOT,
-                collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+                collision_groups::ALL_BULLETS
+                    | collision_groups::ALL_NEW_BULLETS
+                    | collision_groups::DEBRIS,
             ),
             Restitution {
                 coefficient: 1.0,
                 combine_rule: CoefficientCombineRule::Max,
             },
-            collider,
-            SpatialBundle::default(),
+            geometry.shape.wall_collider(),
+            SpatialBundle::from_transform(Transform::from_translation(
+                match_id.world_offset().extend(0.0),
+            )),
         ))
         .id();
     let tile_root = commands
-        .spawn((Name::new("Tile Root"), (TileRoot, SpatialBundle::default())))
+        .spawn((
+            Name::new("Tile Root"),
+            (TileRoot, match_id, SpatialBundle::default()),
+        ))
         .set_parent(root)
         .id();
-    setup_tiles(&mut commands, tile_root, &colors);
-    let mesh = Mesh2dHandle(meshes.add(Circle::new(1.0)));
-    let maps = setup_turrets(&mut commands, root, mesh.clone(), &materials);
-    commands.insert_resource(maps);
-    commands.insert_resource(BulletMesh(mesh));
+    (root, tile_root)
 }
 fn rotate_turret(
     time: Res<Time>,
     mut stopwatch: ResMut<TurretStopwatch>,
-    mut turrets: Query<(&mut Transform, &BarrelOffset)>,
+    mut turrets: Query<(&mut Transform, &BarrelOffset), Without<manual_control::ManualAim>>,
 ) {
     stopwatch.0.tick(time.delta());
     let angle_offset = stopwatch.get();
@@ -544,75 +1178,175 @@ fn rotate_turret(
 }
 fn update_charge_level(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Charge, &Participant, Option<&Turret>), Changed<Charge>>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Charge,
+            &Participant,
+            Option<(&Turret, &MatchId)>,
+        ),
+        Changed<Charge>,
+    >,
     mut event_writer: EventWriter<EliminationEvent>,
 ) {
-    for (entity, mut charge, &participant, firing_queue) in &mut query {
+    for (entity, mut charge, &participant, turret) in &mut query {
         if charge.value > 0 {
             charge.update_level();
-        } else if firing_queue.is_some() {
-            event_writer.send(EliminationEvent::new(participant));
+        } else if let Some((turret, &match_id)) = turret {
+            event_writer.send(EliminationEvent::new(
+                match_id,
+                participant,
+                turret.last_hit_by,
+            ));
         } else {
             commands.entity(entity).despawn_recursive();
         }
     }
 }
-fn setup_tiles(commands: &mut Commands, tile_root: Entity, colors: &ParticipantMap<TileColor>) {
-    for i in 0..TILE_COUNT {
-        let x = TILE_DIMENSION / 2.0 + i as f32 * TILE_DIMENSION;
-        for j in 0..TILE_COUNT {
-            let y = TILE_DIMENSION / 2.0 + j as f32 * TILE_DIMENSION;
-            commands
-                .spawn(TileBundle::new(Participant::A, colors.a.0, x, y))
-                .set_parent(tile_root);
-            commands
-                .spawn(TileBundle::new(Participant::B, colors.b.0, -x, y))
-                .set_parent(tile_root);
-            commands
-                .spawn(TileBundle::new(Participant::C, colors.c.0, x, -y))
-                .set_parent(tile_root);
-            commands
-                .spawn(TileBundle::new(Participant::D, colors.d.0, -x, -y))
-                .set_parent(tile_root);
+/// The fortification around `owner`'s turret, reaching further for each fortification upgrade
+/// they've drafted.
+fn drafted_fortification(
+    config: &GameConfig,
+    upgrades: &DraftedUpgrades,
+    owner: Participant,
+) -> FortificationConfig {
+    let picks = upgrades.count(owner, Upgrade::Fortification);
+    FortificationConfig {
+        radius: config.fortification.radius + picks as f32 * config.draft.fortification_radius,
+        ..config.fortification.clone()
+    }
+}
+fn setup_tiles(
+    commands: &mut Commands,
+    match_id: MatchId,
+    tile_root: Entity,
+    colors: &ParticipantMap<TileColor>,
+    geometry: &BattlefieldGeometry,
+    config: &GameConfig,
+    upgrades: &DraftedUpgrades,
+    assets: &GameAssets,
+    map: &GeneratedMap,
+) -> TileGrid {
+    let mut grid = TileGrid::new(config.tile_shape);
+    let style = TileStyle::new(config.tile_shape, assets);
+    let fortifications =
+        ParticipantMap::from_fn(|owner| drafted_fortification(config, upgrades, owner));
+    for y in 0..grid.rows() {
+        for x in 0..grid.columns(y) {
+            let position = grid.cell_center(x, y);
+            if !geometry.shape.contains(position, 0.0) {
+                continue;
+            }
+            match map.cell_at(position) {
+                LayoutCell::Tile => {}
+                LayoutCell::Gap => continue,
+                LayoutCell::Neutral => {
+                    commands
+                        .spawn(neutral_tile::NeutralTileBundle::new(
+                            match_id, position, &style,
+                        ))
+                        .set_parent(tile_root);
+                    continue;
+                }
+            }
+            let owner = geometry.territory_owner(match_id, position);
+            let tile = commands
+                .spawn(TileBundle::new(
+                    match_id,
+                    owner,
+                    colors[owner].0,
+                    position.x,
+                    position.y,
+                    &style,
+                    &fortifications[owner],
+                    &config.tile_hit_points,
+                    geometry.turret_position(owner),
+                ))
+                .set_parent(tile_root)
+                .id();
+            grid.set(x, y, tile);
         }
     }
+    grid
 }
+/// The angle `participant`'s turret starts out at, spread out so the turrets don't all point the
+/// same way.
+fn turret_base_offset(participant: Participant) -> f32 {
+    match participant {
+        Participant::A => PI,
+        Participant::B => -FRAC_PI_2,
+        Participant::C => FRAC_PI_2,
+        Participant::D => 0.0,
+        Participant::E => 3.0 * FRAC_PI_4,
+        Participant::F => -FRAC_PI_4,
+        Participant::G => FRAC_PI_4,
+        Participant::H => -3.0 * FRAC_PI_4,
+    }
+}
+/// Spawn a turret in `match_id` for every participant in `roster`. The entries for participants
+/// not in the roster are set to [`Entity::PLACEHOLDER`].
 fn setup_turrets(
     commands: &mut Commands,
+    match_id: MatchId,
     root: Entity,
-    mesh: Mesh2dHandle,
-    materials: &ParticipantMap<Handle<ColorMaterial>>,
+    assets: &GameAssets,
+    roster: &Roster,
+    stats: &PersistentStats,
+    ball_colors: &ParticipantMap<BallColor>,
+    geometry: &BattlefieldGeometry,
 ) -> ParticipantMap<Entity> {
-    let mut spawn_turret = |owner: Participant, base_offset: f32, x: f32, y: f32| {
-        let ball = commands
-            .spawn(ChargeBallBundle::new(
-                mesh.clone(),
-                materials.get(owner).clone(),
-            ))
-            .id();
-        let platform = commands
-            .spawn(TurretPlatformBundle::new(base_offset))
-            .set_parent(root)
-            .id();
-        commands
-            .spawn(TurretBarrelBundle::new())
-            .set_parent(platform);
-        commands
-            .spawn(TurretBundle::new(owner, x, y, ball, platform))
-            .set_parent(root)
-            .push_children(&[ball, platform])
-            .id()
-    };
-    let a = spawn_turret(Participant::A, PI, TURRET_POSITION, TURRET_POSITION);
-    let b = spawn_turret(
-        Participant::B,
-        -FRAC_PI_2,
-        -TURRET_POSITION,
-        TURRET_POSITION,
-    );
-    let c = spawn_turret(Participant::C, FRAC_PI_2, TURRET_POSITION, -TURRET_POSITION);
-    let d = spawn_turret(Participant::D, 0.0, -TURRET_POSITION, -TURRET_POSITION);
-    ParticipantMap::new(a, b, c, d)
+    ParticipantMap::from_fn(|owner| match roster[owner] {
+        true => spawn_turret(
+            commands,
+            match_id,
+            root,
+            assets,
+            owner,
+            stats,
+            ball_colors,
+            geometry.turret_position(owner),
+        ),
+        false => Entity::PLACEHOLDER,
+    })
+}
+fn spawn_turret(
+    commands: &mut Commands,
+    match_id: MatchId,
+    root: Entity,
+    assets: &GameAssets,
+    owner: Participant,
+    stats: &PersistentStats,
+    ball_colors: &ParticipantMap<BallColor>,
+    position: Vec2,
+) -> Entity {
+    let ball = commands
+        .spawn(ChargeBallBundle::new(
+            assets.unit_circle.clone(),
+            assets.ball_materials.get(owner).clone(),
+        ))
+        .id();
+    let platform = commands
+        .spawn(TurretPlatformBundle::new(turret_base_offset(owner)))
+        .set_parent(root)
+        .id();
+    let barrel_color = stats.skins[owner]
+        .barrel_color(ball_colors[owner].0)
+        .unwrap_or(TURRET_HEAD_COLOR);
+    commands
+        .spawn(TurretBarrelBundle::new(barrel_color))
+        .set_parent(platform);
+    commands
+        .spawn(TurretBundle::new(
+            match_id,
+            owner,
+            position,
+            ball,
+            platform,
+            assets.font.clone(),
+        ))
+        .set_parent(root)
+        .push_children(&[ball, platform])
+        .id()
 }
 fn update_charge_ball(
     mut balls: Query<
@@ -668,6 +1402,7 @@ fn update_charge_ball(
 fn update_bullets_solver_groups(
     mut commands: Commands,
     rapier: Res<RapierContext>,
+    geometry: Res<BattlefieldGeometry>,
     mut bullet_query: Query<
         (
             Entity,
@@ -682,9 +1417,8 @@ fn update_bullets_solver_groups(
     for (entity, mut collision_groups, mut solver_groups, &participant, transform) in
         &mut bullet_query
     {
-        if BATTLEFIELD_HALF_WIDTH - transform.translation.x.abs() < NEW_BULLET_PHASE_RANGE
-            && BATTLEFIELD_HALF_WIDTH - transform.translation.y.abs() < NEW_BULLET_PHASE_RANGE
-        {
+        let offset = transform.translation.xy() - geometry.turret_position(participant);
+        if offset.abs().max_element() < NEW_BULLET_PHASE_RANGE / 2.0 {
             continue;
         }
         if !rapier
@@ -695,29 +1429,55 @@ fn update_bullets_solver_groups(
             collision_groups.filters = collision_groups::BATTLEFIELD_ROOT
                 | collision_groups::ALL_BULLETS
                 | collision_groups::ALL_NEW_BULLETS
-                | collision_groups::ALL_TURRETS
+                | collision_groups::TURRETS
+                | collision_groups::HAZARDS
+                | collision_groups::PICKUPS
                 | collision_groups::all_tiles_except(participant);
             solver_groups.memberships = collision_groups::bullet(participant);
             solver_groups.filters = collision_groups::BATTLEFIELD_ROOT
                 | collision_groups::ALL_BULLETS
                 | collision_groups::ALL_NEW_BULLETS
-                | collision_groups::ALL_TURRETS;
+                | collision_groups::TURRETS;
             commands.entity(entity).remove::<NewBullet>();
         }
     }
 }
+/// Bring the bullets already flying in line with the config after it's been edited.
+fn apply_physics_config(
+    config: Res<GameConfig>,
+    mut bullets: Query<&mut Restitution, With<Bullet>>,
+) {
+    for mut restitution in &mut bullets {
+        if restitution.coefficient != config.physics.bullet_restitution {
+            restitution.coefficient = config.physics.bullet_restitution;
+        }
+    }
+}
 fn fire_shots(
     mut commands: Commands,
-    mesh: Res<BulletMesh>,
-    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
-    turret_stopwatch: Res<TurretStopwatch>,
-    mut turrets: Query<(&mut Turret, &Transform, &Participant, &TurretPlatformLink)>,
-    platform_query: Query<&BarrelOffset>,
-    battlefield_root: Query<Entity, With<BattlefieldRoot>>,
+    assets: Res<GameAssets>,
+    mut turrets: Query<(
+        &mut Turret,
+        &Transform,
+        &Participant,
+        &TurretPlatformLink,
+        &MatchId,
+    )>,
+    platform_query: Query<&Transform, Without<Turret>>,
+    battlefield_roots: Query<(Entity, &MatchId), With<BattlefieldRoot>>,
+    mut shot_events: EventWriter<ShotFiredEvent>,
     time: Res<Time>,
+    preset: Res<GamePreset>,
+    config: Res<GameConfig>,
+    match_winners: Res<MatchWinners>,
 ) {
-    for (mut turret, transform, &owner, &TurretPlatformLink(link)) in &mut turrets {
-        if time.elapsed_seconds() - turret.last_charged_shot_timestamp < CHARGED_SHOT_COOLDOWN {
+    let cooldown = preset.charged_shot_cooldown_secs();
+    for (mut turret, transform, &owner, &TurretPlatformLink(link), &match_id) in &mut turrets {
+        // A match that's been won holds its fire until the others are done too.
+        if match_winners.0.get(match_id).is_some() {
+            continue;
+        }
+        if time.elapsed_seconds() - turret.last_charged_shot_timestamp < cooldown {
             continue;
         }
         let Some((shot_type, charge)) = turret.firing_queue.pop_back() else {
@@ -756,44 +1516,93 @@ fn fire_shots(
                 (shot, offset, BURST_SHOT_BULLET_SPEED)
             }
         };
-        let &BarrelOffset(base_angle) = platform_query.get(link).unwrap();
+        // Taken from the platform rather than the stopwatch, since turrets can be aimed by hand.
+        let (angle, ..) = platform_query
+            .get(link)
+            .unwrap()
+            .rotation
+            .to_euler(EulerRot::ZYX);
+        let Some((root, _)) = battlefield_roots.iter().find(|(_, &x)| x == match_id) else {
+            continue;
+        };
         let ball = commands
             .spawn(ChargeBallBundle::new(
-                mesh.clone(),
-                materials.get(owner).clone(),
+                assets.unit_circle.clone(),
+                assets.ball_materials.get(owner).clone(),
             ))
             .id();
         commands
             .spawn(BulletBundle::new(
+                match_id,
                 owner,
                 transform.translation.xy() - offset,
                 ball,
                 charge,
-                turret_stopwatch.get() + base_angle,
+                shot_type,
+                angle,
                 bullet_speed,
+                assets.font.clone(),
+                config.physics.bullet_restitution,
             ))
-            .set_parent(battlefield_root.single())
+            .set_parent(root)
             .add_child(ball);
+        shot_events.send(ShotFiredEvent {
+            participant: owner,
+            charge: charge.value,
+        });
     }
 }
 fn handle_trigger_events(
     mut trigger_events: EventReader<TriggerEvent>,
     mut restart_events: EventReader<RestartEvent>,
-    turret_entities: Res<ParticipantMap<Entity>>,
+    mut gamble_events: EventWriter<gamble::GambleEvent>,
+    mut swap_events: EventWriter<swap::SwapEvent>,
+    mut generated_events: EventWriter<ChargeGeneratedEvent>,
+    (turret_entities, matches): (Res<PerMatch<ParticipantMap<Entity>>>, Res<Matches>),
+    survivors: Res<ParticipantMap<bool>>,
     mut turret_query: Query<(&mut Charge, &mut Turret)>,
+    ball_numbers: Query<(&WorkerBallNumber, &Participant)>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    upgrades: Res<DraftedUpgrades>,
 ) {
-    if !restart_events.is_empty() {
-        restart_events.clear();
-        trigger_events.clear();
-    }
+    // The triggers from before a restart don't carry over into the new round.
+    let restarted: Vec<_> = restart_events.read().map(|x| x.match_id).collect();
+    // Swaps need both turrets at once, so they're applied after the rest of the triggers.
+    let mut swaps = Vec::new();
     for event in trigger_events.read() {
-        let &entity = turret_entities.get(event.participant);
+        let match_id = matches[event.participant];
+        if restarted.contains(&match_id) {
+            continue;
+        }
+        let Some(&entity) = turret_entities
+            .get(match_id)
+            .map(|x| x.get(event.participant))
+        else {
+            continue;
+        };
         let Ok((mut charge, mut turret)) = turret_query.get_mut(entity) else {
             continue;
         };
+        let charge_before = charge.value;
         match event.trigger_type {
-            TriggerType::Multiply(factor) => charge.multiply(factor),
+            TriggerType::Multiply(factor) => {
+                let biggest = config
+                    .trigger_zones
+                    .iter()
+                    .filter_map(|x| match x {
+                        TriggerType::Multiply(factor) => Some(*factor),
+                        _ => None,
+                    })
+                    .max();
+                let bonus = if Some(factor) == biggest {
+                    upgrades.count(event.participant, Upgrade::ZoneBonus)
+                } else {
+                    0
+                };
+                charge.multiply(factor.saturating_add(bonus.min(u8::MAX as usize) as u8));
+            }
             TriggerType::BurstShot => {
                 turret.firing_queue.push_front((ShotType::Multi, *charge));
                 if time.elapsed_seconds() - turret.last_hit_timestamp > TURRET_BOOST_COOLDOWN {
@@ -810,8 +1619,67 @@ fn handle_trigger_events(
                     charge.reset();
                 }
             }
+            TriggerType::Gamble => {
+                let config = &config.gamble;
+                let won = rng.gen_bool(config.win_chance.clamp(0.0, 1.0));
+                if won {
+                    charge.multiply(config.win_factor);
+                } else {
+                    // Losing a gamble shouldn't be able to eliminate the turret outright.
+                    charge.value = (charge.value / config.loss_divisor.max(1) as u64).max(1);
+                }
+                gamble_events.send(gamble::GambleEvent {
+                    participant: event.participant,
+                    won,
+                });
+            }
+            TriggerType::Swap => {
+                // Only the participants in the same match are within reach.
+                let reachable = ParticipantMap::from_fn(|p| survivors[p] && matches[p] == match_id);
+                let opponent =
+                    event
+                        .participant
+                        .random_opponent(&reachable, &config.teams, &mut rng.0);
+                if opponent != event.participant {
+                    swaps.push((event.participant, opponent));
+                }
+            }
+            // Handled by the panel plugin.
+            TriggerType::Freeze => (),
+        }
+        if let Some(ball) = event.ball {
+            if let Ok((&WorkerBallNumber(number), &owner)) = ball_numbers.get(ball) {
+                // Releasing the charge can leave the turret with more than it had when the boost
+                // kicks in, but that's not charge the ball generated.
+                let generated = match event.trigger_type {
+                    TriggerType::Multiply(_) | TriggerType::Gamble => {
+                        charge.value.saturating_sub(charge_before)
+                    }
+                    _ => 0,
+                };
+                generated_events.send(ChargeGeneratedEvent {
+                    participant: event.participant,
+                    ball,
+                    owner,
+                    number,
+                    generated,
+                });
+            }
         }
     }
+    let turret_entity = |participant: Participant| {
+        turret_entities
+            .get(matches[participant])
+            .map_or(Entity::PLACEHOLDER, |x| *x.get(participant))
+    };
+    for (a, b) in swaps {
+        let entities = [turret_entity(a), turret_entity(b)];
+        let Ok([(mut charge_a, _), (mut charge_b, _)]) = turret_query.get_many_mut(entities) else {
+            continue;
+        };
+        std::mem::swap(&mut *charge_a, &mut *charge_b);
+        swap_events.send(swap::SwapEvent { a, b });
+    }
 }
 fn handle_bullet_turret_collision(
     mut collision_event_reader: EventReader<CollisionEvent>,
@@ -821,6 +1689,7 @@ fn handle_bullet_turret_collision(
         (With<Turret>, Without<Bullet>),
     >,
     time: Res<Time>,
+    config: Res<GameConfig>,
 ) {
     for event in collision_event_reader.read() {
         let &CollisionEvent::Started(a, b, _) = event else {
@@ -841,142 +1710,797 @@ fn handle_bullet_turret_collision(
         } else {
             continue;
         };
-        if turret_owner == bullet_owner {
+        if config.teams.allies(turret_owner, bullet_owner) {
             continue;
         }
         let min_value = bullet_charge.value.min(turret_charge.value);
         bullet_charge.value -= min_value;
         turret_charge.value -= min_value;
         turret.last_hit_timestamp = time.elapsed_seconds();
+        turret.last_hit_by = Some(bullet_owner);
     }
 }
+/// Remove everything belonging to the eliminated participants, or only their turrets if they're
+/// coming back, in which case their marbles keep running in the meantime.
 fn handle_elimination(
     mut commands: Commands,
     mut events: EventReader<EliminationEvent>,
-    mut survivor_count: ResMut<SurvivorCount>,
+    mut survivor_counts: ResMut<PerMatch<SurvivorCount>>,
     mut survivors: ResMut<ParticipantMap<bool>>,
-    participant_entity_query: Query<(Entity, &Participant), (Without<Tile>, Without<Bullet>)>,
+    config: Res<GameConfig>,
+    participant_entity_query: Query<
+        (Entity, &Participant, Has<Turret>),
+        (Without<Tile>, Without<Bullet>),
+    >,
 ) {
     for event in events.read() {
-        survivors.set(event.participant, false);
-        survivor_count.0 -= 1;
-        for (entity, &participant) in &participant_entity_query {
-            if participant == event.participant {
+        // A participant can be eliminated more than one way in the same frame, like losing their
+        // turret while holding too little territory, but only goes out once.
+        if !survivors[event.participant] {
+            continue;
+        }
+        if !config.comeback.enabled {
+            survivors.set(event.participant, false);
+            if let Some(count) = survivor_counts.get_mut(event.match_id) {
+                count.0 -= 1;
+            }
+        }
+        for (entity, &participant, is_turret) in &participant_entity_query {
+            if participant == event.participant && (is_turret || !config.comeback.enabled) {
                 commands.entity(entity).despawn_recursive();
             }
         }
     }
 }
+/// The bullet components needed to land a hit on a tile.
+type BulletHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Participant,
+        &'static mut Charge,
+        &'static Velocity,
+        &'static ShotType,
+        &'static GlobalTransform,
+        &'static MatchId,
+        Has<capture_streak::OnFire>,
+    ),
+    With<Bullet>,
+>;
+/// The tile components needed to land a hit on a tile.
+type TileHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Participant,
+        &'static mut Sprite,
+        &'static mut CollisionGroups,
+        &'static mut TileDurability,
+        &'static GlobalTransform,
+        &'static MatchId,
+    ),
+    (With<Tile>, Without<Bullet>),
+>;
+/// Land a hit from a bullet on `tile` at the cost of one charge, converting the tile once its
+/// durability runs out, or right away with [`Mutator::Piercing`]. Nothing happens if the tile already
+/// belongs to `bullet_owner`, the bullet has no charge left, or the tile is fortified and the bullet
+/// is too weak to touch it.
+fn hit_tile(
+    tile: Entity,
+    bullet: Entity,
+    bullet_owner: Participant,
+    charge: &mut Charge,
+    tile_query: &mut TileHitQuery,
+    conversions: &mut EventWriter<TileConvertedEvent>,
+    tile_colors: &ParticipantMap<TileColor>,
+    config: &GameConfig,
+    upgrades: &DraftedUpgrades,
+    geometry: &BattlefieldGeometry,
+    mutators: &ActiveMutators,
+) {
+    let Ok((
+        mut tile_owner,
+        mut sprite,
+        mut collision_group,
+        mut durability,
+        tile_transform,
+        &match_id,
+    )) = tile_query.get_mut(tile)
+    else {
+        return;
+    };
+    if config.teams.allies(*tile_owner, bullet_owner)
+        || charge.value == 0
+        || durability.fortified && charge.value < config.fortification.min_charge
+    {
+        return;
+    }
+    charge.value -= 1;
+    durability.hits = match mutators.contains(Mutator::Piercing) {
+        true => 0,
+        false => durability.hits.saturating_sub(1),
+    };
+    if durability.hits > 0 {
+        sprite.color = tile_colors.get(*tile_owner).0.darker(CRACKED_TILE_DARKEN);
+        return;
+    }
+    conversions.send(TileConvertedEvent {
+        match_id,
+        from: *tile_owner,
+        to: bullet_owner,
+        bullet,
+    });
+    *tile_owner = bullet_owner;
+    *durability = TileDurability::new(
+        tile_transform.translation().xy() - match_id.world_offset(),
+        &drafted_fortification(config, upgrades, bullet_owner),
+        &config.tile_hit_points,
+        geometry.turret_position(bullet_owner),
+    );
+    sprite.color = tile_colors.get(bullet_owner).0;
+    *collision_group = CollisionGroups::new(
+        collision_groups::tile(bullet_owner),
+        collision_groups::all_bullets_except(bullet_owner) | all_new_bullets_except(bullet_owner),
+    );
+}
 fn handle_bullet_tile_collision(
-    mut commands: Commands,
     mut events: EventReader<CollisionEvent>,
     tile_colors: Res<ParticipantMap<TileColor>>,
     ball_colors: Res<ParticipantMap<BallColor>>,
-    mut bullet_query: Query<(&Participant, &mut Charge, &Velocity), With<Bullet>>,
-    mut tile_query: Query<
-        (
-            &mut Participant,
-            &mut Sprite,
-            &mut CollisionGroups,
-            &GlobalTransform,
-        ),
-        (With<Tile>, Without<Bullet>),
-    >,
-    effect: Res<TileHitEffect>,
-    mut effect_query: Query<(&mut EffectProperties, &mut Transform, &mut EffectSpawner)>,
-    mut instance_manager: ResMut<EffectInstanceManager>,
+    grids: Res<PerMatch<TileGrid>>,
+    mut bullet_query: BulletHitQuery,
+    mut tile_query: TileHitQuery,
+    mut conversions: EventWriter<TileConvertedEvent>,
+    (config, upgrades): (Res<GameConfig>, Res<DraftedUpgrades>),
+    geometry: Res<BattlefieldGeometry>,
+    mutators: Res<ActiveMutators>,
+    mut effects: ResMut<Backlog<TileHitEffect>>,
 ) {
-    for event in events.read() {
+    let events: Vec<_> = events.read().collect();
+    let splash_regions = splash_regions(&events, &bullet_query, &grids, &config);
+    for event in events {
         match event {
             &CollisionEvent::Started(a, b, _) => {
-                let (&bullet_owner, mut charge, velocity) = if let Ok(x) = bullet_query.get_mut(a) {
-                    x
-                } else if let Ok(x) = bullet_query.get_mut(b) {
-                    x
+                let bullet = if bullet_query.contains(a) {
+                    a
+                } else if bullet_query.contains(b) {
+                    b
+                } else {
+                    continue;
+                };
+                let (&bullet_owner, mut charge, velocity, &shot_type, .., on_fire) =
+                    bullet_query.get_mut(bullet).unwrap();
+                let tile = if tile_query.contains(a) {
+                    a
+                } else if tile_query.contains(b) {
+                    b
                 } else {
                     continue;
                 };
-                let (mut tile_owner, mut sprite, mut collision_group, tile_transform) =
-                    if let Ok(x) = tile_query.get_mut(a) {
-                        x
-                    } else if let Ok(x) = tile_query.get_mut(b) {
-                        x
-                    } else {
-                        continue;
-                    };
-                if bullet_owner == *tile_owner {
+                let (&tile_owner, .., tile_transform, _) = tile_query.get(tile).unwrap();
+                let impact = tile_transform.translation();
+                if config.teams.allies(bullet_owner, tile_owner) {
                     continue;
                 }
                 if charge.value == 0 {
                     continue;
                 }
-                *tile_owner = bullet_owner;
-                sprite.color = tile_colors.get(bullet_owner).0;
-                *collision_group = CollisionGroups::new(
-                    collision_groups::tile(bullet_owner),
-                    collision_groups::all_bullets_except(bullet_owner)
-                        | all_new_bullets_except(bullet_owner),
-                );
-                charge.value -= 1;
-                if let Some(effect_entity) = instance_manager.get() {
-                    let (mut properties, mut transform, mut spawner) = effect_query.get_mut(effect_entity).expect("entity returned by `InstanceManager` should have an `EffectProperties` component.");
-                    properties.set_spawn_color(ball_colors.get(bullet_owner).0);
-                    properties.set_bullet_vel(velocity.linvel);
-                    transform.translation = tile_transform.translation();
-                    spawner.reset();
-                } else {
-                    let entity = commands
-                        .spawn(ParticleEffectBundle {
-                            effect: ParticleEffect::new(effect.0.clone()),
-                            transform: Transform::from_translation(tile_transform.translation()),
-                            ..default()
-                        })
-                        .insert(Name::new("Tile Hit Particle Spawner"))
-                        .id();
-                    instance_manager.add(entity);
+                match shot_type.conversion_mode(&config.tile_conversion) {
+                    TileConversionMode::Paint => {
+                        hit_tile(
+                            tile,
+                            bullet,
+                            bullet_owner,
+                            &mut charge,
+                            &mut tile_query,
+                            &mut conversions,
+                            &tile_colors,
+                            &config,
+                            &upgrades,
+                            &geometry,
+                            &mutators,
+                        );
+                    }
+                    TileConversionMode::Splash => {
+                        for &tile in splash_regions.get(&bullet).into_iter().flatten() {
+                            if charge.value == 0 {
+                                break;
+                            }
+                            hit_tile(
+                                tile,
+                                bullet,
+                                bullet_owner,
+                                &mut charge,
+                                &mut tile_query,
+                                &mut conversions,
+                                &tile_colors,
+                                &config,
+                                &upgrades,
+                                &geometry,
+                                &mutators,
+                            );
+                        }
+                        // Whatever's left of the bullet is spent on the impact.
+                        charge.value = 0;
+                    }
                 }
+                let color = ball_colors.get(bullet_owner).0;
+                effects.push(TileHitEffect {
+                    position: impact,
+                    color: match on_fire {
+                        true => color.lighter(ON_FIRE_SPARK_LIGHTEN),
+                        false => color,
+                    },
+                    velocity: velocity.linvel,
+                });
             }
             CollisionEvent::Stopped(_, _, _) => (),
         }
     }
 }
-pub fn game_is_going(survivor_count: Res<SurvivorCount>) -> bool {
-    survivor_count.0 > 1
+/// The tiles hit by each splash shot that hit a tile this frame, closest first. Working these out
+/// means scanning every cell in the radius, so it's done in parallel up front, while the hits
+/// themselves still land one at a time in the order of the collisions.
+fn splash_regions(
+    events: &[&CollisionEvent],
+    bullet_query: &BulletHitQuery,
+    grids: &PerMatch<TileGrid>,
+    config: &GameConfig,
+) -> HashMap<Entity, Vec<Entity>> {
+    let mut impacts: Vec<(Entity, Vec2, MatchId)> = Vec::new();
+    for event in events {
+        let &&CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let Some((bullet, (_, charge, _, shot_type, transform, &match_id, _))) = [a, b]
+            .into_iter()
+            .find_map(|x| bullet_query.get(x).ok().map(|bullet| (x, bullet)))
+        else {
+            continue;
+        };
+        if charge.value > 0
+            && shot_type.conversion_mode(&config.tile_conversion) == TileConversionMode::Splash
+            && !impacts.iter().any(|&(x, ..)| x == bullet)
+        {
+            let center = transform.translation().xy() - match_id.world_offset();
+            impacts.push((bullet, center, match_id));
+        }
+    }
+    let mut regions = HashMap::new();
+    for (match_id, grid) in grids.iter() {
+        let impacts: Vec<_> = impacts
+            .iter()
+            .filter(|&&(.., x)| x == match_id)
+            .map(|&(bullet, center, _)| (bullet, center))
+            .collect();
+        if !impacts.is_empty() {
+            regions.extend(tiles_in_splashes(
+                &impacts,
+                grid,
+                config.tile_conversion.splash_radius,
+                SPLASH_REGION_CHUNK_SIZE,
+            ));
+        }
+    }
+    regions
+}
+/// The tiles within `radius` of each bullet's impact, worked out `chunk_size` impacts per task.
+fn tiles_in_splashes(
+    impacts: &[(Entity, Vec2)],
+    grid: &TileGrid,
+    radius: f32,
+    chunk_size: usize,
+) -> HashMap<Entity, Vec<Entity>> {
+    impacts
+        .par_chunk_map(ComputeTaskPool::get(), chunk_size, |_, chunk| {
+            chunk
+                .iter()
+                .map(|&(bullet, center)| (bullet, grid.tiles_in_radius(center, radius)))
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+}
+/// Start the tile hit effects waiting in the backlog, a few at a time so that a burst of hits
+/// doesn't stall the frame. The oldest are dropped if too many pile up.
+fn spawn_tile_hit_effects(
+    mut commands: Commands,
+    mut effects: ResMut<Backlog<TileHitEffect>>,
+    assets: Res<GameAssets>,
+    config: Res<GameConfig>,
+    mut effect_query: Query<(&mut EffectProperties, &mut Transform, &mut EffectSpawner)>,
+    mut instance_manager: ResMut<EffectInstanceManager>,
+) {
+    let performance = &config.performance;
+    effects.discard_oldest(performance.tile_hit_effect_backlog as usize);
+    for effect in effects.take(performance.tile_hit_effect_budget()) {
+        if let Some(effect_entity) = instance_manager.get() {
+            // The particle plugin adds these, and it's left out when running headless or in safe
+            // mode, where there's nothing to draw the effects with anyway.
+            let Ok((mut properties, mut transform, mut spawner)) =
+                effect_query.get_mut(effect_entity)
+            else {
+                continue;
+            };
+            properties.set_spawn_color(effect.color);
+            properties.set_bullet_vel(effect.velocity);
+            transform.translation = effect.position;
+            spawner.reset();
+        } else {
+            let entity = commands
+                .spawn(ParticleEffectBundle {
+                    effect: ParticleEffect::new(assets.tile_hit_effect.clone()),
+                    transform: Transform::from_translation(effect.position),
+                    ..default()
+                })
+                .insert(Name::new("Tile Hit Particle Spawner"))
+                .id();
+            instance_manager.add(entity);
+        }
+    }
 }
 fn cleanup_particle_emitters(mut instance_manager: ResMut<EffectInstanceManager>) {
     instance_manager.reset();
 }
+/// Start over with fresh tile hit emitters once the window is visible again, rather than reusing
+/// the pooled ones, and drop the hits that piled up in the meantime.
+fn reset_particle_emitters(
+    mut commands: Commands,
+    mut instance_manager: ResMut<EffectInstanceManager>,
+    mut effects: ResMut<Backlog<TileHitEffect>>,
+) {
+    instance_manager.reset();
+    for entity in instance_manager.pool.drain(..) {
+        commands.entity(entity).despawn_recursive();
+    }
+    effects.clear();
+}
+/// Clear the bullets off the battlefield of a match that's been won while other matches are still
+/// going, and say who won it there. With a single match, the round is simply over.
+fn settle_match(
+    mut commands: Commands,
+    mut events: EventReader<MatchDecidedEvent>,
+    assets: Res<GameAssets>,
+    (names, ball_colors): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    bullets: Query<(Entity, &MatchId), Or<(With<Bullet>, With<NewBullet>)>>,
+    roots: Query<(Entity, &MatchId), With<BattlefieldRoot>>,
+) {
+    if matches.running(&roster).len() < 2 {
+        events.clear();
+        return;
+    }
+    for &MatchDecidedEvent { match_id, winner } in events.read() {
+        for (entity, &x) in &bullets {
+            if x == match_id {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        let Some((root, _)) = roots.iter().find(|(_, &x)| x == match_id) else {
+            continue;
+        };
+        commands
+            .spawn((
+                MatchBanner,
+                match_id,
+                Name::new("Match Banner"),
+                Text2dBundle {
+                    text: Text::from_sections([
+                        TextSection::new(
+                            names[winner].0.clone(),
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: MATCH_BANNER_FONT_SIZE,
+                                color: ball_colors[winner].0,
+                            },
+                        ),
+                        TextSection::new(
+                            " Wins",
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: MATCH_BANNER_FONT_SIZE,
+                                color: Color::WHITE,
+                            },
+                        ),
+                    ]),
+                    transform: Transform::from_xyz(0.0, 0.0, MATCH_BANNER_Z),
+                    ..default()
+                },
+            ))
+            .set_parent(root);
+    }
+}
+/// Set each restarted match up again, leaving the rest of the matches as they are.
 fn restart(
     mut commands: Commands,
-    mut survivor_count: ResMut<SurvivorCount>,
-    mut survivors: ResMut<ParticipantMap<bool>>,
-    mut turrets: ResMut<ParticipantMap<Entity>>,
+    mut events: EventReader<RestartEvent>,
+    (mut survivor_counts, mut survivors): (
+        ResMut<PerMatch<SurvivorCount>>,
+        ResMut<ParticipantMap<bool>>,
+    ),
+    mut turrets: ResMut<PerMatch<ParticipantMap<Entity>>>,
     mut stopwatch: ResMut<TurretStopwatch>,
-    colors: Res<ParticipantMap<TileColor>>,
-    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
-    ball_mesh: Res<BulletMesh>,
-    tile_root: Query<(Entity, &Children), With<TileRoot>>,
-    garbage: Query<Entity, Or<(With<Bullet>, With<NewBullet>, With<Turret>)>>,
-    root: Query<Entity, With<BattlefieldRoot>>,
+    mut round: ResMut<RoundNumber>,
+    mut grids: ResMut<PerMatch<TileGrid>>,
+    (colors, ball_colors): (
+        Res<ParticipantMap<TileColor>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    stats: Res<PersistentStats>,
+    assets: Res<GameAssets>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    (config, upgrades, state, map, geometry): (
+        Res<GameConfig>,
+        Res<DraftedUpgrades>,
+        Res<State<AppState>>,
+        Res<GeneratedMap>,
+        Res<BattlefieldGeometry>,
+    ),
+    mut effects: ResMut<Backlog<TileHitEffect>>,
+    tile_roots: Query<(Entity, &Children, &MatchId), With<TileRoot>>,
+    garbage: Query<
+        (Entity, &MatchId),
+        Or<(
+            With<Bullet>,
+            With<NewBullet>,
+            With<Turret>,
+            With<MatchBanner>,
+        )>,
+    >,
+    (roots, match_scoped): (
+        Query<(Entity, &MatchId), With<BattlefieldRoot>>,
+        Query<(Entity, &MatchId), Without<Parent>>,
+    ),
 ) {
-    survivor_count.0 = 4;
-    survivors.a = true;
-    survivors.b = true;
-    survivors.c = true;
-    survivors.d = true;
-    for entity in garbage.iter() {
-        commands.entity(entity).despawn_recursive();
+    effects.clear();
+    // Clear away the matches nobody plays in any more, like the second of the bracket's opening
+    // heats once they've been played side by side.
+    let running = matches.running(&roster);
+    for (entity, match_id) in &match_scoped {
+        if !running.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
     }
-    let (tile_root_entity, tile_root_children) = tile_root.single();
-    for &tile in tile_root_children.iter() {
-        commands.entity(tile).despawn_recursive();
+    survivor_counts.retain(|x| running.contains(&x));
+    grids.retain(|x| running.contains(&x));
+    turrets.retain(|x| running.contains(&x));
+    for event in events.read() {
+        let match_id = event.match_id;
+        let (root, tile_root) = match (
+            roots.iter().find(|(_, &x)| x == match_id),
+            tile_roots.iter().find(|(.., &x)| x == match_id),
+        ) {
+            (Some((root, _)), Some((tile_root, tile_root_children, _))) => {
+                for &tile in tile_root_children.iter() {
+                    commands.entity(tile).despawn_recursive();
+                }
+                (root, tile_root)
+            }
+            // The match wasn't running last round.
+            _ => spawn_battlefield_root(&mut commands, match_id, &geometry),
+        };
+        let roster = matches.roster(&roster, match_id);
+        survivor_counts.insert(match_id, SurvivorCount(roster.count()));
+        for participant in Participant::ALL {
+            if matches[participant] == match_id {
+                survivors[participant] = roster[participant];
+            }
+        }
+        for (entity, &x) in &garbage {
+            if x == match_id {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        grids.insert(
+            match_id,
+            setup_tiles(
+                &mut commands,
+                match_id,
+                tile_root,
+                &colors,
+                &geometry,
+                &config,
+                &upgrades,
+                &assets,
+                &map,
+            ),
+        );
+        turrets.insert(
+            match_id,
+            setup_turrets(
+                &mut commands,
+                match_id,
+                root,
+                &assets,
+                &roster,
+                &stats,
+                &ball_colors,
+                &geometry,
+            ),
+        );
+        commands.entity(root).insert(geometry.shape.wall_collider());
     }
-    setup_tiles(&mut commands, tile_root_entity, &colors);
-    *turrets = setup_turrets(
-        &mut commands,
-        root.single(),
-        ball_mesh.0.clone(),
-        &materials,
-    );
     stopwatch.0.reset();
+    // Leaving the main menu sets the first round up again rather than starting a new one.
+    if *state.get() != AppState::Menu {
+        round.0 += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, hint::black_box, time::Instant};
+
+    use bevy::{ecs::system::RunSystemOnce, tasks::TaskPool};
+
+    use super::*;
+
+    /// A grid with a tile in every cell, each tile's entity numbered after its cell.
+    fn full_grid(shape: TileShape) -> TileGrid {
+        let mut grid = TileGrid::new(shape);
+        for y in 0..grid.rows() {
+            for x in 0..grid.columns(y) {
+                grid.set(x, y, Entity::from_raw((y * TileGrid::SIZE + x) as u32));
+            }
+        }
+        grid
+    }
+    /// Lines that are steep, run along either axis, or cross the grid from corner to corner
+    /// through the points where the cells meet.
+    fn test_lines() -> Vec<(Vec2, Vec2)> {
+        let edge = BATTLEFIELD_HALF_WIDTH - 1.0;
+        vec![
+            (Vec2::new(-3.0, -edge), Vec2::new(5.0, edge)),
+            (Vec2::new(100.0, edge), Vec2::new(97.0, -edge)),
+            (Vec2::new(-edge, 10.0), Vec2::new(edge, 10.0)),
+            (Vec2::new(edge, -50.0), Vec2::new(-edge, -50.0)),
+            (Vec2::new(20.0, -edge), Vec2::new(20.0, edge)),
+            (Vec2::new(-edge, -edge), Vec2::new(edge, edge)),
+            (Vec2::new(edge, -edge), Vec2::new(-edge, edge)),
+            (Vec2::new(-edge, 0.0), Vec2::new(edge, TILE_DIMENSION * 3.0)),
+            (Vec2::new(-10.0, -10.0), Vec2::new(12.5, 11.0)),
+            // Past the edges of the battlefield.
+            (Vec2::new(-1000.0, 3.0), Vec2::new(1000.0, -7.0)),
+        ]
+    }
+    /// Check that every tile the line crosses is returned, with each one next to the last and the
+    /// first and last under the ends of the line, where there's a tile under them.
+    fn assert_no_gaps(shape: TileShape) {
+        let grid = full_grid(shape);
+        let max = Vec2::splat(BATTLEFIELD_HALF_WIDTH - TILE_DIMENSION * 0.01);
+        // The ends of the shifted hexagon rows are half a cell short, so an end of the line can be
+        // in a cell that's off the grid.
+        let cell_tile = |position: Vec2| {
+            let position = position.clamp(-max, max);
+            let (x, y) = grid.cell(position).unwrap();
+            (shape == TileShape::Square || grid.hex_cell(hex_cube(position)).is_some())
+                .then(|| grid.get(x, y))
+        };
+        for (start, end) in test_lines() {
+            let tiles = grid.tiles_on_line(start, end);
+            if let Some(tile) = cell_tile(start) {
+                assert_eq!(tiles.first().unwrap().0, tile, "{start} to {end}");
+            }
+            if let Some(tile) = cell_tile(end) {
+                assert_eq!(tiles.last().unwrap().0, tile, "{start} to {end}");
+            }
+            for pair in tiles.windows(2) {
+                let distance = pair[0].1.distance(pair[1].1);
+                assert!(
+                    distance < TILE_DIMENSION * 1.01,
+                    "{start} to {end} skips from {} to {}",
+                    pair[0].1,
+                    pair[1].1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn squares_on_line_leaves_no_gaps() {
+        assert_no_gaps(TileShape::Square);
+    }
+    #[test]
+    fn hexagons_on_line_leaves_no_gaps() {
+        assert_no_gaps(TileShape::Hexagon);
+    }
+    #[test]
+    fn hexagons_on_line_returns_every_tile_crossed() {
+        const SAMPLES: usize = 10_000;
+        let grid = full_grid(TileShape::Hexagon);
+        let max = Vec2::splat(BATTLEFIELD_HALF_WIDTH - TILE_DIMENSION * 0.01);
+        let inside = |position: Vec2, center: Vec2| {
+            (0..6).all(|i| {
+                let normal = Vec2::from_angle(i as f32 * PI / 3.0);
+                (position - center).dot(normal) < TILE_DIMENSION * 0.5
+            })
+        };
+        for (start, end) in test_lines() {
+            let tiles = grid
+                .tiles_on_line(start, end)
+                .into_iter()
+                .map(|(tile, _)| tile)
+                .collect::<HashSet<_>>();
+            let (start, end) = (start.clamp(-max, max), end.clamp(-max, max));
+            for i in 0..=SAMPLES {
+                let position = start.lerp(end, i as f32 / SAMPLES as f32);
+                let Some((x, y)) = grid.cell(position) else {
+                    continue;
+                };
+                let center = grid.cell_center(x, y);
+                assert!(
+                    !inside(position, center) || tiles.contains(&grid.get(x, y)),
+                    "{start} to {end} misses the tile at {center}"
+                );
+            }
+        }
+    }
+    /// Impacts spread over the whole battlefield, one per bullet of a busy round.
+    fn test_impacts(count: usize) -> Vec<(Entity, Vec2)> {
+        (0..count)
+            .map(|i| {
+                // Stepping by the golden ratio spreads the points out without an RNG.
+                let position = Vec2::new(i as f32 * 0.618_034, i as f32 * 0.754_878).fract_gl();
+                let position = (position * 2.0 - 1.0) * BATTLEFIELD_HALF_WIDTH;
+                (Entity::from_raw(i as u32), position)
+            })
+            .collect()
+    }
+    fn serial_splashes(
+        impacts: &[(Entity, Vec2)],
+        grid: &TileGrid,
+        radius: f32,
+    ) -> HashMap<Entity, Vec<Entity>> {
+        impacts
+            .iter()
+            .map(|&(bullet, center)| (bullet, grid.tiles_in_radius(center, radius)))
+            .collect()
+    }
+
+    #[test]
+    fn tiles_in_splashes_matches_serial() {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+        let grid = full_grid(TileShape::Square);
+        let impacts = test_impacts(100);
+        let radius = TileConversionConfig::default().splash_radius;
+        assert_eq!(
+            tiles_in_splashes(&impacts, &grid, radius, SPLASH_REGION_CHUNK_SIZE),
+            serial_splashes(&impacts, &grid, radius)
+        );
+    }
+    #[test]
+    fn tiles_on_line_stays_bounded() {
+        // The longest lines there are, plus ones far past the battlefield, which get clamped.
+        let lines = [
+            (
+                Vec2::splat(-BATTLEFIELD_HALF_WIDTH),
+                Vec2::splat(BATTLEFIELD_HALF_WIDTH),
+            ),
+            (Vec2::splat(-1e6), Vec2::new(1e6, 1e6 - 1.0)),
+        ];
+        for shape in [TileShape::Square, TileShape::Hexagon] {
+            let grid = full_grid(shape);
+            for (start, end) in lines {
+                assert!(grid.tiles_on_line(start, end).len() <= TileGrid::SIZE * 3);
+            }
+        }
+    }
+    /// Times the walk for a frame where every bullet of a 10k bullet scenario crosses the whole
+    /// battlefield, far more than any of them moves in a frame. Run with
+    /// `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_tiles_on_line() {
+        const LINES_PER_FRAME: usize = 10_000;
+        const FRAMES: usize = 20;
+        for shape in [TileShape::Square, TileShape::Hexagon] {
+            let grid = full_grid(shape);
+            let lines = test_lines();
+            let started = Instant::now();
+            for _ in 0..FRAMES {
+                for i in 0..LINES_PER_FRAME {
+                    let (start, end) = lines[i % lines.len()];
+                    black_box(grid.tiles_on_line(black_box(start), black_box(end)));
+                }
+            }
+            let per_frame = started.elapsed() / FRAMES as u32;
+            println!("{shape:?}: {per_frame:?} per frame of {LINES_PER_FRAME} lines across the battlefield");
+        }
+    }
+    /// Times working out the splash regions of a frame where 10k splash shots hit at once, in
+    /// parallel with a range of chunk sizes against a plain loop. Run with
+    /// `cargo test -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_tiles_in_splashes() {
+        const IMPACTS: usize = 10_000;
+        const FRAMES: u32 = 10;
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let grid = full_grid(TileShape::Square);
+        let impacts = test_impacts(IMPACTS);
+        let radius = TileConversionConfig::default().splash_radius;
+        println!("{} threads, {IMPACTS} impacts", pool.thread_num());
+        let time = |name: &str, run: &dyn Fn() -> HashMap<Entity, Vec<Entity>>| {
+            // The first run pays for the allocations and caches the rest reuse.
+            black_box(run());
+            let started = Instant::now();
+            for _ in 0..FRAMES {
+                black_box(run());
+            }
+            println!("{name}: {:?} per frame", started.elapsed() / FRAMES);
+        };
+        time("serial", &|| {
+            serial_splashes(black_box(&impacts), &grid, radius)
+        });
+        for chunk_size in [1, 4, 16, 64, 256, 1024] {
+            time(&format!("chunks of {chunk_size}"), &|| {
+                tiles_in_splashes(black_box(&impacts), &grid, radius, chunk_size)
+            });
+        }
+    }
+    /// The tile is on the battlefield of a second match, to check the fortification is measured
+    /// from where it is on its own battlefield.
+    #[test]
+    fn converted_tiles_keep_drafted_fortification() {
+        let mut world = World::new();
+        let config = GameConfig::default();
+        let geometry = BattlefieldGeometry::default();
+        let mut upgrades = DraftedUpgrades::default();
+        upgrades.0[Participant::B].push(Upgrade::Fortification);
+        // Out of reach of the regular fortification around B's turret, but within the drafted one.
+        let distance = config.fortification.radius + config.draft.fortification_radius / 2.0;
+        let position = geometry.turret_position(Participant::B) + Vec2::new(0.0, -distance);
+        world.insert_resource(config);
+        world.insert_resource(geometry);
+        world.insert_resource(upgrades);
+        world.init_resource::<ActiveMutators>();
+        world.init_resource::<ParticipantMap<TileColor>>();
+        world.init_resource::<Events<TileConvertedEvent>>();
+        let tile = world
+            .spawn((
+                Tile,
+                Participant::A,
+                Sprite::default(),
+                CollisionGroups::default(),
+                TileDurability {
+                    hits: 1,
+                    fortified: false,
+                },
+                GlobalTransform::from_translation(
+                    (MatchId(1).world_offset() + position).extend(0.0),
+                ),
+                MatchId(1),
+            ))
+            .id();
+        let bullet = world.spawn_empty().id();
+
+        world.run_system_once(
+            move |mut tiles: TileHitQuery,
+                  mut conversions: EventWriter<TileConvertedEvent>,
+                  colors: Res<ParticipantMap<TileColor>>,
+                  (config, upgrades): (Res<GameConfig>, Res<DraftedUpgrades>),
+                  geometry: Res<BattlefieldGeometry>,
+                  mutators: Res<ActiveMutators>| {
+                hit_tile(
+                    tile,
+                    bullet,
+                    Participant::B,
+                    &mut Charge::from_value(10),
+                    &mut tiles,
+                    &mut conversions,
+                    &colors,
+                    &config,
+                    &upgrades,
+                    &geometry,
+                    &mutators,
+                );
+            },
+        );
+        assert_eq!(*world.get::<Participant>(tile).unwrap(), Participant::B);
+        assert!(world.get::<TileDurability>(tile).unwrap().fortified);
+    }
 }
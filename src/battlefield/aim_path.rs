@@ -0,0 +1,108 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use super::{Turret, TurretPlatformLink, BATTLEFIELD_HALF_WIDTH};
+use crate::{
+    collision_groups,
+    config::GameConfig,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const AIM_PATH_KEY: KeyCode = KeyCode::F3;
+const AIM_PATH_DASH_LENGTH: f32 = 8.0;
+const AIM_PATH_GAP_LENGTH: f32 = 6.0;
+const AIM_PATH_ALPHA: f32 = 0.6;
+/// How far the path is moved off a wall after bouncing, so the next ray doesn't hit the same wall.
+const AIM_PATH_WALL_OFFSET: f32 = 0.01;
+
+// }}}
+
+pub struct AimPathPlugin;
+impl Plugin for AimPathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShowAimPath>().add_systems(
+            Update,
+            (
+                toggle_aim_path.run_if(input_just_pressed(AIM_PATH_KEY)),
+                draw_aim_paths.run_if(|show: Res<ShowAimPath>| show.0),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct ShowAimPath(bool);
+
+fn toggle_aim_path(mut show: ResMut<ShowAimPath>) {
+    show.0 = !show.0;
+}
+/// Draw a dotted line along the path each turret's next shot would take, bouncing off the walls of
+/// the battlefield. Other bullets and turrets are ignored, since they'll have moved by the time the
+/// shot gets there.
+fn draw_aim_paths(
+    mut gizmos: Gizmos,
+    rapier: Res<RapierContext>,
+    config: Res<GameConfig>,
+    colors: Res<ParticipantMap<BallColor>>,
+    turrets: Query<(&GlobalTransform, &Participant, &TurretPlatformLink), With<Turret>>,
+    platforms: Query<&GlobalTransform>,
+) {
+    let config = &config.aim_path;
+    let filter = QueryFilter::new().groups(CollisionGroups::new(
+        Group::ALL,
+        collision_groups::BATTLEFIELD_ROOT,
+    ));
+    // Long enough to cross the battlefield diagonally.
+    let max_segment_length = BATTLEFIELD_HALF_WIDTH * 3.0;
+    for (transform, &participant, &TurretPlatformLink(platform)) in &turrets {
+        // The turret itself doesn't rotate, only the platform holding its barrel does.
+        let Ok(platform) = platforms.get(platform) else {
+            continue;
+        };
+        let color = colors[participant].0.with_alpha(AIM_PATH_ALPHA);
+        let mut origin = transform.translation().xy();
+        let mut direction = platform.right().xy();
+        let mut remaining = config.length;
+        for _ in 0..=config.bounces {
+            if remaining <= 0.0 {
+                break;
+            }
+            let hit = rapier.cast_ray_and_get_normal(
+                origin,
+                direction,
+                remaining.min(max_segment_length),
+                true,
+                filter,
+            );
+            let (end, normal) = match hit {
+                Some((_, intersection)) => (intersection.point, Some(intersection.normal)),
+                None => (origin + direction * remaining.min(max_segment_length), None),
+            };
+            draw_dashes(&mut gizmos, origin, end, color);
+            remaining -= origin.distance(end);
+            let Some(normal) = normal else {
+                break;
+            };
+            direction -= 2.0 * direction.dot(normal) * normal;
+            origin = end + normal * AIM_PATH_WALL_OFFSET;
+        }
+    }
+}
+fn draw_dashes(gizmos: &mut Gizmos, start: Vec2, end: Vec2, color: Color) {
+    let length = start.distance(end);
+    let Some(direction) = (end - start).try_normalize() else {
+        return;
+    };
+    let mut distance = 0.0;
+    while distance < length {
+        let dash_end = (distance + AIM_PATH_DASH_LENGTH).min(length);
+        gizmos.line_2d(
+            start + direction * distance,
+            start + direction * dash_end,
+            color,
+        );
+        distance = dash_end + AIM_PATH_GAP_LENGTH;
+    }
+}
@@ -0,0 +1,115 @@
+use std::f32::consts::{PI, SQRT_2};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{BATTLEFIELD_BOUNDARY_HALF_WIDTH, BATTLEFIELD_HALF_WIDTH};
+use crate::config::ArenaShape;
+
+// Constants {{{
+
+/// Half the width of each arm of [`ArenaShape::Plus`], as a fraction of the battlefield's half width.
+const PLUS_ARM_HALF_WIDTH: f32 = 0.45;
+/// The distance from the middle of a hexagon to its edges, as a fraction of the distance to its
+/// corners, `sqrt(3) / 2`.
+const HEXAGON_APOTHEM: f32 = 0.866_025_4;
+/// The number of straight walls standing in for the curve of [`ArenaShape::Circle`].
+const CIRCLE_WALL_SEGMENTS: usize = 64;
+/// How much closer to the middle a position is moved each step while pulling it inside the arena.
+const PULL_INSIDE_STEP: f32 = 0.98;
+
+// }}}
+
+impl ArenaShape {
+    /// Whether `position` is at least `margin` inside the arena.
+    pub fn contains(self, position: Vec2, margin: f32) -> bool {
+        let half_width = BATTLEFIELD_HALF_WIDTH - margin;
+        let position = position.abs();
+        match self {
+            ArenaShape::Square => position.max_element() <= half_width,
+            ArenaShape::Circle => position.length() <= half_width,
+            // The edges are diagonal, so the margin counts for more along each axis.
+            ArenaShape::Diamond => {
+                position.x + position.y <= BATTLEFIELD_HALF_WIDTH - margin * SQRT_2
+            }
+            ArenaShape::Plus => {
+                position.max_element() <= half_width
+                    && position.min_element()
+                        <= BATTLEFIELD_HALF_WIDTH * PLUS_ARM_HALF_WIDTH - margin
+            }
+            // Measured from the middle to the flat edges, which is less than to the corners.
+            ArenaShape::Hexagon => {
+                let apothem = BATTLEFIELD_HALF_WIDTH * HEXAGON_APOTHEM;
+                position.y <= apothem - margin
+                    && position.x * HEXAGON_APOTHEM + position.y * 0.5 <= apothem - margin
+            }
+        }
+    }
+    /// Move `position` straight toward the middle of the arena until it's at least `margin`
+    /// inside. Positions already inside are left where they are.
+    pub fn pull_inside(self, mut position: Vec2, margin: f32) -> Vec2 {
+        while !self.contains(position, margin) && position.length_squared() > 1.0 {
+            position *= PULL_INSIDE_STEP;
+        }
+        position
+    }
+    /// The corners of the arena going counterclockwise.
+    fn outline(self) -> Vec<Vec2> {
+        let h = BATTLEFIELD_HALF_WIDTH;
+        let arm = BATTLEFIELD_HALF_WIDTH * PLUS_ARM_HALF_WIDTH;
+        match self {
+            ArenaShape::Square => vec![
+                Vec2::new(-h, -h),
+                Vec2::new(h, -h),
+                Vec2::new(h, h),
+                Vec2::new(-h, h),
+            ],
+            ArenaShape::Circle => (0..CIRCLE_WALL_SEGMENTS)
+                .map(|i| Vec2::from_angle(i as f32 * 2.0 * PI / CIRCLE_WALL_SEGMENTS as f32) * h)
+                .collect(),
+            ArenaShape::Diamond => vec![
+                Vec2::new(0.0, -h),
+                Vec2::new(h, 0.0),
+                Vec2::new(0.0, h),
+                Vec2::new(-h, 0.0),
+            ],
+            ArenaShape::Plus => vec![
+                Vec2::new(-arm, -h),
+                Vec2::new(arm, -h),
+                Vec2::new(arm, -arm),
+                Vec2::new(h, -arm),
+                Vec2::new(h, arm),
+                Vec2::new(arm, arm),
+                Vec2::new(arm, h),
+                Vec2::new(-arm, h),
+                Vec2::new(-arm, arm),
+                Vec2::new(-h, arm),
+                Vec2::new(-h, -arm),
+                Vec2::new(-arm, -arm),
+            ],
+            ArenaShape::Hexagon => (0..6)
+                .map(|i| Vec2::from_angle(i as f32 * PI / 3.0) * h)
+                .collect(),
+        }
+    }
+    /// A wall along each edge of the outline, on the outside, as thick as the ones around the square
+    /// arena. The walls stop at the corners rather than overlapping, since past an inside corner
+    /// they would reach into the arena.
+    pub fn wall_collider(self) -> Collider {
+        let outline = self.outline();
+        let walls = outline
+            .iter()
+            .zip(outline.iter().cycle().skip(1))
+            .map(|(&start, &end)| {
+                let direction = (end - start).normalize();
+                // Going counterclockwise, the outside is on the right.
+                let outward = Vec2::new(direction.y, -direction.x);
+                let center = (start + end) / 2.0 + outward * BATTLEFIELD_BOUNDARY_HALF_WIDTH;
+                let wall =
+                    Collider::cuboid(start.distance(end) / 2.0, BATTLEFIELD_BOUNDARY_HALF_WIDTH);
+                (center, direction.to_angle(), wall)
+            })
+            .collect();
+        Collider::compound(walls)
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::{neutral_tile::unclaim_tile, RestartEvent, Tile, TileGrid, TileRoot, TileStyle};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    ui::AnnouncementEvent,
+    utils::{GameAssets, MatchId, Participant, PerMatch},
+};
+
+// Constants {{{
+
+const DECAY_ANNOUNCEMENT_COLOR: Color = Color::srgb(1.0, 0.6, 0.3);
+
+// }}}
+
+pub struct BattleRoyalePlugin;
+impl Plugin for BattleRoyalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Decay>().add_systems(
+            Update,
+            (
+                mark_reinforced,
+                decay_territory.run_if(in_state(AppState::Playing)),
+                restart.run_if(on_event::<RestartEvent>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// How long the current round has been going and how many rings have decayed for it.
+#[derive(Resource, Default)]
+struct Decay {
+    stopwatch: Stopwatch,
+    rings: u32,
+}
+/// When, in seconds into the round, a tile last changed hands.
+#[derive(Component)]
+struct Reinforced(f32);
+
+/// Note down when tiles change hands, which keeps them safe from decay for a while.
+fn mark_reinforced(
+    mut commands: Commands,
+    decay: Res<Decay>,
+    config: Res<GameConfig>,
+    tiles: Query<Entity, (With<Tile>, Changed<Participant>)>,
+) {
+    if !config.battle_royale.enabled {
+        return;
+    }
+    let now = decay.stopwatch.elapsed_secs();
+    for entity in &tiles {
+        commands.entity(entity).insert(Reinforced(now));
+    }
+}
+/// Turn the outermost ring of everyone's territory into neutral ground whenever it's due, sparing
+/// the tiles taken within the grace period. A tile is on the outside if it's next to the edge, a gap
+/// or someone else's tile.
+fn decay_territory(
+    mut commands: Commands,
+    mut decay: ResMut<Decay>,
+    mut grids: ResMut<PerMatch<TileGrid>>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    tiles: Query<(&Participant, Option<&Reinforced>), With<Tile>>,
+    tile_roots: Query<(Entity, &MatchId), With<TileRoot>>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    time: Res<Time>,
+) {
+    let config = &config.battle_royale;
+    if !config.enabled {
+        return;
+    }
+    decay.stopwatch.tick(time.delta());
+    let now = decay.stopwatch.elapsed_secs();
+    let rings = (now / config.interval_secs).floor() as u32;
+    if rings <= decay.rings {
+        return;
+    }
+    if decay.rings == 0 {
+        announcements.send(AnnouncementEvent {
+            message: "Territory Is Decaying!".to_string(),
+            color: DECAY_ANNOUNCEMENT_COLOR,
+        });
+    }
+    decay.rings = rings;
+
+    for (tile_root, &match_id) in &tile_roots {
+        let Some(grid) = grids.get_mut(match_id) else {
+            continue;
+        };
+        let owner_at =
+            |(x, y): (usize, usize)| tiles.get(grid.get(x, y)).ok().map(|(&owner, _)| owner);
+        let mut decayed = Vec::new();
+        for y in 0..grid.rows() {
+            for x in 0..grid.columns(y) {
+                let Ok((&owner, reinforced)) = tiles.get(grid.get(x, y)) else {
+                    continue;
+                };
+                if reinforced.is_some_and(|x| now - x.0 < config.grace_secs) {
+                    continue;
+                }
+                if grid
+                    .neighbors(x, y)
+                    .any(|cell| cell.and_then(owner_at) != Some(owner))
+                {
+                    decayed.push((x, y));
+                }
+            }
+        }
+        let style = TileStyle::new(grid.shape, &assets);
+        for (x, y) in decayed {
+            unclaim_tile(&mut commands, match_id, grid, x, y, &style, tile_root);
+        }
+    }
+}
+fn restart(mut decay: ResMut<Decay>) {
+    *decay = Decay::default();
+}
@@ -0,0 +1,195 @@
+use bevy::{color::palettes::css, prelude::*};
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    update_charge_level, BattlefieldGeometry, Bullet, Charge, RestartEvent, BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    utils::{GameAssets, GameRng, MatchId, Matches, Roster},
+};
+
+// Constants {{{
+
+/// Black holes only appear within this distance from the center on each axis, so they never
+/// swallow bullets right out of a turret's barrel.
+const BLACK_HOLE_SPAWN_HALF_WIDTH: f32 = BATTLEFIELD_HALF_WIDTH * 0.6;
+const BLACK_HOLE_FIELD_COLOR: Color = Color::srgba(0.3, 0.0, 0.5, 0.25);
+const BLACK_HOLE_CORE_COLOR: Color = Color::Srgba(css::BLACK);
+
+// Z-index
+const BLACK_HOLE_Z: f32 = 0.5;
+// The core is a child of the field so this is relative to it.
+const BLACK_HOLE_CORE_Z: f32 = 0.1;
+
+// }}}
+
+pub struct BlackHolePlugin;
+impl Plugin for BlackHolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                advance_black_hole_timer.run_if(in_state(AppState::Playing)),
+                pull_bullets
+                    .run_if(in_state(AppState::Playing))
+                    .after(advance_black_hole_timer)
+                    .before(update_charge_level),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Tracks when the next black hole appears, or when the current one disappears.
+#[derive(Resource)]
+struct BlackHoleTimer(Timer);
+impl BlackHoleTimer {
+    fn next_appearance(config: &GameConfig, rng: &mut GameRng) -> Self {
+        let config = &config.black_hole;
+        let secs = if config.min_interval_secs < config.max_interval_secs {
+            rng.gen_range(config.min_interval_secs..config.max_interval_secs)
+        } else {
+            config.min_interval_secs
+        };
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+#[derive(Resource)]
+struct BlackHoleMaterials {
+    field: Handle<ColorMaterial>,
+    core: Handle<ColorMaterial>,
+}
+/// Marker for an active gravity well. Every match gets one at the same spot at the same time.
+#[derive(Component, Clone, Copy)]
+struct BlackHole;
+
+fn setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+) {
+    commands.insert_resource(BlackHoleTimer::next_appearance(&config, &mut rng));
+    commands.insert_resource(BlackHoleMaterials {
+        field: materials.add(BLACK_HOLE_FIELD_COLOR),
+        core: materials.add(BLACK_HOLE_CORE_COLOR),
+    });
+}
+fn advance_black_hole_timer(
+    mut commands: Commands,
+    mut timer: ResMut<BlackHoleTimer>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    assets: Res<GameAssets>,
+    materials: Res<BlackHoleMaterials>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    black_hole: Query<Entity, With<BlackHole>>,
+    mut bullets: Query<&mut ExternalForce, With<Bullet>>,
+) {
+    if !config.black_hole.enabled && black_hole.is_empty() {
+        return;
+    }
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    if !black_hole.is_empty() {
+        for entity in &black_hole {
+            commands.entity(entity).despawn_recursive();
+        }
+        for mut force in &mut bullets {
+            force.force = Vec2::ZERO;
+        }
+        *timer = BlackHoleTimer::next_appearance(&config, &mut rng);
+        return;
+    }
+
+    let x = rng.gen_range(-BLACK_HOLE_SPAWN_HALF_WIDTH..BLACK_HOLE_SPAWN_HALF_WIDTH);
+    let y = rng.gen_range(-BLACK_HOLE_SPAWN_HALF_WIDTH..BLACK_HOLE_SPAWN_HALF_WIDTH);
+    let position = geometry.shape.pull_inside(Vec2::new(x, y), 0.0);
+    let config = &config.black_hole;
+    for match_id in matches.running(&roster) {
+        let position = match_id.world_offset() + position;
+        commands
+            .spawn((
+                Name::new("Black Hole"),
+                BlackHole,
+                match_id,
+                ColorMesh2dBundle {
+                    mesh: assets.unit_circle.clone(),
+                    material: materials.field.clone(),
+                    transform: Transform::from_translation(position.extend(BLACK_HOLE_Z))
+                        .with_scale(Vec3::new(config.radius, config.radius, 1.0)),
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                let core_scale = config.event_horizon / config.radius;
+                parent.spawn((
+                    Name::new("Black Hole Core"),
+                    ColorMesh2dBundle {
+                        mesh: assets.unit_circle.clone(),
+                        material: materials.core.clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, BLACK_HOLE_CORE_Z)
+                            .with_scale(Vec3::new(core_scale, core_scale, 1.0)),
+                        ..default()
+                    },
+                ));
+            });
+    }
+    timer.0 = Timer::from_seconds(config.duration_secs, TimerMode::Once);
+}
+fn pull_bullets(
+    config: Res<GameConfig>,
+    black_holes: Query<(&Transform, &MatchId), With<BlackHole>>,
+    mut bullets: Query<
+        (
+            &GlobalTransform,
+            &ReadMassProperties,
+            &mut ExternalForce,
+            &mut Charge,
+            &MatchId,
+        ),
+        With<Bullet>,
+    >,
+) {
+    if black_holes.is_empty() {
+        return;
+    }
+    let config = &config.black_hole;
+    for (transform, mass_properties, mut force, mut charge, &match_id) in &mut bullets {
+        let Some((black_hole, _)) = black_holes.iter().find(|(_, &x)| x == match_id) else {
+            continue;
+        };
+        let offset = black_hole.translation.xy() - transform.translation().xy();
+        let distance = offset.length();
+        if distance < config.event_horizon {
+            // A bullet with no charge left gets despawned by `update_charge_level`.
+            charge.value = 0;
+        } else if distance < config.radius {
+            // Scale the force with mass so every bullet accelerates at the same rate regardless of
+            // its size, and ramp it up as the bullet gets closer to the center.
+            let falloff = 1.0 - distance / config.radius;
+            force.force = offset / distance * config.strength * falloff * mass_properties.mass;
+        } else if force.force != Vec2::ZERO {
+            force.force = Vec2::ZERO;
+        }
+    }
+}
+fn restart(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut timer: ResMut<BlackHoleTimer>,
+    mut rng: ResMut<GameRng>,
+    black_hole: Query<Entity, With<BlackHole>>,
+) {
+    for entity in &black_hole {
+        commands.entity(entity).despawn_recursive();
+    }
+    *timer = BlackHoleTimer::next_appearance(&config, &mut rng);
+}
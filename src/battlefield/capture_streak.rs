@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{handle_bullet_tile_collision, Bullet, TileConvertedEvent};
+use crate::{app_state::AppState, config::GameConfig};
+
+pub struct CaptureStreakPlugin;
+impl Plugin for CaptureStreakPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                track_streaks.after(handle_bullet_tile_collision),
+                burn_out.after(track_streaks),
+            )
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// The tiles a bullet has captured in a row, without going more than
+/// [`CaptureStreakConfig::gap_secs`](crate::config::CaptureStreakConfig) between any two.
+#[derive(Component, Default)]
+struct CaptureStreak {
+    count: u32,
+    since_last_capture: f32,
+}
+/// Marker for a bullet on a capture streak. It moves faster and throws brighter sparks until it
+/// goes too long without capturing a tile.
+#[derive(Component)]
+pub struct OnFire {
+    timer: Timer,
+    /// The speed boost the bullet got when it caught fire, to take back off when it burns out.
+    speed_boost: f32,
+}
+
+/// Count the tiles each bullet captures in a row, setting it on fire once the streak is long
+/// enough, and keeping it burning for as long as the streak goes on.
+fn track_streaks(
+    mut commands: Commands,
+    mut conversions: EventReader<TileConvertedEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut bullets: Query<
+        (
+            Entity,
+            Option<&mut CaptureStreak>,
+            Option<&mut OnFire>,
+            &mut Velocity,
+        ),
+        With<Bullet>,
+    >,
+) {
+    let config = &config.capture_streak;
+    if !config.enabled {
+        conversions.clear();
+        return;
+    }
+    for (entity, streak, ..) in &mut bullets {
+        match streak {
+            Some(mut streak) => {
+                streak.since_last_capture += time.delta_seconds();
+                if streak.since_last_capture > config.gap_secs {
+                    streak.count = 0;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(CaptureStreak::default());
+            }
+        }
+    }
+    for event in conversions.read() {
+        let Ok((entity, Some(mut streak), on_fire, mut velocity)) = bullets.get_mut(event.bullet)
+        else {
+            continue;
+        };
+        streak.count += 1;
+        streak.since_last_capture = 0.0;
+        if streak.count < config.threshold {
+            continue;
+        }
+        let duration = std::time::Duration::from_secs_f32(config.duration_secs.max(0.0));
+        match on_fire {
+            Some(mut on_fire) => {
+                on_fire.timer.set_duration(duration);
+                on_fire.timer.reset();
+            }
+            None => {
+                velocity.linvel *= config.speed_boost;
+                commands.entity(entity).insert(OnFire {
+                    timer: Timer::new(duration, TimerMode::Once),
+                    speed_boost: config.speed_boost,
+                });
+            }
+        }
+    }
+}
+/// Put out the bullets that have gone too long without a capture, bringing them back to speed.
+fn burn_out(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bullets: Query<(Entity, &mut OnFire, &mut CaptureStreak, &mut Velocity)>,
+) {
+    for (entity, mut on_fire, mut streak, mut velocity) in &mut bullets {
+        if !on_fire.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        velocity.linvel /= on_fire.speed_boost;
+        streak.count = 0;
+        commands.entity(entity).remove::<OnFire>();
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use super::{
+    handle_elimination, spawn_turret, BattlefieldGeometry, BattlefieldRoot, Charge,
+    EliminationEvent, RestartEvent, Turret,
+};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    stats::PersistentStats,
+    ui::AnnouncementEvent,
+    utils::{BallColor, DisplayName, GameAssets, MatchId, Participant, ParticipantMap, PerMatch},
+};
+
+pub struct ComebackPlugin;
+impl Plugin for ComebackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingComebacks>().add_systems(
+            Update,
+            (
+                queue_comebacks
+                    .after(handle_elimination)
+                    .run_if(on_event::<EliminationEvent>()),
+                respawn_turrets
+                    .after(queue_comebacks)
+                    .run_if(in_state(AppState::Playing)),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The participants waiting to get their turrets back, along with their match and how long they
+/// have left to wait.
+#[derive(Resource, Default)]
+struct PendingComebacks(Vec<(Participant, MatchId, Timer)>);
+
+fn queue_comebacks(
+    mut pending: ResMut<PendingComebacks>,
+    mut events: EventReader<EliminationEvent>,
+    config: Res<GameConfig>,
+) {
+    if !config.comeback.enabled {
+        return;
+    }
+    for event in events.read() {
+        let timer = Timer::from_seconds(config.comeback.delay_secs.max(0.0), TimerMode::Once);
+        pending.0.push((event.participant, event.match_id, timer));
+    }
+}
+/// Bring back the turrets that have waited long enough, each starting with a share of the charge of
+/// the leading turret in its match so that it has a chance to catch up.
+fn respawn_turrets(
+    mut commands: Commands,
+    mut pending: ResMut<PendingComebacks>,
+    mut turret_entities: ResMut<PerMatch<ParticipantMap<Entity>>>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    assets: Res<GameAssets>,
+    stats: Res<PersistentStats>,
+    (names, ball_colors): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    turrets: Query<(&Charge, &MatchId), With<Turret>>,
+    roots: Query<(Entity, &MatchId), With<BattlefieldRoot>>,
+) {
+    let mut leading_charges = PerMatch::default();
+    for (charge, &match_id) in &turrets {
+        let leading = leading_charges.get(match_id).copied().unwrap_or(0);
+        leading_charges.insert(match_id, charge.value().max(leading));
+    }
+    let mut i = 0;
+    while i < pending.0.len() {
+        if !pending.0[i].2.tick(time.delta()).finished() {
+            i += 1;
+            continue;
+        }
+        let (participant, match_id, _) = pending.0.swap_remove(i);
+        let Some((root, _)) = roots.iter().find(|(_, &x)| x == match_id) else {
+            continue;
+        };
+        let leading_charge = leading_charges.get(match_id).copied().unwrap_or(0);
+        let fraction = config.comeback.charge_fraction.clamp(0.0, 1.0);
+        let value = ((leading_charge as f64 * fraction) as u64).max(1);
+        let turret = spawn_turret(
+            &mut commands,
+            match_id,
+            root,
+            &assets,
+            participant,
+            &stats,
+            &ball_colors,
+            geometry.turret_position(participant),
+        );
+        commands.entity(turret).insert(Charge::from_value(value));
+        if let Some(entities) = turret_entities.get_mut(match_id) {
+            entities.set(participant, turret);
+        }
+        leading_charges.insert(match_id, leading_charge.max(value));
+        announcements.send(AnnouncementEvent {
+            message: format!("{} Is Back", names[participant]),
+            color: ball_colors[participant].0,
+        });
+    }
+}
+fn restart(mut pending: ResMut<PendingComebacks>, mut events: EventReader<RestartEvent>) {
+    for event in events.read() {
+        pending.0.retain(|&(_, x, _)| x != event.match_id);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    handle_elimination, ChargeBallLink, EliminationEvent, RestartEvent, Turret, TurretBarrel,
+    TurretPlatformLink, TURRET_HEAD_LENGTH, TURRET_HEAD_THICNESS,
+};
+use crate::{
+    collision_groups,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// The number of chunks a turret breaks into, on top of its barrel.
+const DEBRIS_CHUNKS_PER_TURRET: usize = 6;
+/// The most chunks around at once. The oldest are taken back for new eliminations past this.
+const MAX_DEBRIS: usize = 48;
+/// Time in seconds a chunk stays around, fading out over the last [`DEBRIS_FADE_SECS`].
+const DEBRIS_LIFETIME_SECS: f32 = 3.0;
+const DEBRIS_FADE_SECS: f32 = 1.0;
+/// The range of speeds the chunks fly off at.
+const DEBRIS_MIN_SPEED: f32 = 150.0;
+const DEBRIS_MAX_SPEED: f32 = 400.0;
+/// The fastest the chunks spin, in radians per second.
+const DEBRIS_MAX_SPIN: f32 = 12.0;
+/// Slows the chunks down, so they come to rest before fading out.
+const DEBRIS_DAMPING: f32 = 1.5;
+/// The size of a chunk relative to the turret's radius, kept within the bounds below.
+const DEBRIS_SIZE_FACTOR: f32 = 0.6;
+const DEBRIS_MIN_SIZE: f32 = 4.0;
+const DEBRIS_MAX_SIZE: f32 = 24.0;
+/// Above the tiles, below the bullets.
+const DEBRIS_Z: f32 = 1.0;
+
+// }}}
+
+pub struct DebrisPlugin;
+impl Plugin for DebrisPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebrisPool>().add_systems(
+            Update,
+            (
+                break_turrets
+                    .before(handle_elimination)
+                    .run_if(on_event::<EliminationEvent>()),
+                fade_debris,
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The chunks of the turrets that have been eliminated, reused instead of being spawned for every
+/// elimination.
+#[derive(Resource, Default)]
+struct DebrisPool {
+    /// The chunks flying around, oldest first.
+    active: VecDeque<Entity>,
+    /// The chunks put away, hidden and out of the physics, waiting to be reused.
+    idle: Vec<Entity>,
+}
+impl DebrisPool {
+    /// Find a chunk to use, putting away the oldest one if there are already too many around.
+    fn take(&mut self, commands: &mut Commands) -> Entity {
+        let entity = match self.idle.pop() {
+            Some(entity) => entity,
+            None if self.active.len() < MAX_DEBRIS => commands
+                .spawn((
+                    Name::new("Debris"),
+                    RigidBody::Dynamic,
+                    GravityScale(0.0),
+                    Damping {
+                        linear_damping: DEBRIS_DAMPING,
+                        angular_damping: DEBRIS_DAMPING,
+                    },
+                    Restitution {
+                        coefficient: 0.6,
+                        combine_rule: CoefficientCombineRule::Min,
+                    },
+                    CollisionGroups::new(
+                        collision_groups::DEBRIS,
+                        collision_groups::BATTLEFIELD_ROOT | collision_groups::DEBRIS,
+                    ),
+                    SpriteBundle::default(),
+                ))
+                .id(),
+            None => self.active.pop_front().expect("MAX_DEBRIS is more than 0"),
+        };
+        self.active.push_back(entity);
+        entity
+    }
+}
+/// A piece of a turret, fading out as it comes to the end of its lifetime.
+#[derive(Component)]
+struct Debris {
+    age: f32,
+    color: Color,
+}
+
+/// Break each eliminated turret into chunks in its participant's color, plus its barrel, sending
+/// them flying out from where the turret stood.
+fn break_turrets(
+    mut commands: Commands,
+    mut pool: ResMut<DebrisPool>,
+    mut events: EventReader<EliminationEvent>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    turrets: Query<
+        (
+            &Participant,
+            &GlobalTransform,
+            &ChargeBallLink,
+            &TurretPlatformLink,
+        ),
+        With<Turret>,
+    >,
+    transforms: Query<&Transform>,
+    children: Query<&Children>,
+    barrels: Query<&Sprite, With<TurretBarrel>>,
+) {
+    for event in events.read() {
+        let Some((_, transform, ball, platform)) =
+            turrets.iter().find(|(&x, ..)| x == event.participant)
+        else {
+            continue;
+        };
+        let center = transform.translation().xy();
+        let radius = transforms.get(ball.0).map_or(1.0, |x| x.scale.x);
+        let size = (radius * DEBRIS_SIZE_FACTOR).clamp(DEBRIS_MIN_SIZE, DEBRIS_MAX_SIZE);
+        let color = ball_colors[event.participant].0;
+        let mut chunks = vec![(Vec2::splat(size), color); DEBRIS_CHUNKS_PER_TURRET];
+        let barrel_color = children
+            .iter_descendants(platform.0)
+            .find_map(|x| barrels.get(x).ok())
+            .map(|x| x.color);
+        if let Some(barrel_color) = barrel_color {
+            chunks.push((
+                Vec2::new(TURRET_HEAD_LENGTH / 2.0, TURRET_HEAD_THICNESS),
+                barrel_color,
+            ));
+        }
+        let count = chunks.len();
+        for (i, (chunk_size, color)) in chunks.into_iter().enumerate() {
+            // Spread the chunks evenly around the turret, varying their speed and spin by how far
+            // around they are, which looks random enough without touching the game's RNG.
+            let variation = (i as f32 * 0.618_034).fract();
+            let direction = Vec2::from_angle(TAU * (i as f32 + variation) / count as f32);
+            let speed = DEBRIS_MIN_SPEED + (DEBRIS_MAX_SPEED - DEBRIS_MIN_SPEED) * variation;
+            let spin = DEBRIS_MAX_SPIN * (variation * 2.0 - 1.0);
+            let entity = pool.take(&mut commands);
+            commands
+                .entity(entity)
+                .insert((
+                    Debris { age: 0.0, color },
+                    Collider::cuboid(chunk_size.x / 2.0, chunk_size.y / 2.0),
+                    Velocity {
+                        linvel: direction * speed,
+                        angvel: spin,
+                    },
+                    Transform::from_translation(
+                        (center + direction * radius / 2.0).extend(DEBRIS_Z),
+                    )
+                    .with_rotation(Quat::from_rotation_z(direction.to_angle())),
+                    Sprite {
+                        color,
+                        custom_size: Some(chunk_size),
+                        ..default()
+                    },
+                    Visibility::Inherited,
+                ))
+                .remove::<(RigidBodyDisabled, ColliderDisabled)>();
+        }
+    }
+}
+/// Age the chunks, fading them out toward the end and putting them away once they're gone.
+fn fade_debris(
+    mut commands: Commands,
+    mut pool: ResMut<DebrisPool>,
+    mut debris: Query<(&mut Debris, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    let mut expired = false;
+    for (mut chunk, mut sprite) in &mut debris {
+        chunk.age += time.delta_seconds();
+        let remaining = DEBRIS_LIFETIME_SECS - chunk.age;
+        if remaining <= 0.0 {
+            expired = true;
+        } else if remaining < DEBRIS_FADE_SECS {
+            sprite.color = chunk.color.with_alpha(remaining / DEBRIS_FADE_SECS);
+        }
+    }
+    if !expired {
+        return;
+    }
+    // Chunks that were just sent flying don't have their `Debris` yet, since inserting it is
+    // deferred, so only the ones that have one can have run out their lifetime.
+    let (expired, active) = pool.active.drain(..).partition::<Vec<_>, _>(|&x| {
+        debris
+            .get(x)
+            .is_ok_and(|(x, _)| x.age >= DEBRIS_LIFETIME_SECS)
+    });
+    pool.active = active.into();
+    for entity in expired {
+        put_away(&mut commands, entity);
+        pool.idle.push(entity);
+    }
+}
+fn restart(mut commands: Commands, mut pool: ResMut<DebrisPool>) {
+    let pool = &mut *pool;
+    for entity in pool.active.drain(..) {
+        put_away(&mut commands, entity);
+        pool.idle.push(entity);
+    }
+}
+/// Hide a chunk and take it out of the physics until it's needed again.
+fn put_away(commands: &mut Commands, entity: Entity) {
+    commands
+        .entity(entity)
+        .insert((Visibility::Hidden, RigidBodyDisabled, ColliderDisabled))
+        .remove::<Debris>();
+}
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use super::{EliminationEvent, RestartEvent, ShotFiredEvent, Tile, Turret};
+use crate::{
+    app_state::AppState,
+    config::{EmoteTrigger, GameConfig},
+    stats::{RoundStats, StatsAggregator},
+    utils::{GameAssets, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Time in seconds between checks of how much territory each participant has left, which means
+/// counting every tile.
+const TERRITORY_CHECK_SECS: f32 = 1.0;
+/// How far above the middle of the turret the bubble floats.
+const EMOTE_OFFSET: f32 = 36.0;
+/// Above the turret and the charge written on it.
+const EMOTE_Z: f32 = 4.0;
+const EMOTE_FONT_SIZE: f32 = 16.0;
+const EMOTE_TEXT_COLOR: Color = Color::BLACK;
+const EMOTE_BUBBLE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.9);
+/// Room around the text inside the bubble.
+const EMOTE_PADDING: Vec2 = Vec2::new(8.0, 4.0);
+/// Roughly how wide each character of the emote is, to size the bubble.
+const EMOTE_CHAR_WIDTH: f32 = EMOTE_FONT_SIZE * 0.6;
+
+// }}}
+
+pub struct EmotesPlugin;
+impl Plugin for EmotesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EmoteCooldowns>()
+            .init_resource::<Leader>()
+            .add_event::<EmoteEvent>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        emote_on_events,
+                        emote_on_low_territory
+                            .run_if(on_timer(Duration::from_secs_f32(TERRITORY_CHECK_SECS))),
+                    )
+                        .after(StatsAggregator)
+                        .run_if(in_state(AppState::Playing)),
+                    show_emotes.after(emote_on_events),
+                    expire_emotes,
+                )
+                    .run_if(|config: Res<GameConfig>| config.emotes.enabled),
+            )
+            .add_systems(Update, restart.run_if(on_event::<RestartEvent>()));
+    }
+}
+
+/// Sent when `participant`'s turret has reason to show the emote at `entry` in
+/// [`crate::config::EmoteConfig::table`], whether or not it's cooled down.
+#[derive(Event)]
+struct EmoteEvent {
+    participant: Participant,
+    entry: usize,
+}
+/// Time since the round started when each entry of the emote table can be shown again by each
+/// participant.
+#[derive(Resource, Default)]
+struct EmoteCooldowns(Vec<ParticipantMap<f32>>);
+/// Whoever held the most charge as of the last check, to tell when someone takes the lead.
+#[derive(Resource, Default)]
+struct Leader(Option<Participant>);
+#[derive(Component)]
+struct EmoteBubble(Timer);
+
+/// React to shots, eliminations and the lead changing hands, as counted by the stats aggregator.
+fn emote_on_events(
+    config: Res<GameConfig>,
+    stats: Res<RoundStats>,
+    mut leader: ResMut<Leader>,
+    mut shots: EventReader<ShotFiredEvent>,
+    mut eliminations: EventReader<EliminationEvent>,
+    mut emotes: EventWriter<EmoteEvent>,
+    turrets: Query<&Participant, With<Turret>>,
+) {
+    let new_leader = stats.leading_charge().map(|(participant, _)| participant);
+    let took_the_lead = new_leader.filter(|_| new_leader != leader.0);
+    leader.0 = new_leader;
+    let shots = shots.read().collect::<Vec<_>>();
+    let eliminated = eliminations
+        .read()
+        .map(|x| x.participant)
+        .collect::<Vec<_>>();
+    for (entry, row) in config.emotes.table.iter().enumerate() {
+        let mut emote = |participant| emotes.send(EmoteEvent { participant, entry });
+        match row.trigger {
+            EmoteTrigger::BigShot(charge) => {
+                for shot in shots.iter().filter(|x| x.charge >= charge) {
+                    emote(shot.participant);
+                }
+            }
+            EmoteTrigger::TookTheLead => {
+                if let Some(participant) = took_the_lead {
+                    emote(participant);
+                }
+            }
+            EmoteTrigger::OpponentEliminated if !eliminated.is_empty() => {
+                for &participant in &turrets {
+                    if !eliminated.contains(&participant) {
+                        emote(participant);
+                    }
+                }
+            }
+            EmoteTrigger::OpponentEliminated | EmoteTrigger::LowTerritory(_) => {}
+        }
+    }
+}
+fn emote_on_low_territory(
+    config: Res<GameConfig>,
+    mut emotes: EventWriter<EmoteEvent>,
+    tiles: Query<&Participant, With<Tile>>,
+    turrets: Query<&Participant, With<Turret>>,
+) {
+    let mut counts = ParticipantMap::splat(0);
+    for &owner in &tiles {
+        counts[owner] += 1;
+    }
+    let total = Participant::ALL
+        .into_iter()
+        .map(|x| counts[x])
+        .sum::<usize>();
+    for (entry, row) in config.emotes.table.iter().enumerate() {
+        let EmoteTrigger::LowTerritory(fraction) = row.trigger else {
+            continue;
+        };
+        for &participant in &turrets {
+            if (counts[participant] as f32) < total as f32 * fraction {
+                emotes.send(EmoteEvent { participant, entry });
+            }
+        }
+    }
+}
+/// Pop up a bubble over the turret for each emote that's cooled down, replacing the one it's
+/// already showing. Only the first emote for each turret in a frame is shown.
+fn show_emotes(
+    mut commands: Commands,
+    mut events: EventReader<EmoteEvent>,
+    mut cooldowns: ResMut<EmoteCooldowns>,
+    config: Res<GameConfig>,
+    stats: Res<RoundStats>,
+    assets: Res<GameAssets>,
+    turrets: Query<(Entity, &Participant, Option<&Children>), With<Turret>>,
+    bubbles: Query<(), With<EmoteBubble>>,
+) {
+    let table = &config.emotes.table;
+    cooldowns.0.resize(table.len(), ParticipantMap::splat(0.0));
+    let now = stats.elapsed_secs();
+    let mut shown = ParticipantMap::splat(false);
+    for event in events.read() {
+        let Some(row) = table.get(event.entry) else {
+            continue;
+        };
+        if shown[event.participant] {
+            continue;
+        }
+        let ready_at = &mut cooldowns.0[event.entry][event.participant];
+        if now < *ready_at {
+            continue;
+        }
+        let Some((turret, _, children)) = turrets
+            .iter()
+            .find(|&(_, &participant, _)| participant == event.participant)
+        else {
+            continue;
+        };
+        *ready_at = now + row.cooldown_secs;
+        shown[event.participant] = true;
+        for &child in children.into_iter().flatten() {
+            if bubbles.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+        let size = Vec2::new(
+            row.emote.chars().count() as f32 * EMOTE_CHAR_WIDTH,
+            EMOTE_FONT_SIZE,
+        ) + EMOTE_PADDING * 2.0;
+        commands
+            .spawn((
+                EmoteBubble(Timer::from_seconds(
+                    config.emotes.duration_secs,
+                    TimerMode::Once,
+                )),
+                Name::new("Emote Bubble"),
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, EMOTE_OFFSET, EMOTE_Z),
+                    sprite: Sprite {
+                        color: EMOTE_BUBBLE_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        row.emote.clone(),
+                        TextStyle {
+                            font: assets.font.clone(),
+                            font_size: EMOTE_FONT_SIZE,
+                            color: EMOTE_TEXT_COLOR,
+                        },
+                    ),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                    ..default()
+                });
+            })
+            .set_parent(turret);
+    }
+}
+fn expire_emotes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bubbles: Query<(Entity, &mut EmoteBubble)>,
+) {
+    for (entity, mut bubble) in &mut bubbles {
+        if bubble.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+/// The bubbles go along with the turrets, so only the cooldowns need resetting.
+fn restart(mut cooldowns: ResMut<EmoteCooldowns>, mut leader: ResMut<Leader>) {
+    cooldowns.0.clear();
+    leader.0 = None;
+}
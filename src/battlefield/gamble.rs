@@ -0,0 +1,171 @@
+use std::f32::consts::TAU;
+
+use bevy::{color::palettes::css, prelude::*};
+
+use super::{BattlefieldGeometry, RestartEvent};
+use crate::{
+    config::GameConfig,
+    utils::{GameAssets, Matches},
+    Participant,
+};
+
+// Constants {{{
+
+/// How far the coin is from the turret, towards the center of the battlefield.
+const COIN_OFFSET: f32 = 60.0;
+const COIN_RADIUS: f32 = 20.0;
+const COIN_COLOR: Color = Color::Srgba(css::GOLD);
+const COIN_WIN_COLOR: Color = Color::Srgba(css::LIMEGREEN);
+const COIN_LOSS_COLOR: Color = Color::Srgba(css::CRIMSON);
+const COIN_TEXT_COLOR: Color = Color::BLACK;
+const COIN_TEXT_FONT_SIZE: f32 = 20.0;
+/// Time in seconds the coin spends spinning before revealing the result.
+const COIN_FLIP_SECS: f32 = 1.0;
+/// How many times the coin spins around while it's being flipped.
+const COIN_FLIP_TURNS: f32 = 4.0;
+/// Time in seconds the result stays on screen after the coin lands.
+const COIN_RESULT_SECS: f32 = 1.0;
+
+// Z-index
+const COIN_Z: f32 = 5.0;
+// The text is a child of the coin so this is relative to it.
+const COIN_TEXT_Z: f32 = 0.1;
+
+// }}}
+
+pub struct GamblePlugin;
+impl Plugin for GamblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GambleEvent>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    spawn_coin.run_if(on_event::<GambleEvent>()),
+                    flip_coin,
+                    restart.run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+/// Event sent when a turret's charge is gambled, so the outcome can be shown over the turret.
+#[derive(Event)]
+pub struct GambleEvent {
+    pub participant: Participant,
+    pub won: bool,
+}
+#[derive(Resource)]
+struct CoinMaterials {
+    spinning: Handle<ColorMaterial>,
+    won: Handle<ColorMaterial>,
+    lost: Handle<ColorMaterial>,
+}
+/// Component for the coin displayed over a turret after a gamble.
+#[derive(Component)]
+struct Coin {
+    timer: Timer,
+    won: bool,
+    /// Whether the result has been revealed yet.
+    landed: bool,
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    commands.insert_resource(CoinMaterials {
+        spinning: materials.add(COIN_COLOR),
+        won: materials.add(COIN_WIN_COLOR),
+        lost: materials.add(COIN_LOSS_COLOR),
+    });
+}
+fn spawn_coin(
+    mut commands: Commands,
+    mut events: EventReader<GambleEvent>,
+    assets: Res<GameAssets>,
+    materials: Res<CoinMaterials>,
+    geometry: Res<BattlefieldGeometry>,
+    matches: Res<Matches>,
+) {
+    for event in events.read() {
+        let turret = geometry.turret_position(event.participant);
+        let position =
+            matches[event.participant].world_offset() + turret - turret.signum() * COIN_OFFSET;
+        commands.spawn((
+            Name::new("Gamble Coin"),
+            Coin {
+                timer: Timer::from_seconds(COIN_FLIP_SECS, TimerMode::Once),
+                won: event.won,
+                landed: false,
+            },
+            ColorMesh2dBundle {
+                mesh: assets.unit_circle.clone(),
+                material: materials.spinning.clone(),
+                transform: Transform::from_translation(position.extend(COIN_Z))
+                    .with_scale(Vec3::new(COIN_RADIUS, COIN_RADIUS, 1.0)),
+                ..default()
+            },
+        ));
+    }
+}
+/// Spin the coin by squashing it horizontally, then reveal the result once it lands.
+fn flip_coin(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    materials: Res<CoinMaterials>,
+    mut coins: Query<(
+        Entity,
+        &mut Coin,
+        &mut Transform,
+        &mut Handle<ColorMaterial>,
+    )>,
+) {
+    for (entity, mut coin, mut transform, mut material) in &mut coins {
+        coin.timer.tick(time.delta());
+        if coin.landed {
+            if coin.timer.finished() {
+                commands.entity(entity).despawn_recursive();
+            }
+            continue;
+        }
+        if !coin.timer.finished() {
+            let turns = coin.timer.fraction() * COIN_FLIP_TURNS;
+            transform.scale.x = COIN_RADIUS * (turns * TAU).cos();
+            continue;
+        }
+
+        coin.landed = true;
+        coin.timer = Timer::from_seconds(COIN_RESULT_SECS, TimerMode::Once);
+        transform.scale.x = COIN_RADIUS;
+        let (result_material, result) = if coin.won {
+            (&materials.won, format!("x{}", config.gamble.win_factor))
+        } else {
+            (&materials.lost, format!("/{}", config.gamble.loss_divisor))
+        };
+        *material = result_material.clone();
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(
+                    result,
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: COIN_TEXT_FONT_SIZE,
+                        color: COIN_TEXT_COLOR,
+                    },
+                ),
+                // Undo the coin's scale so the text isn't stretched.
+                transform: Transform::from_xyz(0.0, 0.0, COIN_TEXT_Z).with_scale(Vec3::new(
+                    1.0 / COIN_RADIUS,
+                    1.0 / COIN_RADIUS,
+                    1.0,
+                )),
+                ..default()
+            });
+        });
+    }
+}
+fn restart(mut commands: Commands, coins: Query<Entity, With<Coin>>) {
+    for entity in &coins {
+        commands.entity(entity).despawn_recursive();
+    }
+}
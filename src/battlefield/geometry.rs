@@ -0,0 +1,179 @@
+//! Where the turrets sit and what shape the arena takes, worked out from who's taking part.
+//!
+//! Four participants sit in the corners of the arena picked in the game config. Unless
+//! `arena_layout` is set to keep it fixed, any other number gets a battlefield made for it instead:
+//! two split it down the middle, six play on a hexagon with a turret in each corner, and the rest
+//! sit evenly spaced in a ring on a circle. Each tile starts out owned by the closest turret, so the
+//! territory is shared out evenly either way. Custom maps keep the arena and the usual turret spots
+//! from the config, apart from the turrets they place themselves. With more than one match running,
+//! each is seated on its own battlefield as if it were the only one, and they all take the outline of
+//! the first.
+
+use std::f32::consts::{FRAC_PI_2, PI, SQRT_2};
+
+use bevy::prelude::*;
+
+use super::{RestartEvent, BATTLEFIELD_HALF_WIDTH, TURRET_POSITION};
+use crate::{
+    config::{ArenaLayout, ArenaShape, GameConfig},
+    utils::{MatchId, Matches, Participant, ParticipantMap, Roster},
+};
+
+// Constants {{{
+
+/// How far in from the edge of the arena the turrets sit.
+const TURRET_MARGIN: f32 = BATTLEFIELD_HALF_WIDTH - TURRET_POSITION;
+
+// }}}
+
+pub struct GeometryPlugin;
+impl Plugin for GeometryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BattlefieldGeometry>()
+            .add_systems(Startup, update_geometry.before(super::setup))
+            .add_systems(
+                Update,
+                update_geometry
+                    .before(super::restart)
+                    .run_if(on_event::<RestartEvent>()),
+            );
+    }
+}
+
+/// The layout of the battlefield for the current round.
+#[derive(Resource, Debug, Clone)]
+pub struct BattlefieldGeometry {
+    /// The outline of the battlefield, which may not be the one in the config.
+    pub shape: ArenaShape,
+    turrets: ParticipantMap<Vec2>,
+    roster: Roster,
+    matches: Matches,
+}
+impl Default for BattlefieldGeometry {
+    fn default() -> Self {
+        Self::new(&Roster::default(), &GameConfig::default())
+    }
+}
+impl BattlefieldGeometry {
+    pub fn new(roster: &Roster, config: &GameConfig) -> Self {
+        let seated = Participant::ALL
+            .into_iter()
+            .filter(|&x| roster[x])
+            .collect::<Vec<_>>();
+        let fitted =
+            config.arena_layout == ArenaLayout::FitParticipants && config.map_file.is_none();
+        let shape = match seated.len() {
+            _ if !fitted => config.arena_shape,
+            2 | 4 => config.arena_shape,
+            6 => ArenaShape::Hexagon,
+            _ => ArenaShape::Circle,
+        };
+        let mut turrets = ParticipantMap::from_fn(|x| fixed_turret_position(x, shape));
+        if fitted && seated.len() != 4 {
+            let count = seated.len() as f32;
+            for (i, &participant) in seated.iter().enumerate() {
+                // Going around counterclockwise from the top right, so two sit left and right and
+                // six sit in the corners of the hexagon.
+                let angle = FRAC_PI_2 - PI / count + i as f32 * 2.0 * PI / count;
+                let position = Vec2::from_angle(angle) * BATTLEFIELD_HALF_WIDTH * SQRT_2;
+                turrets[participant] = shape.pull_inside(position, TURRET_MARGIN);
+            }
+        } else if fitted {
+            // The seats of the first four, in the corners, go to whoever's playing.
+            for (&participant, seat) in seated.iter().zip(Participant::ALL) {
+                turrets[participant] = fixed_turret_position(seat, shape);
+            }
+        }
+        if let Some(map) = &config.map_file {
+            for participant in Participant::ALL {
+                if let Some(position) = map.turret_position(participant) {
+                    turrets[participant] = position;
+                }
+            }
+        }
+        Self {
+            shape,
+            turrets,
+            roster: *roster,
+            matches: Matches::default(),
+        }
+    }
+    /// The layout with the participants in each of `matches` seated apart from the others.
+    pub fn for_matches(roster: &Roster, matches: &Matches, config: &GameConfig) -> Self {
+        let mut layouts = matches
+            .running(roster)
+            .into_iter()
+            .map(|x| Self::new(&matches.roster(roster, x), config));
+        let Some(mut geometry) = layouts.next() else {
+            return Self::new(roster, config);
+        };
+        for layout in layouts {
+            for participant in Participant::ALL.into_iter().filter(|&x| layout.roster[x]) {
+                geometry.turrets[participant] = geometry
+                    .shape
+                    .pull_inside(layout.turrets[participant], TURRET_MARGIN);
+            }
+        }
+        geometry.roster = *roster;
+        geometry.matches = *matches;
+        geometry
+    }
+    /// The position of `participant`'s turret, whether or not they're playing.
+    pub fn turret_position(&self, participant: Participant) -> Vec2 {
+        self.turrets[participant]
+    }
+    /// Where `participant`'s turret is in the world, on the battlefield of their match.
+    pub fn turret_world_position(&self, participant: Participant, matches: &Matches) -> Vec2 {
+        matches[participant].world_offset() + self.turret_position(participant)
+    }
+    /// Where the turrets of everyone playing are.
+    pub fn turret_positions(&self) -> impl Iterator<Item = Vec2> + '_ {
+        Participant::ALL
+            .into_iter()
+            .filter(|&x| self.roster[x])
+            .map(|x| self.turret_position(x))
+    }
+    /// Push every turret in until it's clear of the outer `margin` of the arena, for when the edge
+    /// closes in.
+    pub fn pull_turrets_inside(&mut self, margin: f32) {
+        for participant in Participant::ALL {
+            let position = self.turrets[participant];
+            self.turrets[participant] = self.shape.pull_inside(position, margin + TURRET_MARGIN);
+        }
+    }
+    /// Whoever starts out owning the tile at `position` on the battlefield of `match_id`, which is
+    /// whoever's turret in the match is closest.
+    pub fn territory_owner(&self, match_id: MatchId, position: Vec2) -> Participant {
+        Participant::ALL
+            .into_iter()
+            .filter(|&x| self.roster[x] && self.matches[x] == match_id)
+            .min_by(|&a, &b| {
+                let distance = |x| position.distance_squared(self.turret_position(x));
+                distance(a).total_cmp(&distance(b))
+            })
+            .unwrap_or_default()
+    }
+}
+/// The first four are in the corners, and the rest in the middle of the top, bottom, right and
+/// left edges, as far as the arena reaches.
+fn fixed_turret_position(participant: Participant, shape: ArenaShape) -> Vec2 {
+    let position = match participant {
+        Participant::A => Vec2::new(TURRET_POSITION, TURRET_POSITION),
+        Participant::B => Vec2::new(-TURRET_POSITION, TURRET_POSITION),
+        Participant::C => Vec2::new(TURRET_POSITION, -TURRET_POSITION),
+        Participant::D => Vec2::new(-TURRET_POSITION, -TURRET_POSITION),
+        Participant::E => Vec2::new(0.0, TURRET_POSITION),
+        Participant::F => Vec2::new(0.0, -TURRET_POSITION),
+        Participant::G => Vec2::new(TURRET_POSITION, 0.0),
+        Participant::H => Vec2::new(-TURRET_POSITION, 0.0),
+    };
+    shape.pull_inside(position, TURRET_MARGIN)
+}
+
+pub(super) fn update_geometry(
+    mut geometry: ResMut<BattlefieldGeometry>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    config: Res<GameConfig>,
+) {
+    *geometry = BattlefieldGeometry::for_matches(&roster, &matches, &config);
+}
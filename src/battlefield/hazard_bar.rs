@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    geometry::update_geometry, neutral_tile::unclaim_tile, RestartEvent, TileGrid, TileRoot,
+    TileStyle, BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    app_state::AppState,
+    collision_groups,
+    config::GameConfig,
+    utils::{GameAssets, MatchId, Matches, PerMatch, Roster, RoundScoped},
+};
+
+// Constants {{{
+
+const HAZARD_BAR_THICKNESS: f32 = 10.0;
+const HAZARD_BAR_COLOR: Color = Color::srgb(0.85, 0.3, 0.2);
+/// Above the tiles, same as the obstacles.
+const HAZARD_BAR_Z: f32 = -0.5;
+
+// }}}
+
+pub struct HazardBarPlugin;
+impl Plugin for HazardBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_hazard_bar.after(update_geometry))
+            .add_systems(
+                Update,
+                (
+                    spawn_hazard_bar
+                        .after(update_geometry)
+                        .run_if(on_event::<RestartEvent>()),
+                    sweep_hazard_bar.run_if(in_state(AppState::Playing)),
+                ),
+            );
+    }
+}
+
+/// A bar spinning around the middle of the battlefield. Bullets bounce off it, and it takes the
+/// tiles it sweeps over away from whoever owns them.
+#[derive(Component)]
+struct HazardBar {
+    half_length: f32,
+}
+
+/// Put up a fresh bar for each match about to start a round, taking down its last one.
+fn spawn_hazard_bar(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    bars: Query<(Entity, &MatchId), With<HazardBar>>,
+) {
+    for match_id in RestartEvent::matches(&mut events, &roster, &matches) {
+        for (entity, _) in bars.iter().filter(|(_, &x)| x == match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_bar(&mut commands, match_id, &config);
+    }
+}
+fn spawn_bar(commands: &mut Commands, match_id: MatchId, config: &GameConfig) {
+    let config = &config.hazard_bar;
+    if !config.enabled {
+        return;
+    }
+    let half_length = config.length / 2.0;
+    commands.spawn((
+        HazardBar { half_length },
+        Name::new("Hazard Bar"),
+        RoundScoped,
+        match_id,
+        RigidBody::KinematicPositionBased,
+        Collider::cuboid(half_length, HAZARD_BAR_THICKNESS / 2.0),
+        CollisionGroups::new(
+            collision_groups::BATTLEFIELD_ROOT,
+            collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+        ),
+        Restitution {
+            coefficient: 1.0,
+            combine_rule: CoefficientCombineRule::Max,
+        },
+        SpriteBundle {
+            transform: Transform::from_translation(match_id.world_offset().extend(HAZARD_BAR_Z)),
+            sprite: Sprite {
+                color: HAZARD_BAR_COLOR,
+                custom_size: Some(Vec2::new(config.length, HAZARD_BAR_THICKNESS)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+/// Turn the bar, and turn the tiles it's over into neutral ground anyone can take.
+fn sweep_hazard_bar(
+    mut commands: Commands,
+    mut grids: ResMut<PerMatch<TileGrid>>,
+    mut bars: Query<(&HazardBar, &mut Transform, &MatchId)>,
+    tile_roots: Query<(Entity, &MatchId), With<TileRoot>>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    time: Res<Time>,
+) {
+    for (bar, mut transform, &match_id) in &mut bars {
+        let (Some(grid), Some((tile_root, _))) = (
+            grids.get_mut(match_id),
+            tile_roots.iter().find(|(_, &x)| x == match_id),
+        ) else {
+            continue;
+        };
+        let style = TileStyle::new(grid.shape, &assets);
+        transform.rotate_z(config.hazard_bar.degrees_per_sec.to_radians() * time.delta_seconds());
+        let center = transform.translation.xy() - match_id.world_offset();
+        let (angle, ..) = transform.rotation.to_euler(EulerRot::ZYX);
+        let half_size = Vec2::new(bar.half_length, HAZARD_BAR_THICKNESS / 2.0);
+        // Only the cells within reach of the bar's ends need checking.
+        let reach = |x: Vec2| {
+            let edge = Vec2::splat(BATTLEFIELD_HALF_WIDTH - 1.0);
+            grid.cell((center + x * bar.half_length).clamp(-edge, edge))
+        };
+        let (Some(start), Some(end)) = (reach(Vec2::NEG_ONE), reach(Vec2::ONE)) else {
+            continue;
+        };
+        for y in start.1.min(end.1)..=start.1.max(end.1) {
+            for x in start.0.min(end.0)..=start.0.max(end.0).min(grid.columns(y) - 1) {
+                if grid.get(x, y) == Entity::PLACEHOLDER {
+                    continue;
+                }
+                let local = Vec2::from_angle(-angle).rotate(grid.cell_center(x, y) - center);
+                if local.abs().cmple(half_size).all() {
+                    unclaim_tile(&mut commands, match_id, grid, x, y, &style, tile_root);
+                }
+            }
+        }
+    }
+}
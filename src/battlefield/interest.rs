@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use super::{update_charge_level, Charge, RestartEvent, Turret};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    utils::{Participant, ParticipantMap},
+};
+
+pub struct InterestPlugin;
+impl Plugin for InterestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AccruedInterest>().add_systems(
+            Update,
+            (
+                accrue_interest
+                    .run_if(in_state(AppState::Playing))
+                    .before(update_charge_level),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Interest each turret has earned but not yet been paid, since charge only comes in whole units.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct AccruedInterest(ParticipantMap<f64>);
+
+/// Grow the charge held by each turret by a percentage per second, up to the configured cap.
+fn accrue_interest(
+    mut accrued: ResMut<AccruedInterest>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut turrets: Query<(&mut Charge, &Participant), With<Turret>>,
+) {
+    let config = &config.interest;
+    if !config.enabled {
+        return;
+    }
+    for (mut charge, &participant) in &mut turrets {
+        if charge.value >= config.cap {
+            accrued[participant] = 0.0;
+            continue;
+        }
+        accrued[participant] +=
+            charge.value as f64 * config.rate_per_sec * time.delta_seconds_f64();
+        let payout = accrued[participant].floor();
+        if payout < 1.0 {
+            continue;
+        }
+        accrued[participant] -= payout;
+        charge.value = charge.value.saturating_add(payout as u64).min(config.cap);
+    }
+}
+fn restart(mut accrued: ResMut<AccruedInterest>) {
+    *accrued = AccruedInterest::default();
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use super::{update_charge_level, Charge, RestartEvent, Tile, TileGrid, Turret};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    ui::AnnouncementEvent,
+    utils::{BallColor, DisplayName, Participant, ParticipantMap, PerMatch},
+};
+
+// Constants {{{
+
+/// The color of the hill's outline while no one holds it.
+const HILL_NEUTRAL_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+const HILL_OUTLINE_ALPHA: f32 = 0.9;
+
+// }}}
+
+pub struct KingOfTheHillPlugin;
+impl Plugin for KingOfTheHillPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerMatch<Hill>>().add_systems(
+            Update,
+            (
+                hold_hill
+                    .run_if(in_state(AppState::Playing))
+                    .before(update_charge_level),
+                draw_hill.run_if(|config: Res<GameConfig>| config.king_of_the_hill.enabled),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The region in the middle of a match's battlefield that pays out charge to whoever owns most of
+/// it.
+#[derive(Default)]
+struct Hill {
+    holder: Option<Participant>,
+    /// Charge the holder has earned but not yet been paid, since charge only comes in whole units.
+    accrued: f64,
+}
+
+/// Work out who owns the majority of each hill's tiles and pay them their share of charge.
+fn hold_hill(
+    mut hills: ResMut<PerMatch<Hill>>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    grids: Res<PerMatch<TileGrid>>,
+    turret_entities: Res<PerMatch<ParticipantMap<Entity>>>,
+    (names, colors): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    tiles: Query<&Participant, With<Tile>>,
+    mut turrets: Query<&mut Charge, With<Turret>>,
+) {
+    let config = &config.king_of_the_hill;
+    if !config.enabled {
+        return;
+    }
+    for (match_id, grid) in grids.iter() {
+        if hills.get(match_id).is_none() {
+            hills.insert(match_id, Hill::default());
+        }
+        let Some(turret_entities) = turret_entities.get(match_id) else {
+            continue;
+        };
+        let hill = &mut hills[match_id];
+        let region = grid.tiles_in_radius(Vec2::ZERO, config.radius);
+        let mut owned = ParticipantMap::splat(0usize);
+        for &owner in tiles.iter_many(&region) {
+            owned[owner] += 1;
+        }
+        let holder = Participant::ALL
+            .into_iter()
+            .find(|&x| owned[x] * 2 > region.len());
+        if holder != hill.holder {
+            hill.holder = holder;
+            hill.accrued = 0.0;
+            if let Some(holder) = holder {
+                announcements.send(AnnouncementEvent {
+                    message: format!("{} Holds the Hill", names[holder]),
+                    color: colors[holder].0,
+                });
+            }
+        }
+        let Some(holder) = holder else {
+            continue;
+        };
+        hill.accrued += config.charge_per_sec * time.delta_seconds_f64();
+        let payout = hill.accrued.floor();
+        if payout < 1.0 {
+            continue;
+        }
+        hill.accrued -= payout;
+        if let Ok(mut charge) = turrets.get_mut(*turret_entities.get(holder)) {
+            charge.value = charge.value.saturating_add(payout as u64);
+        }
+    }
+}
+/// Outline each hill in the color of whoever holds it.
+fn draw_hill(
+    mut gizmos: Gizmos,
+    hills: Res<PerMatch<Hill>>,
+    config: Res<GameConfig>,
+    colors: Res<ParticipantMap<BallColor>>,
+) {
+    for (_, hill) in hills.iter() {
+        let color = match hill.holder {
+            Some(holder) => colors[holder].0.with_alpha(HILL_OUTLINE_ALPHA),
+            None => HILL_NEUTRAL_COLOR,
+        };
+        gizmos.circle_2d(Vec2::ZERO, config.king_of_the_hill.radius, color);
+    }
+}
+fn restart(mut hills: ResMut<PerMatch<Hill>>, mut events: EventReader<RestartEvent>) {
+    for event in events.read() {
+        hills.insert(event.match_id, Hill::default());
+    }
+}
@@ -0,0 +1,295 @@
+use std::f32::consts::{FRAC_PI_2, PI, SQRT_2, TAU};
+
+use bevy::{color::palettes::css, prelude::*, sprite::Mesh2dHandle};
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{update_charge_level, Bullet, Charge, RestartEvent, BATTLEFIELD_HALF_WIDTH};
+use crate::{
+    app_state::AppState,
+    collision_groups,
+    config::{GameConfig, LaserMode},
+    utils::{GameRng, MatchId, Matches, Roster},
+};
+
+// Constants {{{
+
+/// Long enough for the beam to reach the corners of the battlefield.
+const LASER_HALF_LENGTH: f32 = BATTLEFIELD_HALF_WIDTH * SQRT_2;
+const LASER_THICKNESS: f32 = 4.0;
+const LASER_COLOR: Color = Color::Srgba(css::ORANGE_RED);
+const LASER_WARNING_COLOR: Color = Color::srgba(1.0, 0.27, 0.0, 0.2);
+
+// Z-index
+const LASER_WARNING_Z: f32 = 0.5;
+const LASER_BEAM_Z: f32 = 1.0;
+
+// }}}
+
+pub struct LaserPlugin;
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                advance_laser_cycle.run_if(in_state(AppState::Playing)),
+                handle_laser_collision
+                    .run_if(in_state(AppState::Playing))
+                    .before(update_charge_level),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum LaserPhase {
+    #[default]
+    Idle,
+    /// The warning arc is displayed over the area the beam is about to sweep.
+    Warning,
+    /// The beam is active and rotating.
+    Sweep,
+}
+/// Tracks where the laser is in its idle -> warning -> sweep cycle.
+#[derive(Resource, Default)]
+struct LaserCycle {
+    phase: LaserPhase,
+    timer: Timer,
+    /// The angle of the beam at the start of the current sweep.
+    start_angle: f32,
+}
+impl LaserCycle {
+    fn new(cooldown_secs: f32) -> Self {
+        Self {
+            phase: LaserPhase::Idle,
+            timer: Timer::from_seconds(cooldown_secs, TimerMode::Once),
+            start_angle: 0.0,
+        }
+    }
+    fn set_phase(&mut self, phase: LaserPhase, secs: f32) {
+        self.phase = phase;
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+}
+/// Marker for the rotating sensor that damages bullets. Every match has its own, all sweeping
+/// together.
+#[derive(Component, Clone, Copy)]
+struct LaserBeam;
+/// One of the two translucent sectors showing where the beam will sweep next, on either side of the
+/// middle.
+#[derive(Component, Clone, Copy)]
+struct LaserWarningArc(u8);
+
+/// The mesh and material of the warning arcs, kept for the matches that start running after the
+/// first round.
+#[derive(Resource)]
+struct LaserWarningAssets {
+    mesh: Mesh2dHandle,
+    material: Handle<ColorMaterial>,
+    /// The sweep angle `mesh` was built for.
+    sweep_angle: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+) {
+    commands.insert_resource(LaserCycle::new(config.laser.cooldown_secs));
+    let assets = LaserWarningAssets {
+        material: materials.add(LASER_WARNING_COLOR),
+        mesh: Mesh2dHandle(meshes.add(CircularSector::from_radians(
+            LASER_HALF_LENGTH,
+            config.laser.sweep_angle,
+        ))),
+        sweep_angle: config.laser.sweep_angle,
+    };
+    for match_id in matches.running(&roster) {
+        spawn_laser(&mut commands, match_id, &assets);
+    }
+    commands.insert_resource(assets);
+}
+/// Spawn the beam and the warning arcs over the middle of `match_id`'s battlefield, hidden until the
+/// laser next fires.
+fn spawn_laser(commands: &mut Commands, match_id: MatchId, assets: &LaserWarningAssets) {
+    let center = match_id.world_offset();
+    commands.spawn((
+        Name::new("Laser Beam"),
+        LaserBeam,
+        match_id,
+        SpriteBundle {
+            sprite: Sprite {
+                color: LASER_COLOR,
+                custom_size: Some(Vec2::new(2.0 * LASER_HALF_LENGTH, LASER_THICKNESS)),
+                ..default()
+            },
+            transform: Transform::from_translation(center.extend(LASER_BEAM_Z)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        RigidBody::KinematicPositionBased,
+        Collider::cuboid(LASER_HALF_LENGTH, LASER_THICKNESS / 2.0),
+        CollisionGroups::new(
+            collision_groups::HAZARDS,
+            collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+        ),
+        (Sensor, ActiveEvents::COLLISION_EVENTS, ColliderDisabled),
+    ));
+    // The beam goes all the way through the center, so it sweeps two opposing sectors at once.
+    for i in 0..2 {
+        commands.spawn((
+            Name::new("Laser Warning Arc"),
+            LaserWarningArc(i),
+            match_id,
+            ColorMesh2dBundle {
+                mesh: assets.mesh.clone(),
+                material: assets.material.clone(),
+                transform: Transform::from_translation(center.extend(LASER_WARNING_Z)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ));
+    }
+}
+fn advance_laser_cycle(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cycle: ResMut<LaserCycle>,
+    mut assets: ResMut<LaserWarningAssets>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut beams: Query<(Entity, &mut Transform, &mut Visibility), With<LaserBeam>>,
+    mut arcs: Query<
+        (Entity, &LaserWarningArc, &mut Transform, &mut Visibility),
+        Without<LaserBeam>,
+    >,
+) {
+    let config = &config.laser;
+    if !config.enabled {
+        if cycle.phase != LaserPhase::Idle {
+            cycle.set_phase(LaserPhase::Idle, config.cooldown_secs);
+            for (beam_entity, _, mut beam_visibility) in &mut beams {
+                commands.entity(beam_entity).insert(ColliderDisabled);
+                *beam_visibility = Visibility::Hidden;
+            }
+            for (.., mut visibility) in &mut arcs {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        return;
+    }
+
+    cycle.timer.tick(time.delta());
+    if cycle.phase == LaserPhase::Sweep {
+        let angle = cycle.start_angle + config.sweep_angle * cycle.timer.fraction();
+        for (_, mut beam_transform, _) in &mut beams {
+            beam_transform.rotation = Quat::from_rotation_z(angle);
+        }
+    }
+    if !cycle.timer.finished() {
+        return;
+    }
+
+    match cycle.phase {
+        LaserPhase::Idle => {
+            cycle.start_angle = rng.gen_range(0.0..TAU);
+            cycle.set_phase(LaserPhase::Warning, config.warning_secs);
+            // The sector mesh is centered on the Y axis.
+            let middle_angle = cycle.start_angle + config.sweep_angle / 2.0 - FRAC_PI_2;
+            // The sweep angle can be changed in the config at any time.
+            let resized = assets.sweep_angle != config.sweep_angle;
+            if resized {
+                meshes.remove(&assets.mesh.0);
+                assets.mesh = Mesh2dHandle(meshes.add(CircularSector::from_radians(
+                    LASER_HALF_LENGTH,
+                    config.sweep_angle,
+                )));
+                assets.sweep_angle = config.sweep_angle;
+            }
+            for (entity, &LaserWarningArc(i), mut transform, mut visibility) in &mut arcs {
+                transform.rotation = Quat::from_rotation_z(middle_angle + i as f32 * PI);
+                *visibility = Visibility::Visible;
+                if resized {
+                    commands.entity(entity).insert(assets.mesh.clone());
+                }
+            }
+        }
+        LaserPhase::Warning => {
+            cycle.set_phase(LaserPhase::Sweep, config.sweep_secs);
+            for (beam_entity, mut beam_transform, mut beam_visibility) in &mut beams {
+                beam_transform.rotation = Quat::from_rotation_z(cycle.start_angle);
+                *beam_visibility = Visibility::Visible;
+                commands.entity(beam_entity).remove::<ColliderDisabled>();
+            }
+            for (.., mut visibility) in &mut arcs {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        LaserPhase::Sweep => {
+            cycle.set_phase(LaserPhase::Idle, config.cooldown_secs);
+            for (beam_entity, _, mut beam_visibility) in &mut beams {
+                *beam_visibility = Visibility::Hidden;
+                commands.entity(beam_entity).insert(ColliderDisabled);
+            }
+        }
+    }
+}
+fn handle_laser_collision(
+    mut collision_events: EventReader<CollisionEvent>,
+    config: Res<GameConfig>,
+    beam: Query<(), With<LaserBeam>>,
+    mut bullets: Query<&mut Charge, With<Bullet>>,
+) {
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let bullet = if beam.contains(a) {
+            b
+        } else if beam.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        let Ok(mut charge) = bullets.get_mut(bullet) else {
+            continue;
+        };
+        // A bullet with no charge left gets despawned by `update_charge_level`.
+        match config.laser.mode {
+            LaserMode::Destroy => charge.value = 0,
+            LaserMode::Halve => charge.value /= 2,
+        }
+    }
+}
+fn restart(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    mut cycle: ResMut<LaserCycle>,
+    config: Res<GameConfig>,
+    assets: Res<LaserWarningAssets>,
+    mut beams: Query<(Entity, &mut Visibility, &MatchId), With<LaserBeam>>,
+    mut arcs: Query<&mut Visibility, (With<LaserWarningArc>, Without<LaserBeam>)>,
+) {
+    *cycle = LaserCycle::new(config.laser.cooldown_secs);
+    let restarted: Vec<_> = events.read().map(|x| x.match_id).collect();
+    for &match_id in &restarted {
+        if !beams.iter().any(|(.., &x)| x == match_id) {
+            // The match wasn't running last round.
+            spawn_laser(&mut commands, match_id, &assets);
+        }
+    }
+    // The beams of any match that stopped running are being cleared away.
+    for (beam_entity, mut beam_visibility, _) in
+        beams.iter_mut().filter(|(.., x)| restarted.contains(x))
+    {
+        *beam_visibility = Visibility::Hidden;
+        commands.entity(beam_entity).insert(ColliderDisabled);
+    }
+    for mut visibility in &mut arcs {
+        *visibility = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use super::{rotate_turret, Turret, TurretPlatformLink};
+use crate::app_state::AppState;
+use crate::{config::GameConfig, utils::Participant};
+
+// Constants {{{
+
+/// The speed in radians per second at which a turret aimed by hand turns.
+const MANUAL_AIM_SPEED: f32 = 2.0;
+
+// }}}
+
+pub struct ManualControlPlugin;
+impl Plugin for ManualControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                mark_manual_turrets.before(rotate_turret),
+                aim_manual_turrets
+                    .after(mark_manual_turrets)
+                    .run_if(in_state(AppState::Playing)),
+            ),
+        );
+    }
+}
+
+/// Marker for the platform of a turret that's aimed by hand instead of spinning on its own.
+#[derive(Component)]
+pub struct ManualAim;
+
+/// The keys that turn the turret of `participant` counterclockwise and clockwise.
+pub fn aim_keys(participant: Participant) -> (KeyCode, KeyCode) {
+    match participant {
+        Participant::A => (KeyCode::KeyA, KeyCode::KeyD),
+        Participant::B => (KeyCode::KeyQ, KeyCode::KeyE),
+        Participant::C => (KeyCode::KeyJ, KeyCode::KeyL),
+        Participant::D => (KeyCode::KeyU, KeyCode::KeyO),
+        Participant::E => (KeyCode::KeyZ, KeyCode::KeyC),
+        Participant::F => (KeyCode::KeyV, KeyCode::KeyN),
+        Participant::G => (KeyCode::KeyR, KeyCode::KeyY),
+        Participant::H => (KeyCode::KeyI, KeyCode::KeyP),
+    }
+}
+
+/// Keep the [`ManualAim`] markers in line with the config, including on the turrets spawned on
+/// restart.
+fn mark_manual_turrets(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    turrets: Query<(&Participant, &TurretPlatformLink), With<Turret>>,
+    platforms: Query<Has<ManualAim>>,
+) {
+    for (&participant, &TurretPlatformLink(platform)) in &turrets {
+        let Ok(is_manual) = platforms.get(platform) else {
+            continue;
+        };
+        match (config.control.human[participant], is_manual) {
+            (true, false) => {
+                commands.entity(platform).insert(ManualAim);
+            }
+            (false, true) => {
+                commands.entity(platform).remove::<ManualAim>();
+            }
+            _ => (),
+        }
+    }
+}
+fn aim_manual_turrets(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    turrets: Query<(&Participant, &TurretPlatformLink), With<Turret>>,
+    mut platforms: Query<&mut Transform, (With<ManualAim>, Without<Turret>)>,
+) {
+    for (&participant, &TurretPlatformLink(platform)) in &turrets {
+        let Ok(mut transform) = platforms.get_mut(platform) else {
+            continue;
+        };
+        let (left, right) = aim_keys(participant);
+        let direction = keys.pressed(left) as i8 - keys.pressed(right) as i8;
+        if direction != 0 {
+            transform.rotate_z(direction as f32 * MANUAL_AIM_SPEED * time.delta_seconds());
+        }
+    }
+}
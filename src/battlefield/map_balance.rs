@@ -0,0 +1,193 @@
+//! A check of how fairly a custom map shares out the battlefield and the panels, so a lopsided one
+//! can be spotted before a round is played on it.
+//!
+//! On the battlefield, where the turrets sit and where the tiles are decide how much territory
+//! each participant starts out with. The check counts the tiles each of them would start a round
+//! with, the same way the battlefield is set up.
+//!
+//! In the panels, the pegs decide which trigger zones the worker balls tend to land in, and a map
+//! can lay out each panel differently. The check drops a few hundred simulated balls through each
+//! panel and works out how much a ball is worth on average to the participants dropping there.
+
+use std::fmt::Write;
+
+use super::{
+    map_file::{LayoutCell, MapFile},
+    BattlefieldGeometry, GeneratedMap, TileGrid,
+};
+use crate::{
+    config::{GambleConfig, GameConfig},
+    panel_plugin::{PanelRootSide, TriggerType},
+    utils::{MatchId, Participant, ParticipantMap, Roster},
+};
+
+// Constants {{{
+
+/// A map is lopsided if someone starts out with this many times the territory of someone else.
+const MAX_TERRITORY_RATIO: f32 = 1.5;
+/// A map is lopsided if a worker ball is worth this many times as much to someone as to someone
+/// else.
+const MAX_PANEL_VALUE_RATIO: f32 = 1.2;
+/// The number of simulated worker balls dropped through each panel.
+const PANEL_DROPS: usize = 300;
+/// The seed for where the simulated worker balls are dropped from, the same for both panels so
+/// identical panels come out identical.
+const PANEL_DROP_SEED: u64 = 0x5eed;
+
+// }}}
+
+/// How much of the battlefield each participant starts out with on a custom map, and how much
+/// their worker balls are worth.
+#[derive(Debug, Clone)]
+pub struct MapBalance {
+    /// The share of the starting tiles, from 0 to 1, of everyone playing.
+    shares: Vec<(Participant, f32)>,
+    /// The factor a worker ball multiplies its turret's charge by on average, from the panel
+    /// it's dropped in, of everyone playing.
+    panel_values: Vec<(Participant, f32)>,
+}
+impl MapBalance {
+    /// Lay out `map` for `roster`, count everyone's tiles and simulate drops through its panels.
+    pub fn check(map: &MapFile, roster: &Roster, config: &GameConfig) -> Self {
+        let config = GameConfig {
+            map_file: Some(map.clone()),
+            ..config.clone()
+        };
+        let geometry = BattlefieldGeometry::new(roster, &config);
+        let layout = GeneratedMap::from_file(map);
+        let grid = TileGrid::new(config.tile_shape);
+        let mut tiles = ParticipantMap::<u32>::default();
+        for y in 0..grid.rows() {
+            for x in 0..grid.columns(y) {
+                let position = grid.cell_center(x, y);
+                if geometry.shape.contains(position, 0.0)
+                    && layout.cell_at(position) == LayoutCell::Tile
+                {
+                    tiles[geometry.territory_owner(MatchId::MAIN, position)] += 1;
+                }
+            }
+        }
+        let total = Participant::ALL
+            .into_iter()
+            .map(|x| tiles[x])
+            .sum::<u32>()
+            .max(1);
+        let shares = Participant::ALL
+            .into_iter()
+            .filter(|&x| roster[x])
+            .map(|x| (x, tiles[x] as f32 / total as f32))
+            .collect();
+        let panels = map.panels.clone().unwrap_or_default();
+        let zones = map.trigger_zones.as_ref().unwrap_or(&config.trigger_zones);
+        let panel_value = |side| {
+            let odds = panels
+                .get(side)
+                .landing_odds(&config.physics, PANEL_DROPS, PANEL_DROP_SEED);
+            odds.into_iter()
+                .zip(zones.0)
+                .map(|(odds, trigger_type)| odds * trigger_value(trigger_type, &config.gamble))
+                .sum::<f32>()
+        };
+        let (left, right) = (
+            panel_value(PanelRootSide::Left),
+            panel_value(PanelRootSide::Right),
+        );
+        let panel_values = Participant::ALL
+            .into_iter()
+            .filter(|&x| roster[x])
+            .map(|x| match PanelRootSide::for_participant(x, roster) {
+                PanelRootSide::Left => (x, left),
+                PanelRootSide::Right => (x, right),
+            })
+            .collect();
+        Self {
+            shares,
+            panel_values,
+        }
+    }
+    /// Whether someone starts out with a lot more territory than someone else.
+    fn is_territory_lopsided(&self) -> bool {
+        is_lopsided(&self.shares, MAX_TERRITORY_RATIO)
+    }
+    /// Whether someone's worker balls are worth a lot more than someone else's.
+    fn are_panels_lopsided(&self) -> bool {
+        is_lopsided(&self.panel_values, MAX_PANEL_VALUE_RATIO)
+    }
+    /// Whether the map favors someone, on the battlefield or in the panels.
+    pub fn is_lopsided(&self) -> bool {
+        self.is_territory_lopsided() || self.are_panels_lopsided()
+    }
+    /// Everyone's share and worker ball value, e.g. "Territory: Alice 30%, Bob 20%" and
+    /// "Panels: Alice x2.1, Bob x1.9" on the next line, each with a warning if it's lopsided.
+    pub fn summary(&self, config: &GameConfig) -> String {
+        let mut text = String::from("Territory:");
+        for (i, &(participant, share)) in self.shares.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            let name = &config.participants[participant].name;
+            let _ = write!(text, "{}{} {:.0}%", separator, name, share * 100.0);
+        }
+        if self.is_territory_lopsided() {
+            text.push_str(" (lopsided)");
+        }
+        text.push_str("\nPanels:");
+        for (i, &(participant, value)) in self.panel_values.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            let name = &config.participants[participant].name;
+            let _ = write!(text, "{}{} x{:.2}", separator, name, value);
+        }
+        if self.are_panels_lopsided() {
+            text.push_str(" (lopsided)");
+        }
+        text
+    }
+}
+/// Whether the largest of `values` is more than `ratio` times the smallest.
+fn is_lopsided(values: &[(Participant, f32)], ratio: f32) -> bool {
+    let values = values.iter().map(|&(_, x)| x);
+    let most = values.clone().fold(0.0, f32::max);
+    let least = values.fold(f32::INFINITY, f32::min);
+    most > least * ratio
+}
+/// The factor landing in a trigger zone of `trigger_type` multiplies the charge by on average.
+/// Releasing the charge leaves it as it was.
+fn trigger_value(trigger_type: TriggerType, gamble: &GambleConfig) -> f32 {
+    match trigger_type {
+        TriggerType::Multiply(factor) => factor as f32,
+        TriggerType::Gamble => {
+            let win_chance = gamble.win_chance as f32;
+            win_chance * gamble.win_factor as f32
+                + (1.0 - win_chance) / gamble.loss_divisor.max(1) as f32
+        }
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panel_plugin::{PanelLayout, PanelLayouts};
+    use bevy::prelude::*;
+
+    #[test]
+    fn uneven_panels_make_a_map_lopsided() {
+        let config = GameConfig::default();
+        let roster = Roster::default();
+        let even = MapBalance::check(&MapFile::default(), &roster, &config);
+        assert!(!even.are_panels_lopsided(), "{}", even.summary(&config));
+        // A shelf over the middle of the right panel catches the balls headed for the zones that
+        // multiply, and leaves only the release zones at the sides.
+        let shelf = PanelLayout {
+            pegs: (-4..=4).map(|i| Vec2::new(i as f32 * 15.0, 0.0)).collect(),
+            ..default()
+        };
+        let map = MapFile {
+            panels: Some(PanelLayouts {
+                left: PanelLayout::default(),
+                right: shelf,
+            }),
+            ..default()
+        };
+        let uneven = MapBalance::check(&map, &roster, &config);
+        assert!(uneven.are_panels_lopsided(), "{}", uneven.summary(&config));
+    }
+}
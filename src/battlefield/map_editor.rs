@@ -0,0 +1,941 @@
+//! An editor for the custom maps in `assets/maps/`, opened from the main menu.
+//!
+//! Pick a tool with the number keys, then use the mouse on the battlefield:
+//!
+//! 1. Neutral ground: paint it with the left button and paint the tiles back with the right.
+//! 2. Obstacles: drag out a rectangle with the left button, or drag an existing one to move it, and
+//!    remove one with the right.
+//! 3. Turrets: drag the spawn points around with the left button.
+//! 4. Neutral tiles: paint them with the left button and paint regular tiles back with the right.
+//! 5. Speed zones: like obstacles, making mud that slows bullets down, or a fast lane while holding
+//!    `Shift`.
+//! 6. Teleporters: click with the left button to place one end and again to place the other, and
+//!    remove a pair with the right.
+//! 7. Pegs: click in a panel with the left button to place one, or drag an existing one to move
+//!    it, and remove one with the right.
+//! 8. Dividers: like pegs, for the dividers between the trigger zones.
+//! 9. Trigger zones: click under the pegs of a panel to cycle what the zone there does, forwards
+//!    with the left button and backwards with the right. Both panels share the same zones.
+//!
+//! `G` snaps whatever's placed or moved to a grid, `Ctrl+Z` undoes the last change and `Ctrl+Y`
+//! redoes it. `Ctrl+C` copies the panels as a board code and `Ctrl+V` pastes one in. `S` saves the
+//! map and picks it for the next round, and `Escape` goes back to the menu.
+
+use std::{fs, path::Path};
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use ron::ser::PrettyConfig;
+
+use super::{
+    map_balance::MapBalance,
+    map_file::{layout_cell_at, LayoutCell, MapFile, MapFiles, MAP_DIR, MAP_EXTENSION},
+    map_generator::Obstacle,
+    speed_zone::SpeedZone,
+    BattlefieldGeometry, MatchViewCamera, BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    app_state::AppState,
+    config::{ArenaLayout, GameConfig, TriggerZoneLayout, ValidateConfig},
+    panel_plugin::{
+        contain_divider, contain_peg, PanelLayouts, PanelRoot, PanelRootSide, TriggerType,
+        ARENA_HEIGHT, ARENA_WIDTH, CIRCLE_RADIUS, PANEL_PART_COUNT_MAX, TRIGGER_ZONE_COUNT,
+        TRIGGER_ZONE_DIVIDER_LENGTH, TRIGGER_ZONE_DIVIDER_RADIUS, TRIGGER_ZONE_Y,
+    },
+    utils::{BallColor, GameAssets, MatchId, Participant, ParticipantMap, Roster},
+};
+
+// Constants {{{
+
+/// The number of cells across the tile layout the editor paints.
+const EDITOR_GRID_SIZE: usize = 36;
+const EDITOR_CELL_SIZE: f32 = BATTLEFIELD_HALF_WIDTH * 2.0 / EDITOR_GRID_SIZE as f32;
+/// Covers up the battlefield underneath, so only the map being edited shows.
+const EDITOR_BACKDROP_COLOR: Color = Color::srgb(0.12, 0.12, 0.14);
+/// Above everything on the battlefield.
+const EDITOR_BACKDROP_Z: f32 = 50.0;
+const EDITOR_TILE_COLOR: Color = Color::srgb(0.35, 0.35, 0.4);
+const EDITOR_NEUTRAL_TILE_COLOR: Color = Color::srgb(0.75, 0.7, 0.5);
+const EDITOR_OBSTACLE_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+/// Color of the rectangle being dragged out for a new obstacle.
+const EDITOR_NEW_OBSTACLE_COLOR: Color = Color::srgb(0.9, 0.8, 0.3);
+const EDITOR_TURRET_RADIUS: f32 = 12.0;
+/// Obstacles dragged out smaller than this along either side are dropped.
+const EDITOR_OBSTACLE_HALF_SIZE_MIN: f32 = 4.0;
+const EDITOR_SLOW_ZONE_COLOR: Color = Color::srgb(0.7, 0.5, 0.3);
+const EDITOR_FAST_ZONE_COLOR: Color = Color::srgb(0.4, 0.7, 1.0);
+/// The speed factor of newly placed mud.
+const EDITOR_SLOW_FACTOR: f32 = 0.5;
+/// The speed factor of newly placed fast lanes.
+const EDITOR_FAST_FACTOR: f32 = 2.0;
+const EDITOR_TELEPORTER_RADIUS: f32 = 16.0;
+const EDITOR_TELEPORTER_COLOR: Color = Color::srgb(0.6, 0.4, 1.0);
+const EDITOR_PEG_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+/// How far to either side of a divider it can be grabbed, since it's too thin to hit otherwise.
+const EDITOR_DIVIDER_GRAB_DISTANCE: f32 = 6.0;
+/// Clicks below this height in a panel pick the trigger zone underneath, to stay clear of the
+/// pegs.
+const EDITOR_TRIGGER_ZONE_CLICK_Y_MAX: f32 = TRIGGER_ZONE_Y + 65.0;
+/// What the trigger zone tool cycles through, in order.
+const EDITOR_TRIGGER_TYPES: [TriggerType; 9] = [
+    TriggerType::Multiply(2),
+    TriggerType::Multiply(3),
+    TriggerType::Multiply(4),
+    TriggerType::Multiply(6),
+    TriggerType::BurstShot,
+    TriggerType::ChargedShot,
+    TriggerType::Gamble,
+    TriggerType::Swap,
+    TriggerType::Freeze,
+];
+/// The spacing of the grid that positions snap to, half a cell of the tile layout.
+const EDITOR_SNAP: f32 = EDITOR_CELL_SIZE / 2.0;
+/// The number of changes that can be undone.
+const EDITOR_HISTORY_MAX: usize = 100;
+const EDITOR_TEXT_FONT_SIZE: f32 = 20.0;
+const EDITOR_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const EDITOR_TEXT_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+const SAVE_KEY: KeyCode = KeyCode::KeyS;
+const EXIT_KEY: KeyCode = KeyCode::Escape;
+const SNAP_KEY: KeyCode = KeyCode::KeyG;
+const UNDO_KEY: KeyCode = KeyCode::KeyZ;
+const REDO_KEY: KeyCode = KeyCode::KeyY;
+const COPY_KEY: KeyCode = KeyCode::KeyC;
+const PASTE_KEY: KeyCode = KeyCode::KeyV;
+
+// }}}
+
+pub struct MapEditorPlugin;
+impl Plugin for MapEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Editor), start_editing)
+            .add_systems(OnExit(AppState::Editor), stop_editing)
+            .add_systems(
+                Update,
+                (
+                    pick_tool,
+                    undo_redo.after(pick_tool),
+                    copy_paste_board_code.after(undo_redo),
+                    edit_map.after(copy_paste_board_code),
+                    save_map.after(edit_map).before(ValidateConfig),
+                    draw_map.after(edit_map),
+                    update_editor_text.after(save_map),
+                    exit_editor,
+                )
+                    .run_if(in_state(AppState::Editor)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditorTool {
+    NeutralGround,
+    Obstacles,
+    Turrets,
+    NeutralTiles,
+    SpeedZones,
+    Teleporters,
+    Pegs,
+    Dividers,
+    TriggerZones,
+}
+impl EditorTool {
+    const ALL: [(KeyCode, EditorTool); 9] = [
+        (KeyCode::Digit1, EditorTool::NeutralGround),
+        (KeyCode::Digit2, EditorTool::Obstacles),
+        (KeyCode::Digit3, EditorTool::Turrets),
+        (KeyCode::Digit4, EditorTool::NeutralTiles),
+        (KeyCode::Digit5, EditorTool::SpeedZones),
+        (KeyCode::Digit6, EditorTool::Teleporters),
+        (KeyCode::Digit7, EditorTool::Pegs),
+        (KeyCode::Digit8, EditorTool::Dividers),
+        (KeyCode::Digit9, EditorTool::TriggerZones),
+    ];
+    fn name(self) -> &'static str {
+        match self {
+            EditorTool::NeutralGround => "Neutral ground",
+            EditorTool::Obstacles => "Obstacles",
+            EditorTool::Turrets => "Turrets",
+            EditorTool::NeutralTiles => "Neutral tiles",
+            EditorTool::SpeedZones => "Speed zones",
+            EditorTool::Teleporters => "Teleporters",
+            EditorTool::Pegs => "Pegs",
+            EditorTool::Dividers => "Dividers",
+            EditorTool::TriggerZones => "Trigger zones",
+        }
+    }
+}
+/// What the left mouse button is holding on to.
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    /// A new obstacle or speed zone, dragged out from this corner.
+    Rectangle(Vec2),
+    /// An obstacle being moved, as an index into [`MapFile::obstacles`], with where it was grabbed
+    /// relative to its center.
+    MoveObstacle(usize, Vec2),
+    /// A speed zone being moved, as an index into [`MapFile::speed_zones`], with where it was
+    /// grabbed relative to its center.
+    MoveSpeedZone(usize, Vec2),
+    /// A turret spawn point, as an index into [`MapFile::turrets`].
+    Turret(usize),
+    /// The first end of a teleporter, waiting for the second to be placed.
+    Teleporter(Vec2),
+    /// A peg, as an index into the pegs of the panel on that side.
+    Peg(PanelRootSide, usize),
+    /// A divider, as an index into the dividers of the panel on that side.
+    Divider(PanelRootSide, usize),
+}
+/// The map being edited. Only exists while the editor is open.
+#[derive(Resource)]
+struct MapEditor {
+    map: MapFile,
+    tool: EditorTool,
+    drag: Option<Drag>,
+    snap: bool,
+    /// The map as it was before each change, the latest last.
+    undo: Vec<MapFile>,
+    /// The changes undone, the latest last, cleared by any new change.
+    redo: Vec<MapFile>,
+    /// The map as it was when the mouse button currently held down was pressed, to undo back to.
+    stroke: Option<MapFile>,
+    /// How fairly the map shares out the battlefield, worked out again after each change.
+    balance: Option<MapBalance>,
+    /// The outcome of the last save, shown under the controls.
+    status: String,
+}
+impl MapEditor {
+    /// Start from `map`, or a blank map with the turrets in their usual spots. The tile layout is
+    /// resampled to the editor's grid, every turret gets a spawn point so any of them can be
+    /// moved, and the panels and trigger zones start out as the ones already picked.
+    fn new(map: Option<&MapFile>, maps: &MapFiles, config: &GameConfig) -> Self {
+        let mut map = map.cloned().unwrap_or_else(|| MapFile {
+            name: format!("Custom Map {}", maps.0.len() + 1),
+            ..default()
+        });
+        map.tiles = (0..EDITOR_GRID_SIZE)
+            .map(|y| {
+                (0..EDITOR_GRID_SIZE)
+                    .map(|x| layout_cell_at(&map.tiles, cell_center(x, y)).to_char())
+                    .collect()
+            })
+            .collect();
+        let unplaced = Participant::ALL.into_iter().skip(map.turrets.len());
+        // Custom maps keep the usual turret spots for whoever they don't place.
+        let defaults = GameConfig {
+            map_file: None,
+            arena_layout: ArenaLayout::Fixed,
+            ..config.clone()
+        };
+        let geometry = BattlefieldGeometry::new(&Roster::default(), &defaults);
+        map.turrets
+            .extend(unplaced.map(|x| geometry.turret_position(x)));
+        map.panels.get_or_insert_with(PanelLayouts::default);
+        map.trigger_zones
+            .get_or_insert_with(|| config.trigger_zones.clone());
+        Self {
+            map,
+            tool: EditorTool::NeutralGround,
+            drag: None,
+            snap: false,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            stroke: None,
+            balance: None,
+            status: String::new(),
+        }
+    }
+    fn set_cell(&mut self, position: Vec2, cell: LayoutCell) {
+        let Some((x, y)) = cell_at(position) else {
+            return;
+        };
+        let row = &mut self.map.tiles[y];
+        *row = row
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i == x { cell.to_char() } else { c })
+            .collect();
+    }
+    /// Keep the map as it was before the change that's just been made, so it can be undone.
+    fn record(&mut self, before: MapFile) {
+        if before == self.map {
+            return;
+        }
+        if self.undo.len() == EDITOR_HISTORY_MAX {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+        self.balance = None;
+    }
+}
+fn cell_center(x: usize, y: usize) -> Vec2 {
+    Vec2::new(
+        -BATTLEFIELD_HALF_WIDTH + (x as f32 + 0.5) * EDITOR_CELL_SIZE,
+        BATTLEFIELD_HALF_WIDTH - (y as f32 + 0.5) * EDITOR_CELL_SIZE,
+    )
+}
+/// The cell of the tile layout under `position`, if it's on the battlefield.
+fn cell_at(position: Vec2) -> Option<(usize, usize)> {
+    let x = (position.x + BATTLEFIELD_HALF_WIDTH) / EDITOR_CELL_SIZE;
+    let y = (BATTLEFIELD_HALF_WIDTH - position.y) / EDITOR_CELL_SIZE;
+    let range = 0.0..EDITOR_GRID_SIZE as f32;
+    (range.contains(&x) && range.contains(&y)).then_some((x as usize, y as usize))
+}
+/// Where `cursor` is in the panel on `side`, relative to its center.
+fn panel_point(
+    roots: &Query<(&GlobalTransform, &PanelRoot, &MatchId)>,
+    side: PanelRootSide,
+    cursor: Vec2,
+) -> Option<Vec2> {
+    // The map is edited on the first match's panels, the others just show the same layout.
+    let (transform, ..) = roots
+        .iter()
+        .find(|&(_, &PanelRoot(x), &match_id)| x == side && match_id == MatchId::MAIN)?;
+    Some(
+        transform
+            .affine()
+            .inverse()
+            .transform_point3(cursor.extend(0.0))
+            .xy(),
+    )
+}
+/// The panel under `cursor`, with where the cursor is relative to its center.
+fn panel_at(
+    roots: &Query<(&GlobalTransform, &PanelRoot, &MatchId)>,
+    cursor: Vec2,
+) -> Option<(PanelRootSide, Vec2)> {
+    [PanelRootSide::Left, PanelRootSide::Right]
+        .into_iter()
+        .find_map(|side| {
+            let point = panel_point(roots, side, cursor)?;
+            let inside = point.x.abs() <= ARENA_WIDTH / 2.0 && point.y.abs() <= ARENA_HEIGHT / 2.0;
+            inside.then_some((side, point))
+        })
+}
+/// The trigger zone under `x` across a panel, counting from the left.
+fn trigger_zone_slot(x: f32) -> usize {
+    let width = ARENA_WIDTH / TRIGGER_ZONE_COUNT as f32;
+    (((x + ARENA_WIDTH / 2.0) / width).max(0.0) as usize).min(TRIGGER_ZONE_COUNT - 1)
+}
+/// The trigger type `step` places along [`EDITOR_TRIGGER_TYPES`] from `trigger_type`.
+fn cycle_trigger_type(trigger_type: TriggerType, step: isize) -> TriggerType {
+    let len = EDITOR_TRIGGER_TYPES.len() as isize;
+    let i = EDITOR_TRIGGER_TYPES
+        .iter()
+        .position(|&x| x == trigger_type)
+        .map_or(if step > 0 { -1 } else { 0 }, |x| x as isize);
+    EDITOR_TRIGGER_TYPES[(i + step).rem_euclid(len) as usize]
+}
+/// A file name for the map called `name`, e.g. `custom_map_1.ron` for "Custom Map 1".
+fn file_name(name: &str) -> String {
+    let stem = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect::<String>();
+    format!("{}.{}", stem, MAP_EXTENSION)
+}
+
+#[derive(Component)]
+struct MapEditorUi;
+#[derive(Component)]
+struct MapEditorText;
+
+/// Open the custom map picked in the menu, or a blank one if none is.
+fn start_editing(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    maps: Res<MapFiles>,
+    assets: Res<GameAssets>,
+    roots: Query<(Entity, &MatchId), With<PanelRoot>>,
+) {
+    commands.insert_resource(MapEditor::new(config.map_file.as_ref(), &maps, &config));
+    commands.spawn((
+        MapEditorUi,
+        Name::new("Map Editor Backdrop"),
+        SpriteBundle {
+            transform: Transform::from_xyz(0.0, 0.0, EDITOR_BACKDROP_Z),
+            sprite: Sprite {
+                color: EDITOR_BACKDROP_COLOR,
+                custom_size: Some(Vec2::splat(BATTLEFIELD_HALF_WIDTH * 2.0)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+    for (root, _) in roots.iter().filter(|&(_, &x)| x == MatchId::MAIN) {
+        commands
+            .spawn((
+                MapEditorUi,
+                Name::new("Map Editor Panel Backdrop"),
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, 0.0, EDITOR_BACKDROP_Z),
+                    sprite: Sprite {
+                        color: EDITOR_BACKDROP_COLOR,
+                        custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .set_parent(root);
+    }
+    commands.spawn((
+        MapEditorUi,
+        MapEditorText,
+        Name::new("Map Editor Text"),
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                top: Val::Px(12.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            background_color: EDITOR_TEXT_BACKGROUND.into(),
+            z_index: ZIndex::Global(2),
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: EDITOR_TEXT_FONT_SIZE,
+                    color: EDITOR_TEXT_COLOR,
+                },
+            )
+        },
+    ));
+}
+fn stop_editing(mut commands: Commands, ui: Query<Entity, With<MapEditorUi>>) {
+    commands.remove_resource::<MapEditor>();
+    for entity in &ui {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+fn pick_tool(keys: Res<ButtonInput<KeyCode>>, mut editor: ResMut<MapEditor>) {
+    for (key, tool) in EditorTool::ALL {
+        if keys.just_pressed(key) {
+            editor.tool = tool;
+            editor.drag = None;
+        }
+    }
+    if keys.just_pressed(SNAP_KEY) {
+        editor.snap = !editor.snap;
+    }
+}
+fn undo_redo(keys: Res<ButtonInput<KeyCode>>, mut editor: ResMut<MapEditor>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let editor = &mut *editor;
+    let (from, to) = if keys.just_pressed(UNDO_KEY) && !shift {
+        (&mut editor.undo, &mut editor.redo)
+    } else if keys.just_pressed(REDO_KEY) || keys.just_pressed(UNDO_KEY) && shift {
+        (&mut editor.redo, &mut editor.undo)
+    } else {
+        return;
+    };
+    if let Some(map) = from.pop() {
+        to.push(std::mem::replace(&mut editor.map, map));
+        // Whatever was being dragged might not be there anymore.
+        editor.drag = None;
+        editor.stroke = None;
+        editor.balance = None;
+    }
+}
+/// Copy the panels and trigger zones to the clipboard as a board code, or replace them with the
+/// board code on the clipboard.
+fn copy_paste_board_code(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<MapEditor>,
+    // Kept around, since the copied text can go with the clipboard on some platforms.
+    mut clipboard: Local<Option<arboard::Clipboard>>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.any_just_pressed([COPY_KEY, PASTE_KEY])
+    {
+        return;
+    }
+    if clipboard.is_none() {
+        match arboard::Clipboard::new() {
+            Ok(x) => *clipboard = Some(x),
+            Err(e) => {
+                editor.status = format!("No clipboard: {}", e);
+                return;
+            }
+        }
+    }
+    let clipboard = clipboard.as_mut().unwrap();
+    if keys.just_pressed(COPY_KEY) {
+        let panels = editor.map.panels.clone().unwrap_or_default();
+        let code = panels.board_code(&editor.map.trigger_zones.clone().unwrap_or_default());
+        editor.status = match clipboard.set_text(code) {
+            Ok(()) => "Copied the board code".to_string(),
+            Err(e) => format!("Failed to copy: {}", e),
+        };
+        return;
+    }
+    let result = clipboard
+        .get_text()
+        .map_err(|e| e.to_string())
+        .and_then(|x| PanelLayouts::from_board_code(&x));
+    editor.status = match result {
+        Ok((panels, zones)) => {
+            let before = editor.map.clone();
+            editor.map.panels = Some(panels);
+            editor.map.trigger_zones = Some(zones);
+            editor.record(before);
+            editor.drag = None;
+            "Pasted the board code".to_string()
+        }
+        Err(e) => format!("Failed to paste: {}", e),
+    };
+}
+fn edit_map(
+    mut editor: ResMut<MapEditor>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), Without<MatchViewCamera>>,
+    roots: Query<(&GlobalTransform, &PanelRoot, &MatchId)>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|x| camera.viewport_to_world_2d(camera_transform, x))
+    else {
+        return;
+    };
+    let editor = &mut *editor;
+    if buttons.any_just_pressed([MouseButton::Left, MouseButton::Right]) && editor.stroke.is_none()
+    {
+        editor.stroke = Some(editor.map.clone());
+    }
+    // Painting stays on the cells under the cursor, and only what's placed or moved snaps.
+    let snapped = snap(editor.snap, cursor);
+    match editor.tool {
+        EditorTool::NeutralGround | EditorTool::NeutralTiles => {
+            let painted = match editor.tool {
+                EditorTool::NeutralTiles => LayoutCell::Neutral,
+                _ => LayoutCell::Gap,
+            };
+            if buttons.pressed(MouseButton::Left) {
+                editor.set_cell(cursor, painted);
+            } else if buttons.pressed(MouseButton::Right) {
+                editor.set_cell(cursor, LayoutCell::Tile);
+            }
+        }
+        EditorTool::Obstacles => {
+            if buttons.just_pressed(MouseButton::Left) {
+                let grabbed = editor
+                    .map
+                    .obstacles
+                    .iter()
+                    .rposition(|x| x.contains(cursor));
+                editor.drag = Some(match grabbed {
+                    Some(i) => Drag::MoveObstacle(i, cursor - editor.map.obstacles[i].position),
+                    None => Drag::Rectangle(snapped),
+                });
+            }
+            if let Some(Drag::MoveObstacle(i, offset)) = editor.drag {
+                editor.map.obstacles[i].position = snap(editor.snap, cursor - offset);
+            }
+            if buttons.just_released(MouseButton::Left) {
+                if let Some(Drag::Rectangle(start)) = editor.drag {
+                    let obstacle = dragged_obstacle(start, snapped);
+                    if obstacle.half_size.min_element() >= EDITOR_OBSTACLE_HALF_SIZE_MIN {
+                        editor.map.obstacles.push(obstacle);
+                    }
+                }
+                editor.drag = None;
+            }
+            if buttons.just_pressed(MouseButton::Right) {
+                // The last one placed is drawn on top, so it's the one to go.
+                if let Some(i) = editor
+                    .map
+                    .obstacles
+                    .iter()
+                    .rposition(|x| x.contains(cursor))
+                {
+                    editor.map.obstacles.remove(i);
+                }
+            }
+        }
+        EditorTool::Turrets => {
+            if buttons.just_pressed(MouseButton::Left) {
+                editor.drag = editor
+                    .map
+                    .turrets
+                    .iter()
+                    .position(|x| x.distance(cursor) <= EDITOR_TURRET_RADIUS)
+                    .map(Drag::Turret);
+            }
+            if let Some(Drag::Turret(i)) = editor.drag {
+                editor.map.turrets[i] = snapped.clamp(
+                    Vec2::splat(-BATTLEFIELD_HALF_WIDTH),
+                    Vec2::splat(BATTLEFIELD_HALF_WIDTH),
+                );
+            }
+            if buttons.just_released(MouseButton::Left) {
+                editor.drag = None;
+            }
+        }
+        EditorTool::SpeedZones => {
+            if buttons.just_pressed(MouseButton::Left) {
+                let grabbed = editor
+                    .map
+                    .speed_zones
+                    .iter()
+                    .rposition(|x| x.contains(cursor));
+                editor.drag = Some(match grabbed {
+                    Some(i) => Drag::MoveSpeedZone(i, cursor - editor.map.speed_zones[i].position),
+                    None => Drag::Rectangle(snapped),
+                });
+            }
+            if let Some(Drag::MoveSpeedZone(i, offset)) = editor.drag {
+                editor.map.speed_zones[i].position = snap(editor.snap, cursor - offset);
+            }
+            if buttons.just_released(MouseButton::Left) {
+                if let Some(Drag::Rectangle(start)) = editor.drag {
+                    let area = dragged_obstacle(start, snapped);
+                    if area.half_size.min_element() >= EDITOR_OBSTACLE_HALF_SIZE_MIN {
+                        let fast = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+                        editor.map.speed_zones.push(SpeedZone {
+                            position: area.position,
+                            half_size: area.half_size,
+                            angle: area.angle,
+                            factor: match fast {
+                                true => EDITOR_FAST_FACTOR,
+                                false => EDITOR_SLOW_FACTOR,
+                            },
+                        });
+                    }
+                }
+                editor.drag = None;
+            }
+            if buttons.just_pressed(MouseButton::Right) {
+                if let Some(i) = editor
+                    .map
+                    .speed_zones
+                    .iter()
+                    .rposition(|x| x.contains(cursor))
+                {
+                    editor.map.speed_zones.remove(i);
+                }
+            }
+        }
+        EditorTool::Teleporters => {
+            if buttons.just_pressed(MouseButton::Left) {
+                editor.drag = match editor.drag {
+                    Some(Drag::Teleporter(first)) => {
+                        editor.map.teleporters.push((first, snapped));
+                        None
+                    }
+                    _ => Some(Drag::Teleporter(snapped)),
+                };
+            }
+            if buttons.just_pressed(MouseButton::Right) {
+                if let Some(i) = editor.map.teleporters.iter().rposition(|&(a, b)| {
+                    a.distance(cursor) <= EDITOR_TELEPORTER_RADIUS
+                        || b.distance(cursor) <= EDITOR_TELEPORTER_RADIUS
+                }) {
+                    editor.map.teleporters.remove(i);
+                }
+            }
+        }
+        EditorTool::Pegs => {
+            let panels = editor.map.panels.get_or_insert_with(PanelLayouts::default);
+            if buttons.just_pressed(MouseButton::Left) {
+                if let Some((side, point)) = panel_at(&roots, cursor) {
+                    let pegs = &mut panels.get_mut(side).pegs;
+                    let grabbed = pegs
+                        .iter()
+                        .rposition(|x| x.distance(point) <= CIRCLE_RADIUS);
+                    editor.drag = match grabbed {
+                        Some(i) => Some(Drag::Peg(side, i)),
+                        None if pegs.len() < PANEL_PART_COUNT_MAX => {
+                            pegs.push(contain_peg(snap(editor.snap, point)));
+                            Some(Drag::Peg(side, pegs.len() - 1))
+                        }
+                        None => None,
+                    };
+                }
+            }
+            if let Some(Drag::Peg(side, i)) = editor.drag {
+                if let Some(point) = panel_point(&roots, side, cursor) {
+                    panels.get_mut(side).pegs[i] = contain_peg(snap(editor.snap, point));
+                }
+            }
+            if buttons.just_released(MouseButton::Left) {
+                editor.drag = None;
+            }
+            if buttons.just_pressed(MouseButton::Right) {
+                if let Some((side, point)) = panel_at(&roots, cursor) {
+                    let pegs = &mut panels.get_mut(side).pegs;
+                    if let Some(i) = pegs
+                        .iter()
+                        .rposition(|x| x.distance(point) <= CIRCLE_RADIUS)
+                    {
+                        pegs.remove(i);
+                    }
+                }
+            }
+        }
+        EditorTool::Dividers => {
+            let panels = editor.map.panels.get_or_insert_with(PanelLayouts::default);
+            let near = |x: f32, point: Vec2| (x - point.x).abs() <= EDITOR_DIVIDER_GRAB_DISTANCE;
+            if buttons.just_pressed(MouseButton::Left) {
+                if let Some((side, point)) = panel_at(&roots, cursor) {
+                    let dividers = &mut panels.get_mut(side).dividers;
+                    editor.drag = match dividers.iter().rposition(|&x| near(x, point)) {
+                        Some(i) => Some(Drag::Divider(side, i)),
+                        None if dividers.len() < PANEL_PART_COUNT_MAX => {
+                            dividers.push(contain_divider(snap(editor.snap, point).x));
+                            Some(Drag::Divider(side, dividers.len() - 1))
+                        }
+                        None => None,
+                    };
+                }
+            }
+            if let Some(Drag::Divider(side, i)) = editor.drag {
+                if let Some(point) = panel_point(&roots, side, cursor) {
+                    panels.get_mut(side).dividers[i] = contain_divider(snap(editor.snap, point).x);
+                }
+            }
+            if buttons.just_released(MouseButton::Left) {
+                editor.drag = None;
+            }
+            if buttons.just_pressed(MouseButton::Right) {
+                if let Some((side, point)) = panel_at(&roots, cursor) {
+                    let dividers = &mut panels.get_mut(side).dividers;
+                    if let Some(i) = dividers.iter().rposition(|&x| near(x, point)) {
+                        dividers.remove(i);
+                    }
+                }
+            }
+        }
+        EditorTool::TriggerZones => {
+            let step = if buttons.just_pressed(MouseButton::Left) {
+                1
+            } else if buttons.just_pressed(MouseButton::Right) {
+                -1
+            } else {
+                0
+            };
+            let clicked = panel_at(&roots, cursor)
+                .filter(|&(_, point)| step != 0 && point.y <= EDITOR_TRIGGER_ZONE_CLICK_Y_MAX);
+            if let Some((_, point)) = clicked {
+                let zones = editor
+                    .map
+                    .trigger_zones
+                    .get_or_insert_with(TriggerZoneLayout::default);
+                let slot = trigger_zone_slot(point.x);
+                zones.0[slot] = cycle_trigger_type(zones.0[slot], step);
+            }
+        }
+    }
+    if !buttons.any_pressed([MouseButton::Left, MouseButton::Right]) {
+        if let Some(before) = editor.stroke.take() {
+            editor.record(before);
+        }
+    }
+}
+/// `position` moved onto the nearest point of the grid, if snapping is on.
+fn snap(enabled: bool, position: Vec2) -> Vec2 {
+    match enabled {
+        true => (position / EDITOR_SNAP).round() * EDITOR_SNAP,
+        false => position,
+    }
+}
+/// The obstacle spanning the rectangle dragged out from `start` to `end`.
+fn dragged_obstacle(start: Vec2, end: Vec2) -> Obstacle {
+    Obstacle {
+        position: (start + end) / 2.0,
+        half_size: (end - start).abs() / 2.0,
+        angle: 0.0,
+    }
+}
+fn draw_map(
+    mut gizmos: Gizmos,
+    editor: Res<MapEditor>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), Without<MatchViewCamera>>,
+    roots: Query<(&GlobalTransform, &PanelRoot, &MatchId)>,
+) {
+    for (y, row) in editor.map.tiles.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let color = match LayoutCell::from_char(c) {
+                LayoutCell::Tile => EDITOR_TILE_COLOR,
+                LayoutCell::Neutral => EDITOR_NEUTRAL_TILE_COLOR,
+                LayoutCell::Gap => continue,
+            };
+            let size = Vec2::splat(EDITOR_CELL_SIZE * 0.9);
+            gizmos.rect_2d(cell_center(x, y), 0.0, size, color);
+        }
+    }
+    for obstacle in &editor.map.obstacles {
+        gizmos.rect_2d(
+            obstacle.position,
+            obstacle.angle,
+            obstacle.half_size * 2.0,
+            EDITOR_OBSTACLE_COLOR,
+        );
+    }
+    for zone in &editor.map.speed_zones {
+        let color = match zone.factor < 1.0 {
+            true => EDITOR_SLOW_ZONE_COLOR,
+            false => EDITOR_FAST_ZONE_COLOR,
+        };
+        gizmos.rect_2d(zone.position, zone.angle, zone.half_size * 2.0, color);
+    }
+    for &(a, b) in &editor.map.teleporters {
+        gizmos.circle_2d(a, EDITOR_TELEPORTER_RADIUS, EDITOR_TELEPORTER_COLOR);
+        gizmos.circle_2d(b, EDITOR_TELEPORTER_RADIUS, EDITOR_TELEPORTER_COLOR);
+        gizmos.line_2d(a, b, EDITOR_TELEPORTER_COLOR.with_alpha(0.3));
+    }
+    if let Some(Drag::Teleporter(first)) = editor.drag {
+        gizmos.circle_2d(first, EDITOR_TELEPORTER_RADIUS, EDITOR_NEW_OBSTACLE_COLOR);
+    }
+    for (participant, &position) in Participant::ALL.into_iter().zip(&editor.map.turrets) {
+        gizmos.circle_2d(position, EDITOR_TURRET_RADIUS, ball_colors[participant].0);
+    }
+    let panels = editor.map.panels.clone().unwrap_or_default();
+    let zones = editor.map.trigger_zones.clone().unwrap_or_default();
+    for (transform, &PanelRoot(side), _) in roots.iter().filter(|&(.., &x)| x == MatchId::MAIN) {
+        let scale = transform.compute_transform().scale.x;
+        let point = |x: Vec2| transform.transform_point(x.extend(0.0)).xy();
+        let zone_width = ARENA_WIDTH / TRIGGER_ZONE_COUNT as f32;
+        for (slot, trigger_type) in zones.0.into_iter().enumerate() {
+            let x = (slot as f32 + 0.5) * zone_width - ARENA_WIDTH / 2.0;
+            let size = Vec2::new(zone_width, config.physics.trigger_zone_height) * scale;
+            gizmos.rect_2d(
+                point(Vec2::new(x, TRIGGER_ZONE_Y)),
+                0.0,
+                size * 0.9,
+                trigger_type.color(),
+            );
+        }
+        let layout = panels.get(side);
+        for &peg in &layout.pegs {
+            gizmos.circle_2d(point(peg), CIRCLE_RADIUS * scale, EDITOR_PEG_COLOR);
+        }
+        for &x in &layout.dividers {
+            let size = Vec2::new(
+                TRIGGER_ZONE_DIVIDER_RADIUS * 2.0,
+                TRIGGER_ZONE_DIVIDER_LENGTH + TRIGGER_ZONE_DIVIDER_RADIUS * 2.0,
+            );
+            gizmos.rect_2d(
+                point(Vec2::new(x, TRIGGER_ZONE_Y)),
+                0.0,
+                size * scale,
+                EDITOR_PEG_COLOR,
+            );
+        }
+    }
+    let Some(Drag::Rectangle(start)) = editor.drag else {
+        return;
+    };
+    let cursor = windows.get_single().ok().zip(cameras.get_single().ok());
+    let Some(cursor) = cursor.and_then(|(window, (camera, transform))| {
+        camera.viewport_to_world_2d(transform, window.cursor_position()?)
+    }) else {
+        return;
+    };
+    let obstacle = dragged_obstacle(start, snap(editor.snap, cursor));
+    gizmos.rect_2d(
+        obstacle.position,
+        0.0,
+        obstacle.half_size * 2.0,
+        EDITOR_NEW_OBSTACLE_COLOR,
+    );
+}
+/// Write the map to its file and pick it for the next round. A map of the same name that was
+/// loaded on startup is replaced.
+fn save_map(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<MapEditor>,
+    mut maps: ResMut<MapFiles>,
+    mut config: ResMut<GameConfig>,
+) {
+    if !keys.just_pressed(SAVE_KEY) {
+        return;
+    }
+    let path = Path::new(MAP_DIR).join(file_name(&editor.map.name));
+    let result = ron::ser::to_string_pretty(&editor.map, PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|x| {
+            fs::create_dir_all(MAP_DIR)
+                .and_then(|_| fs::write(&path, x))
+                .map_err(|e| e.to_string())
+        });
+    editor.status = match result {
+        Ok(()) => {
+            info!("Saved the map to {}", path.display());
+            match maps.0.iter_mut().find(|x| x.name == editor.map.name) {
+                Some(map) => *map = editor.map.clone(),
+                None => maps.0.push(editor.map.clone()),
+            }
+            config.map_file = Some(editor.map.clone());
+            format!("Saved to {}", path.display())
+        }
+        Err(e) => {
+            error!("Failed to save the map to {}: {}", path.display(), e);
+            format!("Failed to save: {}", e)
+        }
+    };
+}
+fn update_editor_text(
+    mut editor: ResMut<MapEditor>,
+    config: Res<GameConfig>,
+    roster: Res<Roster>,
+    mut texts: Query<&mut Text, With<MapEditorText>>,
+) {
+    if editor.balance.is_none() {
+        editor.balance = Some(MapBalance::check(&editor.map, &roster, &config));
+    }
+    let tools = EditorTool::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &(_, tool))| {
+            let marker = if tool == editor.tool { ">" } else { " " };
+            format!("{} {}: {}", marker, i + 1, tool.name())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let zones = editor
+        .map
+        .trigger_zones
+        .iter()
+        .flat_map(|x| x.0)
+        .map(|x| x.to_string().replace('\n', " "))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let value = format!(
+        "Editing {}\n{}\n{}\nTrigger zones: {}\nG: Snap to grid ({})\nCtrl+Z: Undo ({})\nCtrl+Y: Redo ({})\nCtrl+C/V: Copy/paste board code\nS: Save\nEscape: Back to the menu\n{}",
+        editor.map.name,
+        editor
+            .balance
+            .as_ref()
+            .map_or_else(String::new, |x| x.summary(&config)),
+        tools,
+        zones,
+        if editor.snap { "on" } else { "off" },
+        editor.undo.len(),
+        editor.redo.len(),
+        editor.status
+    );
+    for mut text in &mut texts {
+        text.sections[0].value.clone_from(&value);
+    }
+}
+fn exit_editor(keys: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keys.just_pressed(EXIT_KEY) {
+        next_state.set(AppState::Menu);
+    }
+}
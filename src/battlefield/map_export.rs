@@ -0,0 +1,215 @@
+use std::{fmt::Write, fs, path::Path};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use super::{RoundNumber, Tile, TileGrid, TILE_DIMENSION};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    utils::{MatchId, Participant, ParticipantMap, PerMatch, TileColor},
+};
+
+pub struct MapExportPlugin;
+impl Plugin for MapExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameOver), export_map);
+    }
+}
+
+/// Save the territory map each match ended the round on as an SVG, for posters and thumbnails, and
+/// as a PNG next to it if the config asks for one.
+fn export_map(
+    config: Res<GameConfig>,
+    round: Res<RoundNumber>,
+    grids: Res<PerMatch<TileGrid>>,
+    colors: Res<ParticipantMap<TileColor>>,
+    tiles: Query<&Participant, With<Tile>>,
+) {
+    let config = &config.map_export;
+    if !config.enabled {
+        return;
+    }
+    for (match_id, grid) in grids.iter() {
+        let name = match match_id {
+            MatchId::MAIN => format!("round-{}", round.0),
+            MatchId(x) => format!("round-{}-match-{}", round.0, x),
+        };
+        let path = Path::new(&config.directory).join(name);
+        let svg = territory_svg(grid, &colors, &tiles, config.pixels_per_tile);
+        let svg_path = path.with_extension("svg");
+        let result = fs::create_dir_all(&config.directory).and_then(|_| fs::write(&svg_path, svg));
+        match result {
+            Ok(()) => info!("Saved the territory map to {}", svg_path.display()),
+            Err(e) => error!(
+                "Failed to save the territory map to {}: {}",
+                svg_path.display(),
+                e
+            ),
+        }
+        if !config.png {
+            continue;
+        }
+        let (width, height, rgba) = rasterize(grid, config.pixels_per_tile, |x, y| {
+            let owner = tiles.get(grid.get(x, y)).ok()?;
+            Some(colors.get(*owner).0.to_srgba().to_u8_array())
+        });
+        let image = Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            rgba,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        let png_path = path.with_extension("png");
+        let result = image
+            .try_into_dynamic()
+            .map_err(|e| e.to_string())
+            .and_then(|x| x.save(&png_path).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!("Saved the territory map to {}", png_path.display()),
+            Err(e) => error!(
+                "Failed to save the territory map to {}: {}",
+                png_path.display(),
+                e
+            ),
+        }
+    }
+}
+
+/// Draw the owner of every tile in the grid, with one unit per tile. Runs of tiles along a row with
+/// the same owner are drawn as a single rectangle, which keeps the file small, since most of a
+/// finished map is made up of large patches of territory. Hexagon tiles are drawn as rectangles too,
+/// staggered the same way as their rows.
+fn territory_svg(
+    grid: &TileGrid,
+    colors: &ParticipantMap<TileColor>,
+    tiles: &Query<&Participant, With<Tile>>,
+    pixels_per_tile: u32,
+) -> String {
+    let size = TileGrid::SIZE;
+    let row_height = grid.row_height() / TILE_DIMENSION;
+    let height = grid.rows() as f32 * row_height;
+    let (width_pixels, height_pixels) = (
+        size as u32 * pixels_per_tile,
+        (height * pixels_per_tile as f32).round() as u32,
+    );
+    // Writing to a `String` can't fail.
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width_pixels}" height="{height_pixels}" viewBox="0 0 {size} {height}" shape-rendering="crispEdges">"#
+    );
+    for y in 0..grid.rows() {
+        // The grid counts rows from the bottom, while SVG counts them from the top.
+        let top = (grid.rows() - 1 - y) as f32 * row_height;
+        let columns = grid.columns(y);
+        let owner_at = |x: usize| tiles.get(grid.get(x, y)).ok().copied();
+        let mut x = 0;
+        while x < columns {
+            let owner = owner_at(x);
+            let start = x;
+            while x < columns && owner_at(x) == owner {
+                x += 1;
+            }
+            let Some(owner) = owner else {
+                continue;
+            };
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                start as f32 + grid.row_offset(y),
+                top,
+                x - start,
+                row_height,
+                colors.get(owner).0.to_srgba().to_hex()
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+/// Draw the tiles of the grid into RGBA pixels, `pixels_per_tile` to a tile across, with the
+/// color `color_at` gives each one, or left transparent where it gives none. Hexagon tiles are drawn
+/// as rectangles, the same as in the SVG. Returns the width and height along with the pixels.
+fn rasterize(
+    grid: &TileGrid,
+    pixels_per_tile: u32,
+    color_at: impl Fn(usize, usize) -> Option<[u8; 4]>,
+) -> (u32, u32, Vec<u8>) {
+    let row_height = grid.row_height() / TILE_DIMENSION;
+    let scale = pixels_per_tile as f32;
+    let width = TileGrid::SIZE as u32 * pixels_per_tile;
+    let height = (grid.rows() as f32 * row_height * scale).round() as u32;
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for pixel_y in 0..height {
+        // Sample the middle of each pixel. Like in the SVG, the image counts rows from the top.
+        let row_from_top = ((pixel_y as f32 + 0.5) / scale / row_height) as usize;
+        let y = grid.rows() - 1 - row_from_top.min(grid.rows() - 1);
+        for pixel_x in 0..width {
+            let x = (pixel_x as f32 + 0.5) / scale - grid.row_offset(y);
+            let color = (x >= 0.0 && (x as usize) < grid.columns(y))
+                .then(|| color_at(x as usize, y))
+                .flatten();
+            rgba.extend(color.unwrap_or([0; 4]));
+        }
+    }
+    (width, height, rgba)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TileShape;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    #[test]
+    fn square_tiles_fill_the_image() {
+        let grid = TileGrid::new(TileShape::Square);
+        let (width, height, rgba) = rasterize(&grid, 2, |x, y| {
+            Some(if (x + y) % 2 == 0 { RED } else { BLUE })
+        });
+        assert_eq!(
+            (width, height),
+            (TileGrid::SIZE as u32 * 2, TileGrid::SIZE as u32 * 2)
+        );
+        assert_eq!(rgba.len(), (width * height * 4) as usize);
+        let pixel = |x: u32, y: u32| &rgba[((y * width + x) * 4) as usize..][..4];
+        // The bottom left tile is the first cell of the grid, drawn at the bottom of the image.
+        assert_eq!(pixel(0, height - 1), RED);
+        assert_eq!(pixel(1, height - 2), RED);
+        assert_eq!(pixel(2, height - 1), BLUE);
+        assert_eq!(pixel(0, height - 3), BLUE);
+    }
+
+    #[test]
+    fn hexagon_rows_are_staggered() {
+        let grid = TileGrid::new(TileShape::Hexagon);
+        let (width, height, rgba) = rasterize(&grid, 4, |_, _| Some(RED));
+        let pixel = |x: u32, y: u32| &rgba[((y * width + x) * 4) as usize..][..4];
+        let mut offset_rows = 0;
+        for y in 0..height {
+            let row = grid.rows()
+                - 1
+                - ((y as f32 + 0.5) / 4.0 * TILE_DIMENSION / grid.row_height()) as usize;
+            if grid.row_offset(row.min(grid.rows() - 1)) > 0.0 {
+                offset_rows += 1;
+                assert_eq!(pixel(0, y), [0; 4]);
+            } else {
+                assert_eq!(pixel(0, y), RED);
+            }
+        }
+        assert!(offset_rows > 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! Custom maps loaded from `assets/maps/`, so new layouts can be made without touching the code.
+//!
+//! Each map is a RON file:
+//!
+//! ```ron
+//! (
+//!     name: "Crossfire",
+//!     // Top to bottom, stretched over the whole battlefield. A `.` or space leaves that part
+//!     // without tiles, an `X` fills it with neutral tiles that can't be converted and stop any
+//!     // bullet that hits them, and anything else has regular tiles. Leave it out to fill the arena.
+//!     tiles: [
+//!         "########",
+//!         "###..###",
+//!         "##XXXX##",
+//!         "########",
+//!     ],
+//!     // Rectangles bullets bounce off. The angle is in radians and can be left out.
+//!     obstacles: [
+//!         (position: (0.0, 0.0), half_size: (20.0, 60.0), angle: 0.0),
+//!     ],
+//!     // Where each turret sits, starting with A. Participants past the end keep their usual spot.
+//!     turrets: [(200.0, 200.0), (-200.0, 200.0), (200.0, -200.0), (-200.0, -200.0)],
+//!     // Pairs of teleporters. A bullet that runs into either end comes out of the other, keeping
+//!     // its velocity.
+//!     teleporters: [((-300.0, 0.0), (300.0, 0.0))],
+//!     // Rectangles that multiply the velocity of the bullets inside them by `factor`, so below 1
+//!     // is mud that slows them down and above 1 is a fast lane.
+//!     speed_zones: [
+//!         (position: (0.0, 150.0), half_size: (120.0, 20.0), angle: 0.0, factor: 0.5),
+//!     ],
+//!     // The trigger zones from left to right. Leave it out to keep the ones already picked.
+//!     trigger_zones: Some((BurstShot, Multiply(2), Gamble, Multiply(2), ChargedShot)),
+//!     // Where the pegs and the trigger zone dividers go in each panel, relative to its center. The
+//!     // panels are 260 wide and 700 tall, the pegs have to stay between -175 and 305 up, and the
+//!     // dividers stand at the bottom. Leave it out for the usual pegs.
+//!     panels: Some((
+//!         left: (pegs: [(0.0, 250.0), (-32.5, 222.0), (32.5, 222.0)], dividers: [-26.0, 26.0]),
+//!         right: (pegs: [(0.0, 100.0)], dividers: [0.0]),
+//!     )),
+//! )
+//! ```
+//!
+//! The battlefield is centered on the origin and reaches 360 out each way. The maps are read once on
+//! startup and picked from the main menu, which also opens the map editor in
+//! [`super::map_editor`].
+
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    map_balance::MapBalance, map_generator::Obstacle, speed_zone::SpeedZone, BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    config::{GameConfig, TriggerZoneLayout},
+    panel_plugin::PanelLayouts,
+    utils::{Participant, Roster},
+};
+
+// Constants {{{
+
+pub const MAP_DIR: &str = "assets/maps";
+pub const MAP_EXTENSION: &str = "ron";
+
+// }}}
+
+pub struct MapFilePlugin;
+impl Plugin for MapFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MapFiles::load());
+    }
+}
+
+/// A custom map. See the module documentation for the format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub struct MapFile {
+    pub name: String,
+    pub tiles: Vec<String>,
+    pub obstacles: Vec<Obstacle>,
+    pub turrets: Vec<Vec2>,
+    pub teleporters: Vec<(Vec2, Vec2)>,
+    pub speed_zones: Vec<SpeedZone>,
+    pub trigger_zones: Option<TriggerZoneLayout>,
+    pub panels: Option<PanelLayouts>,
+}
+impl MapFile {
+    /// Where the map puts `participant`'s turret, if it places it at all.
+    pub fn turret_position(&self, participant: Participant) -> Option<Vec2> {
+        self.turrets.get(participant as usize).copied()
+    }
+}
+/// What a tile layout puts in each spot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutCell {
+    /// A regular tile, owned by whoever's turret is closest.
+    Tile,
+    /// No tile at all.
+    Gap,
+    /// A tile nobody owns, which stops bullets instead of being converted.
+    Neutral,
+}
+impl LayoutCell {
+    pub fn from_char(c: char) -> Self {
+        match c {
+            '.' | ' ' => LayoutCell::Gap,
+            'X' | 'x' => LayoutCell::Neutral,
+            _ => LayoutCell::Tile,
+        }
+    }
+    pub fn to_char(self) -> char {
+        match self {
+            LayoutCell::Tile => '#',
+            LayoutCell::Gap => '.',
+            LayoutCell::Neutral => 'X',
+        }
+    }
+}
+/// Look up `position` in a tile layout, which is stretched over the whole battlefield. An empty
+/// layout has regular tiles everywhere.
+pub fn layout_cell_at(tiles: &[String], position: Vec2) -> LayoutCell {
+    if tiles.is_empty() {
+        return LayoutCell::Tile;
+    }
+    let fraction = |x: f32| ((x / BATTLEFIELD_HALF_WIDTH + 1.0) / 2.0).clamp(0.0, 1.0);
+    let y = ((1.0 - fraction(position.y)) * tiles.len() as f32) as usize;
+    let row = &tiles[y.min(tiles.len() - 1)];
+    let width = row.chars().count();
+    let x = (fraction(position.x) * width as f32) as usize;
+    row.chars()
+        .nth(x.min(width.max(1) - 1))
+        .map_or(LayoutCell::Gap, LayoutCell::from_char)
+}
+
+/// Every map found in [`MAP_DIR`] on startup, sorted by file name.
+#[derive(Debug, Default, Resource)]
+pub struct MapFiles(pub Vec<MapFile>);
+impl MapFiles {
+    /// Read every map, warning about the ones that give some participants a lot more of the
+    /// battlefield than others with the default settings.
+    fn load() -> Self {
+        let mut paths = match fs::read_dir(MAP_DIR) {
+            Ok(entries) => entries
+                .filter_map(|x| x.ok().map(|x| x.path()))
+                .filter(|x| x.extension().is_some_and(|x| x == MAP_EXTENSION))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                info!("No custom maps loaded from {}: {}", MAP_DIR, e);
+                return Self::default();
+            }
+        };
+        paths.sort();
+        let config = GameConfig::default();
+        let maps = paths
+            .into_iter()
+            .filter_map(|path| {
+                let result = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|x| ron::from_str::<MapFile>(&x).map_err(|e| e.to_string()));
+                match result {
+                    Ok(mut map) => {
+                        if map.name.is_empty() {
+                            let stem = path.file_stem().unwrap_or_default();
+                            map.name = stem.to_string_lossy().into_owned();
+                        }
+                        if let Some(panels) = &mut map.panels {
+                            panels.contain();
+                        }
+                        let balance = MapBalance::check(&map, &Roster::default(), &config);
+                        if balance.is_lopsided() {
+                            warn!(
+                                "The map {} is lopsided. {}",
+                                map.name,
+                                balance.summary(&config)
+                            );
+                        }
+                        Some(map)
+                    }
+                    Err(e) => {
+                        warn!("Failed to load the map {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self(maps)
+    }
+}
@@ -0,0 +1,226 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    geometry::update_geometry,
+    map_file::{layout_cell_at, LayoutCell, MapFile},
+    BattlefieldGeometry, RestartEvent, BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    collision_groups,
+    config::GameConfig,
+    utils::{GameRng, MatchId, Matches, Roster, RoundScoped},
+};
+
+// Constants {{{
+
+const OBSTACLE_HALF_SIZE_MIN: f32 = 8.0;
+const OBSTACLE_HALF_SIZE_MAX: f32 = 40.0;
+const OBSTACLE_COLOR: Color = Color::srgb(0.3, 0.3, 0.32);
+/// Above the tiles, so the ones partly covered don't poke out.
+const OBSTACLE_Z: f32 = -0.5;
+const NEUTRAL_REGION_RADIUS_MIN: f32 = 20.0;
+const NEUTRAL_REGION_RADIUS_MAX: f32 = 50.0;
+/// How close anything generated can come to a turret.
+const TURRET_CLEARANCE: f32 = 80.0;
+/// The number of random spots tried for each obstacle or neutral region before giving up on it.
+const PLACEMENT_ATTEMPTS: usize = 20;
+/// The signs that mirror a position in the top right quarter into each of the four quarters.
+pub const MIRRORS: [Vec2; 4] = [
+    Vec2::new(1.0, 1.0),
+    Vec2::new(-1.0, 1.0),
+    Vec2::new(1.0, -1.0),
+    Vec2::new(-1.0, -1.0),
+];
+
+// }}}
+
+pub struct MapGeneratorPlugin;
+impl Plugin for MapGeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GeneratedMap>()
+            .add_systems(
+                Startup,
+                generate_map.after(update_geometry).before(super::setup),
+            )
+            .add_systems(
+                Update,
+                generate_map
+                    .after(update_geometry)
+                    .before(super::restart)
+                    .run_if(on_event::<RestartEvent>()),
+            );
+    }
+}
+
+/// The obstacles and neutral ground of the current round. Empty unless the generator is enabled or
+/// a custom map is picked.
+#[derive(Resource, Default)]
+pub struct GeneratedMap {
+    obstacles: Vec<Obstacle>,
+    /// Circles of neutral ground, given as their center and radius.
+    neutral_regions: Vec<(Vec2, f32)>,
+    /// The tile layout of a custom map. See [`MapFile::tiles`].
+    tiles: Vec<String>,
+}
+impl GeneratedMap {
+    pub(super) fn from_file(file: &MapFile) -> Self {
+        Self {
+            obstacles: file.obstacles.clone(),
+            tiles: file.tiles.clone(),
+            ..default()
+        }
+    }
+    /// Place everything in the top right quarter and mirror it into the other three, as long as
+    /// every copy is clear. Not every turret layout is symmetric the same way, so one copy being
+    /// clear doesn't mean they all are.
+    fn generate(config: &GameConfig, geometry: &BattlefieldGeometry, rng: &mut impl Rng) -> Self {
+        let is_clear = |position, extent| is_clear_mirrored(geometry, position, extent);
+        let mut map = Self::default();
+        for _ in 0..config.map_generator.obstacles {
+            let obstacle = (0..PLACEMENT_ATTEMPTS)
+                .map(|_| Obstacle {
+                    position: random_quarter_position(rng),
+                    half_size: Vec2::new(
+                        rng.gen_range(OBSTACLE_HALF_SIZE_MIN..OBSTACLE_HALF_SIZE_MAX),
+                        rng.gen_range(OBSTACLE_HALF_SIZE_MIN..OBSTACLE_HALF_SIZE_MAX),
+                    ),
+                    angle: rng.gen_range(0.0..PI),
+                })
+                .find(|x| is_clear(x.position, x.half_size.length()));
+            if let Some(obstacle) = obstacle {
+                map.obstacles
+                    .extend(MIRRORS.map(|sign| obstacle.mirrored(sign)));
+            }
+        }
+        for _ in 0..config.map_generator.neutral_regions {
+            let region = (0..PLACEMENT_ATTEMPTS)
+                .map(|_| {
+                    (
+                        random_quarter_position(rng),
+                        rng.gen_range(NEUTRAL_REGION_RADIUS_MIN..NEUTRAL_REGION_RADIUS_MAX),
+                    )
+                })
+                .find(|&(center, radius)| is_clear(center, radius));
+            if let Some((center, radius)) = region {
+                map.neutral_regions
+                    .extend(MIRRORS.map(|sign| (center * sign, radius)));
+            }
+        }
+        map
+    }
+    /// What kind of tile belongs at `position`. There's none under an obstacle or on neutral
+    /// ground, and otherwise it's up to the tile layout.
+    pub fn cell_at(&self, position: Vec2) -> LayoutCell {
+        let cleared = self.obstacles.iter().any(|x| x.contains(position))
+            || self
+                .neutral_regions
+                .iter()
+                .any(|&(center, radius)| center.distance(position) <= radius);
+        match cleared {
+            true => LayoutCell::Gap,
+            false => layout_cell_at(&self.tiles, position),
+        }
+    }
+}
+/// Whether something reaching `extent` out from `position` fits in the arena without crowding any
+/// turret, and so do its copies mirrored into the other three quarters.
+pub fn is_clear_mirrored(geometry: &BattlefieldGeometry, position: Vec2, extent: f32) -> bool {
+    MIRRORS.into_iter().all(|sign| {
+        let position = position * sign;
+        geometry.shape.contains(position, extent)
+            && geometry
+                .turret_positions()
+                .all(|x| x.distance(position) > TURRET_CLEARANCE + extent)
+    })
+}
+pub fn random_quarter_position(rng: &mut impl Rng) -> Vec2 {
+    Vec2::new(
+        rng.gen_range(0.0..BATTLEFIELD_HALF_WIDTH),
+        rng.gen_range(0.0..BATTLEFIELD_HALF_WIDTH),
+    )
+}
+/// A rectangle bullets bounce off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub struct Obstacle {
+    pub position: Vec2,
+    pub half_size: Vec2,
+    /// In radians, counterclockwise.
+    pub angle: f32,
+}
+impl Obstacle {
+    pub fn contains(&self, point: Vec2) -> bool {
+        let local = Vec2::from_angle(-self.angle).rotate(point - self.position);
+        local.abs().cmple(self.half_size).all()
+    }
+    /// This obstacle flipped along the axes where `sign` is negative.
+    fn mirrored(self, sign: Vec2) -> Self {
+        Self {
+            position: self.position * sign,
+            angle: self.angle * sign.x * sign.y,
+            ..self
+        }
+    }
+}
+#[derive(Component)]
+struct GeneratedObstacle;
+
+/// Lay out a fresh map for the round about to start, replacing the obstacles of the last one. This
+/// runs before the battlefield is set up, so the tiles can leave room for it.
+fn generate_map(
+    mut commands: Commands,
+    mut map: ResMut<GeneratedMap>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    mut events: EventReader<RestartEvent>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    obstacles: Query<(Entity, &MatchId), With<GeneratedObstacle>>,
+) {
+    let matches = RestartEvent::matches(&mut events, &roster, &matches);
+    for (entity, match_id) in &obstacles {
+        if matches.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    *map = match (&config.map_file, config.map_generator.enabled) {
+        (Some(file), _) => GeneratedMap::from_file(file),
+        (None, true) => GeneratedMap::generate(&config, &geometry, &mut rng.0),
+        (None, false) => GeneratedMap::default(),
+    };
+    for &match_id in &matches {
+        for obstacle in &map.obstacles {
+            commands.spawn((
+                Name::new("Obstacle"),
+                (GeneratedObstacle, RoundScoped, match_id),
+                RigidBody::Fixed,
+                Collider::cuboid(obstacle.half_size.x, obstacle.half_size.y),
+                CollisionGroups::new(
+                    collision_groups::BATTLEFIELD_ROOT,
+                    collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+                ),
+                Restitution {
+                    coefficient: 1.0,
+                    combine_rule: CoefficientCombineRule::Max,
+                },
+                SpriteBundle {
+                    transform: Transform::from_translation(
+                        (match_id.world_offset() + obstacle.position).extend(OBSTACLE_Z),
+                    )
+                    .with_rotation(Quat::from_rotation_z(obstacle.angle)),
+                    sprite: Sprite {
+                        color: OBSTACLE_COLOR,
+                        custom_size: Some(obstacle.half_size * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        }
+    }
+}
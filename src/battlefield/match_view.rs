@@ -0,0 +1,126 @@
+//! Cameras for playing several matches side by side. Each match is played out at its own spot in
+//! the world, and gets a slice of the window looking at it, left to right in match order. The main
+//! camera always looks at the first match.
+
+use bevy::{
+    prelude::*,
+    render::{camera::Viewport, view::RenderLayers},
+    window::PrimaryWindow,
+};
+
+use crate::utils::{Matches, Roster};
+
+pub struct MatchViewPlugin;
+impl Plugin for MatchViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_match_views
+                    .run_if(resource_changed::<Roster>.or_else(resource_changed::<Matches>)),
+                fit_match_views,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Marker for the cameras added next to the main one when more than one match is running. Holds
+/// the slice of the window the camera draws to, or `None` for the camera that draws the UI across
+/// the whole window.
+#[derive(Component, Clone, Copy)]
+pub struct MatchViewCamera(Option<u32>);
+
+/// Add a camera for each running match after the first, plus one for the UI so it isn't squeezed
+/// into the first match's slice.
+fn spawn_match_views(
+    mut commands: Commands,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    main_camera: Query<(&Transform, &OrthographicProjection), Without<MatchViewCamera>>,
+    views: Query<Entity, With<MatchViewCamera>>,
+) {
+    for entity in &views {
+        commands.entity(entity).despawn_recursive();
+    }
+    let running = matches.running(&roster);
+    let Ok((transform, projection)) = main_camera.get_single() else {
+        return;
+    };
+    if running.len() < 2 {
+        return;
+    }
+    for (slot, &match_id) in running.iter().enumerate().skip(1) {
+        commands.spawn((
+            Name::new(format!("Match Camera {}", slot)),
+            MatchViewCamera(Some(slot as u32)),
+            Camera2dBundle {
+                camera: Camera {
+                    order: slot as isize,
+                    // Clearing would wipe out the slices drawn before this one.
+                    clear_color: ClearColorConfig::None,
+                    ..default()
+                },
+                projection: projection.clone(),
+                transform: Transform {
+                    translation: transform.translation + match_id.world_offset().extend(0.0),
+                    ..*transform
+                },
+                ..default()
+            },
+        ));
+    }
+    commands.spawn((
+        Name::new("UI Camera"),
+        MatchViewCamera(None),
+        IsDefaultUiCamera,
+        RenderLayers::none(),
+        Camera2dBundle {
+            camera: Camera {
+                order: running.len() as isize,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+/// Split the window between the main camera and the match cameras, keeping up with it being
+/// resized.
+fn fit_match_views(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut main_camera: Query<&mut Camera, (With<Camera2d>, Without<MatchViewCamera>)>,
+    mut views: Query<(&mut Camera, &MatchViewCamera)>,
+) {
+    let (Ok(window), Ok(mut main_camera)) = (windows.get_single(), main_camera.get_single_mut())
+    else {
+        return;
+    };
+    let count = 1 + views.iter().filter(|(_, view)| view.0.is_some()).count() as u32;
+    let size = UVec2::new(window.physical_width() / count, window.physical_height());
+    let viewport = |slot: u32| {
+        (count > 1 && size.x > 0).then(|| Viewport {
+            physical_position: UVec2::new(slot * size.x, 0),
+            physical_size: size,
+            ..default()
+        })
+    };
+    let set = |camera: &mut Mut<Camera>, viewport: Option<Viewport>| {
+        let current = camera
+            .viewport
+            .as_ref()
+            .map(|x| (x.physical_position, x.physical_size));
+        if current
+            != viewport
+                .as_ref()
+                .map(|x| (x.physical_position, x.physical_size))
+        {
+            camera.viewport = viewport;
+        }
+    };
+    set(&mut main_camera, viewport(0));
+    for (mut camera, &MatchViewCamera(slot)) in &mut views {
+        if let Some(slot) = slot {
+            set(&mut camera, viewport(slot));
+        }
+    }
+}
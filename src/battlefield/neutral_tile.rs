@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    drafted_fortification, update_charge_level, BattlefieldGeometry, Bullet, Charge, TileBundle,
+    TileGrid, TileRoot, TileStyle, TILE_Z,
+};
+use crate::{
+    collision_groups,
+    config::GameConfig,
+    draft::DraftedUpgrades,
+    utils::{GameAssets, MatchId, Participant, ParticipantMap, PerMatch, RoundScoped, TileColor},
+};
+
+// Constants {{{
+
+const NEUTRAL_TILE_COLOR: Color = Color::srgb(0.55, 0.55, 0.5);
+const UNCLAIMED_TILE_COLOR: Color = Color::srgb(0.4, 0.38, 0.36);
+
+// }}}
+
+pub struct NeutralTilePlugin;
+impl Plugin for NeutralTilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (absorb_bullets, claim_tiles.before(update_charge_level))
+                .run_if(on_event::<CollisionEvent>()),
+        );
+    }
+}
+
+/// A tile nobody owns, placed by a custom map or left behind by the shrinking battlefield. No bullet
+/// can convert it, and any bullet that hits it is stopped dead, so maps can wall off chokepoints and
+/// safe lanes. It's a child of the tile root like the regular tiles, but isn't a [`super::Tile`]
+/// and isn't kept in the [`super::TileGrid`], so it never counts toward anyone's territory.
+///
+/// Territory that decayed in battle royale or was swept by the hazard bar is left as neutral tiles
+/// too, but those let bullets through, since they're there to be taken back. See [`UnclaimedTile`].
+#[derive(Component, Clone, Copy)]
+struct NeutralTile {
+    stops_bullets: bool,
+}
+/// Neutral ground anyone can take, at this cell of the [`TileGrid`]. The first bullet to hit it
+/// turns it back into a regular tile owned by whoever fired it.
+#[derive(Component)]
+struct UnclaimedTile {
+    x: usize,
+    y: usize,
+}
+#[derive(Bundle)]
+pub struct NeutralTileBundle {
+    markers: (NeutralTile, Sensor, RoundScoped),
+    match_id: MatchId,
+    sprite_bundle: SpriteBundle,
+    collider: Collider,
+    collision_groups: CollisionGroups,
+    name: Name,
+}
+impl NeutralTileBundle {
+    pub fn new(match_id: MatchId, position: Vec2, style: &TileStyle) -> Self {
+        Self {
+            markers: (
+                NeutralTile {
+                    stops_bullets: true,
+                },
+                Sensor,
+                RoundScoped,
+            ),
+            match_id,
+            sprite_bundle: SpriteBundle {
+                transform: Transform::from_translation(position.extend(TILE_Z)),
+                sprite: Sprite {
+                    color: NEUTRAL_TILE_COLOR,
+                    custom_size: Some(style.size),
+                    ..default()
+                },
+                texture: style.texture.clone(),
+                ..default()
+            },
+            collider: style.collider.clone(),
+            collision_groups: CollisionGroups::new(
+                collision_groups::HAZARDS,
+                collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+            ),
+            name: Name::new("Neutral Tile"),
+        }
+    }
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.sprite_bundle.sprite.color = color;
+        self
+    }
+    /// Let bullets pass through instead of stopping them.
+    pub fn passable(mut self) -> Self {
+        self.markers.0.stops_bullets = false;
+        self
+    }
+}
+
+/// Take the tile at `x`, `y` of `match_id`'s grid away from its owner, leaving neutral ground anyone
+/// can take instead.
+pub fn unclaim_tile(
+    commands: &mut Commands,
+    match_id: MatchId,
+    grid: &mut TileGrid,
+    x: usize,
+    y: usize,
+    style: &TileStyle,
+    tile_root: Entity,
+) {
+    commands.entity(grid.get(x, y)).despawn_recursive();
+    grid.set(x, y, Entity::PLACEHOLDER);
+    commands
+        .spawn((
+            NeutralTileBundle::new(match_id, grid.cell_center(x, y), style)
+                .with_color(UNCLAIMED_TILE_COLOR)
+                .passable(),
+            UnclaimedTile { x, y },
+        ))
+        .set_parent(tile_root);
+}
+
+fn absorb_bullets(
+    mut collision_events: EventReader<CollisionEvent>,
+    tiles: Query<&NeutralTile>,
+    mut bullets: Query<&mut Charge, With<Bullet>>,
+) {
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let stops_bullets = |x| tiles.get(x).is_ok_and(|x| x.stops_bullets);
+        let bullet = if stops_bullets(a) {
+            b
+        } else if stops_bullets(b) {
+            a
+        } else {
+            continue;
+        };
+        // A bullet with no charge left gets despawned by `update_charge_level`.
+        if let Ok(mut charge) = bullets.get_mut(bullet) {
+            charge.value = 0;
+        }
+    }
+}
+/// Give unclaimed ground to whoever's bullet hits it first, at the cost of one charge like any other
+/// tile.
+fn claim_tiles(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut grids: ResMut<PerMatch<TileGrid>>,
+    mut bullets: Query<(&Participant, &mut Charge), With<Bullet>>,
+    unclaimed: Query<(&UnclaimedTile, &MatchId)>,
+    tile_roots: Query<(Entity, &MatchId), With<TileRoot>>,
+    tile_colors: Res<ParticipantMap<TileColor>>,
+    (config, upgrades): (Res<GameConfig>, Res<DraftedUpgrades>),
+    geometry: Res<BattlefieldGeometry>,
+    assets: Res<GameAssets>,
+) {
+    // The same tile can be hit by more than one bullet in a frame, but only the first one takes it.
+    let mut claimed = HashSet::new();
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let (tile, bullet) = if unclaimed.contains(a) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let Ok((&UnclaimedTile { x, y }, &match_id)) = unclaimed.get(tile) else {
+            continue;
+        };
+        let (Some(grid), Some((tile_root, _))) = (
+            grids.get_mut(match_id),
+            tile_roots.iter().find(|(_, &x)| x == match_id),
+        ) else {
+            continue;
+        };
+        let Ok((&owner, mut charge)) = bullets.get_mut(bullet) else {
+            continue;
+        };
+        if charge.value == 0 || !claimed.insert(tile) {
+            continue;
+        }
+        charge.value -= 1;
+        commands.entity(tile).despawn_recursive();
+        let style = TileStyle::new(grid.shape, &assets);
+        let position = grid.cell_center(x, y);
+        let tile = commands
+            .spawn(TileBundle::new(
+                match_id,
+                owner,
+                tile_colors.get(owner).0,
+                position.x,
+                position.y,
+                &style,
+                &drafted_fortification(&config, &upgrades, owner),
+                &config.tile_hit_points,
+                geometry.turret_position(owner),
+            ))
+            .set_parent(tile_root)
+            .id();
+        grid.set(x, y, tile);
+    }
+}
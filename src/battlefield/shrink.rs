@@ -0,0 +1,109 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::{
+    neutral_tile::NeutralTileBundle, BattlefieldGeometry, RestartEvent, TileGrid, TileRoot,
+    TileStyle, Turret,
+};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    ui::AnnouncementEvent,
+    utils::{GameAssets, MatchId, Participant, PerMatch},
+};
+
+// Constants {{{
+
+const DEAD_ZONE_COLOR: Color = Color::srgb(0.2, 0.18, 0.18);
+const SHRINK_ANNOUNCEMENT_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
+
+// }}}
+
+pub struct ShrinkPlugin;
+impl Plugin for ShrinkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Shrink>().add_systems(
+            Update,
+            (
+                shrink_battlefield.run_if(in_state(AppState::Playing)),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// How long the current round has been going and how far in the edge of the battlefield has moved
+/// for it.
+#[derive(Resource, Default)]
+struct Shrink {
+    stopwatch: Stopwatch,
+    margin: f32,
+}
+
+/// Close the edge of the battlefield in by another step whenever one is due, turning the tiles left
+/// outside into dead zone and pushing the turrets in ahead of it.
+fn shrink_battlefield(
+    mut commands: Commands,
+    mut shrink: ResMut<Shrink>,
+    mut geometry: ResMut<BattlefieldGeometry>,
+    mut grids: ResMut<PerMatch<TileGrid>>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    mut turrets: Query<(&Participant, &mut Transform), With<Turret>>,
+    tile_roots: Query<(Entity, &MatchId), With<TileRoot>>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    time: Res<Time>,
+) {
+    let config = &config.shrink;
+    if !config.enabled {
+        return;
+    }
+    shrink.stopwatch.tick(time.delta());
+    let elapsed = shrink.stopwatch.elapsed_secs() - config.start_secs;
+    if elapsed < 0.0 {
+        return;
+    }
+    let steps = (elapsed / config.interval_secs).floor() + 1.0;
+    let margin = (steps * config.step).min(config.max_shrink);
+    if margin <= shrink.margin {
+        return;
+    }
+    if shrink.margin == 0.0 {
+        announcements.send(AnnouncementEvent {
+            message: "The Battlefield Is Shrinking!".to_string(),
+            color: SHRINK_ANNOUNCEMENT_COLOR,
+        });
+    }
+    shrink.margin = margin;
+
+    for (tile_root, &match_id) in &tile_roots {
+        let Some(grid) = grids.get_mut(match_id) else {
+            continue;
+        };
+        let style = TileStyle::new(grid.shape, &assets);
+        for y in 0..grid.rows() {
+            for x in 0..grid.columns(y) {
+                let tile = grid.get(x, y);
+                let position = grid.cell_center(x, y);
+                if tile == Entity::PLACEHOLDER || geometry.shape.contains(position, margin) {
+                    continue;
+                }
+                commands.entity(tile).despawn_recursive();
+                grid.set(x, y, Entity::PLACEHOLDER);
+                commands
+                    .spawn(
+                        NeutralTileBundle::new(match_id, position, &style)
+                            .with_color(DEAD_ZONE_COLOR),
+                    )
+                    .set_parent(tile_root);
+            }
+        }
+    }
+    geometry.pull_turrets_inside(margin);
+    for (&participant, mut transform) in &mut turrets {
+        let position = geometry.turret_position(participant);
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+fn restart(mut shrink: ResMut<Shrink>) {
+    *shrink = Shrink::default();
+}
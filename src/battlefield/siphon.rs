@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{update_charge_level, Bullet, Charge, NewBullet, Tile, Turret};
+use crate::{
+    app_state::AppState,
+    collision_groups,
+    config::GameConfig,
+    utils::{MatchId, Participant, ParticipantMap, PerMatch},
+};
+
+pub struct SiphonPlugin;
+impl Plugin for SiphonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            siphon_spent_bullets
+                .run_if(in_state(AppState::Playing))
+                .before(update_charge_level),
+        );
+    }
+}
+
+/// Refund part of the charge of bullets that have come to a stop over their owner's tiles, where
+/// they'd otherwise sit forever without hitting anything.
+fn siphon_spent_bullets(
+    config: Res<GameConfig>,
+    rapier: Res<RapierContext>,
+    turret_entities: Res<PerMatch<ParticipantMap<Entity>>>,
+    mut bullets: Query<
+        (
+            &Participant,
+            &mut Charge,
+            &Velocity,
+            &GlobalTransform,
+            &MatchId,
+        ),
+        (With<Bullet>, Without<NewBullet>),
+    >,
+    mut turrets: Query<&mut Charge, (With<Turret>, Without<Bullet>)>,
+    tiles: Query<&Participant, With<Tile>>,
+) {
+    let config = &config.siphon;
+    if !config.enabled {
+        return;
+    }
+    let filter = QueryFilter::new().groups(CollisionGroups::new(
+        Group::ALL,
+        collision_groups::ALL_TILES,
+    ));
+    for (&owner, mut charge, velocity, transform, &match_id) in &mut bullets {
+        if charge.value == 0 || velocity.linvel.length() > config.stop_speed {
+            continue;
+        }
+        let mut over_friendly_tile = false;
+        rapier.intersections_with_point(transform.translation().xy(), filter, |tile| {
+            over_friendly_tile = tiles.get(tile).is_ok_and(|&x| x == owner);
+            false
+        });
+        if !over_friendly_tile {
+            continue;
+        }
+        let refund = (charge.value as f64 * config.refund_fraction.clamp(0.0, 1.0)) as u64;
+        let turret = turret_entities.get(match_id).map(|x| *x.get(owner));
+        if let Some(Ok(mut turret_charge)) = turret.map(|x| turrets.get_mut(x)) {
+            turret_charge.value = turret_charge.value.saturating_add(refund);
+        }
+        charge.value = 0;
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{Bullet, Charge, Tile, Turret};
+use crate::{
+    stats::{ParticipantStats, RoundStats},
+    utils::{MatchId, Matches, Participant, ParticipantMap},
+};
+
+pub struct SnapshotPlugin;
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, log_snapshot.run_if(input_just_pressed(KeyCode::F2)));
+    }
+}
+
+/// The state of a single participant at the time of a [`GameSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantSnapshot {
+    pub participant: Participant,
+    pub alive: bool,
+    /// The number of tiles owned by the participant.
+    pub tiles: usize,
+    /// The charge held by the participant's turret, or `None` if it's been eliminated.
+    pub turret_charge: Option<u64>,
+    /// The number of the participant's bullets currently on the battlefield.
+    pub bullets: usize,
+    /// The total charge of the participant's bullets currently on the battlefield.
+    pub bullet_charge: u64,
+    /// Running totals for the participant over the round so far.
+    pub stats: ParticipantStats,
+}
+/// A serializable summary of the current round, so anything that needs to inspect the game state
+/// from the outside doesn't have to know about the components involved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    /// Time in seconds since the round started.
+    pub elapsed_secs: f32,
+    pub participants: Vec<ParticipantSnapshot>,
+}
+impl GameSnapshot {
+    pub fn capture(world: &mut World) -> Self {
+        let mut participants = Participant::ALL.map(|participant| ParticipantSnapshot {
+            participant,
+            alive: world.resource::<ParticipantMap<bool>>()[participant],
+            tiles: 0,
+            turret_charge: None,
+            bullets: 0,
+            bullet_charge: 0,
+            stats: *world.resource::<RoundStats>().get(participant),
+        });
+        // `Participant::ALL` is in the same order as the variants.
+        let index = |participant: Participant| participant as usize;
+
+        let mut tiles = world.query_filtered::<&Participant, With<Tile>>();
+        for &participant in tiles.iter(world) {
+            participants[index(participant)].tiles += 1;
+        }
+        let mut turrets = world.query_filtered::<(&Participant, &Charge), With<Turret>>();
+        for (&participant, charge) in turrets.iter(world) {
+            participants[index(participant)].turret_charge = Some(charge.value);
+        }
+        let mut bullets = world.query_filtered::<(&Participant, &Charge), With<Bullet>>();
+        for (&participant, charge) in bullets.iter(world) {
+            let snapshot = &mut participants[index(participant)];
+            snapshot.bullets += 1;
+            snapshot.bullet_charge = snapshot.bullet_charge.saturating_add(charge.value);
+        }
+
+        Self {
+            elapsed_secs: world.resource::<RoundStats>().elapsed_secs(),
+            participants: participants.into(),
+        }
+    }
+    /// The snapshot as seen from `match_id`, leaving out everyone playing in another match.
+    pub fn for_match(&self, matches: &Matches, match_id: MatchId) -> Self {
+        Self {
+            elapsed_secs: self.elapsed_secs,
+            participants: self
+                .participants
+                .iter()
+                .filter(|x| matches[x.participant] == match_id)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+fn log_snapshot(world: &mut World) {
+    let snapshot = GameSnapshot::capture(world);
+    match serde_json::to_string(&snapshot) {
+        Ok(json) => info!("{}", json),
+        Err(e) => error!("Failed to serialize game snapshot: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_round_trips_through_json() {
+        let mut world = World::new();
+        let mut alive = ParticipantMap::splat(true);
+        alive[Participant::C] = false;
+        world.insert_resource(alive);
+        world.init_resource::<RoundStats>();
+        world.spawn((Tile, Participant::A));
+        world.spawn((Tile, Participant::A));
+        world.spawn((Tile, Participant::B));
+        world.spawn((Turret::default(), Participant::A, Charge::from_value(42)));
+        world.spawn((Bullet, Participant::B, Charge::from_value(3)));
+        world.spawn((Bullet, Participant::B, Charge::from_value(5)));
+
+        let snapshot = GameSnapshot::capture(&mut world);
+        let a = &snapshot.participants[Participant::A as usize];
+        assert_eq!((a.tiles, a.turret_charge), (2, Some(42)));
+        let b = &snapshot.participants[Participant::B as usize];
+        assert_eq!((b.tiles, b.bullets, b.bullet_charge), (1, 2, 8));
+        assert!(!snapshot.participants[Participant::C as usize].alive);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: GameSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::{RestartEvent, RoundWinner};
+use crate::{app_state::AppState, config::GameConfig, utils::GameAssets};
+
+// Constants {{{
+
+const SPEED_TEXT_FONT_SIZE: f32 = 16.0;
+const SPEED_TEXT_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+// }}}
+
+pub struct SpeedRampPlugin;
+impl Plugin for SpeedRampPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpeedRamp>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    ramp_speed,
+                    update_speed_text
+                        .after(ramp_speed)
+                        .run_if(resource_changed::<SpeedRamp>),
+                    restart.run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+/// How long the current round has been going and how much faster it's been sped up for it.
+#[derive(Resource)]
+struct SpeedRamp {
+    /// Measured in real time, so that speeding up doesn't make the ramp any steeper.
+    stopwatch: Stopwatch,
+    factor: f32,
+}
+impl Default for SpeedRamp {
+    fn default() -> Self {
+        Self {
+            stopwatch: Stopwatch::new(),
+            factor: 1.0,
+        }
+    }
+}
+/// Small text in the corner showing how much the round has been sped up.
+#[derive(Component)]
+struct SpeedText;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        SpeedText,
+        Name::new("Speed Ramp Text"),
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(12.0),
+                bottom: Val::Px(8.0),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: SPEED_TEXT_FONT_SIZE,
+                    color: SPEED_TEXT_COLOR,
+                },
+            )
+        },
+    ));
+}
+/// Speed the simulation up the longer a round goes on, and back down once it's over. The speed is
+/// scaled relative to whatever it was set to otherwise, so it stacks with `--time-scale` and the
+/// demo controls.
+fn ramp_speed(
+    mut ramp: ResMut<SpeedRamp>,
+    mut time: ResMut<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+    config: Res<GameConfig>,
+    winner: Res<RoundWinner>,
+    state: Res<State<AppState>>,
+) {
+    let config = &config.speed_ramp;
+    let factor = if config.enabled && winner.0.is_none() {
+        // Keep the ramp where it is during the countdown or while paused.
+        if *state.get() == AppState::Playing {
+            ramp.stopwatch.tick(real_time.delta());
+        }
+        let minutes = (ramp.stopwatch.elapsed_secs() - config.start_secs).max(0.0) / 60.0;
+        (1.0 + minutes * config.increase_per_min).min(config.max_factor.max(1.0))
+    } else {
+        1.0
+    };
+    if factor != ramp.factor {
+        let speed = time.relative_speed() / ramp.factor * factor;
+        time.set_relative_speed(speed);
+        ramp.factor = factor;
+    }
+}
+fn update_speed_text(
+    ramp: Res<SpeedRamp>,
+    mut texts: Query<(&mut Text, &mut Visibility), With<SpeedText>>,
+) {
+    for (mut text, mut visibility) in &mut texts {
+        if ramp.factor > 1.0 {
+            text.sections[0].value = format!("Speed x{:.2}", ramp.factor);
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+fn restart(mut ramp: ResMut<SpeedRamp>) {
+    ramp.stopwatch.reset();
+}
@@ -0,0 +1,159 @@
+//! Zones placed by custom maps that change how fast bullets go while they're inside, from mud that
+//! slows them down to fast lanes that speed them up.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{geometry::update_geometry, Bullet, RestartEvent};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    utils::{MatchId, Matches, Roster, RoundScoped},
+};
+
+// Constants {{{
+
+/// Factors are kept at least this high, so a bullet can always make its way back out.
+const MIN_SPEED_FACTOR: f32 = 0.05;
+/// The tint over zones that slow bullets down.
+const SLOW_ZONE_COLOR: Color = Color::srgba(0.45, 0.3, 0.15, 0.35);
+/// The tint over zones that speed bullets up.
+const FAST_ZONE_COLOR: Color = Color::srgba(0.3, 0.7, 1.0, 0.3);
+/// Above the tiles, below the obstacles.
+const SPEED_ZONE_Z: f32 = -0.75;
+
+// }}}
+
+pub struct SpeedZonePlugin;
+impl Plugin for SpeedZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_speed_zones.after(update_geometry))
+            .add_systems(
+                Update,
+                (
+                    spawn_speed_zones
+                        .after(update_geometry)
+                        .run_if(on_event::<RestartEvent>()),
+                    apply_speed_zones.run_if(in_state(AppState::Playing)),
+                ),
+            );
+    }
+}
+
+/// A rectangle that multiplies the velocity of the bullets inside it by `factor`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect, Component)]
+#[serde(default)]
+pub struct SpeedZone {
+    pub position: Vec2,
+    pub half_size: Vec2,
+    /// In radians, counterclockwise.
+    pub angle: f32,
+    /// Below 1 slows bullets down and above 1 speeds them up.
+    pub factor: f32,
+}
+impl Default for SpeedZone {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            half_size: Vec2::ZERO,
+            angle: 0.0,
+            factor: 1.0,
+        }
+    }
+}
+impl SpeedZone {
+    pub fn contains(&self, point: Vec2) -> bool {
+        let local = Vec2::from_angle(-self.angle).rotate(point - self.position);
+        local.abs().cmple(self.half_size).all()
+    }
+    fn factor(&self) -> f32 {
+        self.factor.max(MIN_SPEED_FACTOR)
+    }
+}
+/// The speed factor a bullet got from the zones it's in, to take back off once it leaves them.
+#[derive(Component)]
+struct SpedUp(f32);
+
+/// Lay out the speed zones of the custom map for the round about to start, taking away the last
+/// ones.
+fn spawn_speed_zones(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    zones: Query<(Entity, &MatchId), With<SpeedZone>>,
+) {
+    let matches = RestartEvent::matches(&mut events, &roster, &matches);
+    for (entity, match_id) in &zones {
+        if matches.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    let Some(map) = &config.map_file else {
+        return;
+    };
+    for &match_id in &matches {
+        for &zone in &map.speed_zones {
+            commands.spawn((
+                zone,
+                Name::new("Speed Zone"),
+                RoundScoped,
+                match_id,
+                SpriteBundle {
+                    transform: Transform::from_translation(
+                        (match_id.world_offset() + zone.position).extend(SPEED_ZONE_Z),
+                    )
+                    .with_rotation(Quat::from_rotation_z(zone.angle)),
+                    sprite: Sprite {
+                        color: match zone.factor() < 1.0 {
+                            true => SLOW_ZONE_COLOR,
+                            false => FAST_ZONE_COLOR,
+                        },
+                        custom_size: Some(zone.half_size * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+/// Scale the velocity of each bullet by the zones of its match it's in, undoing the last scaling
+/// first. Zones that overlap stack.
+fn apply_speed_zones(
+    mut commands: Commands,
+    zones: Query<(&SpeedZone, &MatchId)>,
+    mut bullets: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Velocity,
+            &MatchId,
+            Option<&SpedUp>,
+        ),
+        With<Bullet>,
+    >,
+) {
+    if zones.is_empty() {
+        return;
+    }
+    for (entity, transform, mut velocity, &match_id, sped_up) in &mut bullets {
+        let position = transform.translation().xy() - match_id.world_offset();
+        let factor = zones
+            .iter()
+            .filter(|&(x, &zone_match)| zone_match == match_id && x.contains(position))
+            .map(|(x, _)| x)
+            .map(SpeedZone::factor)
+            .product::<f32>();
+        let previous = sped_up.map_or(1.0, |x| x.0);
+        if factor == previous {
+            continue;
+        }
+        velocity.linvel *= factor / previous;
+        match factor == 1.0 {
+            true => commands.entity(entity).remove::<SpedUp>(),
+            false => commands.entity(entity).insert(SpedUp(factor)),
+        };
+    }
+}
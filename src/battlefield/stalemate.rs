@@ -0,0 +1,118 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::{EliminationEvent, MatchWinners, RestartEvent, TileConvertedEvent};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    panel_plugin::{TriggerEvent, TriggerType},
+    ui::AnnouncementEvent,
+    utils::{Matches, Participant, ParticipantMap, PerMatch, Roster},
+};
+
+// Constants {{{
+
+/// The most times a turret's charge gets doubled before a forced shot, no matter how long the
+/// stalemate has gone on.
+const MAX_STALEMATE_MULTIPLIES: u32 = 8;
+const STALEMATE_ANNOUNCEMENT_COLOR: Color = Color::WHITE;
+
+// }}}
+
+pub struct StalematePlugin;
+impl Plugin for StalematePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerMatch<StalemateWatchdog>>()
+            .add_systems(
+                Update,
+                (
+                    watch_for_stalemate.run_if(in_state(AppState::Playing)),
+                    restart.run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+/// Tracks how long it's been since the last tile conversion or elimination in a match.
+#[derive(Default)]
+struct StalemateWatchdog {
+    stopwatch: Stopwatch,
+    /// How many times the watchdog has had to intervene since the last progress.
+    escalation: u32,
+}
+
+/// Force the turrets of a match to fire whenever it stops making progress. Every consecutive
+/// intervention doubles their charge one more time before firing. A match that's been won is left
+/// alone while it waits for the others.
+fn watch_for_stalemate(
+    mut watchdogs: ResMut<PerMatch<StalemateWatchdog>>,
+    mut trigger_events: EventWriter<TriggerEvent>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    mut eliminations: EventReader<EliminationEvent>,
+    mut conversions: EventReader<TileConvertedEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    survivors: Res<ParticipantMap<bool>>,
+    (roster, matches, match_winners): (Res<Roster>, Res<Matches>, Res<MatchWinners>),
+) {
+    let progressed: Vec<_> = eliminations
+        .read()
+        .map(|x| x.match_id)
+        .chain(conversions.read().map(|x| x.match_id))
+        .collect();
+    for match_id in matches.running(&roster) {
+        if match_winners.0.get(match_id).is_some() {
+            continue;
+        }
+        if watchdogs.get(match_id).is_none() {
+            watchdogs.insert(match_id, StalemateWatchdog::default());
+        }
+        let watchdog = &mut watchdogs[match_id];
+        if progressed.contains(&match_id) || !config.stalemate.enabled {
+            watchdog.stopwatch.reset();
+            watchdog.escalation = 0;
+            continue;
+        }
+        watchdog.stopwatch.tick(time.delta());
+        if watchdog.stopwatch.elapsed_secs() < config.stalemate.timeout_secs {
+            continue;
+        }
+
+        watchdog.stopwatch.reset();
+        watchdog.escalation += 1;
+        let multiplies = (watchdog.escalation - 1).min(MAX_STALEMATE_MULTIPLIES);
+        warn!(
+            "Stalemate detected in {:?}, no progress for {} seconds. Forcing charged shots with {} \
+             multiplies.",
+            match_id, config.stalemate.timeout_secs, multiplies
+        );
+        announcements.send(AnnouncementEvent {
+            message: "Stalemate! Free Charged Shots".to_string(),
+            color: STALEMATE_ANNOUNCEMENT_COLOR,
+        });
+        for participant in Participant::ALL {
+            if !survivors[participant] || matches[participant] != match_id {
+                continue;
+            }
+            for _ in 0..multiplies {
+                trigger_events.send(TriggerEvent {
+                    participant,
+                    trigger_type: TriggerType::Multiply(2),
+                    ball: None,
+                });
+            }
+            trigger_events.send(TriggerEvent {
+                participant,
+                trigger_type: TriggerType::ChargedShot,
+                ball: None,
+            });
+        }
+    }
+}
+fn restart(
+    mut watchdogs: ResMut<PerMatch<StalemateWatchdog>>,
+    mut events: EventReader<RestartEvent>,
+) {
+    for event in events.read() {
+        watchdogs.insert(event.match_id, StalemateWatchdog::default());
+    }
+}
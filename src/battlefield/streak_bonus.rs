@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use super::{
+    handle_bullet_tile_collision, update_charge_level, Charge, RestartEvent, TileConvertedEvent,
+    Turret,
+};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    ui::AnnouncementEvent,
+    utils::{BallColor, DisplayName, Participant, ParticipantMap},
+};
+
+pub struct StreakBonusPlugin;
+impl Plugin for StreakBonusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StreakEvent>()
+            .init_resource::<Streaks>()
+            .add_systems(
+                Update,
+                (
+                    track_streaks
+                        .after(handle_bullet_tile_collision)
+                        .run_if(in_state(AppState::Playing)),
+                    grant_streak_bonus
+                        .after(track_streaks)
+                        .before(update_charge_level)
+                        .run_if(on_event::<StreakEvent>()),
+                    restart.run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+/// Event sent when a participant's streak of captures reaches one of the
+/// [`StreakBonusConfig::thresholds`](crate::config::StreakBonusConfig).
+#[derive(Event, Clone)]
+pub struct StreakEvent {
+    pub participant: Participant,
+    /// The number of tiles captured in the streak so far.
+    pub count: u32,
+}
+/// The tiles each participant has captured in a row, without losing any or going too long between
+/// two captures.
+#[derive(Resource, Default)]
+struct Streaks(ParticipantMap<Streak>);
+#[derive(Default, Clone, Copy)]
+struct Streak {
+    count: u32,
+    since_last_capture: f32,
+}
+
+/// Count the tiles each participant captures in a row, breaking the streak whenever they lose one,
+/// and send an event each time a streak reaches a threshold.
+fn track_streaks(
+    mut streaks: ResMut<Streaks>,
+    mut conversions: EventReader<TileConvertedEvent>,
+    mut events: EventWriter<StreakEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+) {
+    let config = &config.streak_bonus;
+    if !config.enabled {
+        conversions.clear();
+        return;
+    }
+    for participant in Participant::ALL {
+        let streak = &mut streaks.0[participant];
+        streak.since_last_capture += time.delta_seconds();
+        if streak.since_last_capture > config.gap_secs {
+            streak.count = 0;
+        }
+    }
+    for event in conversions.read() {
+        streaks.0[event.from].count = 0;
+        let streak = &mut streaks.0[event.to];
+        streak.count += 1;
+        streak.since_last_capture = 0.0;
+        if config.thresholds.contains(&streak.count) {
+            events.send(StreakEvent {
+                participant: event.to,
+                count: streak.count,
+            });
+        }
+    }
+}
+/// Reward the turret on a streak with bonus charge and call it out.
+fn grant_streak_bonus(
+    mut events: EventReader<StreakEvent>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    config: Res<GameConfig>,
+    (names, ball_colors): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    mut turrets: Query<(&mut Charge, &Participant), With<Turret>>,
+) {
+    for event in events.read() {
+        for (mut charge, &participant) in &mut turrets {
+            if participant == event.participant {
+                charge.value = charge
+                    .value
+                    .saturating_add(config.streak_bonus.bonus_charge);
+            }
+        }
+        announcements.send(AnnouncementEvent {
+            message: format!(
+                "{} Is on a {}-Tile Streak!",
+                names[event.participant], event.count
+            ),
+            color: ball_colors[event.participant].0,
+        });
+    }
+}
+fn restart(mut streaks: ResMut<Streaks>) {
+    *streaks = Streaks::default();
+}
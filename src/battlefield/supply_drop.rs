@@ -0,0 +1,268 @@
+use std::{collections::HashSet, f32::consts::PI};
+
+use bevy::{color::palettes::css, prelude::*, sprite::Mesh2dHandle};
+use bevy_rapier2d::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use super::{BattlefieldGeometry, Bullet, RestartEvent, BATTLEFIELD_HALF_WIDTH};
+use crate::{
+    app_state::AppState,
+    collision_groups,
+    config::GameConfig,
+    panel_plugin::{TriggerEvent, TriggerType},
+    ui::AnnouncementEvent,
+    utils::{
+        BallColor, DisplayName, GameRng, MatchId, Matches, Participant, ParticipantMap, Roster,
+        RoundScoped,
+    },
+};
+
+// Constants {{{
+
+/// Crates only land within this distance from the center on each axis.
+const SUPPLY_DROP_SPAWN_HALF_WIDTH: f32 = BATTLEFIELD_HALF_WIDTH * 0.6;
+const SUPPLY_CRATE_SIZE: f32 = 24.0;
+const SUPPLY_CRATE_COLOR: Color = Color::Srgba(css::SADDLE_BROWN);
+const PARACHUTE_RADIUS: f32 = 24.0;
+const PARACHUTE_COLOR: Color = Color::srgba(0.95, 0.95, 0.95, 0.8);
+/// Time in seconds the crate takes to float down before it can be picked up.
+const SUPPLY_DROP_DESCENT_SECS: f32 = 3.0;
+/// How much bigger the crate appears at the start of its descent.
+const SUPPLY_DROP_START_SCALE: f32 = 3.0;
+/// The maximum angle in radians the crate sways to either side while descending.
+const SUPPLY_DROP_SWAY_ANGLE: f32 = 0.25;
+/// How many times per second the crate sways back and forth while descending.
+const SUPPLY_DROP_SWAY_FREQUENCY: f32 = 0.75;
+const SUPPLY_DROP_REWARDS: [TriggerType; 4] = [
+    TriggerType::Multiply(2),
+    TriggerType::Multiply(4),
+    TriggerType::BurstShot,
+    TriggerType::ChargedShot,
+];
+
+// Z-index
+const SUPPLY_CRATE_Z: f32 = 0.75;
+// The parachute is a child of the crate so this is relative to it.
+const PARACHUTE_Z: f32 = 0.1;
+
+// }}}
+
+pub struct SupplyDropPlugin;
+impl Plugin for SupplyDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                drop_supply_crate.run_if(in_state(AppState::Playing)),
+                animate_descent,
+                pick_up_supply_crate.run_if(in_state(AppState::Playing)),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Tracks when the next crate drops. It only ticks while there's no crate on the battlefield.
+#[derive(Resource)]
+struct SupplyDropTimer(Timer);
+#[derive(Resource)]
+struct SupplyDropAssets {
+    parachute_mesh: Mesh2dHandle,
+    parachute_material: Handle<ColorMaterial>,
+}
+/// Marker for a crate that can be picked up by shooting it.
+#[derive(Component, Clone, Copy)]
+struct SupplyCrate;
+/// Component for a crate that's still floating down.
+#[derive(Component)]
+struct Descent {
+    timer: Timer,
+    parachute: Entity,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<GameConfig>,
+) {
+    commands.insert_resource(SupplyDropTimer(Timer::from_seconds(
+        config.supply_drop.interval_secs,
+        TimerMode::Once,
+    )));
+    commands.insert_resource(SupplyDropAssets {
+        parachute_mesh: Mesh2dHandle(
+            meshes.add(CircularSector::from_radians(PARACHUTE_RADIUS, PI)),
+        ),
+        parachute_material: materials.add(PARACHUTE_COLOR),
+    });
+}
+fn drop_supply_crate(
+    mut commands: Commands,
+    mut timer: ResMut<SupplyDropTimer>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    assets: Res<SupplyDropAssets>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    crates: Query<&MatchId, With<SupplyCrate>>,
+) {
+    // Each match gets a crate of its own, at the same spot, once its last one has been picked up.
+    let waiting: Vec<_> = matches
+        .running(&roster)
+        .into_iter()
+        .filter(|x| !crates.iter().any(|y| y == x))
+        .collect();
+    if !config.supply_drop.enabled || waiting.is_empty() {
+        return;
+    }
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    timer.0 = Timer::from_seconds(config.supply_drop.interval_secs, TimerMode::Once);
+
+    let x = rng.gen_range(-SUPPLY_DROP_SPAWN_HALF_WIDTH..SUPPLY_DROP_SPAWN_HALF_WIDTH);
+    let y = rng.gen_range(-SUPPLY_DROP_SPAWN_HALF_WIDTH..SUPPLY_DROP_SPAWN_HALF_WIDTH);
+    let position = geometry.shape.pull_inside(Vec2::new(x, y), 0.0);
+    for match_id in waiting {
+        let position = match_id.world_offset() + position;
+        let parachute = commands
+            .spawn((
+                Name::new("Parachute"),
+                ColorMesh2dBundle {
+                    mesh: assets.parachute_mesh.clone(),
+                    material: assets.parachute_material.clone(),
+                    transform: Transform::from_xyz(0.0, SUPPLY_CRATE_SIZE / 2.0, PARACHUTE_Z),
+                    ..default()
+                },
+            ))
+            .id();
+        commands
+            .spawn((
+                Name::new("Supply Crate"),
+                SupplyCrate,
+                RoundScoped,
+                match_id,
+                Descent {
+                    timer: Timer::from_seconds(SUPPLY_DROP_DESCENT_SECS, TimerMode::Once),
+                    parachute,
+                },
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: SUPPLY_CRATE_COLOR,
+                        custom_size: Some(Vec2::splat(SUPPLY_CRATE_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(SUPPLY_CRATE_Z))
+                        .with_scale(Vec3::new(
+                            SUPPLY_DROP_START_SCALE,
+                            SUPPLY_DROP_START_SCALE,
+                            1.0,
+                        )),
+                    ..default()
+                },
+                Collider::cuboid(SUPPLY_CRATE_SIZE / 2.0, SUPPLY_CRATE_SIZE / 2.0),
+                CollisionGroups::new(
+                    collision_groups::PICKUPS,
+                    collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+                ),
+                (Sensor, ActiveEvents::COLLISION_EVENTS, ColliderDisabled),
+            ))
+            .add_child(parachute);
+    }
+}
+/// Shrink the crate down to its actual size while swaying it side to side, to make it look like
+/// it's falling towards the camera. The crate can be picked up once it lands.
+fn animate_descent(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut crates: Query<(Entity, &mut Descent, &mut Transform), With<SupplyCrate>>,
+) {
+    for (entity, mut descent, mut transform) in &mut crates {
+        descent.timer.tick(time.delta());
+        if descent.timer.finished() {
+            transform.scale = Vec3::ONE;
+            transform.rotation = Quat::IDENTITY;
+            commands.entity(descent.parachute).despawn_recursive();
+            commands
+                .entity(entity)
+                .remove::<(Descent, ColliderDisabled)>();
+            continue;
+        }
+        let elapsed = descent.timer.elapsed_secs();
+        let scale = SUPPLY_DROP_START_SCALE.lerp(1.0, descent.timer.fraction());
+        transform.scale = Vec3::new(scale, scale, 1.0);
+        transform.rotation = Quat::from_rotation_z(
+            (elapsed * SUPPLY_DROP_SWAY_FREQUENCY * 2.0 * PI).sin() * SUPPLY_DROP_SWAY_ANGLE,
+        );
+    }
+}
+fn pick_up_supply_crate(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut trigger_events: EventWriter<TriggerEvent>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    colors: Res<ParticipantMap<BallColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    mut rng: ResMut<GameRng>,
+    crates: Query<(), With<SupplyCrate>>,
+    bullets: Query<&Participant, With<Bullet>>,
+) {
+    // The same crate can be hit by more than one bullet in a frame, but only the first one picks
+    // it up.
+    let mut picked_up = HashSet::new();
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let (crate_entity, bullet) = if crates.contains(a) {
+            (a, b)
+        } else if crates.contains(b) {
+            (b, a)
+        } else {
+            continue;
+        };
+        let Ok(&participant) = bullets.get(bullet) else {
+            continue;
+        };
+        if !picked_up.insert(crate_entity) {
+            continue;
+        }
+        commands.entity(crate_entity).despawn_recursive();
+        let &trigger_type = SUPPLY_DROP_REWARDS
+            .choose(&mut rng.0)
+            .expect("`SUPPLY_DROP_REWARDS` is not empty.");
+        trigger_events.send(TriggerEvent {
+            participant,
+            trigger_type,
+            ball: None,
+        });
+        announcements.send(AnnouncementEvent {
+            message: format!(
+                "{} Picked Up {}",
+                names[participant],
+                trigger_type.to_string().replace('\n', " ")
+            ),
+            color: colors.get(participant).0,
+        });
+    }
+}
+fn restart(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    mut timer: ResMut<SupplyDropTimer>,
+    config: Res<GameConfig>,
+    crates: Query<(Entity, &MatchId), With<SupplyCrate>>,
+) {
+    let matches: Vec<_> = events.read().map(|x| x.match_id).collect();
+    for (entity, match_id) in &crates {
+        if matches.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    if matches.contains(&MatchId::MAIN) {
+        timer.0 = Timer::from_seconds(config.supply_drop.interval_secs, TimerMode::Once);
+    }
+}
@@ -0,0 +1,103 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use super::{BattlefieldGeometry, RestartEvent};
+use crate::{
+    utils::{GameAssets, Matches},
+    Participant,
+};
+
+// Constants {{{
+
+const SWAP_ORB_RADIUS: f32 = 12.0;
+/// Time in seconds the orbs take to travel between the turrets.
+const SWAP_ORB_TRAVEL_SECS: f32 = 0.75;
+/// How far the orbs bow out from the straight line between the turrets, so they don't overlap.
+const SWAP_ORB_ARC_HEIGHT: f32 = 80.0;
+
+// Z-index
+const SWAP_ORB_Z: f32 = 5.0;
+
+// }}}
+
+pub struct SwapPlugin;
+impl Plugin for SwapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SwapEvent>().add_systems(
+            Update,
+            (
+                spawn_swap_orbs.run_if(on_event::<SwapEvent>()),
+                move_swap_orbs,
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Event sent when two turrets swap charges, so the exchange can be shown on the battlefield.
+#[derive(Event)]
+pub struct SwapEvent {
+    pub a: Participant,
+    pub b: Participant,
+}
+/// Component for an orb carrying a turret's charge over to another turret.
+#[derive(Component)]
+struct SwapOrb {
+    from: Vec2,
+    to: Vec2,
+    timer: Timer,
+}
+
+fn spawn_swap_orbs(
+    mut commands: Commands,
+    mut events: EventReader<SwapEvent>,
+    assets: Res<GameAssets>,
+    geometry: Res<BattlefieldGeometry>,
+    matches: Res<Matches>,
+) {
+    for event in events.read() {
+        for (from, to) in [(event.a, event.b), (event.b, event.a)] {
+            let start = geometry.turret_world_position(from, &matches);
+            commands.spawn((
+                Name::new("Swap Orb"),
+                SwapOrb {
+                    from: start,
+                    to: geometry.turret_world_position(to, &matches),
+                    timer: Timer::from_seconds(SWAP_ORB_TRAVEL_SECS, TimerMode::Once),
+                },
+                ColorMesh2dBundle {
+                    mesh: assets.unit_circle.clone(),
+                    material: assets.ball_materials.get(from).clone(),
+                    transform: Transform::from_translation(start.extend(SWAP_ORB_Z))
+                        .with_scale(Vec3::new(SWAP_ORB_RADIUS, SWAP_ORB_RADIUS, 1.0)),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+fn move_swap_orbs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut orbs: Query<(Entity, &mut SwapOrb, &mut Transform)>,
+) {
+    for (entity, mut orb, mut transform) in &mut orbs {
+        orb.timer.tick(time.delta());
+        if orb.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let t = orb.timer.fraction();
+        // The two orbs travel in opposite directions, so the same perpendicular sends them along
+        // opposite sides of the line.
+        let normal = (orb.to - orb.from).normalize_or_zero().perp();
+        let position = orb.from.lerp(orb.to, t) + normal * (t * PI).sin() * SWAP_ORB_ARC_HEIGHT;
+        transform.translation = position.extend(SWAP_ORB_Z);
+    }
+}
+fn restart(mut commands: Commands, orbs: Query<Entity, With<SwapOrb>>) {
+    for entity in &orbs {
+        commands.entity(entity).despawn_recursive();
+    }
+}
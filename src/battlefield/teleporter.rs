@@ -0,0 +1,136 @@
+//! Pairs of teleporters placed by custom maps. A bullet that runs into one comes out of the other
+//! just as far from its middle, still moving the same way at the same speed.
+
+use bevy::prelude::*;
+
+use super::{geometry::update_geometry, trail::PreviousPosition, Bullet, RestartEvent};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    utils::{GameAssets, MatchId, Matches, Roster, RoundScoped},
+};
+
+// Constants {{{
+
+/// How far from its middle a teleporter takes in bullets.
+const TELEPORTER_RADIUS: f32 = 16.0;
+/// Each pair gets its own hue, this far around the color wheel from the one before.
+const TELEPORTER_HUE_STEP: f32 = 67.0;
+const TELEPORTER_ALPHA: f32 = 0.6;
+/// Above the tiles and obstacles, below the bullets.
+const TELEPORTER_Z: f32 = -0.25;
+
+// }}}
+
+pub struct TeleporterPlugin;
+impl Plugin for TeleporterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_teleporters.after(update_geometry))
+            .add_systems(
+                Update,
+                (
+                    spawn_teleporters
+                        .after(update_geometry)
+                        .run_if(on_event::<RestartEvent>()),
+                    teleport_bullets.run_if(in_state(AppState::Playing)),
+                ),
+            );
+    }
+}
+
+/// One end of a pair of teleporters.
+#[derive(Component)]
+struct Teleporter {
+    entrance: Vec2,
+    exit: Vec2,
+}
+/// A bullet that just came out of the teleporter at this position. It can't go back in until it's
+/// left, or it would bounce between the two forever.
+#[derive(Component)]
+struct Teleported(Vec2);
+
+/// Put down the teleporters of the custom map for the round about to start, taking away the last
+/// ones.
+fn spawn_teleporters(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut events: EventReader<RestartEvent>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    teleporters: Query<(Entity, &MatchId), With<Teleporter>>,
+) {
+    let matches = RestartEvent::matches(&mut events, &roster, &matches);
+    for (entity, match_id) in &teleporters {
+        if matches.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    let Some(map) = &config.map_file else {
+        return;
+    };
+    for (i, &(a, b)) in map.teleporters.iter().enumerate() {
+        let color = Color::hsla(
+            i as f32 * TELEPORTER_HUE_STEP % 360.0,
+            0.8,
+            0.6,
+            TELEPORTER_ALPHA,
+        );
+        let material = materials.add(color);
+        for match_id in matches.iter().copied() {
+            for (entrance, exit) in [(a, b), (b, a)] {
+                commands.spawn((
+                    Teleporter { entrance, exit },
+                    Name::new("Teleporter"),
+                    RoundScoped,
+                    match_id,
+                    ColorMesh2dBundle {
+                        mesh: assets.unit_circle.clone(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(
+                            (match_id.world_offset() + entrance).extend(TELEPORTER_Z),
+                        )
+                        .with_scale(Vec3::new(
+                            TELEPORTER_RADIUS,
+                            TELEPORTER_RADIUS,
+                            1.0,
+                        )),
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+}
+/// Move the bullets that ran into a teleporter over to its pair. Only their position changes, so
+/// they keep their velocity.
+fn teleport_bullets(
+    mut commands: Commands,
+    teleporters: Query<(&Teleporter, &MatchId)>,
+    mut bullets: Query<(Entity, &mut Transform, &MatchId, Option<&Teleported>), With<Bullet>>,
+) {
+    if teleporters.is_empty() {
+        return;
+    }
+    for (entity, mut transform, &match_id, teleported) in &mut bullets {
+        let position = transform.translation.xy();
+        if let Some(&Teleported(exit)) = teleported {
+            if position.distance(exit) <= TELEPORTER_RADIUS {
+                continue;
+            }
+            commands.entity(entity).remove::<Teleported>();
+        }
+        let Some((teleporter, _)) = teleporters.iter().find(|&(x, &teleporter_match)| {
+            teleporter_match == match_id && position.distance(x.entrance) <= TELEPORTER_RADIUS
+        }) else {
+            continue;
+        };
+        let position = teleporter.exit + (position - teleporter.entrance);
+        transform.translation = position.extend(transform.translation.z);
+        // The trail would otherwise be painted all the way across from the entrance.
+        commands
+            .entity(entity)
+            .insert(Teleported(teleporter.exit))
+            .remove::<PreviousPosition>();
+    }
+}
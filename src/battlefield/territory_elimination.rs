@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use super::{handle_elimination, EliminationEvent, RestartEvent, Tile, Turret};
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    ui::AnnouncementEvent,
+    utils::{BallColor, DisplayName, MatchId, Participant, ParticipantMap, PerMatch},
+};
+
+pub struct TerritoryEliminationPlugin;
+impl Plugin for TerritoryEliminationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LowTerritory>().add_systems(
+            Update,
+            (
+                eliminate_landless
+                    .run_if(in_state(AppState::Playing))
+                    .before(handle_elimination),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Time in seconds each participant has spent in a row owning less than the minimum share of the
+/// battlefield.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct LowTerritory(ParticipantMap<f32>);
+
+/// Eliminate the participants whose share of their match's battlefield has stayed below the minimum
+/// for too long, warning them the moment they drop below it.
+fn eliminate_landless(
+    mut low: ResMut<LowTerritory>,
+    mut eliminations: EventWriter<EliminationEvent>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    (names, colors): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<BallColor>>,
+    ),
+    tiles: Query<(&Participant, &MatchId), With<Tile>>,
+    turrets: Query<(&Participant, &MatchId), With<Turret>>,
+) {
+    let config = &config.territory_elimination;
+    if !config.enabled {
+        return;
+    }
+    let mut owned = ParticipantMap::splat(0usize);
+    let mut totals = PerMatch::default();
+    for (&owner, &match_id) in &tiles {
+        owned[owner] += 1;
+        match totals.get_mut(match_id) {
+            Some(total) => *total += 1,
+            None => totals.insert(match_id, 1usize),
+        }
+    }
+    for (&participant, &match_id) in &turrets {
+        let total = totals.get(match_id).copied().unwrap_or_default();
+        if owned[participant] as f32 >= total as f32 * config.min_fraction {
+            low[participant] = 0.0;
+            continue;
+        }
+        if low[participant] == 0.0 {
+            announcements.send(AnnouncementEvent {
+                message: format!("{} Is Running Out of Ground", names[participant]),
+                color: colors[participant].0,
+            });
+        }
+        low[participant] += time.delta_seconds();
+        if low[participant] >= config.grace_secs {
+            low[participant] = 0.0;
+            eliminations.send(EliminationEvent::new(match_id, participant, None));
+        }
+    }
+}
+fn restart(mut low: ResMut<LowTerritory>) {
+    *low = LowTerritory::default();
+}
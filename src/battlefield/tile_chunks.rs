@@ -0,0 +1,265 @@
+//! Drawing the tiles in chunks, so the cost of a frame goes with the number of tiles that changed
+//! rather than the size of the battlefield.
+//!
+//! Each chunk is a small image with one pixel per tile, stretched over its part of the battlefield.
+//! The tiles keep their sprites as the record of their color but stay hidden, and whenever one of
+//! them changes, its pixel is painted and its chunk marked dirty. Only the dirty chunks are copied
+//! over to their images at the end of the frame, so only those are uploaded to the GPU again.
+//!
+//! Hexagon tiles don't line up with the pixels of an image, so they're still drawn one by one, as
+//! are square tiles with `performance.tile_chunk_size` set to 0.
+
+use std::collections::HashMap;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::query::QueryFilter,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+        view::VisibilitySystems,
+    },
+};
+
+use super::{BattlefieldRoot, Tile, TileGrid, BATTLEFIELD_HALF_WIDTH, TILE_DIMENSION, TILE_Z};
+use crate::{
+    config::{GameConfig, TileShape},
+    utils::{MatchId, PerMatch},
+};
+
+// Constants {{{
+
+/// The number of chunks uploaded to the GPU again each frame.
+const TILE_CHUNK_UPLOADS: DiagnosticPath =
+    DiagnosticPath::const_new("battlefield/tile_chunk_uploads");
+const EMPTY_PIXEL: [u8; 4] = [0; 4];
+
+// }}}
+
+pub struct TileChunksPlugin;
+impl Plugin for TileChunksPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(TILE_CHUNK_UPLOADS).with_suffix(" chunks"))
+            .init_resource::<PerMatch<TileChunks>>()
+            .add_systems(
+                PostUpdate,
+                update_tile_chunks.before(VisibilitySystems::VisibilityPropagate),
+            );
+    }
+}
+
+/// The chunks over one match's battlefield.
+#[derive(Default)]
+struct TileChunks {
+    /// The number of tiles across each chunk, or 0 if the tiles are drawn one by one.
+    size: usize,
+    /// The chunks in rows from the bottom left, like the cells of the [`TileGrid`].
+    chunks: Vec<Chunk>,
+    /// Where each tile is drawn, as the index of its chunk and of its pixel in the chunk.
+    pixels: HashMap<Entity, (usize, usize)>,
+}
+impl TileChunks {
+    /// The number of chunks across the battlefield.
+    fn columns(&self) -> usize {
+        TileGrid::SIZE.div_ceil(self.size)
+    }
+    /// The chunk and pixel that draw the grid cell at column `x` and row `y`.
+    fn pixel(&self, x: usize, y: usize) -> (usize, usize) {
+        let chunk = y / self.size * self.columns() + x / self.size;
+        // The rows of an image go from the top down.
+        let row = self.size - 1 - y % self.size;
+        (chunk, row * self.size + x % self.size)
+    }
+}
+struct Chunk {
+    entity: Entity,
+    image: Handle<Image>,
+    /// The pixels as they'll be uploaded, four bytes each.
+    data: Vec<u8>,
+    /// Whether `data` has changed since it was last copied to the image.
+    dirty: bool,
+}
+impl Chunk {
+    fn new(
+        commands: &mut Commands,
+        images: &mut Assets<Image>,
+        root: Entity,
+        size: usize,
+        center: Vec2,
+    ) -> Self {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: size as u32,
+                height: size as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &EMPTY_PIXEL,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        // Blurring the pixels together would smear the tiles' edges.
+        image.sampler = ImageSampler::nearest();
+        let data = image.data.clone();
+        let image = images.add(image);
+        let entity = commands
+            .spawn((
+                Name::new("Tile Chunk"),
+                SpriteBundle {
+                    texture: image.clone(),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(size as f32 * TILE_DIMENSION)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(center.extend(TILE_Z)),
+                    ..default()
+                },
+            ))
+            .set_parent(root)
+            .id();
+        Self {
+            entity,
+            image,
+            data,
+            dirty: false,
+        }
+    }
+    fn paint(&mut self, pixel: usize, color: [u8; 4]) {
+        let bytes = &mut self.data[pixel * 4..pixel * 4 + 4];
+        if *bytes != color {
+            bytes.copy_from_slice(&color);
+            self.dirty = true;
+        }
+    }
+}
+
+type TileQuery<'w, 's, F> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Sprite,
+        &'static Transform,
+        &'static mut Visibility,
+        &'static MatchId,
+    ),
+    F,
+>;
+
+/// Paint the tiles that changed into their chunks and upload the chunks that were painted, laying
+/// the chunks out again first if the chunk size or the tile shape changed. Each match's battlefield
+/// has chunks of its own.
+fn update_tile_chunks(
+    mut commands: Commands,
+    mut chunks: ResMut<PerMatch<TileChunks>>,
+    mut images: ResMut<Assets<Image>>,
+    mut diagnostics: Diagnostics,
+    mut removed: RemovedComponents<Tile>,
+    mut tiles: ParamSet<(
+        TileQuery<With<Tile>>,
+        TileQuery<(With<Tile>, Changed<Sprite>)>,
+    )>,
+    roots: Query<(Entity, &MatchId), With<BattlefieldRoot>>,
+    grids: Res<PerMatch<TileGrid>>,
+    config: Res<GameConfig>,
+) {
+    let removed: Vec<_> = removed.read().collect();
+    // The chunks of a match that's stopped running went with its battlefield.
+    chunks.retain(|match_id| roots.iter().any(|(_, &x)| x == match_id));
+    let mut uploads = 0;
+    for (root, &match_id) in &roots {
+        let Some(grid) = grids.get(match_id) else {
+            continue;
+        };
+        if chunks.get(match_id).is_none() {
+            chunks.insert(match_id, TileChunks::default());
+        }
+        let chunks = &mut chunks[match_id];
+        let size = match grid.shape {
+            TileShape::Square => config.performance.tile_chunk_size as usize,
+            TileShape::Hexagon => 0,
+        };
+        let relayout = size != chunks.size;
+        if relayout {
+            for chunk in chunks.chunks.drain(..) {
+                commands.entity(chunk.entity).despawn_recursive();
+                images.remove(&chunk.image);
+            }
+            chunks.pixels.clear();
+            chunks.size = size;
+            if size > 0 {
+                let columns = chunks.columns();
+                let span = size as f32 * TILE_DIMENSION;
+                chunks.chunks = (0..columns * columns)
+                    .map(|i| {
+                        let corner = Vec2::new((i % columns) as f32, (i / columns) as f32) * span;
+                        let center = corner + span / 2.0 - BATTLEFIELD_HALF_WIDTH;
+                        Chunk::new(&mut commands, &mut images, root, size, center)
+                    })
+                    .collect();
+            }
+        }
+        if size == 0 {
+            if relayout {
+                for (.., mut visibility, &x) in &mut tiles.p0() {
+                    if x == match_id {
+                        *visibility = Visibility::Inherited;
+                    }
+                }
+            }
+            continue;
+        }
+
+        for entity in &removed {
+            if let Some((chunk, pixel)) = chunks.pixels.remove(entity) {
+                chunks.chunks[chunk].paint(pixel, EMPTY_PIXEL);
+            }
+        }
+        // Every tile has to be painted into the new chunks after a relayout, but otherwise only the
+        // ones whose color changed, which is what keeps this from walking the whole battlefield.
+        if relayout {
+            paint_tiles(chunks, match_id, grid, &mut tiles.p0());
+        } else {
+            paint_tiles(chunks, match_id, grid, &mut tiles.p1());
+        }
+
+        for chunk in chunks.chunks.iter_mut().filter(|x| x.dirty) {
+            if let Some(image) = images.get_mut(&chunk.image) {
+                image.data.copy_from_slice(&chunk.data);
+                uploads += 1;
+            }
+            chunk.dirty = false;
+        }
+    }
+    diagnostics.add_measurement(&TILE_CHUNK_UPLOADS, || uploads as f64);
+}
+/// Paint the tiles of `match_id` among `tiles` into their chunks, hiding their own sprites.
+fn paint_tiles<F: QueryFilter>(
+    chunks: &mut TileChunks,
+    match_id: MatchId,
+    grid: &TileGrid,
+    tiles: &mut TileQuery<F>,
+) {
+    for (entity, sprite, transform, mut visibility, &x) in tiles {
+        if x != match_id {
+            continue;
+        }
+        let (chunk, pixel) = match chunks.pixels.get(&entity) {
+            Some(&found) => found,
+            None => {
+                let Some((x, y)) = grid.cell(transform.translation.xy()) else {
+                    continue;
+                };
+                let found = chunks.pixel(x, y);
+                chunks.pixels.insert(entity, found);
+                found
+            }
+        };
+        chunks.chunks[chunk].paint(pixel, sprite.color.to_srgba().to_u8_array());
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    utils::Instant,
+};
+
+use super::{
+    handle_bullet_tile_collision, hit_tile, update_charge_level, BattlefieldGeometry, Bullet,
+    Charge, ShotType, TileConvertedEvent, TileGrid, TileHitQuery,
+};
+use crate::{
+    config::{GameConfig, TileConversionMode},
+    draft::DraftedUpgrades,
+    mutators::ActiveMutators,
+    occlusion::WindowShownEvent,
+    utils::{MatchId, Participant, ParticipantMap, PerMatch, TileColor},
+};
+
+// Constants {{{
+
+/// The number of grid cells walked to fill in trail gaps each frame.
+const TRAIL_CELLS: DiagnosticPath = DiagnosticPath::const_new("battlefield/trail_cells");
+/// Time in milliseconds spent filling in trail gaps each frame.
+const TRAIL_TIME: DiagnosticPath = DiagnosticPath::const_new("battlefield/trail_ms");
+
+// }}}
+
+pub struct TrailPlugin;
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(TRAIL_CELLS).with_suffix(" cells"))
+            .register_diagnostic(Diagnostic::new(TRAIL_TIME).with_suffix("ms"))
+            .add_systems(
+                Update,
+                (
+                    forget_positions.run_if(on_event::<WindowShownEvent>()),
+                    fill_trail_gaps
+                        .after(forget_positions)
+                        .after(handle_bullet_tile_collision)
+                        .before(update_charge_level),
+                ),
+            );
+    }
+}
+
+/// The position of a bullet on the battlefield of its match as of the previous frame.
+#[derive(Component)]
+pub(super) struct PreviousPosition(Vec2);
+
+/// Hit the tiles a painting bullet skipped over since the previous frame. The collision system only
+/// sees the tiles under the bullet at the end of each physics step, so a bullet that moves further
+/// than its own diameter in a frame would otherwise leave gaps in its trail.
+///
+/// The cost is bounded by the size of the grid rather than the bullet's speed, since
+/// [`TileGrid::tiles_on_line`] visits at most `3 * TileGrid::SIZE` cells per bullet. The cells
+/// walked and the time taken are reported as diagnostics.
+fn fill_trail_gaps(
+    mut commands: Commands,
+    mut diagnostics: Diagnostics,
+    grids: Res<PerMatch<TileGrid>>,
+    (config, upgrades): (Res<GameConfig>, Res<DraftedUpgrades>),
+    geometry: Res<BattlefieldGeometry>,
+    mutators: Res<ActiveMutators>,
+    tile_colors: Res<ParticipantMap<TileColor>>,
+    mut bullets: Query<
+        (
+            Entity,
+            &Participant,
+            &mut Charge,
+            &ShotType,
+            &GlobalTransform,
+            &MatchId,
+            Option<&mut PreviousPosition>,
+        ),
+        With<Bullet>,
+    >,
+    mut tile_query: TileHitQuery,
+    mut conversions: EventWriter<TileConvertedEvent>,
+) {
+    let start_time = Instant::now();
+    let mut cells = 0;
+    for (entity, &owner, mut charge, &shot_type, transform, &match_id, previous) in &mut bullets {
+        // Measured on the battlefield of the bullet's match, where its grid is laid out.
+        let end = transform.translation().xy() - match_id.world_offset();
+        let Some(mut previous) = previous else {
+            commands.entity(entity).insert(PreviousPosition(end));
+            continue;
+        };
+        let start = std::mem::replace(&mut previous.0, end);
+        if shot_type.conversion_mode(&config.tile_conversion) != TileConversionMode::Paint
+            || charge.value == 0
+        {
+            continue;
+        }
+        // The tiles within the bullet's reach at either end are left to the collision system, so
+        // there's only a gap to fill if those two areas don't overlap.
+        let radius = charge.get_scale();
+        if start.distance(end) <= radius * 2.0 {
+            continue;
+        }
+        let Some(grid) = grids.get(match_id) else {
+            continue;
+        };
+        let tiles = grid.tiles_on_line(start, end);
+        cells += tiles.len();
+        for (tile, center) in tiles {
+            if center.distance(start) > radius && center.distance(end) > radius {
+                hit_tile(
+                    tile,
+                    entity,
+                    owner,
+                    &mut charge,
+                    &mut tile_query,
+                    &mut conversions,
+                    &tile_colors,
+                    &config,
+                    &upgrades,
+                    &geometry,
+                    &mutators,
+                );
+            }
+        }
+    }
+    diagnostics.add_measurement(&TRAIL_CELLS, || cells as f64);
+    diagnostics.add_measurement(&TRAIL_TIME, || start_time.elapsed().as_secs_f64() * 1000.0);
+}
+/// Start the trails over from where the bullets are once the window is visible again. A bullet may
+/// have bounced around during a stall while it was hidden, and painting a straight line from where it
+/// was to where it is would cut through whatever it bounced off.
+fn forget_positions(mut commands: Commands, bullets: Query<Entity, With<PreviousPosition>>) {
+    for entity in &bullets {
+        commands.entity(entity).remove::<PreviousPosition>();
+    }
+}
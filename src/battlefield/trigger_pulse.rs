@@ -0,0 +1,136 @@
+use bevy::{prelude::*, sprite::Mesh2dHandle};
+
+use super::{BattlefieldGeometry, RestartEvent};
+use crate::{
+    panel_plugin::TriggerEvent,
+    utils::{BallColor, Matches, ParticipantMap},
+};
+
+// Constants {{{
+
+/// The number of pulses that can be showing at once. The oldest one is cut short past this.
+const PULSE_POOL_SIZE: usize = 16;
+const PULSE_RADIUS: f32 = 30.0;
+const PULSE_WIDTH: f32 = 6.0;
+/// How much bigger the pulse is by the time it fades out.
+const PULSE_MAX_SCALE: f32 = 3.0;
+/// Time in seconds a pulse takes to fade out.
+const PULSE_SECS: f32 = 0.6;
+
+// Z-index
+const PULSE_Z: f32 = 4.0;
+
+// }}}
+
+/// A ring pulsing out of a turret whenever a worker ball lands in one of its trigger zones, so
+/// viewers watching only the battlefield can tell who just got powered up.
+pub struct TriggerPulsePlugin;
+impl Plugin for TriggerPulsePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                start_pulses.run_if(on_event::<TriggerEvent>()),
+                animate_pulses.after(start_pulses),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// One of the pooled pulse entities, which are reused rather than spawned for every trigger. Each
+/// has a material of its own to fade out.
+#[derive(Component)]
+struct TriggerPulse {
+    /// Counts down the current pulse, or `None` while the pulse is free.
+    timer: Option<Timer>,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mesh = Mesh2dHandle(meshes.add(Annulus::new(PULSE_RADIUS - PULSE_WIDTH, PULSE_RADIUS)));
+    for _ in 0..PULSE_POOL_SIZE {
+        commands.spawn((
+            Name::new("Trigger Pulse"),
+            TriggerPulse { timer: None },
+            ColorMesh2dBundle {
+                mesh: mesh.clone(),
+                material: materials.add(Color::NONE),
+                transform: Transform::from_xyz(0.0, 0.0, PULSE_Z),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ));
+    }
+}
+fn start_pulses(
+    mut events: EventReader<TriggerEvent>,
+    colors: Res<ParticipantMap<BallColor>>,
+    (geometry, matches): (Res<BattlefieldGeometry>, Res<Matches>),
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pulses: Query<(
+        &mut TriggerPulse,
+        &mut Transform,
+        &mut Visibility,
+        &Handle<ColorMaterial>,
+    )>,
+) {
+    for event in events.read() {
+        // Take a free pulse, or cut short the one that's furthest along.
+        let Some((mut pulse, mut transform, mut visibility, material)) =
+            pulses.iter_mut().max_by(|a, b| {
+                let progress =
+                    |x: &TriggerPulse| x.timer.as_ref().map_or(f32::INFINITY, Timer::fraction);
+                progress(&a.0).total_cmp(&progress(&b.0))
+            })
+        else {
+            return;
+        };
+        pulse.timer = Some(Timer::from_seconds(PULSE_SECS, TimerMode::Once));
+        transform.translation = geometry
+            .turret_world_position(event.participant, &matches)
+            .extend(PULSE_Z);
+        transform.scale = Vec3::ONE;
+        *visibility = Visibility::Inherited;
+        if let Some(material) = materials.get_mut(material) {
+            material.color = colors[event.participant].0;
+        }
+    }
+}
+/// Grow each pulse while fading it out, then free it up.
+fn animate_pulses(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut pulses: Query<(
+        &mut TriggerPulse,
+        &mut Transform,
+        &mut Visibility,
+        &Handle<ColorMaterial>,
+    )>,
+) {
+    for (mut pulse, mut transform, mut visibility, material) in &mut pulses {
+        let Some(timer) = &mut pulse.timer else {
+            continue;
+        };
+        timer.tick(time.delta());
+        if timer.finished() {
+            pulse.timer = None;
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        let t = timer.fraction();
+        transform.scale = Vec3::splat(1.0 + (PULSE_MAX_SCALE - 1.0) * t);
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_alpha(1.0 - t);
+        }
+    }
+}
+fn restart(mut pulses: Query<(&mut TriggerPulse, &mut Visibility)>) {
+    for (mut pulse, mut visibility) in &mut pulses {
+        pulse.timer = None;
+        *visibility = Visibility::Hidden;
+    }
+}
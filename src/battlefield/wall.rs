@@ -0,0 +1,161 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    geometry::update_geometry,
+    map_generator::{is_clear_mirrored, random_quarter_position, MIRRORS},
+    update_charge_level, BattlefieldGeometry, Bullet, Charge, RestartEvent,
+};
+use crate::{
+    collision_groups,
+    config::GameConfig,
+    utils::{GameRng, MatchId, Matches, Roster, RoundScoped},
+};
+
+// Constants {{{
+
+const WALL_THICKNESS: f32 = 8.0;
+const WALL_COLOR: Color = Color::srgb(0.55, 0.4, 0.3);
+/// How see-through a wall is just before it breaks, fading from solid as it's worn down.
+const WALL_MIN_ALPHA: f32 = 0.25;
+/// Above the tiles, same as the obstacles.
+const WALL_Z: f32 = -0.5;
+/// The number of random spots tried for each wall before giving up on it.
+const PLACEMENT_ATTEMPTS: usize = 20;
+
+// }}}
+
+pub struct WallPlugin;
+impl Plugin for WallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_walls.after(update_geometry))
+            .add_systems(
+                Update,
+                (
+                    spawn_walls
+                        .after(update_geometry)
+                        .run_if(on_event::<RestartEvent>()),
+                    chip_walls
+                        .run_if(on_event::<CollisionEvent>())
+                        .before(update_charge_level),
+                ),
+            );
+    }
+}
+
+/// A wall bullets bounce off until it's worn down.
+#[derive(Component)]
+struct Wall {
+    hit_points: u64,
+    max_hit_points: u64,
+}
+
+/// Put up fresh walls for the round about to start, taking down whatever's left of the last ones.
+fn spawn_walls(
+    mut commands: Commands,
+    mut events: EventReader<RestartEvent>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    walls: Query<(Entity, &MatchId), With<Wall>>,
+) {
+    let matches = RestartEvent::matches(&mut events, &roster, &matches);
+    for (entity, match_id) in &walls {
+        if matches.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    let config = &*config;
+    if !config.walls.enabled || config.walls.hit_points == 0 {
+        return;
+    }
+    let half_length = config.walls.length / 2.0;
+    for &match_id in &matches {
+        for _ in 0..config.walls.count {
+            let placement = (0..PLACEMENT_ATTEMPTS)
+                .map(|_| {
+                    (
+                        random_quarter_position(&mut rng.0),
+                        rng.0.gen_range(0.0..PI),
+                    )
+                })
+                .find(|&(position, _)| is_clear_mirrored(&geometry, position, half_length));
+            let Some((position, angle)) = placement else {
+                continue;
+            };
+            for sign in MIRRORS {
+                commands.spawn((
+                    Wall {
+                        hit_points: config.walls.hit_points,
+                        max_hit_points: config.walls.hit_points,
+                    },
+                    Name::new("Wall"),
+                    RoundScoped,
+                    match_id,
+                    RigidBody::Fixed,
+                    Collider::cuboid(half_length, WALL_THICKNESS / 2.0),
+                    CollisionGroups::new(
+                        collision_groups::BATTLEFIELD_ROOT,
+                        collision_groups::ALL_BULLETS | collision_groups::ALL_NEW_BULLETS,
+                    ),
+                    Restitution {
+                        coefficient: 1.0,
+                        combine_rule: CoefficientCombineRule::Max,
+                    },
+                    SpriteBundle {
+                        transform: Transform::from_translation(
+                            (match_id.world_offset() + position * sign).extend(WALL_Z),
+                        )
+                        .with_rotation(Quat::from_rotation_z(angle * sign.x * sign.y)),
+                        sprite: Sprite {
+                            color: WALL_COLOR,
+                            custom_size: Some(Vec2::new(config.walls.length, WALL_THICKNESS)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+}
+/// Wear down the walls by the charge of the bullets hitting them, which uses up that much of the
+/// bullets' charge in turn, and break the ones that are worn through.
+fn chip_walls(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut walls: Query<(&mut Wall, &mut Sprite)>,
+    mut bullets: Query<&mut Charge, With<Bullet>>,
+) {
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let (wall_entity, bullet) = if walls.contains(a) { (a, b) } else { (b, a) };
+        let Ok((mut wall, mut sprite)) = walls.get_mut(wall_entity) else {
+            continue;
+        };
+        let Ok(mut charge) = bullets.get_mut(bullet) else {
+            continue;
+        };
+        // A wall that's already down might still get hit before it's despawned.
+        if wall.hit_points == 0 {
+            continue;
+        }
+        let consumed = charge.value.min(wall.hit_points);
+        charge.value -= consumed;
+        wall.hit_points -= consumed;
+        if wall.hit_points == 0 {
+            commands.entity(wall_entity).despawn_recursive();
+        } else {
+            let health = wall.hit_points as f32 / wall.max_hit_points as f32;
+            sprite
+                .color
+                .set_alpha(WALL_MIN_ALPHA + (1.0 - WALL_MIN_ALPHA) * health);
+        }
+    }
+}
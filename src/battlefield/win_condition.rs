@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+use super::{handle_elimination, snapshot::GameSnapshot, RestartEvent};
+use crate::app_state::AppState;
+use crate::{
+    config::GameConfig,
+    utils::{MatchId, Matches, Participant, PerMatch, Roster},
+};
+
+pub struct WinConditionPlugin;
+impl Plugin for WinConditionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RoundWinner>()
+            .init_resource::<MatchWinners>()
+            .add_event::<MatchDecidedEvent>()
+            .add_systems(Startup, build_win_conditions)
+            .add_systems(
+                Update,
+                (
+                    check_win_conditions
+                        .run_if(in_state(AppState::Playing))
+                        .after(handle_elimination),
+                    (reset_winner, build_win_conditions).run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+/// A rule that decides when a round is over and who won it.
+pub trait WinCondition: Send + Sync + 'static {
+    /// Returns the winner if the round should end with the state in `snapshot`.
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant>;
+    /// A short explanation of the condition for the rules overlay.
+    fn describe(&self) -> String;
+    /// Time in seconds after which the condition is sure to end the round, if there is one, for
+    /// the match clock.
+    fn time_limit_secs(&self) -> Option<f32> {
+        None
+    }
+}
+
+/// The participant who won the current round, or `None` while it's still going. With more than one
+/// match running, the round is only over once every match has been won, and this is the winner of
+/// the first of them. The rest are in [`MatchWinners`].
+#[derive(Resource, Default)]
+pub struct RoundWinner(pub Option<Participant>);
+/// The winner of each match in the current round that's been won so far.
+#[derive(Resource, Default)]
+pub struct MatchWinners(pub PerMatch<Participant>);
+impl MatchWinners {
+    /// Everyone credited with a win this round, in the order of their matches. A team win counts
+    /// for both teammates, as long as they both took part in the same match.
+    pub fn credited(
+        &self,
+        config: &GameConfig,
+        roster: &Roster,
+        matches: &Matches,
+    ) -> Vec<Participant> {
+        let mut credited = Vec::new();
+        for (match_id, &winner) in self.0.iter() {
+            credited.push(winner);
+            let teammate = winner.teammate();
+            if config.teams.enabled && matches.roster(roster, match_id)[teammate] {
+                credited.push(teammate);
+            }
+        }
+        credited
+    }
+}
+/// Event sent when one of the matches in the round has been won. The round itself goes on until
+/// every match has been.
+#[derive(Event)]
+pub struct MatchDecidedEvent {
+    pub match_id: MatchId,
+    pub winner: Participant,
+}
+/// The win conditions in effect for the current round. The first one to be met ends the round.
+#[derive(Resource, Default)]
+pub struct WinConditions(pub Vec<Box<dyn WinCondition>>);
+
+/// The round ends when only one turret is left.
+pub struct LastTurretStanding;
+impl WinCondition for LastTurretStanding {
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant> {
+        let mut alive = snapshot.participants.iter().filter(|x| x.alive);
+        match (alive.next(), alive.next()) {
+            (Some(survivor), None) => Some(survivor.participant),
+            _ => None,
+        }
+    }
+    fn describe(&self) -> String {
+        "Be the last turret standing".to_string()
+    }
+}
+/// The round ends when only the turrets of one team are left. The winner is whichever of them comes
+/// first, and shares the win with its teammate.
+pub struct LastTeamStanding;
+impl WinCondition for LastTeamStanding {
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant> {
+        let mut alive = snapshot.participants.iter().filter(|x| x.alive);
+        let first = alive.next()?.participant;
+        alive
+            .all(|x| x.participant == first.teammate())
+            .then_some(first)
+    }
+    fn describe(&self) -> String {
+        "Be the last team standing".to_string()
+    }
+}
+/// The round ends when a participant owns at least `fraction` of the battlefield.
+pub struct Territory {
+    pub fraction: f32,
+}
+impl WinCondition for Territory {
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant> {
+        let total = snapshot.participants.iter().map(|x| x.tiles).sum::<usize>();
+        snapshot
+            .participants
+            .iter()
+            .find(|x| x.tiles as f32 >= total as f32 * self.fraction)
+            .map(|x| x.participant)
+    }
+    fn describe(&self) -> String {
+        format!("Own {:.0}% of the battlefield", self.fraction * 100.0)
+    }
+}
+/// The round ends after `secs` seconds, won by the surviving participant with the most tiles. Ties
+/// go to whoever's holding the most charge between their turret and bullets, then to whoever
+/// generated the most charge over the round, then to whoever comes first.
+pub struct Timed {
+    pub secs: f32,
+}
+impl WinCondition for Timed {
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant> {
+        if snapshot.elapsed_secs < self.secs {
+            return None;
+        }
+        snapshot
+            .participants
+            .iter()
+            .filter(|x| x.alive)
+            // `max_by_key` picks the last of equal elements, so go backwards for the first.
+            .rev()
+            .max_by_key(|x| {
+                let held = x.turret_charge.unwrap_or(0).saturating_add(x.bullet_charge);
+                (x.tiles, held, x.stats.charge_generated)
+            })
+            .map(|x| x.participant)
+    }
+    fn describe(&self) -> String {
+        format!(
+            "Own the most tiles after {:.0} seconds, with ties going to the most charge held",
+            self.secs
+        )
+    }
+    fn time_limit_secs(&self) -> Option<f32> {
+        Some(self.secs)
+    }
+}
+/// The round ends when a turret holds at least `charge`.
+pub struct ScoreTarget {
+    pub charge: u64,
+}
+impl WinCondition for ScoreTarget {
+    fn check(&self, snapshot: &GameSnapshot) -> Option<Participant> {
+        snapshot
+            .participants
+            .iter()
+            .find(|x| x.turret_charge.is_some_and(|charge| charge >= self.charge))
+            .map(|x| x.participant)
+    }
+    fn describe(&self) -> String {
+        format!("Hold a charge of {} in a turret", self.charge)
+    }
+}
+
+fn build_win_conditions(mut commands: Commands, config: Res<GameConfig>) {
+    let conditions = config
+        .win_conditions
+        .iter()
+        .map(|kind| kind.build())
+        .collect();
+    commands.insert_resource(WinConditions(conditions));
+}
+/// Check each match that's still going against the win conditions, and end the round once they've
+/// all been won.
+fn check_win_conditions(world: &mut World) {
+    let snapshot = GameSnapshot::capture(world);
+    let matches = *world.resource::<Matches>();
+    let running = matches.running(world.resource::<Roster>());
+    for &match_id in &running {
+        if world.resource::<MatchWinners>().0.get(match_id).is_some() {
+            continue;
+        }
+        let snapshot = snapshot.for_match(&matches, match_id);
+        let winner = world
+            .resource::<WinConditions>()
+            .0
+            .iter()
+            .find_map(|condition| condition.check(&snapshot));
+        if let Some(winner) = winner {
+            world
+                .resource_mut::<MatchWinners>()
+                .0
+                .insert(match_id, winner);
+            world.send_event(MatchDecidedEvent { match_id, winner });
+        }
+    }
+    let winners = world.resource::<MatchWinners>();
+    let first = running
+        .iter()
+        .map(|&x| winners.0.get(x).copied())
+        .collect::<Option<Vec<_>>>()
+        .and_then(|x| x.first().copied());
+    if let Some(winner) = first {
+        info!("Round won by {}.", winner);
+        world.resource_mut::<RoundWinner>().0 = Some(winner);
+    }
+}
+fn reset_winner(
+    mut events: EventReader<RestartEvent>,
+    mut winner: ResMut<RoundWinner>,
+    mut match_winners: ResMut<MatchWinners>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+) {
+    for event in events.read() {
+        match_winners.0.remove(event.match_id);
+    }
+    let running = matches.running(&roster);
+    match_winners.0.retain(|x| running.contains(&x));
+    winner.0 = None;
+}
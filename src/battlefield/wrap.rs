@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    trail::PreviousPosition, BattlefieldGeometry, BattlefieldRoot, Bullet, RestartEvent,
+    BATTLEFIELD_HALF_WIDTH,
+};
+use crate::{
+    collision_groups,
+    config::{ArenaShape, GameConfig},
+};
+
+pub struct WrapPlugin;
+impl Plugin for WrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wrapping>()
+            .add_systems(Startup, update_wrapping.after(super::setup))
+            .add_systems(
+                Update,
+                (
+                    update_wrapping
+                        .after(super::restart)
+                        .run_if(on_event::<RestartEvent>()),
+                    wrap_bullets.run_if(|wrapping: Res<Wrapping>| wrapping.0),
+                ),
+            );
+    }
+}
+
+/// Whether the edges of the battlefield wrap around for the current round.
+#[derive(Resource, Default)]
+struct Wrapping(bool);
+
+/// Take the walls around the battlefield away from the bullets when the edges wrap around, and put
+/// them back when they don't. Debris keeps bouncing off them either way. Only the square arena
+/// wraps, since the edges of the other shapes don't line up across from each other.
+fn update_wrapping(
+    mut wrapping: ResMut<Wrapping>,
+    config: Res<GameConfig>,
+    geometry: Res<BattlefieldGeometry>,
+    mut roots: Query<&mut CollisionGroups, With<BattlefieldRoot>>,
+) {
+    wrapping.0 = config.wrap_around && geometry.shape == ArenaShape::Square;
+    let filters = match wrapping.0 {
+        true => collision_groups::DEBRIS,
+        false => {
+            collision_groups::ALL_BULLETS
+                | collision_groups::ALL_NEW_BULLETS
+                | collision_groups::DEBRIS
+        }
+    };
+    for mut groups in &mut roots {
+        groups.filters = filters;
+    }
+}
+/// Bring the bullets that went off one edge back in on the opposite edge, still going the same way.
+fn wrap_bullets(
+    mut commands: Commands,
+    mut bullets: Query<(Entity, &mut Transform), With<Bullet>>,
+) {
+    let width = BATTLEFIELD_HALF_WIDTH * 2.0;
+    for (entity, mut transform) in &mut bullets {
+        let position = transform.translation.xy();
+        if position.abs().max_element() <= BATTLEFIELD_HALF_WIDTH {
+            continue;
+        }
+        let wrapped = (position + BATTLEFIELD_HALF_WIDTH).rem_euclid(Vec2::splat(width))
+            - BATTLEFIELD_HALF_WIDTH;
+        transform.translation = wrapped.extend(transform.translation.z);
+        // The trail would otherwise be painted all the way across the battlefield.
+        commands.entity(entity).remove::<PreviousPosition>();
+    }
+}
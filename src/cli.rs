@@ -0,0 +1,376 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate::{
+    config::{GameConfig, GamePreset},
+    utils::{MatchId, Matches, Participant, Roster, MATCH_COUNT_MAX},
+};
+
+/// The command line as it's written, before it's turned into [`LaunchOptions`].
+#[derive(Debug, Parser)]
+#[command(name = "multiply_or_release")]
+struct Args {
+    /// Seed for everything random in the game
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+    /// Tuning to play with: classic, fast, chaos, coop or timed
+    #[arg(long, value_name = "PRESET", value_parser = parse_preset)]
+    preset: Option<GamePreset>,
+    /// Who takes part in the first round, either a count from 2 to 8 or a comma separated list of
+    /// colors, e.g. red,violet
+    #[arg(long, value_name = "LIST", value_parser = parse_roster)]
+    participants: Option<Roster>,
+    /// Play separate matches side by side, as groups of colors separated by a slash, e.g.
+    /// red,violet/blue,green. Overrides --participants
+    #[arg(long, value_name = "LIST", value_parser = parse_matches)]
+    matches: Option<(Roster, Matches)>,
+    /// Start in a window of this size instead of fullscreen, e.g. 1280x720
+    #[arg(long, value_name = "WxH", value_parser = parse_window_size)]
+    window_size: Option<Vec2>,
+    /// Simulation speed relative to real time
+    #[arg(long, value_name = "SCALE", default_value_t = 1.0, value_parser = parse_time_scale)]
+    time_scale: f32,
+    /// Run the simulation without a window
+    #[arg(long)]
+    headless: bool,
+    /// Speed up rounds that drag on
+    #[arg(long)]
+    auto_accelerate: bool,
+    /// Load a tuning file saved with F5, overriding --preset
+    #[arg(long, value_name = "PATH")]
+    tuning: Option<PathBuf>,
+    /// Replay a demo script
+    #[arg(long, value_name = "PATH", conflicts_with = "record_demo")]
+    demo: Option<PathBuf>,
+    /// Record a demo script
+    #[arg(long, value_name = "PATH")]
+    record_demo: Option<PathBuf>,
+    /// Check that a daily results file was signed with this game's key and hasn't been edited
+    /// since, then exit
+    #[arg(long, value_name = "PATH")]
+    verify_results: Option<PathBuf>,
+    /// Start the first round without showing the main menu
+    #[arg(long)]
+    skip_menu: bool,
+    /// Keep starting new rounds with random presets and lineups, for running unattended
+    #[arg(long)]
+    attract: bool,
+    /// Lock out the keyboard and hide the cursor for a live stream, until the unlock chord set in
+    /// the game config is pressed
+    #[arg(long)]
+    presentation: bool,
+    /// Stress test with giant panels, hundreds of balls and a performance overlay
+    #[arg(long)]
+    stress: bool,
+    /// Turn off particles, sound and window integrations, which is otherwise done automatically
+    /// after a crash
+    #[arg(long, conflicts_with = "no_safe_mode")]
+    safe_mode: bool,
+    /// Start normally even after a crash
+    #[arg(long)]
+    no_safe_mode: bool,
+}
+
+/// Options passed on the command line at launch.
+#[derive(Debug, Clone, Resource)]
+pub struct LaunchOptions {
+    pub seed: Option<u64>,
+    pub preset: GamePreset,
+    /// The roster for the first round.
+    pub roster: Option<Roster>,
+    /// The match each participant plays in, when there's more than one.
+    pub matches: Option<Matches>,
+    pub window_size: Option<Vec2>,
+    pub time_scale: f32,
+    pub headless: bool,
+    pub auto_accelerate: bool,
+    pub tuning: Option<PathBuf>,
+    pub demo: Option<DemoOption>,
+    pub verify_results: Option<PathBuf>,
+    pub skip_menu: bool,
+    pub attract: bool,
+    pub presentation: bool,
+    pub stress: bool,
+    /// Whether to start in safe mode, or `None` to decide based on how the previous session ended.
+    pub safe_mode: Option<bool>,
+}
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            preset: GamePreset::default(),
+            roster: None,
+            matches: None,
+            window_size: None,
+            time_scale: 1.0,
+            headless: false,
+            auto_accelerate: false,
+            tuning: None,
+            demo: None,
+            verify_results: None,
+            skip_menu: false,
+            attract: false,
+            presentation: false,
+            stress: false,
+            safe_mode: None,
+        }
+    }
+}
+#[derive(Debug, Clone)]
+pub enum DemoOption {
+    Play(PathBuf),
+    Record(PathBuf),
+}
+
+impl From<Args> for LaunchOptions {
+    fn from(args: Args) -> Self {
+        let (roster, matches) = match args.matches {
+            Some((roster, matches)) => (Some(roster), Some(matches)),
+            None => (args.participants, None),
+        };
+        let demo = match (args.demo, args.record_demo) {
+            (Some(path), _) => Some(DemoOption::Play(path)),
+            (None, Some(path)) => Some(DemoOption::Record(path)),
+            (None, None) => None,
+        };
+        let safe_mode = match (args.safe_mode, args.no_safe_mode) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        };
+        Self {
+            seed: args.seed,
+            preset: args.preset.unwrap_or_default(),
+            roster,
+            matches,
+            window_size: args.window_size,
+            time_scale: args.time_scale,
+            headless: args.headless,
+            auto_accelerate: args.auto_accelerate,
+            tuning: args.tuning,
+            demo,
+            verify_results: args.verify_results,
+            skip_menu: args.skip_menu,
+            attract: args.attract,
+            presentation: args.presentation,
+            stress: args.stress,
+            safe_mode,
+        }
+    }
+}
+impl LaunchOptions {
+    /// Parse the arguments, starting with the program name. The error prints the usage along with
+    /// what's wrong, or just the usage for `--help`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, clap::Error> {
+        Args::try_parse_from(args).map(Self::from)
+    }
+    /// Apply the options that override the game config to `config`, which is done again whenever
+    /// it's started over from a preset. Nobody's watching the window in attract or presentation
+    /// mode, so the round keeps going when it's covered up unless a tuning file says otherwise.
+    pub fn override_config(&self, config: &mut GameConfig) {
+        config.speed_ramp.enabled |= self.auto_accelerate;
+        config.presentation.enabled |= self.presentation;
+        config.performance.pause_when_hidden &= !(self.attract || self.presentation);
+    }
+}
+
+fn parse_preset(value: &str) -> Result<GamePreset, String> {
+    GamePreset::ALL
+        .into_iter()
+        .find(|x| x.name().eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("unknown preset {}", value))
+}
+fn parse_roster(value: &str) -> Result<Roster, String> {
+    let mut roster = Roster(Default::default());
+    if let Ok(count) = value.parse::<usize>() {
+        if !(2..=Participant::ALL.len()).contains(&count) {
+            return Err(format!(
+                "--participants must be between 2 and {}",
+                Participant::ALL.len()
+            ));
+        }
+        for &participant in &Participant::ALL[..count] {
+            roster[participant] = true;
+        }
+        return Ok(roster);
+    }
+    for name in value.split(',') {
+        let participant = Participant::ALL
+            .into_iter()
+            .find(|x| x.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("unknown participant {}", name))?;
+        roster[participant] = true;
+    }
+    if roster.count() < 2 {
+        return Err("--participants needs at least 2 participants".to_string());
+    }
+    Ok(roster)
+}
+/// The roster made up of every group, and the matches with each group playing in its own.
+fn parse_matches(value: &str) -> Result<(Roster, Matches), String> {
+    let groups = value.split('/').collect::<Vec<_>>();
+    if !(1..=MATCH_COUNT_MAX).contains(&groups.len()) {
+        return Err(format!(
+            "--matches takes between 1 and {} groups",
+            MATCH_COUNT_MAX
+        ));
+    }
+    let mut roster = Roster(Default::default());
+    let mut matches = Matches::default();
+    for (i, group) in groups.into_iter().enumerate() {
+        let group = parse_roster(group)?;
+        for participant in Participant::ALL.into_iter().filter(|&p| group[p]) {
+            if roster[participant] {
+                return Err(format!("{} can only play in one match", participant));
+            }
+            roster[participant] = true;
+            matches.0[participant] = MatchId(i as u8);
+        }
+    }
+    Ok((roster, matches))
+}
+fn parse_time_scale(value: &str) -> Result<f32, String> {
+    let scale: f32 = value
+        .parse()
+        .map_err(|_| format!("invalid number {}", value))?;
+    if !(scale > 0.0 && scale.is_finite()) {
+        return Err("--time-scale must be a positive number".to_string());
+    }
+    Ok(scale)
+}
+fn parse_window_size(value: &str) -> Result<Vec2, String> {
+    let invalid = || format!("invalid window size {}, expected WxH", value);
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: f32 = width.parse().map_err(|_| invalid())?;
+    let height: f32 = height.parse().map_err(|_| invalid())?;
+    if !(width > 0.0 && width.is_finite() && height > 0.0 && height.is_finite()) {
+        return Err(invalid());
+    }
+    Ok(Vec2::new(width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, clap::Error> {
+        let args = ["multiply_or_release"].iter().chain(args);
+        LaunchOptions::parse(args.map(|x| x.to_string()))
+    }
+    fn is_invalid(result: Result<LaunchOptions, clap::Error>) -> bool {
+        result.is_err_and(|e| e.kind() != ErrorKind::DisplayHelp)
+    }
+
+    #[test]
+    fn values_can_be_inline_or_separate() {
+        for args in [&["--seed=42"][..], &["--seed", "42"]] {
+            assert_eq!(parse(args).unwrap().seed, Some(42));
+        }
+        let options = parse(&["--preset=fast", "--time-scale", "2.5", "--headless"]).unwrap();
+        assert_eq!(options.preset, GamePreset::Fast);
+        assert_eq!(options.time_scale, 2.5);
+        assert!(options.headless);
+    }
+
+    #[test]
+    fn missing_values_are_rejected() {
+        for flag in [
+            "--seed",
+            "--preset",
+            "--participants",
+            "--time-scale",
+            "--tuning",
+        ] {
+            assert!(is_invalid(parse(&[flag])), "{}", flag);
+        }
+        assert!(is_invalid(parse(&["--seed", "many"])));
+        assert!(is_invalid(parse(&["--bogus"])));
+        assert!(parse(&["-h"]).is_err_and(|e| e.kind() == ErrorKind::DisplayHelp));
+        assert!(is_invalid(parse(&[
+            "--demo",
+            "a.ron",
+            "--record-demo",
+            "b.ron"
+        ])));
+        assert!(is_invalid(parse(&["--safe-mode", "--no-safe-mode"])));
+    }
+
+    #[test]
+    fn participants_are_a_count_or_colors() {
+        let roster = parse(&["--participants", "3"]).unwrap().roster.unwrap();
+        assert!(Participant::ALL
+            .iter()
+            .enumerate()
+            .all(|(i, &x)| roster[x] == (i < 3)));
+
+        let roster = parse(&["--participants=red, Violet"])
+            .unwrap()
+            .roster
+            .unwrap();
+        assert_eq!(roster.count(), 2);
+        assert!(roster[Participant::A] && roster[Participant::C]);
+
+        for value in ["0", "1", "9", "red", "red,red", "red,mauve", ""] {
+            assert!(is_invalid(parse(&["--participants", value])), "{}", value);
+        }
+    }
+
+    #[test]
+    fn matches_are_groups_of_colors() {
+        let options = parse(&["--matches", "red,violet/blue,green"]).unwrap();
+        let (roster, matches) = (options.roster.unwrap(), options.matches.unwrap());
+        assert_eq!(roster.count(), 4);
+        assert_eq!(matches.running(&roster), [MatchId(0), MatchId(1)]);
+        assert_eq!(matches[Participant::A], matches[Participant::C]);
+        assert_ne!(matches[Participant::A], matches[Participant::B]);
+
+        for value in [
+            "red,violet/red,blue",
+            "red/blue,green",
+            "red,violet/blue,green/",
+        ] {
+            assert!(is_invalid(parse(&["--matches", value])), "{}", value);
+        }
+    }
+
+    #[test]
+    fn time_scale_must_be_positive_and_finite() {
+        for value in ["0", "-1", "NaN", "inf"] {
+            assert!(is_invalid(parse(&["--time-scale", value])), "{}", value);
+        }
+    }
+
+    #[test]
+    fn window_size_must_be_two_positive_numbers() {
+        let size = parse(&["--window-size=1280X720"]).unwrap().window_size;
+        assert_eq!(size, Some(Vec2::new(1280.0, 720.0)));
+        for value in [
+            "1280",
+            "1280x",
+            "x720",
+            "0x720",
+            "1280x-720",
+            "NaNx720",
+            "widexhigh",
+        ] {
+            assert!(is_invalid(parse(&["--window-size", value])), "{}", value);
+        }
+    }
+
+    #[test]
+    fn unattended_modes_keep_playing_when_hidden() {
+        for (args, pause) in [
+            (&[][..], true),
+            (&["--attract"], false),
+            (&["--presentation"], false),
+        ] {
+            let mut config = GameConfig::default();
+            parse(args).unwrap().override_config(&mut config);
+            assert_eq!(config.performance.pause_when_hidden, pause, "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,1545 @@
+use bevy::{color::palettes::css, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{
+        LastTeamStanding, LastTurretStanding, MapFile, ScoreTarget, Territory, Timed, WinCondition,
+    },
+    locale::Locale,
+    panel_plugin::{TriggerType, TRIGGER_ZONE_COUNT, TRIGGER_ZONE_HEIGHT_MAX},
+    utils::{Participant, ParticipantMap},
+};
+
+/// Tunable game rules. Everything that's optional or adjustable per session lives here, while
+/// layout values that never change stay as constants in their respective modules.
+///
+/// This is registered for reflection so it can be edited live from the world inspector.
+#[derive(Debug, Clone, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct GameConfig {
+    pub participants: ParticipantIdentities,
+    pub control: ControlConfig,
+    pub teams: TeamConfig,
+    pub trigger_zones: TriggerZoneLayout,
+    pub physics: PhysicsConfig,
+    pub aim_path: AimPathConfig,
+    pub laser: LaserConfig,
+    pub black_hole: BlackHoleConfig,
+    pub fortification: FortificationConfig,
+    pub tile_hit_points: TileHitPointsConfig,
+    pub supply_drop: SupplyDropConfig,
+    pub gamble: GambleConfig,
+    pub freeze: FreezeConfig,
+    pub stalemate: StalemateConfig,
+    pub interest: InterestConfig,
+    pub king_of_the_hill: KingOfTheHillConfig,
+    pub siphon: SiphonConfig,
+    pub capture_streak: CaptureStreakConfig,
+    pub streak_bonus: StreakBonusConfig,
+    pub comeback: ComebackConfig,
+    pub draft: DraftConfig,
+    pub mutator_roll: MutatorRollConfig,
+    pub series: SeriesConfig,
+    pub bracket: BracketConfig,
+    pub speed_ramp: SpeedRampConfig,
+    pub arena_shape: ArenaShape,
+    pub arena_layout: ArenaLayout,
+    /// Bullets going off one edge of the battlefield come back in on the opposite edge instead of
+    /// bouncing off. Only the square arena wraps around, and it takes effect on the next round.
+    pub wrap_around: bool,
+    pub map_generator: MapGeneratorConfig,
+    pub walls: WallConfig,
+    pub shrink: ShrinkConfig,
+    pub battle_royale: BattleRoyaleConfig,
+    pub territory_elimination: TerritoryEliminationConfig,
+    pub hazard_bar: HazardBarConfig,
+    /// A custom map from `assets/maps/` laying out the tiles, obstacles and turrets, picked from
+    /// the main menu. Takes the place of the map generator.
+    pub map_file: Option<MapFile>,
+    pub tile_shape: TileShape,
+    pub tile_conversion: TileConversionConfig,
+    pub win_conditions: WinConditionLayout,
+    pub audio: AudioConfig,
+    pub emotes: EmoteConfig,
+    pub map_export: MapExportConfig,
+    pub stats_export: StatsExportConfig,
+    pub daily_results: DailyResultsConfig,
+    /// How numbers and dates are written on screen and in the exported stats.
+    pub locale: Locale,
+    pub overlay_feed: OverlayFeedConfig,
+    pub presentation: PresentationConfig,
+    pub accessibility: AccessibilityConfig,
+    pub performance: PerformanceConfig,
+}
+
+impl GameConfig {
+    /// The config a preset starts out with. It can still be edited live from there.
+    pub fn from_preset(preset: GamePreset) -> Self {
+        let mut config = Self {
+            trigger_zones: preset.map().trigger_zones(),
+            ..default()
+        };
+        if preset == GamePreset::Chaos {
+            config.laser.enabled = true;
+            config.black_hole.enabled = true;
+            config.supply_drop.enabled = true;
+        }
+        if preset == GamePreset::Coop {
+            config.control.human[Participant::A] = true;
+            config.control.human[Participant::C] = true;
+            config.teams.enabled = true;
+            config.win_conditions = WinConditionLayout(vec![WinConditionKind::LastTeamStanding]);
+        }
+        if preset == GamePreset::Timed {
+            config.win_conditions = WinConditionLayout(vec![
+                WinConditionKind::LastTurretStanding,
+                WinConditionKind::Timed(180.0),
+            ]);
+        }
+        config
+    }
+    /// Check the values that can't be used as they are, clamping them to the nearest usable value.
+    /// Returns a message for each one that was changed.
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut clamp = |name: &str, value: &mut f32, min: f32, max: f32, reason: &str| {
+            // Unlike `f32::clamp`, this doesn't panic if the bounds come from another invalid value,
+            // and `NaN` ends up as `min`.
+            let clamped = value.max(min).min(max);
+            if clamped != *value {
+                messages.push(format!(
+                    "{} is {}, but {}. Using {} instead.",
+                    name, value, reason, clamped
+                ));
+                *value = clamped;
+            }
+        };
+
+        let physics = &mut self.physics;
+        clamp(
+            "physics.worker_ball_gravity_scale",
+            &mut physics.worker_ball_gravity_scale,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive for the worker balls to fall",
+        );
+        clamp(
+            "physics.worker_ball_restitution",
+            &mut physics.worker_ball_restitution,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "physics.bullet_restitution",
+            &mut physics.bullet_restitution,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "physics.trigger_zone_height",
+            &mut physics.trigger_zone_height,
+            1.0,
+            TRIGGER_ZONE_HEIGHT_MAX,
+            &format!(
+                "it has to be between 1 and {} to fit between the pegs and the bottom of the panel",
+                TRIGGER_ZONE_HEIGHT_MAX
+            ),
+        );
+
+        let black_hole = &mut self.black_hole;
+        clamp(
+            "black_hole.min_interval_secs",
+            &mut black_hole.min_interval_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "black_hole.max_interval_secs",
+            &mut black_hole.max_interval_secs,
+            black_hole.min_interval_secs,
+            f32::MAX,
+            "it can't be less than black_hole.min_interval_secs",
+        );
+        clamp(
+            "black_hole.event_horizon",
+            &mut black_hole.event_horizon,
+            0.0,
+            black_hole.radius,
+            "it can't be larger than black_hole.radius",
+        );
+        clamp(
+            "black_hole.duration_secs",
+            &mut black_hole.duration_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+
+        let laser = &mut self.laser;
+        clamp(
+            "laser.warning_secs",
+            &mut laser.warning_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "laser.cooldown_secs",
+            &mut laser.cooldown_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "laser.sweep_secs",
+            &mut laser.sweep_secs,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive",
+        );
+        clamp(
+            "freeze.duration_secs",
+            &mut self.freeze.duration_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "supply_drop.interval_secs",
+            &mut self.supply_drop.interval_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "speed_ramp.max_factor",
+            &mut self.speed_ramp.max_factor,
+            1.0,
+            f32::MAX,
+            "it can't slow the game down",
+        );
+        clamp(
+            "tile_hit_points.range",
+            &mut self.tile_hit_points.range,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive",
+        );
+        clamp(
+            "shrink.interval_secs",
+            &mut self.shrink.interval_secs,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive",
+        );
+        clamp(
+            "shrink.max_shrink",
+            &mut self.shrink.max_shrink,
+            0.0,
+            300.0,
+            "it has to be between 0 and 300 to leave room for the turrets",
+        );
+        clamp(
+            "battle_royale.interval_secs",
+            &mut self.battle_royale.interval_secs,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive",
+        );
+        clamp(
+            "battle_royale.grace_secs",
+            &mut self.battle_royale.grace_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "territory_elimination.min_fraction",
+            &mut self.territory_elimination.min_fraction,
+            0.0,
+            1.0,
+            "it's a fraction of the battlefield",
+        );
+        clamp(
+            "territory_elimination.grace_secs",
+            &mut self.territory_elimination.grace_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "hazard_bar.length",
+            &mut self.hazard_bar.length,
+            0.0,
+            400.0,
+            "it has to be between 0 and 400 to stay clear of the turrets",
+        );
+        clamp(
+            "capture_streak.speed_boost",
+            &mut self.capture_streak.speed_boost,
+            f32::EPSILON,
+            f32::MAX,
+            "it has to be positive",
+        );
+        clamp(
+            "streak_bonus.gap_secs",
+            &mut self.streak_bonus.gap_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "tile_conversion.splash_radius",
+            &mut self.tile_conversion.splash_radius,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "king_of_the_hill.radius",
+            &mut self.king_of_the_hill.radius,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "audio.music_ducking",
+            &mut self.audio.music_ducking,
+            0.0,
+            1.0,
+            "it's a fraction of the music's volume",
+        );
+        clamp(
+            "overlay_feed.updates_per_sec",
+            &mut self.overlay_feed.updates_per_sec,
+            0.1,
+            30.0,
+            "it has to be between 0.1 and 30",
+        );
+        clamp(
+            "emotes.duration_secs",
+            &mut self.emotes.duration_secs,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        clamp(
+            "accessibility.worker_ball_outline",
+            &mut self.accessibility.worker_ball_outline,
+            0.0,
+            f32::MAX,
+            "it can't be negative",
+        );
+        for (i, kind) in self.win_conditions.0.iter_mut().enumerate() {
+            if let WinConditionKind::Territory(fraction) = kind {
+                clamp(
+                    &format!("win_conditions[{}]", i),
+                    fraction,
+                    f32::EPSILON,
+                    1.0,
+                    "a territory fraction has to be above 0 and at most 1",
+                );
+            }
+        }
+
+        for (i, trigger_type) in self.trigger_zones.0.iter_mut().enumerate() {
+            if *trigger_type == TriggerType::Multiply(0) {
+                messages.push(format!(
+                    "trigger_zones[{}] multiplies by 0, but that would wipe out the charge. Using 1 \
+                     instead.",
+                    i
+                ));
+                *trigger_type = TriggerType::Multiply(1);
+            }
+        }
+
+        if self.fortification.hits == 0 {
+            messages.push(
+                "fortification.hits is 0, but tiles need at least 1 hit to convert. Using 1 \
+                 instead."
+                    .to_string(),
+            );
+            self.fortification.hits = 1;
+        }
+        if self.performance.tile_chunk_size > 200 {
+            messages.push(format!(
+                "performance.tile_chunk_size is {}, but there are only 200 tiles across the \
+                 battlefield. Using 200 instead.",
+                self.performance.tile_chunk_size
+            ));
+            self.performance.tile_chunk_size = 200;
+        }
+        if !(1..=MapExportConfig::MAX_PIXELS_PER_TILE).contains(&self.map_export.pixels_per_tile) {
+            let clamped = self
+                .map_export
+                .pixels_per_tile
+                .clamp(1, MapExportConfig::MAX_PIXELS_PER_TILE);
+            messages.push(format!(
+                "map_export.pixels_per_tile is {}, but it has to be between 1 and {}. Using {} \
+                 instead.",
+                self.map_export.pixels_per_tile,
+                MapExportConfig::MAX_PIXELS_PER_TILE,
+                clamped
+            ));
+            self.map_export.pixels_per_tile = clamped;
+        }
+        if self.series.wins_needed == 0 {
+            messages.push(
+                "series.wins_needed is 0, but the series can't be won without winning a round. \
+                 Using 1 instead."
+                    .to_string(),
+            );
+            self.series.wins_needed = 1;
+        }
+        messages
+    }
+}
+
+/// The systems that clamp the [`GameConfig`] to usable values after it's edited. The systems that
+/// edit the config run before this set and the ones that react to it changing run after it, so
+/// they never see an out-of-range value, even for a frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidateConfig;
+
+/// Clamp the config to usable values on startup and whenever it's edited, warning about anything
+/// that had to be changed.
+pub fn validate_config(mut config: ResMut<GameConfig>) {
+    // Validate a copy so the config is only marked as changed if something is actually fixed.
+    let mut validated = config.clone();
+    let messages = validated.validate();
+    if messages.is_empty() {
+        return;
+    }
+    for message in &messages {
+        warn!("Invalid config: {}", message);
+    }
+    *config = validated;
+}
+
+/// Curated tunings picked at launch or from the main menu. Unlike [`GameConfig`], these cover
+/// values that are baked into the panels and turrets when they're spawned, so changing the preset
+/// only takes effect on restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Resource, Reflect)]
+#[reflect(Resource)]
+pub enum GamePreset {
+    /// The game as originally designed.
+    #[default]
+    Classic,
+    /// Worker balls and shots come faster, and the multipliers are bigger.
+    Fast,
+    /// Fast, with gambles and swaps in place of the small multipliers, plus every hazard.
+    Chaos,
+    /// Two people aim the turrets of A and C by hand, teamed up against B and D.
+    Coop,
+    /// Classic, but against the clock. Whoever owns the most tiles when time runs out wins.
+    Timed,
+}
+impl GamePreset {
+    pub const ALL: [GamePreset; 5] = [
+        GamePreset::Classic,
+        GamePreset::Fast,
+        GamePreset::Chaos,
+        GamePreset::Coop,
+        GamePreset::Timed,
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            GamePreset::Classic => "Classic",
+            GamePreset::Fast => "Fast",
+            GamePreset::Chaos => "Chaos",
+            GamePreset::Coop => "Coop",
+            GamePreset::Timed => "Timed",
+        }
+    }
+    /// Time in seconds between worker balls spawning in each panel.
+    pub fn worker_ball_spawn_secs(self) -> f32 {
+        match self {
+            GamePreset::Classic | GamePreset::Coop | GamePreset::Timed => 10.0,
+            GamePreset::Fast => 5.0,
+            GamePreset::Chaos => 4.0,
+        }
+    }
+    /// Time in seconds a turret stops firing for after firing a charged shot.
+    pub fn charged_shot_cooldown_secs(self) -> f32 {
+        match self {
+            GamePreset::Classic | GamePreset::Coop | GamePreset::Timed => 0.5,
+            GamePreset::Fast => 0.25,
+            GamePreset::Chaos => 0.25,
+        }
+    }
+    pub fn map(self) -> PanelMap {
+        match self {
+            GamePreset::Classic | GamePreset::Coop | GamePreset::Timed => PanelMap::Classic,
+            GamePreset::Fast => PanelMap::HighStakes,
+            GamePreset::Chaos => PanelMap::WildCards,
+        }
+    }
+}
+
+/// The trigger zone layouts that can be picked from the main menu, independently of the preset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanelMap {
+    #[default]
+    Classic,
+    /// Bigger multipliers.
+    HighStakes,
+    /// Gambles and swaps in place of the small multipliers.
+    WildCards,
+}
+impl PanelMap {
+    pub const ALL: [PanelMap; 3] = [PanelMap::Classic, PanelMap::HighStakes, PanelMap::WildCards];
+    pub fn name(self) -> &'static str {
+        match self {
+            PanelMap::Classic => "Classic",
+            PanelMap::HighStakes => "High Stakes",
+            PanelMap::WildCards => "Wild Cards",
+        }
+    }
+    pub fn trigger_zones(self) -> TriggerZoneLayout {
+        match self {
+            PanelMap::Classic => TriggerZoneLayout::default(),
+            PanelMap::HighStakes => TriggerZoneLayout([
+                TriggerType::BurstShot,
+                TriggerType::Multiply(3),
+                TriggerType::Multiply(6),
+                TriggerType::Multiply(3),
+                TriggerType::ChargedShot,
+            ]),
+            PanelMap::WildCards => TriggerZoneLayout([
+                TriggerType::BurstShot,
+                TriggerType::Gamble,
+                TriggerType::Multiply(8),
+                TriggerType::Swap,
+                TriggerType::ChargedShot,
+            ]),
+        }
+    }
+}
+
+/// How a participant is presented to the viewers.
+#[derive(Debug, Clone, Reflect)]
+pub struct ParticipantIdentity {
+    /// The name shown in announcements, on the panels and in the window title.
+    pub name: String,
+    pub tile_color: Color,
+    pub ball_color: Color,
+    /// Color of the worker ball trails, or `None` to use the tile color. Turret skins can override
+    /// it.
+    pub trail_color: Option<Color>,
+    /// File name of an image under `mods/avatars/` shown next to the name, or `None` to show the
+    /// initials of the name over the ball color instead.
+    pub avatar: Option<String>,
+}
+/// The identity of each participant. Only takes effect on startup.
+#[derive(Debug, Clone, Deref, Reflect)]
+pub struct ParticipantIdentities(pub ParticipantMap<ParticipantIdentity>);
+impl Default for ParticipantIdentities {
+    fn default() -> Self {
+        let identity =
+            |participant: Participant, tile_color: Srgba, ball_color: Srgba| ParticipantIdentity {
+                name: participant.to_string(),
+                tile_color: tile_color.into(),
+                ball_color: ball_color.into(),
+                trail_color: None,
+                avatar: None,
+            };
+        Self(ParticipantMap::new(
+            identity(Participant::A, css::MAROON, css::RED),
+            identity(Participant::B, css::DARK_GREEN, css::LIMEGREEN),
+            identity(Participant::C, css::PURPLE, css::VIOLET),
+            identity(Participant::D, css::DARK_GOLDENROD, css::YELLOW),
+            identity(Participant::E, css::NAVY, css::DODGER_BLUE),
+            identity(Participant::F, css::SADDLE_BROWN, css::ORANGE),
+            identity(Participant::G, css::TEAL, css::AQUA),
+            identity(Participant::H, css::MEDIUM_VIOLET_RED, css::HOT_PINK),
+        ))
+    }
+}
+
+/// Which turrets are aimed by hand. Each participant has its own pair of keys to turn the turret
+/// left and right, and the turrets that aren't aimed by hand keep spinning on their own.
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct ControlConfig {
+    pub human: ParticipantMap<bool>,
+}
+
+/// Configuration for playing in teams of two: A and C, B and D, E and F, and G and H. Teammates
+/// don't convert each other's tiles, don't damage each other's turrets and aren't picked as
+/// opponents by triggers.
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct TeamConfig {
+    pub enabled: bool,
+}
+impl TeamConfig {
+    /// Whether `a` and `b` are on the same side, which is always the case if they're the same
+    /// participant.
+    pub fn allies(&self, a: Participant, b: Participant) -> bool {
+        a == b || (self.enabled && a.teammate() == b)
+    }
+}
+
+/// The trigger zones at the bottom of each panel, from left to right. Takes effect on restart.
+#[derive(Debug, Clone, PartialEq, Deref, Serialize, Deserialize, Reflect)]
+#[serde(transparent)]
+pub struct TriggerZoneLayout(pub [TriggerType; TRIGGER_ZONE_COUNT]);
+impl Default for TriggerZoneLayout {
+    fn default() -> Self {
+        Self([
+            TriggerType::BurstShot,
+            TriggerType::Multiply(2),
+            TriggerType::Multiply(4),
+            TriggerType::Multiply(2),
+            TriggerType::ChargedShot,
+        ])
+    }
+}
+
+/// Physical properties of the worker balls, the bullets and the trigger zones. Changes apply to
+/// everything already spawned as well.
+#[derive(Debug, Clone, Reflect)]
+pub struct PhysicsConfig {
+    pub worker_ball_gravity_scale: f32,
+    pub worker_ball_restitution: f32,
+    pub bullet_restitution: f32,
+    /// Height of the trigger zones at the bottom of each panel.
+    pub trigger_zone_height: f32,
+}
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            worker_ball_gravity_scale: 15.0,
+            worker_ball_restitution: 0.5,
+            bullet_restitution: 0.75,
+            trigger_zone_height: 40.0,
+        }
+    }
+}
+
+/// Configuration for the predicted shot paths shown with `F3`.
+#[derive(Debug, Clone, Reflect)]
+pub struct AimPathConfig {
+    /// The number of times the path bounces off the walls. Set to 0 to stop at the first wall.
+    pub bounces: u8,
+    /// Total length of the path.
+    pub length: f32,
+}
+impl Default for AimPathConfig {
+    fn default() -> Self {
+        Self {
+            bounces: 2,
+            length: 1500.0,
+        }
+    }
+}
+
+/// What happens to a bullet when it's hit by the rotating laser.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum LaserMode {
+    /// The bullet loses all its charge.
+    Destroy,
+    /// The bullet loses half its charge, rounded up.
+    #[default]
+    Halve,
+}
+
+/// Configuration for the rotating laser hazard in the center of the battlefield.
+#[derive(Debug, Clone, Reflect)]
+pub struct LaserConfig {
+    pub enabled: bool,
+    pub mode: LaserMode,
+    /// Time in seconds the warning arc is displayed before the laser starts sweeping.
+    pub warning_secs: f32,
+    /// Time in seconds the laser takes to complete a sweep.
+    pub sweep_secs: f32,
+    /// Time in seconds between the end of a sweep and the warning for the next.
+    pub cooldown_secs: f32,
+    /// The angle in radians the laser covers in a single sweep.
+    pub sweep_angle: f32,
+}
+impl Default for LaserConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: LaserMode::default(),
+            warning_secs: 2.0,
+            sweep_secs: 3.0,
+            cooldown_secs: 10.0,
+            sweep_angle: std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+/// Configuration for the black hole event, a gravity well that occasionally appears on the
+/// battlefield and swallows bullets.
+#[derive(Debug, Clone, Reflect)]
+pub struct BlackHoleConfig {
+    pub enabled: bool,
+    /// Minimum time in seconds between black holes.
+    pub min_interval_secs: f32,
+    /// Maximum time in seconds between black holes.
+    pub max_interval_secs: f32,
+    /// Time in seconds a black hole stays on the battlefield.
+    pub duration_secs: f32,
+    /// Bullets within this distance from the center get pulled in.
+    pub radius: f32,
+    /// Bullets within this distance from the center get despawned.
+    pub event_horizon: f32,
+    /// Acceleration applied to bullets right next to the event horizon. It falls off linearly to
+    /// zero at `radius`.
+    pub strength: f32,
+}
+impl Default for BlackHoleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: 60.0,
+            max_interval_secs: 120.0,
+            duration_secs: 8.0,
+            radius: 150.0,
+            event_horizon: 12.0,
+            strength: 600.0,
+        }
+    }
+}
+
+/// Configuration for the fortified tiles around each turret, which take more than one hit to
+/// convert.
+#[derive(Debug, Clone, Reflect)]
+pub struct FortificationConfig {
+    /// Tiles within this distance from their owner's turret are fortified. Set to 0 to disable.
+    pub radius: f32,
+    /// The number of hits a fortified tile takes to convert.
+    pub hits: u8,
+    /// The least charge a bullet needs to have left to make a dent in a fortified tile, so a turret
+    /// can't be picked off with small shots early on. Weaker bullets pass over them.
+    pub min_charge: u64,
+}
+impl Default for FortificationConfig {
+    fn default() -> Self {
+        Self {
+            radius: 100.0,
+            hits: 2,
+            min_charge: 0,
+        }
+    }
+}
+
+/// Configuration for tiles taking more hits to convert the deeper they are in their owner's
+/// territory, so a steamroll slows down the further it pushes and a comeback has a chance.
+#[derive(Debug, Clone, Reflect)]
+pub struct TileHitPointsConfig {
+    pub enabled: bool,
+    /// The number of hits a tile right next to its owner's turret takes to convert.
+    pub max_hits: u8,
+    /// The distance from the owner's turret where tiles are back down to a single hit. The hits
+    /// fall off evenly on the way out.
+    pub range: f32,
+}
+impl TileHitPointsConfig {
+    /// The number of hits a tile `distance` away from its owner's turret takes to convert.
+    pub fn hits_at(&self, distance: f32) -> u8 {
+        if !self.enabled {
+            return 1;
+        }
+        let depth = (1.0 - distance / self.range).clamp(0.0, 1.0);
+        1 + (depth * self.max_hits.saturating_sub(1) as f32).round() as u8
+    }
+}
+impl Default for TileHitPointsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_hits: 4,
+            range: 300.0,
+        }
+    }
+}
+
+/// Configuration for the supply crates that parachute onto the battlefield and grant a random
+/// trigger to whoever shoots them first.
+#[derive(Debug, Clone, Reflect)]
+pub struct SupplyDropConfig {
+    pub enabled: bool,
+    /// Time in seconds between a crate being picked up and the next one dropping.
+    pub interval_secs: f32,
+}
+impl Default for SupplyDropConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 45.0,
+        }
+    }
+}
+
+/// Configuration for the gamble trigger zone, which either multiplies or divides the turret's
+/// charge on a coin flip.
+#[derive(Debug, Clone, Reflect)]
+pub struct GambleConfig {
+    /// The probability of winning the gamble, between 0 and 1.
+    pub win_chance: f64,
+    /// The charge is multiplied by this on a win.
+    pub win_factor: u8,
+    /// The charge is divided by this on a loss, but never drops below 1.
+    pub loss_divisor: u8,
+}
+impl Default for GambleConfig {
+    fn default() -> Self {
+        Self {
+            win_chance: 0.5,
+            win_factor: 2,
+            loss_divisor: 2,
+        }
+    }
+}
+
+/// Configuration for the freeze trigger zone, which pauses the worker balls on the opposite panel.
+#[derive(Debug, Clone, Reflect)]
+pub struct FreezeConfig {
+    /// Time in seconds the panel stays frozen.
+    pub duration_secs: f32,
+}
+impl Default for FreezeConfig {
+    fn default() -> Self {
+        Self { duration_secs: 5.0 }
+    }
+}
+
+/// Configuration for the watchdog that forces the turrets to fire when a round stops making
+/// progress.
+#[derive(Debug, Clone, Reflect)]
+pub struct StalemateConfig {
+    pub enabled: bool,
+    /// Time in seconds without any tile conversions or eliminations before the watchdog steps in.
+    pub timeout_secs: f32,
+}
+impl Default for StalemateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: 60.0,
+        }
+    }
+}
+
+/// Configuration for the passive interest on the charge held by each turret, which rewards holding
+/// on to charge instead of releasing it right away.
+#[derive(Debug, Clone, Reflect)]
+pub struct InterestConfig {
+    pub enabled: bool,
+    /// The fraction of its charge a turret gains every second.
+    pub rate_per_sec: f64,
+    /// Interest stops once a turret's charge reaches this.
+    pub cap: u64,
+}
+impl Default for InterestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rate_per_sec: 0.02,
+            cap: 1 << 20,
+        }
+    }
+}
+
+/// Configuration for the hill in the middle of the battlefield, which grants charge to whoever owns
+/// most of its tiles.
+#[derive(Debug, Clone, Reflect)]
+pub struct KingOfTheHillConfig {
+    pub enabled: bool,
+    /// The radius of the hill, centered on the middle of the battlefield.
+    pub radius: f32,
+    /// The charge the holder's turret gains every second.
+    pub charge_per_sec: f64,
+}
+impl Default for KingOfTheHillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 60.0,
+            charge_per_sec: 8.0,
+        }
+    }
+}
+
+/// Configuration for playing rounds as a series, won by whoever wins enough rounds first.
+#[derive(Debug, Clone, Reflect)]
+pub struct SeriesConfig {
+    pub enabled: bool,
+    /// The number of round wins it takes to win the series.
+    pub wins_needed: u32,
+}
+impl Default for SeriesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wins_needed: 3,
+        }
+    }
+}
+
+/// Configuration for playing an 8 participant knockout bracket, 4 at a time. Each of the two
+/// opening heats sends its top two through to the final, ranked by who was knocked out last, and the
+/// final decides the champion. The lineup of each heat overrides the roster, which needs all 8
+/// participants for the bracket to start.
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct BracketConfig {
+    pub enabled: bool,
+    /// Play the two opening heats at the same time, side by side in the window, rather than one
+    /// after the other.
+    pub side_by_side: bool,
+}
+
+/// Configuration for drafting upgrades for each participant between rounds.
+#[derive(Debug, Clone, Reflect)]
+pub struct DraftConfig {
+    pub enabled: bool,
+    /// The number of upgrades each participant gets to pick.
+    pub budget: u8,
+    /// How much further the fortified tiles reach for each [`crate::draft::Upgrade::Fortification`]
+    /// picked.
+    pub fortification_radius: f32,
+}
+impl Default for DraftConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget: 2,
+            fortification_radius: 50.0,
+        }
+    }
+}
+
+/// Configuration for rolling random [`crate::mutators::Mutator`]s at the start of each round,
+/// instead of voting on them.
+#[derive(Debug, Clone, Reflect)]
+pub struct MutatorRollConfig {
+    pub enabled: bool,
+    /// The most mutators rolled for a round. At least one always is.
+    pub max_count: u8,
+}
+impl Default for MutatorRollConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_count: 2,
+        }
+    }
+}
+
+/// Configuration for how much non-critical work is done each frame. Anything past the budget is
+/// carried over to later frames, so bursts of collisions don't stall the game. Turret hits, trigger
+/// zones and tile conversions always run in full.
+#[derive(Debug, Clone, Reflect)]
+pub struct PerformanceConfig {
+    /// The number of gameplay events of each kind counted towards the round stats per frame.
+    /// Whatever's left is counted in full once the round is over.
+    pub stats_events_per_frame: u32,
+    /// The number of tile hit particle effects started per frame.
+    pub tile_hit_effects_per_frame: u32,
+    /// The most tile hit effects waiting to be started. The oldest are dropped past this, since
+    /// they'd show up too late to matter.
+    pub tile_hit_effect_backlog: u32,
+    /// The number of tiles across each chunk of the battlefield drawn as a single image, so a tile
+    /// changing only uploads its own chunk to the GPU again. Set to 0 to draw every tile on its
+    /// own. Hexagon tiles are always drawn on their own.
+    pub tile_chunk_size: u32,
+    /// The most frames drawn per second, or 0 to leave it up to the display.
+    pub max_fps: u32,
+    /// Wait for the display before drawing each frame, which avoids tearing.
+    pub vsync: bool,
+    /// Cap the frame rate at [`BATTERY_SAVER_FPS`](PerformanceConfig::BATTERY_SAVER_FPS) and start
+    /// fewer particle effects, overriding the settings above.
+    pub battery_saver: bool,
+    /// Pause the round while the window is minimized or covered up, and pick it back up once it's
+    /// visible again.
+    pub pause_when_hidden: bool,
+}
+impl PerformanceConfig {
+    pub const BATTERY_SAVER_FPS: u32 = 30;
+    /// Battery saver mode starts one in this many of the tile hit effects.
+    const BATTERY_SAVER_EFFECT_DIVISOR: u32 = 4;
+    /// The frame rate to stay under, if any.
+    pub fn frame_cap(&self) -> Option<u32> {
+        match self.battery_saver {
+            true => Some(Self::BATTERY_SAVER_FPS),
+            false => (self.max_fps > 0).then_some(self.max_fps),
+        }
+    }
+    /// The number of tile hit particle effects to start per frame.
+    pub fn tile_hit_effect_budget(&self) -> usize {
+        match self.battery_saver {
+            true => (self.tile_hit_effects_per_frame / Self::BATTERY_SAVER_EFFECT_DIVISOR) as usize,
+            false => self.tile_hit_effects_per_frame as usize,
+        }
+    }
+}
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            stats_events_per_frame: 1024,
+            tile_hit_effects_per_frame: 32,
+            tile_hit_effect_backlog: 256,
+            tile_chunk_size: 25,
+            max_fps: 0,
+            vsync: true,
+            battery_saver: false,
+            pause_when_hidden: true,
+        }
+    }
+}
+
+/// Configuration for speeding up the simulation the longer a round lasts, so unattended sessions
+/// don't get stuck on a grindy round.
+#[derive(Debug, Clone, Reflect)]
+pub struct SpeedRampConfig {
+    pub enabled: bool,
+    /// Time in seconds, in real time, before the speed starts going up.
+    pub start_secs: f32,
+    /// How much the speed goes up by for every minute past the start, where 1 doubles it.
+    pub increase_per_min: f32,
+    /// The most the speed gets multiplied by.
+    pub max_factor: f32,
+}
+impl Default for SpeedRampConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_secs: 300.0,
+            increase_per_min: 0.25,
+            max_factor: 3.0,
+        }
+    }
+}
+
+/// Configuration for refunding the charge of bullets that run out of steam in their owner's
+/// territory.
+#[derive(Debug, Clone, Reflect)]
+pub struct SiphonConfig {
+    pub enabled: bool,
+    /// Bullets slower than this are considered to have finished their flight.
+    pub stop_speed: f32,
+    /// The fraction of the bullet's remaining charge that goes back to its turret.
+    pub refund_fraction: f64,
+}
+impl Default for SiphonConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stop_speed: 5.0,
+            refund_fraction: 0.5,
+        }
+    }
+}
+
+/// Configuration for setting bullets on fire when they capture a run of tiles in quick succession.
+#[derive(Debug, Clone, Reflect)]
+pub struct CaptureStreakConfig {
+    pub enabled: bool,
+    /// The number of tiles a bullet has to capture in a row to catch fire.
+    pub threshold: u32,
+    /// Time in seconds a bullet can go without capturing a tile before its streak is broken.
+    pub gap_secs: f32,
+    /// How much a bullet's speed gets multiplied by while it's on fire.
+    pub speed_boost: f32,
+    /// Time in seconds a bullet stays on fire after its last capture.
+    pub duration_secs: f32,
+}
+impl Default for CaptureStreakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 12,
+            gap_secs: 0.25,
+            speed_boost: 1.5,
+            duration_secs: 1.5,
+        }
+    }
+}
+
+/// Configuration for rewarding participants who capture a run of tiles without losing any, so a
+/// dominant push pays off in charge.
+#[derive(Debug, Clone, Reflect)]
+pub struct StreakBonusConfig {
+    pub enabled: bool,
+    /// The streak lengths, in tiles, that earn a bonus. Each one is called out once per streak.
+    pub thresholds: Vec<u32>,
+    /// Time in seconds a participant can go without capturing a tile before their streak is
+    /// broken. Losing a tile breaks it right away.
+    pub gap_secs: f32,
+    /// The charge added to the turret at each threshold.
+    pub bonus_charge: u64,
+}
+impl Default for StreakBonusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            thresholds: vec![50, 100, 200],
+            gap_secs: 1.0,
+            bonus_charge: 10,
+        }
+    }
+}
+
+/// Configuration for bringing eliminated participants back after a while instead of knocking them
+/// out, so every panel stays busy through long sessions. Rounds only end on win conditions that
+/// don't wait for eliminations, such as [`WinConditionKind::Timed`] or
+/// [`WinConditionKind::Territory`].
+#[derive(Debug, Clone, Reflect)]
+pub struct ComebackConfig {
+    pub enabled: bool,
+    /// Time in seconds before an eliminated turret comes back.
+    pub delay_secs: f32,
+    /// The fraction of the leading turret's charge the returning turret starts with.
+    pub charge_fraction: f64,
+}
+impl Default for ComebackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_secs: 10.0,
+            charge_fraction: 0.25,
+        }
+    }
+}
+
+/// The outline of the battlefield. Tiles outside of it are left out and the walls follow it, with
+/// turrets that would end up outside moved in toward the middle. Only takes effect on restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ArenaShape {
+    #[default]
+    Square,
+    Circle,
+    /// A square standing on one of its corners.
+    Diamond,
+    /// A cross with four arms of equal length.
+    Plus,
+    /// A hexagon with corners to the left and right.
+    Hexagon,
+}
+/// How the battlefield is laid out when there aren't four participants. See
+/// [`crate::battlefield::BattlefieldGeometry`]. Only takes effect on restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ArenaLayout {
+    /// Two split the battlefield in halves, six play on a hexagon, and any other number but four
+    /// sit in a ring on a circle, in place of `arena_shape`.
+    #[default]
+    FitParticipants,
+    /// Always play on `arena_shape`, with everyone in their usual spots.
+    Fixed,
+}
+
+/// Configuration for generating obstacles and patches of neutral ground on the battlefield at the
+/// start of each round, from the game's seed. Everything is placed in one quarter of the battlefield
+/// and mirrored into the other three, so every turret gets the same layout. Bullets bounce off the
+/// obstacles, and no tiles are laid on the neutral ground, so no one can own it.
+#[derive(Debug, Clone, Reflect)]
+pub struct MapGeneratorConfig {
+    pub enabled: bool,
+    /// The number of obstacles placed in each quarter.
+    pub obstacles: u8,
+    /// The number of patches of neutral ground placed in each quarter.
+    pub neutral_regions: u8,
+}
+impl Default for MapGeneratorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            obstacles: 2,
+            neutral_regions: 1,
+        }
+    }
+}
+
+/// Configuration for the walls put up on the battlefield at the start of each round. Like the
+/// generated obstacles, they're placed in one quarter and mirrored into the other three. Bullets
+/// bounce off them, and each hit wears a wall down by the bullet's charge, using it up, until it
+/// breaks and opens up the way.
+#[derive(Debug, Clone, Reflect)]
+pub struct WallConfig {
+    pub enabled: bool,
+    /// The number of walls placed in each quarter.
+    pub count: u8,
+    /// The charge it takes to break a wall.
+    pub hit_points: u64,
+    /// The length of each wall.
+    pub length: f32,
+}
+impl Default for WallConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            count: 2,
+            hit_points: 256,
+            length: 80.0,
+        }
+    }
+}
+
+/// Configuration for closing in the battlefield the longer a round lasts, battle royale style. The
+/// outer rings of tiles turn into dead zone one step at a time, which nobody owns and which stops
+/// any bullet that hits it, and the turrets are pushed in ahead of it.
+#[derive(Debug, Clone, Reflect)]
+pub struct ShrinkConfig {
+    pub enabled: bool,
+    /// Time in seconds into the round before the first step.
+    pub start_secs: f32,
+    /// Time in seconds between steps.
+    pub interval_secs: f32,
+    /// How far in the edge moves with each step.
+    pub step: f32,
+    /// How far in the edge can move in total.
+    pub max_shrink: f32,
+}
+impl Default for ShrinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_secs: 90.0,
+            interval_secs: 20.0,
+            step: 18.0,
+            max_shrink: 200.0,
+        }
+    }
+}
+
+/// Configuration for battle royale, where everyone's territory wears away from the edges in unless
+/// they keep taking ground. The outermost ring of each territory decays into neutral ground every
+/// so often, apart from the tiles taken recently. Any bullet can take the neutral ground back.
+#[derive(Debug, Clone, Reflect)]
+pub struct BattleRoyaleConfig {
+    pub enabled: bool,
+    /// Time in seconds between rings decaying.
+    pub interval_secs: f32,
+    /// Time in seconds after a tile is taken that it's safe from decay.
+    pub grace_secs: f32,
+}
+impl Default for BattleRoyaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 15.0,
+            grace_secs: 10.0,
+        }
+    }
+}
+
+/// Configuration for eliminating participants who hold on to too little of the battlefield for too
+/// long, even if their turret was never hit.
+#[derive(Debug, Clone, Reflect)]
+pub struct TerritoryEliminationConfig {
+    pub enabled: bool,
+    /// The share of the owned tiles, from 0 to 1, a participant has to keep to stay in.
+    pub min_fraction: f32,
+    /// Time in seconds a participant can spend below the minimum before they're eliminated.
+    pub grace_secs: f32,
+}
+impl Default for TerritoryEliminationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_fraction: 0.03,
+            grace_secs: 10.0,
+        }
+    }
+}
+
+/// Configuration for a bar spinning around the middle of the battlefield. Bullets bounce off it,
+/// and the tiles it sweeps over are left as neutral ground for anyone to take.
+#[derive(Debug, Clone, Reflect)]
+pub struct HazardBarConfig {
+    pub enabled: bool,
+    /// From one end of the bar to the other.
+    pub length: f32,
+    /// How fast the bar turns, counterclockwise. Negative turns it clockwise.
+    pub degrees_per_sec: f32,
+}
+impl Default for HazardBarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            length: 240.0,
+            degrees_per_sec: 15.0,
+        }
+    }
+}
+
+/// The shape of the tiles the battlefield is made of. Only takes effect on restart, since the whole
+/// battlefield has to be rebuilt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum TileShape {
+    /// A square grid.
+    #[default]
+    Square,
+    /// Pointy-topped hexagons, with every other row shifted over by half a tile.
+    Hexagon,
+}
+
+/// How a bullet converts the tiles it hits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum TileConversionMode {
+    /// Every tile along the bullet's path takes a hit as the bullet passes over it.
+    #[default]
+    Paint,
+    /// The first enemy tile the bullet touches sets off a splash that hits every tile in a radius,
+    /// closest first, until the bullet runs out of charge. The bullet is spent on impact.
+    Splash,
+}
+
+/// Configuration for how each kind of shot converts tiles.
+#[derive(Debug, Clone, Reflect)]
+pub struct TileConversionConfig {
+    pub charged_shot: TileConversionMode,
+    pub burst_shot: TileConversionMode,
+    /// The radius of the splash in [`TileConversionMode::Splash`].
+    pub splash_radius: f32,
+}
+impl Default for TileConversionConfig {
+    fn default() -> Self {
+        Self {
+            charged_shot: TileConversionMode::Paint,
+            burst_shot: TileConversionMode::Paint,
+            splash_radius: 30.0,
+        }
+    }
+}
+
+/// A win condition that can be picked from the config. Any of them being met ends the round.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum WinConditionKind {
+    /// Only one turret is left.
+    LastTurretStanding,
+    /// Only the turrets of one team are left.
+    LastTeamStanding,
+    /// A participant owns at least this fraction of the battlefield.
+    Territory(f32),
+    /// This many seconds have passed. The surviving participant with the most tiles wins.
+    Timed(f32),
+    /// A turret holds at least this much charge.
+    ScoreTarget(u64),
+}
+impl WinConditionKind {
+    pub fn build(self) -> Box<dyn WinCondition> {
+        match self {
+            Self::LastTurretStanding => Box::new(LastTurretStanding),
+            Self::LastTeamStanding => Box::new(LastTeamStanding),
+            Self::Territory(fraction) => Box::new(Territory { fraction }),
+            Self::Timed(secs) => Box::new(Timed { secs }),
+            Self::ScoreTarget(charge) => Box::new(ScoreTarget { charge }),
+        }
+    }
+}
+/// The win conditions for each round. Changes take effect on restart.
+#[derive(Debug, Clone, Deref, Reflect)]
+pub struct WinConditionLayout(pub Vec<WinConditionKind>);
+impl Default for WinConditionLayout {
+    fn default() -> Self {
+        Self(vec![WinConditionKind::LastTurretStanding])
+    }
+}
+
+/// Configuration for the live feed of the game state for stream overlays. See
+/// [`crate::overlay_feed`].
+#[derive(Debug, Clone, Reflect)]
+pub struct OverlayFeedConfig {
+    pub enabled: bool,
+    /// The port the overlays connect to on localhost.
+    pub port: u16,
+    /// The number of times a second the state is sent out.
+    pub updates_per_sec: f32,
+}
+impl Default for OverlayFeedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9455,
+            updates_per_sec: 2.0,
+        }
+    }
+}
+
+/// Configuration for presentation mode, which locks out the keyboard and hides the cursor while the
+/// game runs unattended on a stream. See [`crate::presentation`].
+#[derive(Debug, Clone, Reflect)]
+pub struct PresentationConfig {
+    pub enabled: bool,
+    /// The keys to hold down together to unlock the keyboard, or lock it again.
+    pub unlock_chord: Vec<KeyCode>,
+}
+impl Default for PresentationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unlock_chord: vec![KeyCode::ControlLeft, KeyCode::AltLeft, KeyCode::KeyU],
+        }
+    }
+}
+
+/// Configuration for saving the territory map at the end of each round.
+#[derive(Debug, Clone, Reflect)]
+pub struct MapExportConfig {
+    pub enabled: bool,
+    /// The directory the maps are saved to, relative to the working directory.
+    pub directory: String,
+    /// The size each tile is drawn at by default. The maps are saved as SVG, so they can be scaled
+    /// to any size from there without losing detail.
+    pub pixels_per_tile: u32,
+    /// Also save each map as a PNG image at `pixels_per_tile`, for the places that don't take SVG.
+    pub png: bool,
+}
+impl MapExportConfig {
+    /// Past this, a single PNG would take hundreds of megabytes to draw.
+    pub const MAX_PIXELS_PER_TILE: u32 = 64;
+}
+impl Default for MapExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "maps".to_string(),
+            pixels_per_tile: 8,
+            png: true,
+        }
+    }
+}
+
+/// Configuration for saving the stats of each round as a spreadsheet at the end of it.
+#[derive(Debug, Clone, Reflect)]
+pub struct StatsExportConfig {
+    pub enabled: bool,
+    /// The directory the stats are saved to, relative to the working directory.
+    pub directory: String,
+}
+impl Default for StatsExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "stats".to_string(),
+        }
+    }
+}
+
+/// Configuration for keeping a signed record of every round played each day, to be checked before
+/// it's put on a leaderboard.
+#[derive(Debug, Clone, Reflect)]
+pub struct DailyResultsConfig {
+    pub enabled: bool,
+    /// The directory the results are saved to, relative to the working directory.
+    pub directory: String,
+}
+impl Default for DailyResultsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "results".to_string(),
+        }
+    }
+}
+
+/// Configuration for the sound effects.
+#[derive(Debug, Clone, Reflect)]
+pub struct AudioConfig {
+    /// The name of the sound pack directory under `mods/sound_packs/`.
+    pub sound_pack: String,
+    /// Volume of the trigger zone stingers, where 1 is the original volume of the sound files.
+    pub volume: f32,
+    /// Volume of the hum that rises with the leading turret's charge. Set to 0 to mute it.
+    pub charge_hum_volume: f32,
+    /// Volume of the background music, if the sound pack has any.
+    pub music_volume: f32,
+    /// Volume of the participants' themes and the victory jingle.
+    pub theme_volume: f32,
+    /// How much of the music's volume is left while a theme or the victory jingle plays over it.
+    pub music_ducking: f32,
+    /// Pan the stingers and themes toward where they happen on screen, and make the ones further
+    /// from the middle quieter.
+    pub spatial: bool,
+}
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            sound_pack: "default".to_string(),
+            volume: 1.0,
+            charge_hum_volume: 0.15,
+            music_volume: 0.4,
+            theme_volume: 1.0,
+            music_ducking: 0.3,
+            spatial: true,
+        }
+    }
+}
+
+/// Configuration for the emote bubbles that pop up over the turrets in reaction to the round. They're
+/// purely for show.
+#[derive(Debug, Clone, Reflect)]
+pub struct EmoteConfig {
+    pub enabled: bool,
+    /// Time in seconds each bubble stays up.
+    pub duration_secs: f32,
+    /// What the turrets emote and when. A turret shows one emote at a time, with the newest
+    /// replacing the last, and each entry has its own cooldown for each turret.
+    pub table: Vec<EmoteEntry>,
+}
+impl Default for EmoteConfig {
+    fn default() -> Self {
+        let entry = |trigger, emote: &str, cooldown_secs| EmoteEntry {
+            trigger,
+            emote: emote.to_string(),
+            cooldown_secs,
+        };
+        Self {
+            enabled: false,
+            duration_secs: 2.0,
+            table: vec![
+                entry(EmoteTrigger::LowTerritory(0.1), "^^;", 15.0),
+                entry(EmoteTrigger::BigShot(1000), "B-)", 8.0),
+                entry(EmoteTrigger::TookTheLead, "\\o/", 10.0),
+                entry(EmoteTrigger::OpponentEliminated, ">:)", 5.0),
+            ],
+        }
+    }
+}
+/// A row of [`EmoteConfig::table`].
+#[derive(Debug, Clone, Reflect)]
+pub struct EmoteEntry {
+    pub trigger: EmoteTrigger,
+    /// The text shown in the bubble.
+    pub emote: String,
+    /// Time in seconds before the same turret can show this entry again.
+    pub cooldown_secs: f32,
+}
+/// What a turret reacts to with an emote.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum EmoteTrigger {
+    /// The participant owns less than this fraction of the tiles.
+    LowTerritory(f32),
+    /// The turret fired a shot with at least this much charge.
+    BigShot(u64),
+    /// The turret started holding more charge than any other.
+    TookTheLead,
+    /// Another turret was knocked out. Every turret still standing reacts.
+    OpponentEliminated,
+}
+
+/// Configuration for making the game easier to follow, like telling the participants apart without
+/// relying on small areas of color, or turning off motion that can be uncomfortable to watch.
+#[derive(Debug, Clone, Reflect)]
+pub struct AccessibilityConfig {
+    /// Width of the ring in the participant's color around each worker ball. Set to 0 to disable.
+    pub worker_ball_outline: f32,
+    /// Mark each worker ball with a shape unique to its participant.
+    pub colorblind_icons: bool,
+    /// Shake the panel when a charged shot is triggered from it.
+    pub panel_shake: bool,
+}
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            worker_ball_outline: 1.5,
+            colorblind_icons: false,
+            panel_shake: true,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! A drop-down console for poking at the game while it runs, opened with the backquote key.
+//!
+//! Each line is parsed into a [`GameCommand`]; see [`crate::game_command`] for the commands.
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+};
+
+use crate::{
+    game_command::{CommandRejectedEvent, GameCommand},
+    utils::GameAssets,
+};
+
+// Constants {{{
+
+const CONSOLE_KEY: KeyCode = KeyCode::Backquote;
+const CONSOLE_FONT_SIZE: f32 = 18.0;
+const CONSOLE_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const CONSOLE_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+/// The number of lines of output kept on screen.
+const CONSOLE_HISTORY: usize = 10;
+
+// }}}
+
+pub struct ConsolePlugin;
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_systems(Startup, setup)
+            .add_systems(PreUpdate, read_console_input.after(InputSystem))
+            .add_systems(
+                Update,
+                (
+                    run_console_commands,
+                    print_rejections.run_if(on_event::<CommandRejectedEvent>()),
+                    update_console_text
+                        .after(run_console_commands)
+                        .after(print_rejections)
+                        .run_if(resource_changed::<Console>),
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+struct Console {
+    open: bool,
+    input: String,
+    /// Lines entered but not run yet.
+    submitted: Vec<String>,
+    output: Vec<String>,
+}
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.output.push(line.into());
+        let excess = self.output.len().saturating_sub(CONSOLE_HISTORY);
+        self.output.drain(..excess);
+    }
+}
+#[derive(Component)]
+struct ConsolePanel;
+#[derive(Component)]
+struct ConsoleText;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    let panel = commands
+        .spawn((
+            ConsolePanel,
+            Name::new("Console"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(0.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                background_color: CONSOLE_BACKGROUND.into(),
+                z_index: ZIndex::Global(3),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((
+            ConsoleText,
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: CONSOLE_FONT_SIZE,
+                    color: CONSOLE_TEXT_COLOR,
+                },
+            ),
+        ))
+        .set_parent(panel);
+}
+/// Type into the console while it's open. The keys are taken away from everything else in the
+/// meantime, so typing doesn't aim turrets or pause the game.
+fn read_console_input(
+    mut console: ResMut<Console>,
+    mut events: EventReader<KeyboardInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let was_open = console.open;
+    for event in events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        if event.key_code == CONSOLE_KEY {
+            console.open = !console.open;
+            continue;
+        }
+        if !console.open {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if !line.trim().is_empty() {
+                    console.submitted.push(line);
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Escape => console.open = false,
+            Key::Space => console.input.push(' '),
+            Key::Character(text) => console.input.push_str(text),
+            _ => (),
+        }
+    }
+    if was_open || console.open {
+        keys.reset_all();
+    }
+}
+fn run_console_commands(mut console: ResMut<Console>, mut events: EventWriter<GameCommand>) {
+    if console.submitted.is_empty() {
+        return;
+    }
+    for line in std::mem::take(&mut console.submitted) {
+        console.print(format!("> {}", line));
+        match line.parse() {
+            Ok(command) => {
+                events.send(command);
+            }
+            Err(e) => console.print(e),
+        }
+    }
+}
+fn print_rejections(mut console: ResMut<Console>, mut events: EventReader<CommandRejectedEvent>) {
+    for event in events.read() {
+        console.print(format!("{:?} rejected: {}", event.command, event.reason));
+    }
+}
+fn update_console_text(
+    console: Res<Console>,
+    mut panels: Query<&mut Visibility, With<ConsolePanel>>,
+    mut texts: Query<&mut Text, With<ConsoleText>>,
+) {
+    for mut visibility in &mut panels {
+        visibility.set_if_neq(match console.open {
+            true => Visibility::Visible,
+            false => Visibility::Hidden,
+        });
+    }
+    let mut value = console.output.join("\n");
+    if !value.is_empty() {
+        value.push('\n');
+    }
+    value.push_str(&format!("> {}_", console.input));
+    for mut text in &mut texts {
+        text.sections[0].value.clone_from(&value);
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::{color::palettes::css, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Cosmetic looks for a participant's turret barrel and worker ball trails, unlocked by winning
+/// rounds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurretSkin {
+    #[default]
+    Classic,
+    Gilded,
+    Neon,
+    Shadow,
+}
+impl TurretSkin {
+    pub const ALL: [TurretSkin; 4] = [Self::Classic, Self::Gilded, Self::Neon, Self::Shadow];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Gilded => "Gilded",
+            Self::Neon => "Neon",
+            Self::Shadow => "Shadow",
+        }
+    }
+    /// The number of rounds a participant has to win to unlock this skin.
+    pub fn required_wins(self) -> u32 {
+        match self {
+            Self::Classic => 0,
+            Self::Gilded => 3,
+            Self::Neon => 10,
+            Self::Shadow => 25,
+        }
+    }
+    /// The color of the turret barrel, or `None` to keep the default.
+    pub fn barrel_color(self, ball_color: Color) -> Option<Color> {
+        match self {
+            Self::Classic => None,
+            Self::Gilded => Some(css::GOLD.into()),
+            Self::Neon => Some(ball_color),
+            Self::Shadow => Some(Color::BLACK),
+        }
+    }
+    pub fn trail_color(self, base_color: Color, ball_color: Color) -> Color {
+        match self {
+            Self::Classic => base_color,
+            Self::Gilded => css::GOLD.into(),
+            Self::Neon => ball_color,
+            Self::Shadow => Color::BLACK,
+        }
+    }
+    /// The next skin after this one that's unlocked with `wins`, wrapping around to
+    /// [`TurretSkin::Classic`].
+    pub fn next_unlocked(self, wins: u32) -> Self {
+        let index = Self::ALL.iter().position(|&x| x == self).unwrap_or(0);
+        Self::ALL
+            .into_iter()
+            .cycle()
+            .skip(index + 1)
+            .find(|x| x.required_wins() <= wins)
+            .unwrap_or_default()
+    }
+}
@@ -5,9 +5,10 @@ use bevy_hanabi::prelude::*;
 use rand::{distributions::Uniform, prelude::*};
 
 use crate::{
-    battlefield::{EliminationEvent, BATTLEFIELD_HALF_WIDTH},
+    battlefield::BATTLEFIELD_HALF_WIDTH,
+    game_command::GameCommand,
     panel_plugin::{TriggerEvent, TriggerType},
-    utils::{BallColor, Participant, ParticipantMap, TileHitEffect},
+    utils::{BallColor, GameAssets, Participant, ParticipantMap},
 };
 
 pub struct DebugUtilsPlugin;
@@ -31,7 +32,7 @@ fn auto_hanabi(
     mut commands: Commands,
     mut timer: ResMut<AutoTimer>,
     time: Res<Time>,
-    effect: Res<TileHitEffect>,
+    assets: Res<GameAssets>,
     colors: Res<ParticipantMap<BallColor>>,
 ) {
     timer.tick(time.delta());
@@ -40,13 +41,7 @@ fn auto_hanabi(
         let mut rng = thread_rng();
         let x = rng.sample(dist);
         let y = rng.sample(dist);
-        let p = match rng.sample(Uniform::new(0, 4)) {
-            0 => Participant::A,
-            1 => Participant::B,
-            2 => Participant::C,
-            3 => Participant::D,
-            _ => unreachable!(),
-        };
+        let p = Participant::ALL[rng.sample(Uniform::new(0, Participant::ALL.len()))];
         let color = Srgba::from(colors.get(p).0);
         let color = 0xFF000000u32
             | ((color.blue * 255.0) as u32) << 16
@@ -55,7 +50,7 @@ fn auto_hanabi(
         let mut effect_properties = EffectProperties::default();
         effect_properties.set("spawn_color", color.into());
         commands.spawn(ParticleEffectBundle {
-            effect: ParticleEffect::new(effect.0.clone()),
+            effect: ParticleEffect::new(assets.tile_hit_effect.clone()),
             transform: Transform::from_xyz(x, y, 5.0),
             effect_properties,
             ..default()
@@ -63,34 +58,31 @@ fn auto_hanabi(
     }
 }
 fn auto_elimination(
-    mut writer: EventWriter<EliminationEvent>,
+    mut writer: EventWriter<GameCommand>,
     mut timer: ResMut<AutoTimer>,
     time: Res<Time>,
 ) {
     timer.tick(time.delta());
     if timer.just_finished() {
-        writer.send(EliminationEvent {
-            participant: Participant::A,
-        });
-        writer.send(EliminationEvent {
-            participant: Participant::B,
-        });
-        writer.send(EliminationEvent {
-            participant: Participant::C,
-        });
+        for participant in [Participant::A, Participant::B, Participant::C] {
+            writer.send(GameCommand::SetCharge {
+                participant,
+                charge: 0,
+            });
+        }
     }
 }
-fn auto_fire(mut writer: EventWriter<TriggerEvent>, mut timer: ResMut<AutoTimer>, time: Res<Time>) {
+fn auto_fire(mut writer: EventWriter<GameCommand>, mut timer: ResMut<AutoTimer>, time: Res<Time>) {
     timer.tick(time.delta());
     if timer.just_finished() {
         for participant in Participant::ALL {
             for _ in 0..3 {
-                writer.send(TriggerEvent {
+                writer.send(GameCommand::Trigger {
                     participant,
                     trigger_type: TriggerType::Multiply(4),
                 });
             }
-            writer.send(TriggerEvent {
+            writer.send(GameCommand::Trigger {
                 participant,
                 trigger_type: TriggerType::BurstShot,
             });
@@ -98,13 +90,13 @@ fn auto_fire(mut writer: EventWriter<TriggerEvent>, mut timer: ResMut<AutoTimer>
     }
 }
 fn auto_multiply(
-    mut writer: EventWriter<TriggerEvent>,
+    mut writer: EventWriter<GameCommand>,
     mut timer: ResMut<AutoTimer>,
     time: Res<Time>,
 ) {
     timer.tick(time.delta());
     if timer.just_finished() {
-        writer.send(TriggerEvent {
+        writer.send(GameCommand::Trigger {
             participant: Participant::A,
             trigger_type: TriggerType::Multiply(4),
         });
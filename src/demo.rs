@@ -0,0 +1,519 @@
+//! Scripted camera, speed and UI changes for recording consistent trailer footage.
+//!
+//! Launch with `--record-demo <path>` to record a script with the controls below, then with
+//! `--demo <path>` to replay it over the same seeded round.
+//!
+//! - Arrow keys: pan the camera.
+//! - `=`/`-`: zoom the camera in and out.
+//! - `[`/`]`: halve or double the simulation speed.
+//! - `H`: show or hide the UI.
+//! - `F9`: save the script. It's also saved on exit.
+//!
+//! A script is saved with a hash of the game config it was recorded with, how each round of the
+//! recording ended, and a signature over all of it under the game's [`SigningKey`], so a shared
+//! script can be checked for having been edited or replayed under a different config. Without the
+//! key, an edited script can't be signed again. One that doesn't check out still plays, but
+//! nothing from the session counts toward the stats.
+//!
+//! Each round of a replay is also checked against how it ended in the recording. The physics steps
+//! along with the frame rate, so a replay can drift from the recording, which is logged but doesn't
+//! keep the session out of the stats, since the rounds were still played out for real.
+
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    reflect::{serde::TypedReflectSerializer, TypeRegistry},
+};
+use rand::random;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{MatchViewCamera, RoundNumber, RoundWinner, Tile},
+    cli::{DemoOption, LaunchOptions},
+    config::GameConfig,
+    signing::SigningKey,
+    utils::{GameRng, Participant, Seed},
+};
+
+// Constants {{{
+
+/// Camera pan speed in world units per second, at a zoom level of 1.
+const DEMO_CAMERA_PAN_SPEED: f32 = 400.0;
+/// How much the camera zooms per second while the zoom key is held.
+const DEMO_CAMERA_ZOOM_SPEED: f32 = 1.0;
+const DEMO_CAMERA_MIN_SCALE: f32 = 0.1;
+const DEMO_CAMERA_MAX_SCALE: f32 = 4.0;
+const DEMO_MIN_SPEED: f32 = 0.125;
+const DEMO_MAX_SPEED: f32 = 8.0;
+
+// }}}
+
+pub struct DemoPlugin;
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        let world = app.world();
+        let config = world.resource::<GameConfig>();
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let config_hash = config_hash(config, &registry)
+            .map_err(|e| error!("Failed to hash the game config: {}", e))
+            .ok();
+        drop(registry);
+        let options = world.resource::<LaunchOptions>();
+        let Some(mode) = DemoMode::from_options(options, config_hash.as_deref()) else {
+            return;
+        };
+        if let DemoMode::Playback { script, .. } = &mode {
+            let key = world.resource::<SigningKey>();
+            let verified = match &config_hash {
+                Some(config_hash) => script.verify(key, config_hash),
+                None => Err("the game config couldn't be hashed to check it against".to_string()),
+            };
+            if let Err(e) = verified {
+                error!(
+                    "The demo script can't be verified, so this session won't count toward the \
+                     stats: {}",
+                    e
+                );
+                app.insert_resource(UnverifiedReplay);
+            }
+        }
+        app.insert_resource(GameRng::from_seed(mode.script().seed))
+            .insert_resource(Seed(mode.script().seed))
+            .insert_resource(mode)
+            .add_systems(
+                Update,
+                (
+                    (control_camera, control_speed, control_ui, save_recording)
+                        .run_if(DemoMode::is_recording),
+                    play_demo.run_if(not(DemoMode::is_recording)),
+                    trace_outcome.run_if(resource_changed::<RoundWinner>),
+                ),
+            )
+            .add_systems(
+                Last,
+                save_recording.run_if(DemoMode::is_recording.and_then(on_event::<AppExit>())),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum DemoAction {
+    Camera { x: f32, y: f32, scale: f32 },
+    Speed(f32),
+    ShowUi(bool),
+}
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct DemoStep {
+    /// Time in seconds since launch, in real time so that speed changes don't shift the rest of
+    /// the script.
+    at_secs: f32,
+    action: DemoAction,
+}
+/// How a round ended, to check a replay against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundOutcome {
+    pub round: u32,
+    pub winner: Participant,
+    /// The number of tiles each participant held at the end, in the order of [`Participant::ALL`].
+    pub tiles: Vec<u32>,
+}
+impl RoundOutcome {
+    /// The outcome of round `round`, won by `winner`, with `tiles` being the owner of every tile.
+    pub fn count<'a>(
+        round: u32,
+        winner: Participant,
+        tiles: impl IntoIterator<Item = &'a Participant>,
+    ) -> Self {
+        let mut counts = vec![0; Participant::ALL.len()];
+        for &participant in tiles {
+            counts[participant as usize] += 1;
+        }
+        Self {
+            round,
+            winner,
+            tiles: counts,
+        }
+    }
+}
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DemoScript {
+    /// Seed for [`GameRng`], so the round plays out the same way as when it was recorded.
+    seed: u64,
+    /// The hash of the game config the script was recorded with, since the same seed plays out
+    /// differently under a different config.
+    #[serde(default)]
+    config_hash: String,
+    steps: Vec<DemoStep>,
+    /// How each round of the recording ended, in order.
+    #[serde(default)]
+    outcomes: Vec<RoundOutcome>,
+    /// The signature over everything above, to tell whether the script was edited after it was
+    /// saved.
+    #[serde(default)]
+    signature: String,
+}
+impl DemoScript {
+    /// Everything the signature covers, written out the way it's saved, which comes out the same
+    /// after loading it back in.
+    fn signed_contents(&self) -> Result<String, ron::Error> {
+        ron::to_string(&(self.seed, &self.config_hash, &self.steps, &self.outcomes))
+    }
+    /// Check that the script was signed with `key` and hasn't been edited since, and that it was
+    /// recorded with the config that hashes to `config_hash`.
+    fn verify(&self, key: &SigningKey, config_hash: &str) -> Result<(), String> {
+        if self.signature.is_empty() {
+            return Err("it isn't signed, so it was saved by an older version".to_string());
+        }
+        let contents = self.signed_contents().map_err(|e| e.to_string())?;
+        if !key.verify(contents.as_bytes(), &self.signature) {
+            return Err(
+                "its signature doesn't match its contents under the key in signing.key, so it was \
+                 either edited after it was saved or signed with another key"
+                    .to_string(),
+            );
+        }
+        if self.config_hash != config_hash {
+            return Err("it was recorded with a different game config".to_string());
+        }
+        Ok(())
+    }
+}
+/// Marks a session replaying a demo script that didn't pass [`DemoScript::verify`], which keeps it
+/// out of the stats.
+#[derive(Resource)]
+pub struct UnverifiedReplay;
+#[derive(Resource)]
+enum DemoMode {
+    Recording {
+        path: PathBuf,
+        script: DemoScript,
+    },
+    Playback {
+        script: DemoScript,
+        /// Index of the next step to apply.
+        next: usize,
+        /// Whether the replay has drifted from the recording, after which it isn't checked again.
+        diverged: bool,
+    },
+}
+impl DemoMode {
+    /// Set up from `--demo <path>` or `--record-demo <path>`. A recording uses `--seed` if given,
+    /// and needs the hash of the config, if it could be worked out. Errors are logged and the game
+    /// starts normally.
+    fn from_options(options: &LaunchOptions, config_hash: Option<&str>) -> Option<Self> {
+        match options.demo.as_ref()? {
+            DemoOption::Play(path) => {
+                let script = fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|x| ron::from_str(&x).map_err(|e| e.to_string()));
+                match script {
+                    Ok(script) => Some(Self::Playback {
+                        script,
+                        next: 0,
+                        diverged: false,
+                    }),
+                    Err(e) => {
+                        error!("Failed to load demo script {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            }
+            DemoOption::Record(path) => {
+                let Some(config_hash) = config_hash else {
+                    error!(
+                        "Not recording {}, since a script has to have the config hash",
+                        path.display()
+                    );
+                    return None;
+                };
+                Some(Self::Recording {
+                    path: path.clone(),
+                    script: DemoScript {
+                        seed: options.seed.unwrap_or_else(random),
+                        config_hash: config_hash.to_string(),
+                        ..default()
+                    },
+                })
+            }
+        }
+    }
+    fn is_recording(mode: Option<Res<Self>>) -> bool {
+        matches!(mode.as_deref(), Some(Self::Recording { .. }))
+    }
+    fn script(&self) -> &DemoScript {
+        match self {
+            Self::Recording { script, .. } | Self::Playback { script, .. } => script,
+        }
+    }
+    fn record(&mut self, at_secs: f32, action: DemoAction) {
+        if let Self::Recording { script, .. } = self {
+            script.steps.push(DemoStep { at_secs, action });
+        }
+    }
+}
+
+/// The hash of everything in `config`, written out the same way as a tuning file, which has every
+/// field by name in a fixed order and doesn't change along with the debug output.
+pub fn config_hash(config: &GameConfig, registry: &TypeRegistry) -> Result<String, ron::Error> {
+    let serializer = TypedReflectSerializer::new(config, registry);
+    let text = ron::to_string(&serializer)?;
+    Ok(blake3::hash(text.as_bytes()).to_hex().to_string())
+}
+
+/// The camera showing the whole game, rather than one of the match views.
+type MainCamera<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static mut OrthographicProjection),
+    (With<Camera2d>, Without<MatchViewCamera>),
+>;
+fn control_camera(
+    mut mode: ResMut<DemoMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut camera: MainCamera,
+) {
+    let (mut transform, mut projection) = camera.single_mut();
+    let mut direction = Vec2::ZERO;
+    for (key, offset) in [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ] {
+        if keys.pressed(key) {
+            direction += offset;
+        }
+    }
+    let mut zoom = 0.0;
+    if keys.pressed(KeyCode::Equal) {
+        zoom -= 1.0;
+    }
+    if keys.pressed(KeyCode::Minus) {
+        zoom += 1.0;
+    }
+    if direction == Vec2::ZERO && zoom == 0.0 {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    let offset = direction * DEMO_CAMERA_PAN_SPEED * projection.scale * delta;
+    transform.translation += offset.extend(0.0);
+    projection.scale = (projection.scale * (1.0 + zoom * DEMO_CAMERA_ZOOM_SPEED * delta))
+        .clamp(DEMO_CAMERA_MIN_SCALE, DEMO_CAMERA_MAX_SCALE);
+    mode.record(
+        time.elapsed_seconds(),
+        DemoAction::Camera {
+            x: transform.translation.x,
+            y: transform.translation.y,
+            scale: projection.scale,
+        },
+    );
+}
+fn control_speed(
+    mut mode: ResMut<DemoMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+    real_time: Res<Time<Real>>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let factor = if keys.just_pressed(KeyCode::BracketLeft) {
+        0.5
+    } else if keys.just_pressed(KeyCode::BracketRight) {
+        2.0
+    } else {
+        return;
+    };
+    let speed = (time.relative_speed() * factor).clamp(DEMO_MIN_SPEED, DEMO_MAX_SPEED);
+    time.set_relative_speed(speed);
+    mode.record(real_time.elapsed_seconds(), DemoAction::Speed(speed));
+}
+fn control_ui(
+    mut mode: ResMut<DemoMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut ui_roots: Query<&mut Visibility, (With<Node>, Without<Parent>)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    let show = ui_roots
+        .iter()
+        .next()
+        .is_none_or(|x| *x == Visibility::Hidden);
+    set_ui_visibility(&mut ui_roots, show);
+    mode.record(time.elapsed_seconds(), DemoAction::ShowUi(show));
+}
+fn set_ui_visibility(
+    ui_roots: &mut Query<&mut Visibility, (With<Node>, Without<Parent>)>,
+    show: bool,
+) {
+    for mut visibility in ui_roots {
+        *visibility = if show {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+fn save_recording(
+    mode: Res<DemoMode>,
+    key: Res<SigningKey>,
+    keys: Res<ButtonInput<KeyCode>>,
+    exit: EventReader<AppExit>,
+) {
+    if !keys.just_pressed(KeyCode::F9) && exit.is_empty() {
+        return;
+    }
+    let DemoMode::Recording { path, script } = &*mode else {
+        return;
+    };
+    let result = script
+        .signed_contents()
+        .and_then(|x| {
+            let script = DemoScript {
+                signature: key.sign(x.as_bytes()),
+                ..script.clone()
+            };
+            ron::ser::to_string_pretty(&script, default())
+        })
+        .map_err(|e| e.to_string())
+        .and_then(|x| fs::write(path, x).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => info!("Saved demo script to {}", path.display()),
+        Err(e) => error!("Failed to save demo script to {}: {}", path.display(), e),
+    }
+}
+fn play_demo(
+    mut mode: ResMut<DemoMode>,
+    real_time: Res<Time<Real>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut camera: MainCamera,
+    mut ui_roots: Query<&mut Visibility, (With<Node>, Without<Parent>)>,
+) {
+    let DemoMode::Playback { script, next, .. } = &mut *mode else {
+        return;
+    };
+    let now = real_time.elapsed_seconds();
+    while let Some(step) = script.steps.get(*next).filter(|x| x.at_secs <= now) {
+        match step.action {
+            DemoAction::Camera { x, y, scale } => {
+                let (mut transform, mut projection) = camera.single_mut();
+                transform.translation.x = x;
+                transform.translation.y = y;
+                projection.scale = scale;
+            }
+            DemoAction::Speed(speed) => time.set_relative_speed(speed),
+            DemoAction::ShowUi(show) => set_ui_visibility(&mut ui_roots, show),
+        }
+        *next += 1;
+    }
+}
+/// Add how each round ended to the script while recording, and check it against the recording while
+/// replaying.
+fn trace_outcome(
+    mut mode: ResMut<DemoMode>,
+    winner: Res<RoundWinner>,
+    round: Res<RoundNumber>,
+    tiles: Query<&Participant, With<Tile>>,
+) {
+    let Some(winner) = winner.0 else {
+        return;
+    };
+    let outcome = RoundOutcome::count(round.0, winner, &tiles);
+    match &mut *mode {
+        DemoMode::Recording { script, .. } => script.outcomes.push(outcome),
+        DemoMode::Playback {
+            script,
+            diverged: diverged @ false,
+            ..
+        } => {
+            let recorded = script.outcomes.iter().find(|x| x.round == outcome.round);
+            if recorded != Some(&outcome) {
+                warn!(
+                    "The replay drifted from the recording in round {}, which ended as {:?} \
+                     instead of {:?}.",
+                    outcome.round, outcome, recorded
+                );
+                *diverged = true;
+            }
+        }
+        DemoMode::Playback { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn world_with(mode: DemoMode, winner: Participant, tiles: &[Participant]) -> World {
+        let mut world = World::new();
+        world.insert_resource(mode);
+        world.insert_resource(RoundWinner(Some(winner)));
+        world.insert_resource(RoundNumber(1));
+        for &participant in tiles {
+            world.spawn((Tile, participant));
+        }
+        world
+    }
+
+    #[test]
+    fn outcomes_are_recorded_and_hashed() {
+        let mut world = world_with(
+            DemoMode::Recording {
+                path: PathBuf::new(),
+                script: DemoScript::default(),
+            },
+            Participant::B,
+            &[Participant::A, Participant::B, Participant::B],
+        );
+        world.run_system_once(trace_outcome);
+        let mut script = world.resource::<DemoMode>().script().clone();
+        assert_eq!(script.outcomes[0].winner, Participant::B);
+        assert_eq!(script.outcomes[0].tiles[..3], [1, 2, 0]);
+
+        let key = SigningKey([1; blake3::KEY_LEN]);
+        script.signature = key.sign(script.signed_contents().unwrap().as_bytes());
+        assert!(script.verify(&key, "").is_ok());
+        assert!(script.verify(&key, "another config").is_err());
+        assert!(script
+            .verify(&SigningKey([2; blake3::KEY_LEN]), "")
+            .is_err());
+        script.outcomes[0].winner = Participant::A;
+        assert!(script.verify(&key, "").is_err());
+    }
+
+    #[test]
+    fn replays_that_end_differently_are_caught() {
+        let script = DemoScript {
+            outcomes: vec![RoundOutcome {
+                round: 1,
+                winner: Participant::A,
+                tiles: vec![1; Participant::ALL.len()],
+            }],
+            ..default()
+        };
+        let playback = || DemoMode::Playback {
+            script: script.clone(),
+            next: 0,
+            diverged: false,
+        };
+        let diverged = |world: &World| {
+            matches!(
+                world.resource::<DemoMode>(),
+                DemoMode::Playback { diverged: true, .. }
+            )
+        };
+
+        let mut world = world_with(playback(), Participant::A, &Participant::ALL);
+        world.run_system_once(trace_outcome);
+        assert!(!diverged(&world));
+
+        let mut world = world_with(playback(), Participant::B, &Participant::ALL);
+        world.run_system_once(trace_outcome);
+        assert!(diverged(&world));
+    }
+}
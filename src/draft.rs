@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::utils::{Participant, ParticipantMap};
+
+/// Upgrades drafted for a participant between rounds, out of the budget in
+/// [`crate::config::DraftConfig`]. Each one can be picked more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Upgrade {
+    /// An extra worker ball that drops with the participant's first one.
+    ExtraBall,
+    /// The biggest multiply zone multiplies by one more for the participant.
+    ZoneBonus,
+    /// The fortified tiles around the participant's turret reach further at the start of the round.
+    Fortification,
+}
+impl Upgrade {
+    pub const ALL: [Upgrade; 3] = [
+        Upgrade::ExtraBall,
+        Upgrade::ZoneBonus,
+        Upgrade::Fortification,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::ExtraBall => "Extra Ball",
+            Upgrade::ZoneBonus => "Zone +1",
+            Upgrade::Fortification => "Fortify",
+        }
+    }
+}
+
+/// The upgrades drafted for the current round.
+#[derive(Debug, Clone, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DraftedUpgrades(pub ParticipantMap<Vec<Upgrade>>);
+impl DraftedUpgrades {
+    /// The number of times `upgrade` was picked for `participant`.
+    pub fn count(&self, participant: Participant, upgrade: Upgrade) -> usize {
+        self.0[participant]
+            .iter()
+            .filter(|&&x| x == upgrade)
+            .count()
+    }
+}
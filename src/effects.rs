@@ -0,0 +1,144 @@
+//! Particle effects loaded from the mod directory, so particle counts, lifetimes and gradients can
+//! be tweaked while the game is running.
+//!
+//! Each effect is a RON serialized [`EffectAsset`] under `mods/effects/`:
+//!
+//! - `tile_hit.effect.ron`: the burst where a bullet hits a tile.
+//! - `trail.effect.ron`: the trail behind the worker balls.
+//!
+//! Create the directory and launch the game once to have the built-in effects written out as a
+//! starting point. The properties the game sets on the effects (`spawn_color`, `bullet_vel` and
+//! `position`) have to be kept. An effect that's missing or fails to load falls back to the
+//! built-in one.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_hanabi::prelude::*;
+use ron::ser::PrettyConfig;
+
+use crate::{
+    cli::LaunchOptions,
+    utils::{create_tile_hit_effect, create_trail_effect, modified_time, GameAssets},
+};
+
+// Constants {{{
+
+const EFFECT_DIR: &str = "mods/effects";
+/// Time in seconds between checks for changes to the effects on disk.
+const EFFECT_POLL_INTERVAL: f32 = 1.0;
+
+// }}}
+
+pub struct EffectsPlugin;
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EffectFiles>()
+            .add_systems(Startup, write_default_effects)
+            .add_systems(
+                Update,
+                reload_effects.run_if(on_timer(Duration::from_secs_f32(EFFECT_POLL_INTERVAL))),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum EffectKind {
+    TileHit,
+    Trail,
+}
+impl EffectKind {
+    const ALL: [EffectKind; 2] = [EffectKind::TileHit, EffectKind::Trail];
+    fn path(self) -> PathBuf {
+        let file_name = match self {
+            EffectKind::TileHit => "tile_hit.effect.ron",
+            EffectKind::Trail => "trail.effect.ron",
+        };
+        Path::new(EFFECT_DIR).join(file_name)
+    }
+    fn built_in(self, options: &LaunchOptions) -> EffectAsset {
+        match self {
+            EffectKind::TileHit => create_tile_hit_effect(),
+            EffectKind::Trail => create_trail_effect(options),
+        }
+    }
+    fn handle(self, assets: &GameAssets) -> &Handle<EffectAsset> {
+        match self {
+            EffectKind::TileHit => &assets.tile_hit_effect,
+            EffectKind::Trail => &assets.trail_effect,
+        }
+    }
+    fn load(self) -> Result<EffectAsset, String> {
+        let path = self.path();
+        fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+            .and_then(|x| ron::from_str(&x).map_err(|e| format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// When each effect file was last modified as of the last check, to tell when it needs to be
+/// reloaded. `None` for the files that don't exist, which the built-in effects are already in place
+/// for.
+#[derive(Resource, Default)]
+struct EffectFiles([Option<SystemTime>; EffectKind::ALL.len()]);
+
+/// Write out the built-in effects that don't have a file yet, as long as the effect directory has
+/// been created.
+fn write_default_effects(options: Res<LaunchOptions>) {
+    if !Path::new(EFFECT_DIR).is_dir() {
+        return;
+    }
+    for kind in EffectKind::ALL {
+        let path = kind.path();
+        if path.exists() {
+            continue;
+        }
+        let result = ron::ser::to_string_pretty(&kind.built_in(&options), PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|x| fs::write(&path, x).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!("Saved the built-in effect to {}", path.display()),
+            Err(e) => error!("Failed to save effect to {}: {}", path.display(), e),
+        }
+    }
+}
+/// Swap in any effect whose file was changed, added or removed since the last check. The effects
+/// already playing are rebuilt from the new definition.
+fn reload_effects(
+    mut files: ResMut<EffectFiles>,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    assets: Res<GameAssets>,
+    options: Res<LaunchOptions>,
+) {
+    for (i, kind) in EffectKind::ALL.into_iter().enumerate() {
+        let path = kind.path();
+        let modified = modified_time(&path);
+        if files.0[i] == modified {
+            continue;
+        }
+        files.0[i] = modified;
+        let effect = if modified.is_none() {
+            info!(
+                "No effect found at {}, using the built-in one.",
+                path.display()
+            );
+            kind.built_in(&options)
+        } else {
+            match kind.load() {
+                Ok(effect) => {
+                    info!("Loaded effect from {}.", path.display());
+                    effect
+                }
+                Err(e) => {
+                    warn!("Failed to load effect, using the built-in one: {}", e);
+                    kind.built_in(&options)
+                }
+            }
+        };
+        effects.insert(kind.handle(&assets), effect);
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::Instant, window::PresentMode};
+
+use crate::config::{GameConfig, ValidateConfig};
+
+pub struct FramePacingPlugin;
+impl Plugin for FramePacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            apply_vsync
+                .after(ValidateConfig)
+                .run_if(resource_changed::<GameConfig>),
+        )
+        .add_systems(Last, limit_frame_rate);
+    }
+}
+
+fn apply_vsync(config: Res<GameConfig>, mut windows: Query<&mut Window>) {
+    let present_mode = match config.performance.vsync {
+        true => PresentMode::AutoVsync,
+        false => PresentMode::AutoNoVsync,
+    };
+    for mut window in &mut windows {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+}
+/// Sleep off whatever's left of the frame time at the end of each frame, to stay under the frame
+/// cap. Nothing else happens in the meantime, so this saves power on top of the frames not drawn.
+fn limit_frame_rate(mut frame_start: Local<Option<Instant>>, config: Res<GameConfig>) {
+    if let (Some(fps), Some(start)) = (config.performance.frame_cap(), *frame_start) {
+        let frame_time = Duration::from_secs_f64(1.0 / fps as f64);
+        if let Some(left) = frame_time.checked_sub(start.elapsed()) {
+            std::thread::sleep(left);
+        }
+    }
+    *frame_start = Some(Instant::now());
+}
@@ -0,0 +1,204 @@
+//! The one path for changing the game from the outside. The console, the buttons between rounds and
+//! the debug utilities all send a [`GameCommand`] rather than writing the gameplay events
+//! themselves, so every change is checked and logged in one place.
+//!
+//! Commands can also be parsed from text:
+//!
+//! - `spawn ball <left|right> <participant>` drops an extra worker ball into a panel.
+//! - `charge <participant> <value>` sets the charge of a turret. A charge of 0 eliminates it.
+//! - `trigger <participant> <x2|x4|...|burst|charged|gamble|swap|freeze>` fires a trigger zone.
+//! - `speed <scale>` sets the simulation speed relative to real time.
+//! - `seed <seed>` reseeds everything random from here on.
+//! - `restart` starts a new round.
+//!
+//! Participants can be given by letter or by color, e.g. `A` or `red`.
+
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{Charge, RestartEvent, Turret},
+    panel_plugin::{PanelRootSide, SpawnWorkerBallEvent, TriggerEvent, TriggerType},
+    utils::{GameRng, Matches, Participant, ParticipantMap, Roster, Seed},
+};
+
+pub struct GameCommandPlugin;
+impl Plugin for GameCommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameCommand>()
+            .add_event::<CommandRejectedEvent>()
+            .add_systems(Update, execute_commands.run_if(on_event::<GameCommand>()));
+    }
+}
+
+/// A change to the game requested from the outside.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum GameCommand {
+    SpawnBall {
+        side: PanelRootSide,
+        participant: Participant,
+    },
+    SetCharge {
+        participant: Participant,
+        charge: u64,
+    },
+    Trigger {
+        participant: Participant,
+        trigger_type: TriggerType,
+    },
+    SetSpeed(f32),
+    Reseed(u64),
+    Restart,
+}
+impl FromStr for GameCommand {
+    type Err = String;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words[..] {
+            ["spawn", "ball", side, participant] => {
+                let side = match side {
+                    "left" => PanelRootSide::Left,
+                    "right" => PanelRootSide::Right,
+                    _ => return Err(format!("unknown side {}, expected left or right", side)),
+                };
+                Ok(Self::SpawnBall {
+                    side,
+                    participant: parse_participant(participant)?,
+                })
+            }
+            ["charge", participant, value] => Ok(Self::SetCharge {
+                participant: parse_participant(participant)?,
+                charge: parse_number(value)?,
+            }),
+            ["trigger", participant, trigger_type] => Ok(Self::Trigger {
+                participant: parse_participant(participant)?,
+                trigger_type: parse_trigger_type(trigger_type)?,
+            }),
+            ["speed", scale] => Ok(Self::SetSpeed(parse_number(scale)?)),
+            ["seed", seed] => Ok(Self::Reseed(parse_number(seed)?)),
+            ["restart"] => Ok(Self::Restart),
+            _ => Err(format!("unknown command {}", line)),
+        }
+    }
+}
+/// Event sent when a [`GameCommand`] can't be carried out, with the reason why.
+#[derive(Event, Debug, Clone)]
+pub struct CommandRejectedEvent {
+    pub command: GameCommand,
+    pub reason: String,
+}
+
+fn parse_participant(value: &str) -> Result<Participant, String> {
+    Participant::ALL
+        .into_iter()
+        .find(|x| {
+            format!("{:?}", x).eq_ignore_ascii_case(value)
+                || x.to_string().eq_ignore_ascii_case(value)
+        })
+        .ok_or_else(|| format!("unknown participant {}", value))
+}
+fn parse_number<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} is not a valid number", value))
+}
+fn parse_trigger_type(value: &str) -> Result<TriggerType, String> {
+    match value {
+        "burst" => Ok(TriggerType::BurstShot),
+        "charged" => Ok(TriggerType::ChargedShot),
+        "gamble" => Ok(TriggerType::Gamble),
+        "swap" => Ok(TriggerType::Swap),
+        "freeze" => Ok(TriggerType::Freeze),
+        _ => value
+            .strip_prefix('x')
+            .and_then(|x| x.parse().ok())
+            .map(TriggerType::Multiply)
+            .ok_or_else(|| format!("unknown trigger {}", value)),
+    }
+}
+
+/// Check each command against the current state of the game and apply it, or reject it with the
+/// reason why.
+pub fn execute_commands(
+    mut commands: Commands,
+    mut events: EventReader<GameCommand>,
+    mut rejections: EventWriter<CommandRejectedEvent>,
+    (mut ball_events, mut trigger_events, mut restart_events): (
+        EventWriter<SpawnWorkerBallEvent>,
+        EventWriter<TriggerEvent>,
+        EventWriter<RestartEvent>,
+    ),
+    mut time: ResMut<Time<Virtual>>,
+    (roster, survivors, matches): (Res<Roster>, Res<ParticipantMap<bool>>, Res<Matches>),
+    mut turrets: Query<(&Participant, &mut Charge), With<Turret>>,
+) {
+    for &command in events.read() {
+        let result = match command {
+            GameCommand::SpawnBall { side, participant } => {
+                if roster[participant] {
+                    ball_events.send(SpawnWorkerBallEvent { participant, side });
+                    Ok(())
+                } else {
+                    Err(format!("{} isn't taking part", participant))
+                }
+            }
+            GameCommand::SetCharge {
+                participant,
+                charge,
+            } => match turrets.iter_mut().find(|(&x, _)| x == participant) {
+                Some((_, mut turret_charge)) => {
+                    *turret_charge = Charge::from_value(charge);
+                    Ok(())
+                }
+                None => Err(format!("{} has no turret", participant)),
+            },
+            GameCommand::Trigger {
+                participant,
+                trigger_type,
+            } => {
+                if survivors[participant] {
+                    trigger_events.send(TriggerEvent {
+                        participant,
+                        trigger_type,
+                        ball: None,
+                    });
+                    Ok(())
+                } else {
+                    Err(format!("{} is out of the round", participant))
+                }
+            }
+            GameCommand::SetSpeed(scale) => {
+                if scale > 0.0 && scale.is_finite() {
+                    time.set_relative_speed(scale);
+                    Ok(())
+                } else {
+                    Err("the speed has to be a positive number".to_string())
+                }
+            }
+            GameCommand::Reseed(seed) => {
+                commands.insert_resource(GameRng::from_seed(seed));
+                commands.insert_resource(Seed(seed));
+                Ok(())
+            }
+            GameCommand::Restart => {
+                // It's not much of a game with less than 2 participants.
+                if roster.count() >= 2 {
+                    for match_id in matches.running(&roster) {
+                        restart_events.send(RestartEvent { match_id });
+                    }
+                    Ok(())
+                } else {
+                    Err("it takes at least 2 participants to play".to_string())
+                }
+            }
+        };
+        match result {
+            Ok(()) => info!("Applied {:?}", command),
+            Err(reason) => {
+                warn!("Rejected {:?}: {}", command, reason);
+                rejections.send(CommandRejectedEvent { command, reason });
+            }
+        }
+    }
+}
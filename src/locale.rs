@@ -0,0 +1,108 @@
+//! How numbers and dates are written for people to read, both on screen and in the files exported
+//! for spreadsheets, so a session's numbers read the same wherever they end up.
+//!
+//! Files meant for other programs, like `stats.ron` and the JSON of the overlay feed and snapshots,
+//! keep their numbers in the one format those parsers expect, whatever the locale.
+
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+/// The conventions used to write numbers and dates, picked with `locale` in the game config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum Locale {
+    /// Decimal points and dates written as `2024-05-31`.
+    #[default]
+    Iso,
+    /// Decimal points and dates written as `05/31/2024`.
+    EnUs,
+    /// Decimal points and dates written as `31/05/2024`.
+    EnGb,
+    /// Decimal commas and dates written as `31.05.2024`.
+    De,
+    /// Decimal commas and dates written as `31/05/2024`.
+    Fr,
+}
+impl Locale {
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Locale::Iso | Locale::EnUs | Locale::EnGb => '.',
+            Locale::De | Locale::Fr => ',',
+        }
+    }
+    /// The separator between the fields of a CSV row. Spreadsheets in locales with a decimal comma
+    /// expect a semicolon, so the commas in numbers aren't taken for new columns.
+    pub fn field_separator(self) -> char {
+        match self.decimal_separator() {
+            ',' => ';',
+            _ => ',',
+        }
+    }
+    /// Write `value` with `places` digits after the decimal separator.
+    pub fn format_decimal(self, value: f64, places: usize) -> String {
+        let text = format!("{:.*}", places, value);
+        match self.decimal_separator() {
+            '.' => text,
+            separator => text.replace('.', &separator.to_string()),
+        }
+    }
+    /// The UTC date `unix_secs` falls on.
+    pub fn format_date(self, unix_secs: u64) -> String {
+        let (year, month, day) = civil_date(unix_secs);
+        match self {
+            Locale::Iso => format!("{:04}-{:02}-{:02}", year, month, day),
+            Locale::EnUs => format!("{:02}/{:02}/{:04}", month, day, year),
+            Locale::EnGb | Locale::Fr => format!("{:02}/{:02}/{:04}", day, month, year),
+            Locale::De => format!("{:02}.{:02}.{:04}", day, month, year),
+        }
+    }
+    /// The UTC date and time of `unix_secs`, to the minute.
+    pub fn format_date_time(self, unix_secs: u64) -> String {
+        let minutes = unix_secs / 60;
+        format!(
+            "{} {:02}:{:02}",
+            self.format_date(unix_secs),
+            minutes / 60 % 24,
+            minutes % 60
+        )
+    }
+    /// Join `fields` into a CSV row, quoting the ones that would otherwise be split up.
+    pub fn csv_row<S: AsRef<str>>(self, fields: &[S]) -> String {
+        let separator = self.field_separator();
+        let mut row = String::new();
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                row.push(separator);
+            }
+            let field = field.as_ref();
+            if field.contains([separator, '"', '\n', '\r']) {
+                // Writing to a `String` can't fail.
+                let _ = write!(row, "\"{}\"", field.replace('"', "\"\""));
+            } else {
+                row.push_str(field);
+            }
+        }
+        row.push('\n');
+        row
+    }
+}
+
+/// The UTC year, month and day `unix_secs` falls on.
+fn civil_date(unix_secs: u64) -> (i64, i64, i64) {
+    // Howard Hinnant's `civil_from_days`, counting eras of 400 years from 0000-03-01.
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -1,37 +1,170 @@
+use std::time::Duration;
+
 use battlefield::BattlefieldPlugin;
-use bevy::{prelude::*, render::camera::ScalingMode};
+use bevy::{
+    app::{AppExit, ScheduleRunnerPlugin},
+    audio::AudioPlugin,
+    prelude::*,
+    render::{camera::ScalingMode, settings::WgpuSettings, RenderPlugin},
+    window::{ExitCondition, WindowMode, WindowResolution},
+    winit::WinitPlugin,
+};
 use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
+use cli::LaunchOptions;
+use config::{GameConfig, GamePreset};
+use draft::DraftedUpgrades;
 use panel_plugin::PanelPlugin;
+use safe_mode::SafeMode;
+use signing::SigningKey;
 use ui::UIPlugin;
 use utils::{Participant, UtilsPlugin};
 
+mod app_state;
+mod attract;
+mod audio;
 mod battlefield;
+mod cli;
 mod collision_groups;
+mod config;
+mod console;
+mod cosmetics;
 mod debug_utils;
+mod demo;
+mod draft;
+mod effects;
+mod frame_pacing;
+mod game_command;
+mod locale;
+mod mutators;
+mod occlusion;
+mod overlay_feed;
 mod panel_plugin;
+mod platform;
+mod presentation;
+mod safe_mode;
+mod signing;
+mod stats;
+mod stress;
+mod tuning;
 mod ui;
 mod utils;
 
 const WINDOW_TITLE: &str = "Multiply or Release";
+/// Time between updates when running without a window, which would otherwise set the pace.
+const HEADLESS_FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+fn main() -> AppExit {
+    let options = LaunchOptions::parse(std::env::args()).unwrap_or_else(|e| e.exit());
+    if let Some(path) = &options.verify_results {
+        return stats::verify_results(path, &SigningKey::load());
+    }
 
-fn main() {
-    let window_plugin = WindowPlugin {
-        primary_window: Some(Window {
-            title: WINDOW_TITLE.to_string(),
-            mode: bevy::window::WindowMode::BorderlessFullscreen,
+    // Headless runs don't touch the lock file, since they don't draw or play anything that could
+    // crash the game, and any number of them may run side by side.
+    let headless = options.headless;
+    let safe_mode = !headless
+        && options
+            .safe_mode
+            .unwrap_or_else(safe_mode::previous_session_crashed);
+
+    let mut app = App::new();
+    if headless {
+        // Nothing is drawn, so skip creating a renderer too, which lets this run on machines
+        // without a GPU. The particle effects need one, so only their assets are kept around.
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+        )
+        .add_plugins(ScheduleRunnerPlugin::run_loop(HEADLESS_FRAME_TIME))
+        .init_asset::<EffectAsset>();
+    } else {
+        let mode = match options.window_size {
+            Some(_) => WindowMode::Windowed,
+            None => WindowMode::BorderlessFullscreen,
+        };
+        let resolution = options
+            .window_size
+            .map_or_else(WindowResolution::default, |size| size.into());
+        let plugins = DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: WINDOW_TITLE.to_string(),
+                mode,
+                resolution,
+                ..default()
+            }),
+            // Closing the window goes through the session summary first.
+            close_when_requested: false,
             ..default()
-        }),
-        ..default()
-    };
-    App::new()
-        .add_plugins(DefaultPlugins.set(window_plugin))
+        });
+        if safe_mode {
+            // Only the particle effect assets are kept around, like when running headless.
+            app.add_plugins(plugins.disable::<AudioPlugin>())
+                .init_asset::<EffectAsset>();
+        } else {
+            app.add_plugins((plugins, HanabiPlugin));
+        }
+        safe_mode::acquire_lock();
+    }
+    let mut config = GameConfig::from_preset(options.preset);
+    options.override_config(&mut config);
+    app.insert_resource(config)
+        .insert_resource(options.preset)
+        .insert_resource(options)
+        .insert_resource(SafeMode(safe_mode))
+        .insert_resource(SigningKey::load())
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
-        .add_plugins(HanabiPlugin)
+        .register_type::<GameConfig>()
+        .register_type::<GamePreset>()
+        .init_resource::<DraftedUpgrades>()
+        .register_type::<DraftedUpgrades>()
+        .add_plugins((app_state::AppStatePlugin, tuning::TuningPlugin))
         .add_plugins((UtilsPlugin, PanelPlugin, BattlefieldPlugin, UIPlugin))
+        .add_plugins((
+            attract::AttractPlugin,
+            console::ConsolePlugin,
+            demo::DemoPlugin,
+            stats::StatsPlugin,
+            stress::StressPlugin,
+            effects::EffectsPlugin,
+            frame_pacing::FramePacingPlugin,
+            game_command::GameCommandPlugin,
+            mutators::MutatorsPlugin,
+            occlusion::OcclusionPlugin,
+            overlay_feed::OverlayFeedPlugin,
+            presentation::PresentationPlugin,
+            safe_mode::SafeModePlugin,
+        ))
         // .add_plugins(debug_utils::DebugUtilsPlugin)
-        .add_systems(Startup, setup)
-        .run();
+        .add_systems(PreStartup, config::validate_config)
+        .add_systems(
+            Update,
+            config::validate_config
+                .in_set(config::ValidateConfig)
+                .run_if(resource_changed::<GameConfig>),
+        )
+        .add_systems(Startup, setup);
+    if !safe_mode {
+        app.add_plugins((platform::PlatformPlugin, audio::SoundPackPlugin));
+    }
+    let exit = app.run();
+    if !headless {
+        safe_mode::release_lock();
+    }
+    exit
 }
 
 fn setup(mut commands: Commands) {
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    battlefield::RestartEvent,
+    config::{GameConfig, PhysicsConfig},
+    utils::GameRng,
+};
+
+// Constants {{{
+
+/// How many times stronger gravity pulls on the worker balls with [`Mutator::HeavyBalls`].
+const HEAVY_BALLS_GRAVITY_MULTIPLIER: f32 = 2.0;
+
+// }}}
+
+pub struct MutatorsPlugin;
+impl Plugin for MutatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveMutators>()
+            .register_type::<ActiveMutators>()
+            .add_systems(Startup, roll_mutators)
+            .add_systems(Update, roll_mutators.run_if(on_event::<RestartEvent>()));
+    }
+}
+
+/// Chaos rules that shake up a round. Unlike [`crate::config::GameConfig`], these are meant to be
+/// swapped out between rounds rather than tuned once per session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Mutator {
+    /// Landing in a multiply zone boosts a random opponent instead of the marble's own turret.
+    Reverse,
+    /// Gravity pulls twice as hard on the worker balls.
+    HeavyBalls,
+    /// Every bullet hit converts the tile outright, however fortified it is.
+    Piercing,
+    /// The trigger zones are dealt out to the slots in a random order.
+    Shuffle,
+}
+impl Mutator {
+    pub const ALL: [Mutator; 4] = [
+        Mutator::Reverse,
+        Mutator::HeavyBalls,
+        Mutator::Piercing,
+        Mutator::Shuffle,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::Reverse => "Reverse",
+            Mutator::HeavyBalls => "Double Gravity",
+            Mutator::Piercing => "Piercing Bullets",
+            Mutator::Shuffle => "Shuffled Zones",
+        }
+    }
+    /// What the mutator changes, for the rules overlay.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Mutator::Reverse => "Multiply zones boost a random rival instead",
+            Mutator::HeavyBalls => "Worker balls fall twice as hard",
+            Mutator::Piercing => "Bullets break through fortified tiles in one hit",
+            Mutator::Shuffle => "Trigger zones are in a random order",
+        }
+    }
+}
+
+/// The mutators in effect for the current round.
+#[derive(Debug, Clone, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct ActiveMutators(pub Vec<Mutator>);
+impl ActiveMutators {
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+    /// The gravity scale of the worker balls, accounting for [`Mutator::HeavyBalls`].
+    pub fn worker_ball_gravity_scale(&self, physics: &PhysicsConfig) -> f32 {
+        match self.contains(Mutator::HeavyBalls) {
+            true => physics.worker_ball_gravity_scale * HEAVY_BALLS_GRAVITY_MULTIPLIER,
+            false => physics.worker_ball_gravity_scale,
+        }
+    }
+}
+
+/// Roll a fresh set of mutators for the round about to start, replacing whatever was voted in.
+pub fn roll_mutators(
+    mut mutators: ResMut<ActiveMutators>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+) {
+    if !config.mutator_roll.enabled {
+        return;
+    }
+    let max_count = (config.mutator_roll.max_count as usize).clamp(1, Mutator::ALL.len());
+    let count = rng.0.gen_range(1..=max_count);
+    mutators.0 = Mutator::ALL
+        .choose_multiple(&mut rng.0, count)
+        .copied()
+        .collect();
+    info!("Rolled mutators: {:?}", mutators.0);
+}
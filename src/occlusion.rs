@@ -0,0 +1,83 @@
+//! Pausing while the window is out of sight, i.e. minimized or covered up by other windows.
+//!
+//! While nobody can see the window, a round being played is paused, so nothing happens off screen
+//! and nothing piles up to catch up on. Once it's visible again, a [`WindowShownEvent`] is sent for
+//! anything that should start over rather than carry on from before the stall, like the trails, and
+//! the round picks up where it left off if this was what paused it. Set
+//! `performance.pause_when_hidden` to false to keep playing in the background, e.g. when streaming
+//! a window that's covered by the streaming software. It's off by default in attract and
+//! presentation mode, where nobody's looking at the window anyway.
+//!
+//! Losing the window surface or the GPU device, e.g. on a driver reset, isn't handled yet. A surface
+//! that's outdated after a resize or a fullscreen switch is configured again by the renderer
+//! itself, but one that's lost outright, or the device itself, makes the renderer give up with a
+//! panic before the game gets a say, and nothing it set up on the GPU can be started over without
+//! restarting the game. The next launch then comes up in safe mode.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowOccluded},
+};
+
+use crate::{app_state::AppState, config::GameConfig};
+
+pub struct OcclusionPlugin;
+impl Plugin for OcclusionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WindowOcclusion>()
+            .add_event::<WindowShownEvent>()
+            .add_systems(PreUpdate, watch_window);
+    }
+}
+
+/// Event sent when the window is visible again after being out of sight.
+#[derive(Event, Default)]
+pub struct WindowShownEvent;
+
+#[derive(Resource, Default)]
+struct WindowOcclusion {
+    /// Whether the window is covered up, as of the last time it said so.
+    occluded: bool,
+    /// Whether the window is currently out of sight, whether covered up or minimized.
+    hidden: bool,
+    /// Whether the round was paused because of it, so it's only unpaused if it was.
+    paused: bool,
+}
+
+fn watch_window(
+    mut occlusion: ResMut<WindowOcclusion>,
+    mut occlusions: EventReader<WindowOccluded>,
+    mut shown: EventWriter<WindowShownEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    state: Res<State<AppState>>,
+    config: Res<GameConfig>,
+) {
+    let Ok((entity, window)) = windows.get_single() else {
+        return;
+    };
+    for event in occlusions.read() {
+        if event.window == entity {
+            occlusion.occluded = event.occluded;
+        }
+    }
+    let minimized = window.physical_width() == 0 || window.physical_height() == 0;
+    let hidden = occlusion.occluded || minimized;
+    if hidden == occlusion.hidden {
+        return;
+    }
+    occlusion.hidden = hidden;
+    if hidden {
+        info!("The window is out of sight.");
+        if config.performance.pause_when_hidden && *state.get() == AppState::Playing {
+            next_state.set(AppState::Paused);
+            occlusion.paused = true;
+        }
+        return;
+    }
+    info!("The window is visible again.");
+    shown.send(WindowShownEvent);
+    if std::mem::take(&mut occlusion.paused) && *state.get() == AppState::Paused {
+        next_state.set(AppState::Playing);
+    }
+}
@@ -0,0 +1,313 @@
+//! A live feed of the game state for stream overlays, so a browser source in OBS can draw its own
+//! scoreboard in sync with the game.
+//!
+//! When `overlay_feed` is enabled in the game config, the game accepts WebSocket connections on
+//! `ws://localhost:<port>` and sends every client a JSON text message a couple of times a second.
+//! Each message is a [`GameSnapshot`], with the charges, territory and stats of every participant,
+//! along with the participants' names in the same order:
+//!
+//! ```js
+//! const feed = new WebSocket("ws://localhost:9455");
+//! feed.onmessage = (event) => {
+//!     const state = JSON.parse(event.data);
+//!     state.participants.forEach((x, i) => console.log(state.names[i], x.turret_charge, x.tiles));
+//! };
+//! ```
+//!
+//! Only connections from the same machine are accepted. Clients get pongs for their pings and are
+//! let go when they close the connection, but anything else they send after connecting is ignored.
+
+use std::{
+    io::ErrorKind,
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use serde::Serialize;
+use tungstenite::{
+    handshake::{
+        server::{NoCallback, ServerHandshake},
+        HandshakeError, MidHandshake,
+    },
+    protocol::WebSocketConfig,
+    Message, WebSocket,
+};
+
+use crate::{
+    battlefield::GameSnapshot,
+    config::{GameConfig, ValidateConfig},
+    utils::Participant,
+};
+
+// Constants {{{
+
+/// Clients that send a message bigger than this are dropped, since overlays aren't expected to
+/// send anything but pings and closes.
+const MAX_MESSAGE_LEN: usize = 8192;
+/// Clients that fall this many bytes behind are dropped instead of being buffered for forever.
+const MAX_PENDING_OUTPUT: usize = 1 << 16;
+
+// }}}
+
+pub struct OverlayFeedPlugin;
+impl Plugin for OverlayFeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OverlayFeed>().add_systems(
+            Update,
+            (
+                update_listener
+                    .after(ValidateConfig)
+                    .run_if(resource_changed::<GameConfig>),
+                serve_clients,
+                broadcast_state.run_if(|feed: Res<OverlayFeed>| {
+                    feed.timer.just_finished() && feed.clients.iter().any(Client::is_open)
+                }),
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct OverlayFeed {
+    /// The port being listened on, with the listener.
+    listener: Option<(u16, TcpListener)>,
+    clients: Vec<Client>,
+    /// Counts down to the next message.
+    timer: Timer,
+}
+enum Client {
+    /// Waiting for the rest of the handshake request, or for the response to go out.
+    Connecting(MidHandshake<ServerHandshake<TcpStream, NoCallback>>),
+    Open(WebSocket<TcpStream>),
+}
+impl Client {
+    /// Start the handshake with a client that just connected, or `None` if it isn't asking for a
+    /// WebSocket.
+    fn new(stream: TcpStream) -> Option<Self> {
+        let config = WebSocketConfig::default()
+            .write_buffer_size(0)
+            .max_write_buffer_size(MAX_PENDING_OUTPUT)
+            .max_message_size(Some(MAX_MESSAGE_LEN))
+            .max_frame_size(Some(MAX_MESSAGE_LEN));
+        Self::resume(tungstenite::accept_with_config(stream, Some(config)))
+    }
+    fn resume(
+        handshake: Result<
+            WebSocket<TcpStream>,
+            HandshakeError<ServerHandshake<TcpStream, NoCallback>>,
+        >,
+    ) -> Option<Self> {
+        match handshake {
+            Ok(socket) => Some(Self::Open(socket)),
+            Err(HandshakeError::Interrupted(handshake)) => Some(Self::Connecting(handshake)),
+            Err(HandshakeError::Failure(_)) => None,
+        }
+    }
+    fn is_open(&self) -> bool {
+        matches!(self, Self::Open(_))
+    }
+    /// Carry on with the handshake, or read whatever the client sent and send whatever's waiting,
+    /// which answers any pings and closes. Returns the client if it's still connected and keeping
+    /// up.
+    fn poll(self) -> Option<Self> {
+        let mut socket = match self {
+            Self::Connecting(handshake) => match Self::resume(handshake.handshake())? {
+                Self::Open(socket) => socket,
+                client => return Some(client),
+            },
+            Self::Open(socket) => socket,
+        };
+        loop {
+            match socket.read() {
+                // Nothing the overlays send is used, but reading it is what answers them.
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return None,
+            }
+        }
+        match socket.flush() {
+            Ok(()) => Some(Self::Open(socket)),
+            Err(tungstenite::Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {
+                Some(Self::Open(socket))
+            }
+            Err(_) => None,
+        }
+    }
+    /// Queue `message` up to be sent, if the connection is open. Returns whether the client is
+    /// keeping up.
+    fn send(&mut self, message: Message) -> bool {
+        let Self::Open(socket) = self else {
+            return true;
+        };
+        match socket.write(message) {
+            Ok(()) => true,
+            Err(tungstenite::Error::Io(e)) => e.kind() == ErrorKind::WouldBlock,
+            Err(_) => false,
+        }
+    }
+}
+/// What's sent to the overlays.
+#[derive(Serialize)]
+struct OverlayState<'a> {
+    /// The name of each participant, in the same order as `participants`.
+    names: Vec<&'a str>,
+    #[serde(flatten)]
+    snapshot: GameSnapshot,
+}
+
+/// Start or stop listening to match the config, and keep the time between messages up to date.
+fn update_listener(mut feed: ResMut<OverlayFeed>, config: Res<GameConfig>) {
+    let config = &config.overlay_feed;
+    // The config is validated by now, but a rate of zero would still panic here if it slipped by.
+    let interval = Duration::from_secs_f32(1.0 / config.updates_per_sec.max(0.1));
+    if feed.timer.duration() != interval {
+        feed.timer = Timer::new(interval, TimerMode::Repeating);
+    }
+    let port = config.enabled.then_some(config.port);
+    if feed.listener.as_ref().map(|&(port, _)| port) == port {
+        return;
+    }
+    feed.listener = None;
+    feed.clients.clear();
+    let Some(port) = port else {
+        return;
+    };
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).and_then(|listener| {
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    });
+    match listener {
+        Ok(listener) => {
+            info!("Overlay feed listening on ws://localhost:{}.", port);
+            feed.listener = Some((port, listener));
+        }
+        Err(e) => warn!("Failed to start the overlay feed on port {}: {}", port, e),
+    }
+}
+fn serve_clients(mut feed: ResMut<OverlayFeed>, time: Res<Time<Real>>) {
+    let feed = &mut *feed;
+    let Some((_, listener)) = &feed.listener else {
+        return;
+    };
+    feed.timer.tick(time.delta());
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if stream.set_nonblocking(true).is_ok() {
+                    feed.clients.extend(Client::new(stream));
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(e) => {
+                warn!("Failed to accept an overlay feed client: {}", e);
+                break;
+            }
+        }
+    }
+    feed.clients = feed.clients.drain(..).filter_map(Client::poll).collect();
+}
+fn broadcast_state(world: &mut World) {
+    let snapshot = GameSnapshot::capture(world);
+    let config = world.resource::<GameConfig>();
+    let state = OverlayState {
+        names: Participant::ALL
+            .iter()
+            .map(|&x| config.participants[x].name.as_str())
+            .collect(),
+        snapshot,
+    };
+    let json = match serde_json::to_string(&state) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize the overlay feed: {}", e);
+            return;
+        }
+    };
+    let message = Message::text(json);
+    let mut feed = world.resource_mut::<OverlayFeed>();
+    feed.clients = feed
+        .clients
+        .drain(..)
+        .filter_map(|mut client| client.send(message.clone()).then_some(client)?.poll())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        time::Instant,
+    };
+
+    use super::*;
+
+    /// Connect a client that sends `request`, along with the other end of the connection.
+    fn connect(request: &[u8]) -> (TcpStream, Option<Client>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let mut remote = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nonblocking(true).unwrap();
+        remote.write_all(request).unwrap();
+        (remote, Client::new(stream))
+    }
+
+    /// Poll `client` until `done` says so, or fail if it takes too long.
+    fn poll_until(
+        mut client: Option<Client>,
+        done: impl Fn(&Option<Client>) -> bool,
+    ) -> Option<Client> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(&client) {
+            assert!(Instant::now() < deadline, "the client never got there");
+            client = client.and_then(Client::poll);
+        }
+        client
+    }
+
+    #[test]
+    fn pings_are_answered_and_closes_drop_the_client() {
+        // The sample handshake from RFC 6455.
+        let (mut remote, client) = connect(
+            b"GET /chat HTTP/1.1\r\n\
+            Host: server.example.com\r\n\
+            Upgrade: websocket\r\n\
+            Connection: Upgrade\r\n\
+            Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+            Sec-WebSocket-Version: 13\r\n\r\n",
+        );
+        let client = poll_until(client, |x| x.as_ref().is_some_and(Client::is_open));
+
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            let mut byte = [0];
+            remote.read_exact(&mut byte).unwrap();
+            response.push(byte[0]);
+        }
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(response.contains("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+        // A masked ping, and a masked close.
+        remote
+            .write_all(&[0x89, 0x82, 1, 2, 3, 4, b'h' ^ 1, b'i' ^ 2])
+            .unwrap();
+        remote
+            .write_all(&[0x88, 0x82, 0, 0, 0, 0, 0x03, 0xe8])
+            .unwrap();
+        poll_until(client, Option::is_none);
+        let mut received = Vec::new();
+        remote.read_to_end(&mut received).unwrap();
+        assert_eq!(received, [0x8a, 2, b'h', b'i', 0x88, 2, 0x03, 0xe8]);
+    }
+
+    #[test]
+    fn requests_that_arent_websockets_are_dropped() {
+        let (mut remote, client) = connect(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        poll_until(client, Option::is_none);
+        let mut received = Vec::new();
+        remote.read_to_end(&mut received).ok();
+        assert!(!received.starts_with(b"HTTP/1.1 101"));
+    }
+}
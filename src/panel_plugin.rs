@@ -1,9 +1,19 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use crate::{
-    battlefield::{game_is_going, RestartEvent},
+    app_state::AppState,
+    battlefield::{MatchDecidedEvent, RestartEvent, BATTLEFIELD_HALF_WIDTH},
+    cli::LaunchOptions,
     collision_groups::{self, PANEL_OBSTACLES, PANEL_TRIGGER_ZONES},
-    utils::{EffectPropertiesExt, ParticipantMap, TileColor, TrailEffect, TRAIL_LIFETIME},
+    config::{GameConfig, GamePreset, PhysicsConfig, ValidateConfig},
+    draft::{DraftedUpgrades, Upgrade},
+    mutators::{roll_mutators, ActiveMutators, Mutator},
+    stats::PersistentStats,
+    stress::{STRESS_PANEL_SCALE, STRESS_WORKER_BALL_COUNT_MAX, STRESS_WORKER_BALL_SPAWN_SECS},
+    utils::{
+        BallColor, DisplayName, EffectPropertiesExt, GameAssets, GameRng, MatchId, Matches,
+        ParticipantMap, PerMatch, Roster, RoundScoped, TrailColor, TRAIL_LIFETIME,
+    },
     Participant,
 };
 use bevy::{
@@ -15,11 +25,20 @@ use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::{
     distributions::{DistIter, Distribution, Uniform},
-    rngs::ThreadRng,
-    thread_rng, Rng,
+    seq::SliceRandom,
+    Rng,
 };
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, time::Duration};
 
+mod freeze;
+mod layout;
+mod markings;
+mod shake;
+
+pub use layout::{contain_divider, contain_peg, PanelLayout, PanelLayouts, PANEL_PART_COUNT_MAX};
+
 // Constants {{{
 
 // Configurable
@@ -30,30 +49,36 @@ const RIGHT_ROOT_X: f32 = 500.0;
 const WALL_THICKNESS: f32 = 10.0;
 const WALL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const ARENA_COLOR: Color = Color::Srgba(css::DARK_SLATE_GRAY);
-const ARENA_HEIGHT: f32 = 700.0;
-const ARENA_WIDTH: f32 = 260.0;
+pub const ARENA_HEIGHT: f32 = 700.0;
+pub const ARENA_WIDTH: f32 = 260.0;
 
-const TRIGGER_ZONE_Y: f32 = -250.0;
+pub const TRIGGER_ZONE_Y: f32 = -250.0;
 const TRIGGER_ZONE_HEIGHT: f32 = 40.0;
-/// The color of the center trigger zone.
+/// The color of the x4 and bigger multiply zones.
 const TRIGGER_ZONE_COLOR_0: Color = Color::Srgba(css::ALICE_BLUE);
-/// The color of the trigger zones to the left and right of center.
+/// The color of the smaller multiply zones.
 const TRIGGER_ZONE_COLOR_1: Color = Color::Srgba(css::LIGHT_PINK);
-/// The color of the outer trigger zones.
+/// The color of the release zones.
 const TRIGGER_ZONE_COLOR_2: Color = Color::Srgba(css::LIGHT_SKY_BLUE);
+const TRIGGER_ZONE_GAMBLE_COLOR: Color = Color::Srgba(css::GOLD);
+const TRIGGER_ZONE_SWAP_COLOR: Color = Color::Srgba(css::PLUM);
+const TRIGGER_ZONE_FREEZE_COLOR: Color = Color::Srgba(css::PALE_TURQUOISE);
 const TRIGGER_ZONE_TEXT_COLOR: Color = Color::BLACK;
 const TRIGGER_ZONE_TEXT_SIZE: f32 = 12.0;
+/// Distance from the center of the trigger zones down to the names of the panel's participants.
+const PANEL_NAMES_OFFSET_Y: f32 = 50.0;
+const PANEL_NAMES_TEXT_SIZE: f32 = 16.0;
 
-const CIRCLE_RADIUS: f32 = 10.0;
-const CIRCLE_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+pub const CIRCLE_RADIUS: f32 = 10.0;
+pub const CIRCLE_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const CIRCLE_PYRAMID_VERTICAL_OFFSET: f32 = 250.0;
 const CIRCLE_PYRAMID_VERTICAL_COUNT: usize = 5;
 const CIRCLE_PYRAMID_VERTICAL_GAP: f32 = 8.0;
 const CIRCLE_PYRAMID_HORIZONTAL_GAP: f32 = 45.0;
 
-const TRIGGER_ZONE_DIVIDER_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+pub const TRIGGER_ZONE_DIVIDER_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const TRIGGER_ZONE_DIVIDER_HEIGHT_OFFSET: f32 = 2.5;
-const TRIGGER_ZONE_DIVIDER_RADIUS: f32 = 2.5;
+pub const TRIGGER_ZONE_DIVIDER_RADIUS: f32 = 2.5;
 
 const CIRCLE_GRID_VERTICAL_OFFSET: f32 = 70.0;
 const CIRCLE_GRID_VERTICAL_COUNT: usize = 8;
@@ -64,10 +89,7 @@ const CIRCLE_GRID_HORIZONTAL_HALF_COUNT_ODD_ROW: usize = 3;
 
 pub const WORKER_BALL_RADIUS: f32 = 5.0;
 const WORKER_BALL_SPAWN_Y: f32 = 320.0;
-const WORKER_BALL_RESTITUTION_COEFFICIENT: f32 = 0.5;
-const WORKER_BALL_SPAWN_TIMER_SECS: f32 = 10.0;
 pub const WORKER_BALL_COUNT_MAX: usize = 6;
-const WORKER_BALL_GRAVITY_SCALE: f32 = 15.0;
 
 // Z-index
 const WALL_Z: f32 = -4.0;
@@ -91,47 +113,162 @@ const CIRCLE_DIAMETER: f32 = CIRCLE_RADIUS * 2.0;
 
 const WORKER_BALL_DIAMETER: f32 = WORKER_BALL_RADIUS * 2.0;
 
+pub const TRIGGER_ZONE_DIVIDER_LENGTH: f32 =
+    TRIGGER_ZONE_DIVIDER_HEIGHT_OFFSET + TRIGGER_ZONE_HEIGHT;
+
+/// The number of trigger zones at the bottom of each panel.
+pub const TRIGGER_ZONE_COUNT: usize = 5;
+/// The y coordinate of the bottom of the lowest row of pegs.
+const CIRCLE_GRID_BOTTOM_Y: f32 = CIRCLE_GRID_VERTICAL_OFFSET
+    - (CIRCLE_GRID_VERTICAL_COUNT - 1) as f32 * (CIRCLE_DIAMETER + CIRCLE_GRID_VERTICAL_GAP)
+    - CIRCLE_RADIUS;
+/// The tallest the trigger zones can be while staying inside the arena and clear of the pegs.
+pub const TRIGGER_ZONE_HEIGHT_MAX: f32 =
+    2.0 * (CIRCLE_GRID_BOTTOM_Y - TRIGGER_ZONE_Y).min(TRIGGER_ZONE_Y + ARENA_HEIGHT_FRAC_2);
+
+// Checks
+
+/// Whether a worker ball fits between two pegs whose centers are `dx` and `dy` apart.
+const fn worker_ball_fits_between(dx: f32, dy: f32) -> bool {
+    let min_distance = CIRCLE_DIAMETER + WORKER_BALL_DIAMETER;
+    dx * dx + dy * dy > min_distance * min_distance
+}
+
+// A mistake in the layout above would leave worker balls stuck between the pegs or outside the
+// arena, so it's caught at compile time rather than discovered mid-game.
+const _: () = {
+    assert!(
+        ARENA_WIDTH % TRIGGER_ZONE_COUNT as f32 == 0.0,
+        "ARENA_WIDTH has to be divisible by TRIGGER_ZONE_COUNT so the trigger zones line up",
+    );
+    assert!(
+        CIRCLE_PYRAMID_HORIZONTAL_GAP > WORKER_BALL_DIAMETER,
+        "CIRCLE_PYRAMID_HORIZONTAL_GAP has to be wider than a worker ball",
+    );
+    assert!(
+        CIRCLE_GRID_HORIZONTAL_GAP > WORKER_BALL_DIAMETER,
+        "CIRCLE_GRID_HORIZONTAL_GAP has to be wider than a worker ball",
+    );
+    // Odd rows are shifted sideways, so the tightest gaps are between neighboring rows.
+    let odd_row_x = CIRCLE_HALF_GAP + CIRCLE_RADIUS;
+    assert!(
+        worker_ball_fits_between(odd_row_x, CIRCLE_DIAMETER + CIRCLE_PYRAMID_VERTICAL_GAP),
+        "the rows of the peg pyramid are too close together for a worker ball to pass",
+    );
+    assert!(
+        worker_ball_fits_between(
+            odd_row_x.min(CIRCLE_DIAMETER + CIRCLE_GRID_HORIZONTAL_GAP - odd_row_x),
+            CIRCLE_DIAMETER + CIRCLE_GRID_VERTICAL_GAP,
+        ),
+        "the rows of the peg grid are too close together for a worker ball to pass",
+    );
+    assert!(
+        WORKER_BALL_SPAWN_Y + WORKER_BALL_RADIUS < ARENA_HEIGHT_FRAC_2,
+        "WORKER_BALL_SPAWN_Y has to be inside the arena",
+    );
+    assert!(
+        WORKER_BALL_SPAWN_Y - WORKER_BALL_RADIUS > CIRCLE_PYRAMID_VERTICAL_OFFSET + CIRCLE_RADIUS,
+        "WORKER_BALL_SPAWN_Y has to be above the peg pyramid",
+    );
+    assert!(
+        TRIGGER_ZONE_HEIGHT <= TRIGGER_ZONE_HEIGHT_MAX,
+        "TRIGGER_ZONE_HEIGHT has to leave the trigger zones clear of the pegs",
+    );
+};
+
 // Messages
 
 const EXPECT_EACH_PANEL_SIDE_EXIST_MSG: &str =
     "There should be exactly one `PanelRootSide::Left` and one `PanelRootSide::Right`.";
-const EXPECT_TWO_PANELS_MSG: &str = "There should be exactly two entities with `PanelRoot`.";
 
 // }}}
 
 pub struct PanelPlugin;
 impl Plugin for PanelPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<TriggerEvent>()
-            .add_systems(Startup, setup)
-            .add_systems(
-                Update,
-                spawn_workers.run_if(game_is_going.and_then(spawn_workers_condition)),
-            )
-            .add_systems(Update, ball_reset.run_if(game_is_going))
-            .add_systems(
-                Update,
-                trigger_event
-                    .run_if(on_event::<CollisionEvent>().or_else(on_event::<RestartEvent>())),
-            )
-            .add_systems(
-                Update,
-                update_workers_particle_position.before(spawn_workers),
+        app.add_plugins((
+            freeze::FreezePlugin,
+            markings::MarkingsPlugin,
+            shake::ShakePlugin,
+        ))
+        .add_event::<TriggerEvent>()
+        .add_event::<SpawnWorkerBallEvent>()
+        .add_systems(Startup, setup)
+        .add_systems(PostStartup, apply_trigger_zone_layout)
+        .add_systems(
+            Update,
+            spawn_workers.run_if(in_state(AppState::Playing).and_then(spawn_workers_condition)),
+        )
+        .add_systems(
+            Update,
+            spawn_drafted_balls
+                .after(spawn_workers)
+                .run_if(in_state(AppState::Playing)),
+        )
+        .add_systems(
+            Update,
+            spawn_requested_balls.run_if(on_event::<SpawnWorkerBallEvent>()),
+        )
+        .add_systems(Update, ball_reset.run_if(in_state(AppState::Playing)))
+        .add_systems(Update, settle_match.run_if(on_event::<MatchDecidedEvent>()))
+        .add_systems(
+            Update,
+            trigger_event.run_if(on_event::<CollisionEvent>().or_else(on_event::<RestartEvent>())),
+        )
+        .add_systems(
+            Update,
+            update_workers_particle_position.before(spawn_workers),
+        )
+        .add_systems(
+            Update,
+            update_trigger_zone_text.run_if(resource_changed::<ActiveMutators>),
+        )
+        .add_systems(
+            Update,
+            apply_physics_config
+                .after(ValidateConfig)
+                .run_if(resource_changed::<GameConfig>.or_else(resource_changed::<ActiveMutators>)),
+        )
+        .add_systems(
+            Update,
+            (
+                restart,
+                // After the panels of any match that wasn't running are set up.
+                apply_panel_layouts.after(restart),
+                apply_trigger_zone_layout
+                    .before(trigger_event)
+                    .after(roll_mutators)
+                    .after(restart),
             )
-            .add_systems(Update, restart.run_if(on_event::<RestartEvent>()));
+                .run_if(on_event::<RestartEvent>()),
+        );
     }
 }
 
-#[derive(Debug, Event)]
+#[derive(Debug, Clone, Event)]
 pub struct TriggerEvent {
     pub participant: Participant,
     pub trigger_type: TriggerType,
+    /// The worker ball that landed in the trigger zone, if the trigger came from the panels.
+    pub ball: Option<Entity>,
+}
+/// Event to drop an extra worker ball into a panel, outside of the regular waves.
+#[derive(Debug, Clone, Event)]
+pub struct SpawnWorkerBallEvent {
+    pub participant: Participant,
+    pub side: PanelRootSide,
 }
-#[derive(Debug, Component, Clone, Copy)]
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
 pub enum TriggerType {
     Multiply(u8),
     BurstShot,
     ChargedShot,
+    /// Multiplies or divides the charge depending on a coin flip.
+    Gamble,
+    /// Swaps the charge with a random opponent's.
+    Swap,
+    /// Freezes the worker balls on the opposite panel for a few seconds.
+    Freeze,
 }
 impl std::fmt::Display for TriggerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -139,23 +276,72 @@ impl std::fmt::Display for TriggerType {
             Self::Multiply(factor) => write!(f, "x{}", factor),
             Self::BurstShot => write!(f, "Release\nBurst\nShots"),
             Self::ChargedShot => write!(f, "Release\nChanged\nShots"),
+            Self::Gamble => write!(f, "Gamble"),
+            Self::Swap => write!(f, "Swap\nCharge"),
+            Self::Freeze => write!(f, "Freeze\nRivals"),
         }
     }
 }
-
-#[derive(Bundle, Clone, Resource)]
-struct TriggerZoneDividerBundle {
-    // {{{
-    matmesh: MaterialMesh2dBundle<ColorMaterial>,
-    collider: Collider,
-    collision_groups: CollisionGroups,
-    rigidbody: RigidBody,
+impl TriggerType {
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Multiply(factor) if *factor >= 4 => TRIGGER_ZONE_COLOR_0,
+            Self::Multiply(_) => TRIGGER_ZONE_COLOR_1,
+            Self::BurstShot | Self::ChargedShot => TRIGGER_ZONE_COLOR_2,
+            Self::Gamble => TRIGGER_ZONE_GAMBLE_COLOR,
+            Self::Swap => TRIGGER_ZONE_SWAP_COLOR,
+            Self::Freeze => TRIGGER_ZONE_FREEZE_COLOR,
+        }
+    }
+    /// The text displayed on the trigger zone, accounting for mutators that change what it does.
+    fn label(&self, mutators: &ActiveMutators) -> String {
+        match self {
+            Self::Multiply(_) if mutators.contains(Mutator::Reverse) => {
+                format!("{}\nto Rival", self)
+            }
+            _ => self.to_string(),
+        }
+    }
+    /// What landing in the trigger zone does, for the rules overlay.
+    pub fn describe(&self, config: &GameConfig, mutators: &ActiveMutators) -> String {
+        match self {
+            Self::Multiply(factor) if mutators.contains(Mutator::Reverse) => {
+                format!("Multiplies a random rival's charge by {}", factor)
+            }
+            Self::Multiply(factor) => format!("Multiplies the turret's charge by {}", factor),
+            Self::BurstShot => "Releases the charge as a burst of small shots".to_string(),
+            Self::ChargedShot => "Releases the charge as one big shot".to_string(),
+            Self::Gamble => format!(
+                "{:.0}% chance to multiply the charge by {}, otherwise divides it by {}",
+                config.gamble.win_chance * 100.0,
+                config.gamble.win_factor,
+                config.gamble.loss_divisor
+            ),
+            Self::Swap => "Swaps the charge with a random rival's".to_string(),
+            Self::Freeze => format!(
+                "Freezes the opposite panel for {:.0} seconds",
+                config.freeze.duration_secs
+            ),
+        }
+    }
 }
+/// Marker for the text on top of a trigger zone.
+#[derive(Component, Clone, Copy)]
+struct TriggerZoneText(TriggerType);
+/// Which of the trigger zones in a panel an entity belongs to, counting from the left, so the
+/// layout can be swapped out on restart.
+#[derive(Component, Clone, Copy)]
+struct TriggerZoneSlot(usize);
+
+/// Marker for the sprite showing a trigger zone, which is kept apart from the zone's collider so it
+/// can be shaken along with the rest of the panel's visuals.
+#[derive(Component, Clone, Copy)]
+struct TriggerZoneSprite;
 
 #[derive(Bundle, Clone, Resource)]
 struct TriggerZoneBundle {
     // {{{
-    sprite_bundle: SpriteBundle,
+    transform: TransformBundle,
     collider: Collider,
     collision_groups: CollisionGroups,
     trigger_type: TriggerType,
@@ -163,17 +349,9 @@ struct TriggerZoneBundle {
     name: Name,
 }
 impl TriggerZoneBundle {
-    fn new(trigger_type: TriggerType, size: Vec2, translation: Vec3, color: Color) -> Self {
+    fn new(trigger_type: TriggerType, transform: Transform) -> Self {
         Self {
-            sprite_bundle: SpriteBundle {
-                sprite: Sprite { color, ..default() },
-                transform: Transform {
-                    translation,
-                    scale: size.extend(1.0),
-                    rotation: Quat::IDENTITY,
-                },
-                ..default()
-            },
+            transform: TransformBundle::from_transform(transform),
             name: Name::new(format!("Trigger Zone: {}", trigger_type)),
             collider: Collider::cuboid(0.5, 0.5),
             collision_groups: CollisionGroups::new(
@@ -200,7 +378,7 @@ struct WorkerBallTrailBundle {
 impl WorkerBallTrailBundle {
     fn new(
         target: Entity,
-        target_x: f32,
+        target_position: Vec2,
         color: impl Into<LinearRgba>,
         effect: Handle<EffectAsset>,
     ) -> Self {
@@ -209,7 +387,7 @@ impl WorkerBallTrailBundle {
             peb: ParticleEffectBundle {
                 effect: ParticleEffect::new(effect),
                 effect_properties: EffectProperties::from_spawn_color(color)
-                    .with_position(target_x, WORKER_BALL_SPAWN_Y),
+                    .with_position(target_position.x, target_position.y),
                 ..default()
             },
             name: Name::new("Worker Ball Trail"),
@@ -219,29 +397,40 @@ impl WorkerBallTrailBundle {
 #[derive(Component, Clone, Copy, Default)]
 /// Marker to mark this entity as a worker ball.
 struct WorkerBall;
-#[derive(Resource, Clone, Default)]
+/// Which of its participant's worker balls this is, counting from 1 in the order they spawned.
+#[derive(Component, Clone, Copy, Default)]
+pub struct WorkerBallNumber(pub usize);
+/// Drops the waves of worker balls for one match, kept for each match in a [`PerMatch`].
+#[derive(Clone, Default)]
 struct WorkerBallSpawner {
-    mesh: Mesh2dHandle,
     timer: Timer,
     counter: usize,
+    /// The number of balls each participant gets.
+    max_count: usize,
 }
 impl WorkerBallSpawner {
-    fn new(mesh: Mesh2dHandle) -> Self {
-        let mut timer = Timer::from_seconds(WORKER_BALL_SPAWN_TIMER_SECS, TimerMode::Repeating);
-        timer.tick(Duration::from_secs_f32(
-            WORKER_BALL_SPAWN_TIMER_SECS - TRAIL_LIFETIME,
-        ));
-        Self {
-            mesh,
+    fn new(preset: GamePreset, options: &LaunchOptions) -> Self {
+        let (secs, max_count) = match options.stress {
+            true => (STRESS_WORKER_BALL_SPAWN_SECS, STRESS_WORKER_BALL_COUNT_MAX),
+            false => (preset.worker_ball_spawn_secs(), WORKER_BALL_COUNT_MAX),
+        };
+        let timer = Timer::from_seconds(secs, TimerMode::Repeating);
+        let mut spawner = Self {
             timer,
             counter: 0,
-        }
+            max_count,
+        };
+        spawner.reset();
+        spawner
     }
     fn reset(&mut self) {
         self.timer.reset();
-        self.timer.tick(Duration::from_secs_f32(
-            WORKER_BALL_SPAWN_TIMER_SECS - TRAIL_LIFETIME,
-        ));
+        // Start the trail heading to the spawn point just in time for the first ball.
+        let head_start = self
+            .timer
+            .duration()
+            .saturating_sub(Duration::from_secs_f32(TRAIL_LIFETIME));
+        self.timer.tick(head_start);
         self.counter = 0;
     }
 }
@@ -249,6 +438,9 @@ impl WorkerBallSpawner {
 struct WorkerBallBundle {
     // {{{
     marker: WorkerBall,
+    round_scoped: RoundScoped,
+    match_id: MatchId,
+    number: WorkerBallNumber,
     participant: Participant,
     matmesh: MaterialMesh2dBundle<ColorMaterial>,
     collider: Collider,
@@ -261,14 +453,21 @@ struct WorkerBallBundle {
 }
 impl WorkerBallBundle {
     fn new(
+        match_id: MatchId,
         participant: Participant,
+        number: usize,
         x: f32,
         mesh: Mesh2dHandle,
         material: Handle<ColorMaterial>,
+        physics: &PhysicsConfig,
+        mutators: &ActiveMutators,
     ) -> Self {
         Self {
             name: Name::new("Worker Ball"),
             marker: WorkerBall,
+            round_scoped: RoundScoped,
+            match_id,
+            number: WorkerBallNumber(number),
             participant,
             matmesh: MaterialMesh2dBundle {
                 material,
@@ -282,39 +481,80 @@ impl WorkerBallBundle {
                 collision_groups::PANEL_BALLS | PANEL_OBSTACLES | PANEL_TRIGGER_ZONES,
             ),
             restitution: Restitution {
-                coefficient: WORKER_BALL_RESTITUTION_COEFFICIENT,
+                coefficient: physics.worker_ball_restitution,
                 combine_rule: CoefficientCombineRule::Max,
             },
             rigidbody: RigidBody::Dynamic,
             velocity: Velocity::zero(),
-            gravity: GravityScale(WORKER_BALL_GRAVITY_SCALE),
+            gravity: GravityScale(mutators.worker_ball_gravity_scale(physics)),
         }
     }
     // }}}
 }
-#[derive(Clone, Copy, Component, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Component, PartialEq, Eq)]
 pub enum PanelRootSide {
     Left,
     Right,
 }
 impl PanelRootSide {
-    fn for_participant(p: Participant) -> Self {
-        match p {
-            Participant::A | Participant::B => Self::Left,
-            Participant::C | Participant::D => Self::Right,
+    /// The panel `p`'s worker balls drop in. The participants in `roster` are split between the
+    /// panels in order, with the left one taking the extra participant if there's an odd number.
+    pub fn for_participant(p: Participant, roster: &Roster) -> Self {
+        let before = Participant::ALL
+            .into_iter()
+            .take_while(|&x| x != p)
+            .filter(|&x| roster[x])
+            .count();
+        if before < (roster.count() as usize).div_ceil(2) {
+            Self::Left
+        } else {
+            Self::Right
+        }
+    }
+    /// The match `p` plays in, and which of that match's panels `p`'s worker balls drop in.
+    fn for_participant_in(p: Participant, roster: &Roster, matches: &Matches) -> (MatchId, Self) {
+        let match_id = matches[p];
+        (
+            match_id,
+            Self::for_participant(p, &matches.roster(roster, match_id)),
+        )
+    }
+    /// The participants in `roster` whose worker balls drop in this panel.
+    fn participants(self, roster: &Roster) -> Vec<Participant> {
+        Participant::ALL
+            .into_iter()
+            .filter(|&p| roster[p] && Self::for_participant(p, roster) == self)
+            .collect()
+    }
+    fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
         }
     }
 }
 #[derive(Component, Clone, Copy)]
-pub struct PanelRoot(PanelRootSide);
+pub struct PanelRoot(pub PanelRootSide);
+/// The names of the participants whose worker balls drop in a panel, shown under its trigger zones.
+#[derive(Component, Clone, Copy)]
+struct PanelNames(PanelRootSide);
+/// Parent of the parts of a panel that are only for show, so they can be moved around without
+/// disturbing the colliders under the [`PanelRoot`].
+#[derive(Component, Clone, Copy)]
+struct PanelVisuals;
+/// Marker for the pegs and dividers spawned from a [`PanelLayout`], both their colliders and their
+/// meshes, so they can be swapped out for another layout.
+#[derive(Component, Clone, Copy)]
+struct PanelLayoutPart;
 #[derive(Bundle)]
 /// Component bundle for the round obstacles in the side panels and the walls.
 /// (I don't know if meshes and colliders have to be continous. Maybe we can just make a single
 /// entity for the entire obstacle course.)
+///
+/// The mesh is spawned separately, under the panel's [`PanelVisuals`].
 struct ObstacleBundle {
     // {{{
-    /// Bevy rendering component used to display the ball.
-    matmesh: MaterialMesh2dBundle<ColorMaterial>,
+    transform: TransformBundle,
     /// Rapier collider component.
     collider: Collider,
     collision_groups: CollisionGroups,
@@ -350,8 +590,8 @@ impl ObstacleBundleBuilder {
         self.material = Some(material);
         self
     }
-    fn mesh(mut self, mesh: Handle<Mesh>) -> Self {
-        self.mesh = Some(mesh.into());
+    fn mesh(mut self, mesh: Mesh2dHandle) -> Self {
+        self.mesh = Some(mesh);
         self
     }
     fn collider(mut self, collider: Collider) -> Self {
@@ -362,7 +602,7 @@ impl ObstacleBundleBuilder {
         self.name = Some(Name::new(name));
         self
     }
-    fn build(self) -> Option<ObstacleBundle> {
+    fn build(self) -> Option<(ObstacleBundle, MaterialMesh2dBundle<ColorMaterial>)> {
         let ObstacleBundleBuilder {
             translation: Vec3 { x, y, z },
             material: Some(material),
@@ -373,13 +613,15 @@ impl ObstacleBundleBuilder {
         else {
             return None;
         };
-        Some(ObstacleBundle {
-            matmesh: MaterialMesh2dBundle {
-                mesh,
-                material,
-                transform: Transform::from_xyz(x, y, z),
-                ..default()
-            },
+        let transform = Transform::from_xyz(x, y, z);
+        let matmesh = MaterialMesh2dBundle {
+            mesh,
+            material,
+            transform,
+            ..default()
+        };
+        let obstacle = ObstacleBundle {
+            transform: TransformBundle::from_transform(transform),
             collider,
             collision_groups: CollisionGroups::new(
                 collision_groups::PANEL_OBSTACLES,
@@ -387,28 +629,86 @@ impl ObstacleBundleBuilder {
             ),
             rigidbody: RigidBody::Fixed,
             name,
-        })
+        };
+        Some((obstacle, matmesh))
     }
     /// Build trust me bro.
-    fn buildtmb(self) -> ObstacleBundle {
+    fn buildtmb(self) -> (ObstacleBundle, MaterialMesh2dBundle<ColorMaterial>) {
         self.build().unwrap()
     }
+    /// Spawn the obstacle's collider under the panel root and its mesh under the panel visuals.
+    fn spawn(self, commands: &mut Commands, root: Entity, visuals: Entity) {
+        let (obstacle, matmesh) = self.buildtmb();
+        let name = obstacle.name.clone();
+        commands.spawn((obstacle, PanelLayoutPart)).set_parent(root);
+        commands
+            .spawn((matmesh, name, PanelLayoutPart))
+            .set_parent(visuals);
+    }
     // }}}
 }
 
 fn setup(
     mut commands: Commands,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<GameAssets>,
+    mutators: Res<ActiveMutators>,
+    config: Res<GameConfig>,
+    preset: Res<GamePreset>,
+    names: Res<ParticipantMap<DisplayName>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    roster: Res<Roster>,
+    matches: Res<Matches>,
+    options: Res<LaunchOptions>,
+) {
+    let mut spawners = PerMatch::default();
+    for match_id in matches.running(&roster) {
+        spawners.insert(match_id, WorkerBallSpawner::new(*preset, &options));
+    }
+    commands.insert_resource(spawners);
+    let layouts = PanelLayouts::for_config(&config);
+    // Each match gets a pair of panels of its own, either side of its battlefield.
+    for match_id in matches.running(&roster) {
+        spawn_panels(
+            &mut commands,
+            match_id,
+            &matches.roster(&roster, match_id),
+            &options,
+            &layouts,
+            (&assets, &mutators, &config),
+            (&names, &ball_colors),
+        );
+    }
+    commands.insert_resource(layouts);
+}
+/// Spawn the pair of panels for `match_id`, either side of its battlefield, where the worker balls
+/// of the participants in `roster` drop.
+fn spawn_panels(
+    commands: &mut Commands,
+    match_id: MatchId,
+    roster: &Roster,
+    options: &LaunchOptions,
+    layouts: &PanelLayouts,
+    (assets, mutators, config): (&GameAssets, &ActiveMutators, &GameConfig),
+    (names, ball_colors): (&ParticipantMap<DisplayName>, &ParticipantMap<BallColor>),
 ) {
-    commands.insert_resource(WorkerBallSpawner::new(Mesh2dHandle(
-        meshes.add(Circle::new(WORKER_BALL_RADIUS)),
-    )));
+    // The stress test scales up the panels, moving them out to keep clear of the battlefield.
+    let (root_scale, root_offset) = match options.stress {
+        true => (
+            STRESS_PANEL_SCALE,
+            BATTLEFIELD_HALF_WIDTH + WALL_WIDTH / 2.0 * STRESS_PANEL_SCALE + WALL_THICKNESS,
+        ),
+        false => (1.0, RIGHT_ROOT_X),
+    };
+    let root_transform = |x: f32| {
+        Transform::from_translation((match_id.world_offset() + Vec2::new(x, 0.0)).extend(0.0))
+            .with_scale(Vec3::splat(root_scale))
+    };
     let left_root = commands
         .spawn((
             Name::new("Left Panel Root"),
             PanelRoot(PanelRootSide::Left),
-            SpatialBundle::from_transform(Transform::from_xyz(LEFT_ROOT_X, 0.0, 0.0)),
+            match_id,
+            SpatialBundle::from_transform(root_transform(-root_offset)),
             RigidBody::Fixed,
             CollisionGroups::new(
                 collision_groups::PANEL_OBSTACLES,
@@ -430,7 +730,8 @@ fn setup(
         .spawn((
             Name::new("Right Panel Root"),
             PanelRoot(PanelRootSide::Right),
-            SpatialBundle::from_transform(Transform::from_xyz(RIGHT_ROOT_X, 0.0, 0.0)),
+            match_id,
+            SpatialBundle::from_transform(root_transform(root_offset)),
             RigidBody::Fixed,
             CollisionGroups::new(
                 collision_groups::PANEL_OBSTACLES,
@@ -448,148 +749,52 @@ fn setup(
             ),
         ))
         .id();
-    let circle_builder = ObstacleBundleBuilder::new()
-        .name("Circle Obstacle")
-        .z(CIRCLE_Z)
-        .material(materials.add(CIRCLE_COLOR))
-        .mesh(meshes.add(Circle::new(CIRCLE_RADIUS)))
-        .collider(Collider::ball(CIRCLE_RADIUS));
-
-    const LENGTH: f32 = TRIGGER_ZONE_DIVIDER_HEIGHT_OFFSET + TRIGGER_ZONE_HEIGHT;
-    let divider_builder = ObstacleBundleBuilder::new()
-        .name("Trigger Zone Divider")
-        .z(TRIGGER_ZONE_DIVIDER_Z)
-        .material(materials.add(TRIGGER_ZONE_DIVIDER_COLOR))
-        .mesh(meshes.add(Capsule2d::new(TRIGGER_ZONE_DIVIDER_RADIUS, LENGTH)))
-        .collider(Collider::capsule_y(
-            LENGTH / 2.0,
-            TRIGGER_ZONE_DIVIDER_RADIUS,
-        ));
-
-    let mut f = |root: Entity| {
-        for i in 0..CIRCLE_PYRAMID_VERTICAL_COUNT {
-            let y = -(i as f32) * (CIRCLE_DIAMETER + CIRCLE_PYRAMID_VERTICAL_GAP)
-                + CIRCLE_PYRAMID_VERTICAL_OFFSET;
-            if i % 2 == 0 {
-                commands
-                    .spawn(circle_builder.clone().xy(0.0, y).buildtmb())
-                    .set_parent(root);
-
-                for j in 1..=i / 2 {
-                    let x = j as f32 * (CIRCLE_DIAMETER + CIRCLE_PYRAMID_HORIZONTAL_GAP);
-                    commands
-                        .spawn(circle_builder.clone().xy(x, y).buildtmb())
-                        .set_parent(root);
-                    commands
-                        .spawn(circle_builder.clone().xy(-x, y).buildtmb())
-                        .set_parent(root);
-                }
-            } else {
-                let x0 = CIRCLE_HALF_GAP + CIRCLE_RADIUS;
-                commands
-                    .spawn(circle_builder.clone().xy(x0, y).buildtmb())
-                    .set_parent(root);
-                commands
-                    .spawn(circle_builder.clone().xy(-x0, y).buildtmb())
-                    .set_parent(root);
-                for j in 1..(i / 2) + 1 {
-                    let x = j as f32 * (CIRCLE_DIAMETER + CIRCLE_PYRAMID_HORIZONTAL_GAP) + x0;
-                    commands
-                        .spawn(circle_builder.clone().xy(x, y).buildtmb())
-                        .set_parent(root);
-                    commands
-                        .spawn(circle_builder.clone().xy(-x, y).buildtmb())
-                        .set_parent(root);
-                }
-            }
-        }
-
-        for i in 0..CIRCLE_GRID_VERTICAL_COUNT {
-            let y = -(i as f32) * (CIRCLE_DIAMETER + CIRCLE_GRID_VERTICAL_GAP)
-                + CIRCLE_GRID_VERTICAL_OFFSET;
-            if i % 2 == 0 {
-                commands
-                    .spawn(circle_builder.clone().xy(0.0, y).buildtmb())
-                    .set_parent(root);
-
-                for j in 1..=CIRCLE_GRID_HORIZONTAL_HALF_COUNT_EVEN_ROW {
-                    let x = j as f32 * (CIRCLE_DIAMETER + CIRCLE_GRID_HORIZONTAL_GAP);
-                    commands
-                        .spawn(circle_builder.clone().xy(x, y).buildtmb())
-                        .set_parent(root);
-                    commands
-                        .spawn(circle_builder.clone().xy(-x, y).buildtmb())
-                        .set_parent(root);
-                }
-            } else {
-                let x0 = CIRCLE_HALF_GAP + CIRCLE_RADIUS;
-                commands
-                    .spawn(circle_builder.clone().xy(x0, y).buildtmb())
-                    .set_parent(root);
-                commands
-                    .spawn(circle_builder.clone().xy(-x0, y).buildtmb())
-                    .set_parent(root);
-                for j in 1..CIRCLE_GRID_HORIZONTAL_HALF_COUNT_ODD_ROW {
-                    let x = j as f32 * (CIRCLE_DIAMETER + CIRCLE_GRID_HORIZONTAL_GAP) + x0;
-                    commands
-                        .spawn(circle_builder.clone().xy(x, y).buildtmb())
-                        .set_parent(root);
-                    commands
-                        .spawn(circle_builder.clone().xy(-x, y).buildtmb())
-                        .set_parent(root);
-                }
-            }
-        }
-
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(-ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(-ARENA_WIDTH_FRAC_5 - ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(ARENA_WIDTH_FRAC_5 + ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        let mut f = |trigger_type, x, color| {
+    let mut f = |root: Entity, side: PanelRootSide| {
+        let visuals = commands
+            .spawn((
+                PanelVisuals,
+                match_id,
+                Name::new("Panel Visuals"),
+                SpatialBundle::default(),
+            ))
+            .set_parent(root)
+            .id();
+        spawn_panel_layout(commands, assets, root, visuals, layouts.get(side));
+        let mut f = |slot: usize, trigger_type: TriggerType, x| {
+            let transform = Transform {
+                translation: Vec3::new(x, TRIGGER_ZONE_Y, TRIGGER_ZONE_Z),
+                scale: Vec3::new(ARENA_WIDTH_FRAC_5, config.physics.trigger_zone_height, 1.0),
+                rotation: Quat::IDENTITY,
+            };
             commands
-                .spawn(TriggerZoneBundle::new(
-                    trigger_type,
-                    Vec2::new(ARENA_WIDTH_FRAC_5, TRIGGER_ZONE_HEIGHT),
-                    Vec3::new(x, TRIGGER_ZONE_Y, TRIGGER_ZONE_Z),
-                    color,
+                .spawn((
+                    TriggerZoneBundle::new(trigger_type, transform),
+                    TriggerZoneSlot(slot),
                 ))
                 .set_parent(root);
+            commands
+                .spawn((
+                    TriggerZoneSprite,
+                    TriggerZoneSlot(slot),
+                    Name::new(format!("Trigger Zone Sprite: {}", trigger_type)),
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: trigger_type.color(),
+                            ..default()
+                        },
+                        transform,
+                        ..default()
+                    },
+                ))
+                .set_parent(visuals);
             commands
                 .spawn(Text2dBundle {
                     text: Text::from_section(
-                        trigger_type.to_string(),
+                        trigger_type.label(mutators),
                         TextStyle {
+                            font: assets.font.clone(),
                             color: TRIGGER_ZONE_TEXT_COLOR,
                             font_size: TRIGGER_ZONE_TEXT_SIZE,
-                            ..default()
                         },
                     )
                     .with_justify(JustifyText::Center),
@@ -603,30 +808,39 @@ fn setup(
                     },
                     ..default()
                 })
-                .insert(Name::new(format!("Trigger Zone Text: {}", trigger_type)))
-                .set_parent(root);
+                .insert((
+                    TriggerZoneText(trigger_type),
+                    TriggerZoneSlot(slot),
+                    Name::new(format!("Trigger Zone Text: {}", trigger_type)),
+                ))
+                .set_parent(visuals);
         };
-        f(TriggerType::Multiply(4), 0.0, TRIGGER_ZONE_COLOR_0);
-        f(
-            TriggerType::Multiply(2),
-            -ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_1,
-        );
-        f(
-            TriggerType::Multiply(2),
-            ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_1,
-        );
-        f(
-            TriggerType::BurstShot,
-            -2.0 * ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_2,
-        );
-        f(
-            TriggerType::ChargedShot,
-            2.0 * ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_2,
-        );
+        for (i, &trigger_type) in config.trigger_zones.iter().enumerate() {
+            f(i, trigger_type, (i as f32 - 2.0) * ARENA_WIDTH_FRAC_5);
+        }
+
+        commands
+            .spawn((
+                PanelNames(side),
+                match_id,
+                Name::new("Panel Names"),
+                Text2dBundle {
+                    text: Text::from_sections(panel_names_text(
+                        side,
+                        roster,
+                        names,
+                        ball_colors,
+                        assets,
+                    )),
+                    transform: Transform::from_xyz(
+                        0.0,
+                        TRIGGER_ZONE_Y - PANEL_NAMES_OFFSET_Y,
+                        TRIGGER_ZONE_TEXT_OFFSET_Z,
+                    ),
+                    ..default()
+                },
+            ))
+            .set_parent(visuals);
 
         commands
             .spawn(SpriteBundle {
@@ -642,7 +856,7 @@ fn setup(
                 ..default()
             })
             .insert(Name::new("Panel Wall"))
-            .set_parent(root);
+            .set_parent(visuals);
         commands
             .spawn(SpriteBundle {
                 transform: Transform {
@@ -657,133 +871,335 @@ fn setup(
                 ..default()
             })
             .insert(Name::new("Panel Background"))
-            .set_parent(root);
+            .set_parent(visuals);
+    };
+    f(left_root, PanelRootSide::Left);
+    f(right_root, PanelRootSide::Right);
+}
+/// Spawn the pegs and dividers in `layout` into the panel under `root`.
+fn spawn_panel_layout(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    root: Entity,
+    visuals: Entity,
+    layout: &PanelLayout,
+) {
+    let circle_builder = ObstacleBundleBuilder::new()
+        .name("Circle Obstacle")
+        .z(CIRCLE_Z)
+        .material(assets.peg_material.clone())
+        .mesh(assets.peg.clone())
+        .collider(Collider::ball(CIRCLE_RADIUS));
+    let divider_builder = ObstacleBundleBuilder::new()
+        .name("Trigger Zone Divider")
+        .z(TRIGGER_ZONE_DIVIDER_Z)
+        .material(assets.divider_material.clone())
+        .mesh(assets.divider.clone())
+        .collider(Collider::capsule_y(
+            TRIGGER_ZONE_DIVIDER_LENGTH / 2.0,
+            TRIGGER_ZONE_DIVIDER_RADIUS,
+        ));
+    for peg in &layout.pegs {
+        circle_builder
+            .clone()
+            .xy(peg.x, peg.y)
+            .spawn(commands, root, visuals);
+    }
+    for &x in &layout.dividers {
+        divider_builder
+            .clone()
+            .xy(x, TRIGGER_ZONE_Y)
+            .spawn(commands, root, visuals);
+    }
+}
+/// Swap the pegs and dividers over to the layouts of the map in the config, in case another map
+/// was picked or the map was edited since the last round.
+fn apply_panel_layouts(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    mut current: ResMut<PanelLayouts>,
+    parts: Query<Entity, With<PanelLayoutPart>>,
+    visuals: Query<(Entity, &Parent), With<PanelVisuals>>,
+    roots: Query<&PanelRoot>,
+) {
+    let layouts = PanelLayouts::for_config(&config);
+    if *current == layouts {
+        return;
+    }
+    for entity in &parts {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (visuals, root) in &visuals {
+        let Ok(&PanelRoot(side)) = roots.get(root.get()) else {
+            continue;
+        };
+        spawn_panel_layout(
+            &mut commands,
+            &assets,
+            root.get(),
+            visuals,
+            layouts.get(side),
+        );
+    }
+    *current = layouts;
+}
+/// The names of the participants in `roster` whose worker balls drop in the panel on `side`, each in
+/// their own color.
+fn panel_names_text(
+    side: PanelRootSide,
+    roster: &Roster,
+    names: &ParticipantMap<DisplayName>,
+    ball_colors: &ParticipantMap<BallColor>,
+    assets: &GameAssets,
+) -> Vec<TextSection> {
+    let style = |color| TextStyle {
+        font: assets.font.clone(),
+        color,
+        font_size: PANEL_NAMES_TEXT_SIZE,
     };
-    f(left_root);
-    f(right_root);
+    let mut sections = Vec::new();
+    for (i, participant) in side.participants(roster).into_iter().enumerate() {
+        if i > 0 {
+            sections.push(TextSection::new(" & ", style(TRIGGER_ZONE_TEXT_COLOR)));
+        }
+        sections.push(TextSection::new(
+            names[participant].0.clone(),
+            style(ball_colors[participant].0),
+        ));
+    }
+    sections
 }
-fn spawn_workers_condition(spawner: Res<WorkerBallSpawner>) -> bool {
-    spawner.counter < WORKER_BALL_COUNT_MAX
+fn spawn_workers_condition(spawners: Res<PerMatch<WorkerBallSpawner>>) -> bool {
+    spawners.iter().any(|(_, x)| x.counter < x.max_count)
 }
+/// Drop the next wave of worker balls for each match whose spawner is due.
 fn spawn_workers(
     mut commands: Commands,
-    mut spawner: ResMut<WorkerBallSpawner>,
+    (mut spawners, matches): (ResMut<PerMatch<WorkerBallSpawner>>, Res<Matches>),
     time: Res<Time>,
     rapier: Res<RapierContext>,
-    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
-    colors: Res<ParticipantMap<TileColor>>,
+    assets: Res<GameAssets>,
+    trail_colors: Res<ParticipantMap<TrailColor>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    stats: Res<PersistentStats>,
     survivors: Res<ParticipantMap<bool>>,
-    root: Query<(Entity, &GlobalTransform, &PanelRoot)>,
-    effect: Res<TrailEffect>,
+    roster: Res<Roster>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mutators: Res<ActiveMutators>,
+    roots: Query<(Entity, &GlobalTransform, &PanelRoot, &MatchId)>,
     mut trail_query: Query<(Entity, &mut EffectProperties, &InactiveWorkerBallTrail)>,
 ) {
-    spawner.timer.tick(time.delta());
-    if !spawner.timer.just_finished() {
+    // The number of the wave each due match is dropping.
+    let mut numbers = PerMatch::default();
+    for (match_id, spawner) in spawners.iter_mut() {
+        if spawner.counter >= spawner.max_count {
+            continue;
+        }
+        spawner.timer.tick(time.delta());
+        if spawner.timer.just_finished() {
+            spawner.counter += 1;
+            numbers.insert(match_id, spawner.counter);
+        }
+    }
+    if numbers.iter().next().is_none() {
+        return;
+    }
+    let collider = Collider::ball(WORKER_BALL_RADIUS);
+    for (root_entity, root_transform, &PanelRoot(side), &match_id) in &roots {
+        let Some(&number) = numbers.get(match_id) else {
+            continue;
+        };
+        let mut caster = WorkerBallShapeCaster::new(
+            *root_transform,
+            Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
+            &rapier,
+            &collider,
+            &mut rng.0,
+        );
+        let participants = side
+            .participants(&matches.roster(&roster, match_id))
+            .into_iter()
+            .filter(|&p| survivors[p])
+            .collect::<Vec<_>>();
+        // Keep the balls dropped together from overlapping.
+        let mut xs = Vec::<f32>::with_capacity(participants.len());
+        while xs.len() < participants.len() {
+            let x = caster.get();
+            if xs
+                .iter()
+                .all(|&other| (x - other).abs() > WORKER_BALL_DIAMETER)
+            {
+                xs.push(x);
+            }
+        }
+        let want_left = side == PanelRootSide::Left;
+        let mut trail_query_iter =
+            trail_query
+                .iter_mut()
+                .filter_map(|(e, p, &InactiveWorkerBallTrail(is_left))| {
+                    (is_left == want_left).then_some((e, p))
+                });
+        for (participant, x) in participants.into_iter().zip(xs) {
+            let trail_color = stats.skins[participant]
+                .trail_color(trail_colors[participant].0, ball_colors[participant].0);
+            let ball = commands
+                .spawn(WorkerBallBundle::new(
+                    match_id,
+                    participant,
+                    number,
+                    x,
+                    assets.worker_ball.clone(),
+                    assets.ball_materials.get(participant).clone(),
+                    &config.physics,
+                    &mutators,
+                ))
+                .set_parent(root_entity)
+                .id();
+            if let Some((trail_entity, mut trail_properties)) = trail_query_iter.next() {
+                commands
+                    .entity(trail_entity)
+                    .insert(WorkerBallTrail(ball))
+                    .remove::<InactiveWorkerBallTrail>();
+                trail_properties.set_spawn_color(trail_color);
+                trail_properties.set_position(spawn_point(root_transform, x).extend(0.0));
+            } else {
+                commands.spawn(WorkerBallTrailBundle::new(
+                    ball,
+                    spawn_point(root_transform, x),
+                    trail_color,
+                    assets.trail_effect.clone(),
+                ));
+            }
+        }
+    }
+}
+/// Drop the extra worker balls drafted for each participant along with their first ball.
+fn spawn_drafted_balls(
+    mut commands: Commands,
+    (spawners, matches): (Res<PerMatch<WorkerBallSpawner>>, Res<Matches>),
+    rapier: Res<RapierContext>,
+    assets: Res<GameAssets>,
+    trail_colors: Res<ParticipantMap<TrailColor>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    stats: Res<PersistentStats>,
+    survivors: Res<ParticipantMap<bool>>,
+    roster: Res<Roster>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mutators: Res<ActiveMutators>,
+    upgrades: Res<DraftedUpgrades>,
+    roots: Query<(Entity, &GlobalTransform, &PanelRoot, &MatchId)>,
+) {
+    // The extra balls go along with the first wave of each match.
+    let first_wave = |match_id| {
+        spawners
+            .get(match_id)
+            .filter(|x| x.timer.just_finished() && x.counter == 1)
+    };
+    if Participant::ALL
+        .into_iter()
+        .all(|p| first_wave(matches[p]).is_none())
+    {
         return;
     }
-    // TODO: handle trail effect
-    let mut f = |a, b, root_entity, root_transform: &GlobalTransform, want_left| {
-        let root_translation = root_transform.translation();
-        let collider = Collider::ball(WORKER_BALL_RADIUS);
+    let collider = Collider::ball(WORKER_BALL_RADIUS);
+    for (root_entity, root_transform, &PanelRoot(side), &match_id) in &roots {
         let mut caster = WorkerBallShapeCaster::new(
-            root_translation.xy(),
+            *root_transform,
             Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
             &rapier,
             &collider,
+            &mut rng.0,
         );
-        match (survivors[a].then_some(a), survivors[b].then_some(b)) {
-            (None, None) => (),
-            (Some(survivor), None) | (None, Some(survivor)) => {
+        for participant in side.participants(&matches.roster(&roster, match_id)) {
+            let Some(spawner) = first_wave(match_id).filter(|_| survivors[participant]) else {
+                continue;
+            };
+            let extra = upgrades.count(participant, Upgrade::ExtraBall);
+            for i in 0..extra {
                 let x = caster.get();
+                // Numbered after the regular balls so they can still be told apart in the stats.
                 let ball = commands
                     .spawn(WorkerBallBundle::new(
-                        survivor,
+                        match_id,
+                        participant,
+                        spawner.max_count + i + 1,
                         x,
-                        spawner.mesh.clone(),
-                        materials.get(survivor).clone(),
+                        assets.worker_ball.clone(),
+                        assets.ball_materials.get(participant).clone(),
+                        &config.physics,
+                        &mutators,
                     ))
                     .set_parent(root_entity)
                     .id();
                 commands.spawn(WorkerBallTrailBundle::new(
                     ball,
-                    x + root_translation.x,
-                    colors.get(survivor).0,
-                    effect.0.clone(),
+                    spawn_point(root_transform, x),
+                    stats.skins[participant]
+                        .trail_color(trail_colors[participant].0, ball_colors[participant].0),
+                    assets.trail_effect.clone(),
                 ));
             }
-            (Some(a), Some(b)) => {
-                let mut xa;
-                let mut xb;
-                loop {
-                    xa = caster.get();
-                    xb = caster.get();
-                    if (xa - xb).abs() > WORKER_BALL_DIAMETER {
-                        break;
-                    }
-                }
-                let mut trail_query_iter = trail_query.iter_mut().filter_map(
-                    |(e, p, &InactiveWorkerBallTrail(is_left))| {
-                        (is_left == want_left).then_some((e, p))
-                    },
-                );
-                let mut setup_trail = |participant, x| {
-                    let ball = commands
-                        .spawn(WorkerBallBundle::new(
-                            participant,
-                            x,
-                            spawner.mesh.clone(),
-                            materials.get(participant).clone(),
-                        ))
-                        .set_parent(root_entity)
-                        .id();
-                    if let Some((trail_entity, mut trail_properties)) = trail_query_iter.next() {
-                        commands
-                            .entity(trail_entity)
-                            .insert(WorkerBallTrail(ball))
-                            .remove::<InactiveWorkerBallTrail>();
-                        trail_properties.set_spawn_color(colors.get(participant).0);
-                        trail_properties.set_position(Vec3::new(
-                            x + root_translation.x,
-                            WORKER_BALL_SPAWN_Y,
-                            0.0,
-                        ));
-                    } else {
-                        commands.spawn(WorkerBallTrailBundle::new(
-                            ball,
-                            x + root_translation.x,
-                            colors.get(participant).0,
-                            effect.0.clone(),
-                        ));
-                    }
-                };
-                setup_trail(a, xa);
-                setup_trail(b, xb);
-            }
         }
-    };
-    let &[root0, root1] = root.into_iter().collect::<Vec<_>>().as_slice() else {
-        panic!("{}", EXPECT_TWO_PANELS_MSG);
-    };
-    let (left_root, right_root) = match (root0.2 .0, root1.2 .0) {
-        (PanelRootSide::Left, PanelRootSide::Right) => (root0, root1),
-        (PanelRootSide::Right, PanelRootSide::Left) => (root1, root0),
-        _ => panic!("{}", EXPECT_EACH_PANEL_SIDE_EXIST_MSG),
-    };
-    f(
-        Participant::A,
-        Participant::B,
-        left_root.0,
-        left_root.1,
-        true,
-    );
-    f(
-        Participant::C,
-        Participant::D,
-        right_root.0,
-        right_root.1,
-        false,
-    );
-    spawner.counter += 1;
+    }
+}
+/// Drop the worker balls asked for with [`SpawnWorkerBallEvent`]. They're numbered 0, since they
+/// aren't part of any wave.
+fn spawn_requested_balls(
+    mut commands: Commands,
+    mut events: EventReader<SpawnWorkerBallEvent>,
+    rapier: Res<RapierContext>,
+    assets: Res<GameAssets>,
+    trail_colors: Res<ParticipantMap<TrailColor>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    stats: Res<PersistentStats>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mutators: Res<ActiveMutators>,
+    matches: Res<Matches>,
+    roots: Query<(Entity, &GlobalTransform, &PanelRoot, &MatchId)>,
+) {
+    let collider = Collider::ball(WORKER_BALL_RADIUS);
+    for event in events.read() {
+        let Some((root_entity, root_transform, ..)) =
+            roots.iter().find(|&(.., &PanelRoot(side), &match_id)| {
+                side == event.side && match_id == matches[event.participant]
+            })
+        else {
+            continue;
+        };
+        let x = WorkerBallShapeCaster::new(
+            *root_transform,
+            Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
+            &rapier,
+            &collider,
+            &mut rng.0,
+        )
+        .get();
+        let participant = event.participant;
+        let ball = commands
+            .spawn(WorkerBallBundle::new(
+                matches[participant],
+                participant,
+                0,
+                x,
+                assets.worker_ball.clone(),
+                assets.ball_materials.get(participant).clone(),
+                &config.physics,
+                &mutators,
+            ))
+            .set_parent(root_entity)
+            .id();
+        commands.spawn(WorkerBallTrailBundle::new(
+            ball,
+            spawn_point(root_transform, x),
+            stats.skins[participant]
+                .trail_color(trail_colors[participant].0, ball_colors[participant].0),
+            assets.trail_effect.clone(),
+        ));
+    }
 }
 fn update_workers_particle_position(
     mut commands: Commands,
@@ -813,12 +1229,14 @@ fn trigger_event(
     mut restart_event: EventReader<RestartEvent>,
     mut trigger_event: EventWriter<TriggerEvent>,
     trigger_zone_query: Query<&TriggerType>,
-    worker_ball_query: Query<&Participant, With<WorkerBall>>,
+    worker_ball_query: Query<(&Participant, &MatchId), With<WorkerBall>>,
+    mutators: Res<ActiveMutators>,
+    (survivors, matches): (Res<ParticipantMap<bool>>, Res<Matches>),
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
 ) {
-    if !restart_event.is_empty() {
-        collision_events.clear();
-        restart_event.clear();
-    }
+    // The balls of a restarted match don't carry their triggers over into the new round.
+    let restarted: Vec<_> = restart_event.read().map(|x| x.match_id).collect();
     for collision_event in collision_events.read() {
         match collision_event {
             &CollisionEvent::Started(a, b, _) => {
@@ -829,26 +1247,70 @@ fn trigger_event(
                 } else {
                     continue;
                 };
-                let &participant = if let Ok(x) = worker_ball_query.get(a) {
-                    x
+                let (ball, (&participant, &match_id)) = if let Ok(x) = worker_ball_query.get(a) {
+                    (a, x)
                 } else if let Ok(x) = worker_ball_query.get(b) {
-                    x
+                    (b, x)
                 } else {
                     continue;
                 };
+                if restarted.contains(&match_id) {
+                    continue;
+                }
+                let participant = match trigger_type {
+                    TriggerType::Multiply(_) if mutators.contains(Mutator::Reverse) => {
+                        // Only the participants in the same match are within reach.
+                        let reachable =
+                            ParticipantMap::from_fn(|p| survivors[p] && matches[p] == match_id);
+                        participant.random_opponent(&reachable, &config.teams, &mut rng.0)
+                    }
+                    _ => participant,
+                };
                 trigger_event.send(TriggerEvent {
                     participant,
                     trigger_type,
+                    ball: Some(ball),
                 });
             }
             CollisionEvent::Stopped(_, _, _) => (),
         }
     }
 }
+fn update_trigger_zone_text(
+    mutators: Res<ActiveMutators>,
+    mut texts: Query<(&TriggerZoneText, &mut Text)>,
+) {
+    for (&TriggerZoneText(trigger_type), mut text) in &mut texts {
+        text.sections[0].value = trigger_type.label(&mutators);
+    }
+}
+/// Bring the worker balls and trigger zones already spawned in line with the config after it's been
+/// edited, or the mutators have changed.
+fn apply_physics_config(
+    config: Res<GameConfig>,
+    mutators: Res<ActiveMutators>,
+    mut balls: Query<(&mut GravityScale, &mut Restitution), With<WorkerBall>>,
+    mut trigger_zones: Query<&mut Transform, Or<(With<TriggerType>, With<TriggerZoneSprite>)>>,
+) {
+    let physics = &config.physics;
+    for (mut gravity, mut restitution) in &mut balls {
+        gravity.set_if_neq(GravityScale(mutators.worker_ball_gravity_scale(physics)));
+        if restitution.coefficient != physics.worker_ball_restitution {
+            restitution.coefficient = physics.worker_ball_restitution;
+        }
+    }
+    for mut transform in &mut trigger_zones {
+        if transform.scale.y != physics.trigger_zone_height {
+            transform.scale.y = physics.trigger_zone_height;
+        }
+    }
+}
 fn ball_reset(
     mut collision_events: EventReader<CollisionEvent>,
     rapier: Res<RapierContext>,
-    root_query: Query<(&GlobalTransform, &PanelRoot)>,
+    mut rng: ResMut<GameRng>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    root_query: Query<(&GlobalTransform, &PanelRoot, &MatchId)>,
     trigger_zone_query: Query<(), With<TriggerType>>,
     mut worker_ball_query: Query<
         (&mut Transform, &mut Velocity, &Collider, &Participant),
@@ -872,18 +1334,20 @@ fn ball_reset(
                     continue;
                 };
 
-                let target_side = PanelRootSide::for_participant(participant);
+                let (target_match, target_side) =
+                    PanelRootSide::for_participant_in(participant, &roster, &matches);
                 let root = root_query
                     .into_iter()
-                    .find_map(|(transform, &PanelRoot(side))| {
-                        (side == target_side).then_some(transform)
+                    .find_map(|(transform, &PanelRoot(side), &match_id)| {
+                        (side == target_side && match_id == target_match).then_some(transform)
                     })
                     .expect(EXPECT_EACH_PANEL_SIDE_EXIST_MSG);
                 let x = WorkerBallShapeCaster::new(
-                    root.translation().xy(),
+                    *root,
                     Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
                     &rapier,
                     collider,
+                    &mut rng.0,
                 )
                 .get();
                 ball_transform.translation.x = x;
@@ -893,22 +1357,28 @@ fn ball_reset(
         }
     }
 }
-struct WorkerBallShapeCaster<'a, 'b, D> {
-    root_position: Vec2,
-    rng_iter: DistIter<D, ThreadRng, f32>,
+/// Where a worker ball dropped at `x` across the panel under `root` starts out, in world space.
+fn spawn_point(root: &GlobalTransform, x: f32) -> Vec2 {
+    root.transform_point(Vec3::new(x, WORKER_BALL_SPAWN_Y, 0.0))
+        .xy()
+}
+struct WorkerBallShapeCaster<'a, 'b, 'c, D> {
+    root: GlobalTransform,
+    rng_iter: DistIter<D, &'c mut ChaCha8Rng, f32>,
     rapier: &'a RapierContext,
     collider: &'b Collider,
 }
-impl<'a, 'b, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, D> {
+impl<'a, 'b, 'c, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, 'c, D> {
     fn new(
-        root_position: Vec2,
+        root: GlobalTransform,
         dist: D,
         rapier: &'a RapierContext,
         collider: &'b Collider,
+        rng: &'c mut ChaCha8Rng,
     ) -> Self {
         Self {
-            root_position,
-            rng_iter: thread_rng().sample_iter(dist),
+            root,
+            rng_iter: rng.sample_iter(dist),
             rapier,
             collider,
         }
@@ -918,10 +1388,7 @@ impl<'a, 'b, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, D> {
             if self
                 .rapier
                 .intersection_with_shape(
-                    Vect::new(
-                        x + self.root_position.x,
-                        WORKER_BALL_SPAWN_Y + self.root_position.y,
-                    ),
+                    spawn_point(&self.root, x),
                     0.0,
                     self.collider,
                     QueryFilter::only_dynamic().groups(CollisionGroups::new(
@@ -937,15 +1404,120 @@ impl<'a, 'b, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, D> {
         unreachable!("`self.rng_iter: DistIter` is an infinite iterator.");
     }
 }
+/// Swap the trigger zones already spawned over to the layout in the config, in case it was changed
+/// since the last round, dealing them out in a random order with [`Mutator::Shuffle`].
+fn apply_trigger_zone_layout(
+    config: Res<GameConfig>,
+    mutators: Res<ActiveMutators>,
+    mut rng: ResMut<GameRng>,
+    mut zones: Query<(&TriggerZoneSlot, &mut TriggerType, &mut Name)>,
+    mut sprites: Query<
+        (&TriggerZoneSlot, &mut Sprite, &mut Name),
+        (With<TriggerZoneSprite>, Without<TriggerType>),
+    >,
+    mut texts: Query<
+        (&TriggerZoneSlot, &mut TriggerZoneText, &mut Text, &mut Name),
+        (Without<TriggerType>, Without<TriggerZoneSprite>),
+    >,
+) {
+    let mut layout = config.trigger_zones.0;
+    if mutators.contains(Mutator::Shuffle) {
+        layout.shuffle(&mut rng.0);
+    }
+    for (&TriggerZoneSlot(slot), mut trigger_type, mut name) in &mut zones {
+        *trigger_type = layout[slot];
+        *name = Name::new(format!("Trigger Zone: {}", *trigger_type));
+    }
+    for (&TriggerZoneSlot(slot), mut sprite, mut name) in &mut sprites {
+        let trigger_type = layout[slot];
+        sprite.color = trigger_type.color();
+        *name = Name::new(format!("Trigger Zone Sprite: {}", trigger_type));
+    }
+    for (&TriggerZoneSlot(slot), mut zone_text, mut text, mut name) in &mut texts {
+        let trigger_type = layout[slot];
+        zone_text.0 = trigger_type;
+        text.sections[0].value = trigger_type.label(&mutators);
+        *name = Name::new(format!("Trigger Zone Text: {}", trigger_type));
+    }
+}
+/// Stop dropping worker balls into the panels of a match that's been won while other matches are
+/// still going, and clear the ones already in there.
+fn settle_match(
+    mut commands: Commands,
+    mut events: EventReader<MatchDecidedEvent>,
+    mut spawners: ResMut<PerMatch<WorkerBallSpawner>>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    balls: Query<(Entity, &MatchId), With<WorkerBall>>,
+) {
+    if matches.running(&roster).len() < 2 {
+        events.clear();
+        return;
+    }
+    for event in events.read() {
+        if let Some(spawner) = spawners.get_mut(event.match_id) {
+            spawner.counter = spawner.max_count;
+        }
+        for (entity, &match_id) in &balls {
+            if match_id == event.match_id {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
 fn restart(
     mut commands: Commands,
-    mut spawner: ResMut<WorkerBallSpawner>,
+    mut events: EventReader<RestartEvent>,
+    mut spawners: ResMut<PerMatch<WorkerBallSpawner>>,
+    preset: Res<GamePreset>,
+    options: Res<LaunchOptions>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    names: Res<ParticipantMap<DisplayName>>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    (assets, mutators, config, layouts): (
+        Res<GameAssets>,
+        Res<ActiveMutators>,
+        Res<GameConfig>,
+        Res<PanelLayouts>,
+    ),
     mut trails: Query<(&mut EffectProperties, &mut InactiveWorkerBallTrail)>,
-    garbage: Query<Entity, With<WorkerBall>>,
+    mut panel_names: Query<(&PanelNames, &MatchId, &mut Text)>,
+    garbage: Query<(Entity, &MatchId), With<WorkerBall>>,
+    roots: Query<&MatchId, With<PanelRoot>>,
 ) {
-    spawner.reset();
-    for entity in garbage.iter() {
-        commands.entity(entity).despawn_recursive();
+    // The roster may have changed, which moves participants between the panels.
+    for (&PanelNames(side), &match_id, mut text) in &mut panel_names {
+        text.sections = panel_names_text(
+            side,
+            &matches.roster(&roster, match_id),
+            &names,
+            &ball_colors,
+            &assets,
+        );
+    }
+    let restarted: Vec<_> = events.read().map(|x| x.match_id).collect();
+    // The panels of the matches that stopped running are cleared away with their battlefields.
+    let running = matches.running(&roster);
+    spawners.retain(|x| running.contains(&x));
+    for &match_id in &restarted {
+        // The preset may have been changed from the main menu.
+        spawners.insert(match_id, WorkerBallSpawner::new(*preset, &options));
+        if !roots.iter().any(|&x| x == match_id) {
+            // The match wasn't running last round.
+            spawn_panels(
+                &mut commands,
+                match_id,
+                &matches.roster(&roster, match_id),
+                &options,
+                &layouts,
+                (&assets, &mutators, &config),
+                (&names, &ball_colors),
+            );
+        }
+    }
+    for (entity, match_id) in &garbage {
+        if restarted.contains(match_id) {
+            commands.entity(entity).despawn_recursive();
+        }
     }
     let mut go_left = false;
     for (mut properties, mut trail) in trails.iter_mut() {
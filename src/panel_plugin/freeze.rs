@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    PanelRoot, PanelRootSide, TriggerEvent, TriggerType, WorkerBall, ARENA_HEIGHT, ARENA_WIDTH,
+};
+use crate::{
+    battlefield::RestartEvent,
+    config::GameConfig,
+    utils::{MatchId, Matches, Roster},
+};
+
+// Constants {{{
+
+const ICE_OVERLAY_COLOR: Color = Color::srgba(0.7, 0.9, 1.0, 0.35);
+
+// Z-index
+/// In front of the worker balls.
+const ICE_OVERLAY_Z: f32 = 2.0;
+
+// }}}
+
+pub struct FreezePlugin;
+impl Plugin for FreezePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_freeze.run_if(on_event::<TriggerEvent>()),
+                update_frozen_panels.after(start_freeze),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Component for a panel whose worker balls are frozen in place.
+#[derive(Component)]
+struct PanelFreeze {
+    timer: Timer,
+    overlay: Entity,
+}
+/// Component for a frozen worker ball, holding the velocity it had before it was frozen.
+#[derive(Component, Clone, Copy)]
+struct FrozenWorkerBall(Velocity);
+
+/// Freeze the opposite panel whenever a marble lands in a freeze zone. Freezing a panel that's
+/// already frozen restarts its timer.
+fn start_freeze(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    mut panels: Query<(Entity, &PanelRoot, &MatchId, Option<&mut PanelFreeze>)>,
+) {
+    let targets = events
+        .read()
+        .filter(|event| event.trigger_type == TriggerType::Freeze)
+        .map(|event| {
+            let (match_id, side) =
+                PanelRootSide::for_participant_in(event.participant, &roster, &matches);
+            (match_id, side.opposite())
+        })
+        .collect::<Vec<_>>();
+    for (entity, &PanelRoot(side), &match_id, freeze) in &mut panels {
+        if !targets.contains(&(match_id, side)) {
+            continue;
+        }
+        let timer = Timer::from_seconds(config.freeze.duration_secs, TimerMode::Once);
+        if let Some(mut freeze) = freeze {
+            freeze.timer = timer;
+            continue;
+        }
+        let overlay = commands
+            .spawn((
+                Name::new("Ice Overlay"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: ICE_OVERLAY_COLOR,
+                        custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, ICE_OVERLAY_Z),
+                    ..default()
+                },
+            ))
+            .set_parent(entity)
+            .id();
+        commands
+            .entity(entity)
+            .insert(PanelFreeze { timer, overlay });
+    }
+}
+/// Freeze every worker ball on a frozen panel, including the ones spawned after the freeze
+/// started, and thaw them once the timer runs out.
+fn update_frozen_panels(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut panels: Query<(Entity, &mut PanelFreeze, &Children)>,
+    balls: Query<(&Velocity, Option<&FrozenWorkerBall>), With<WorkerBall>>,
+) {
+    for (panel, mut freeze, children) in &mut panels {
+        freeze.timer.tick(time.delta());
+        let thawed = freeze.timer.finished();
+        for &child in children {
+            let Ok((&velocity, frozen)) = balls.get(child) else {
+                continue;
+            };
+            match (frozen, thawed) {
+                (None, false) => {
+                    commands
+                        .entity(child)
+                        .insert((FrozenWorkerBall(velocity), RigidBody::Fixed));
+                }
+                (Some(&FrozenWorkerBall(velocity)), true) => {
+                    commands
+                        .entity(child)
+                        .insert((RigidBody::Dynamic, velocity))
+                        .remove::<FrozenWorkerBall>();
+                }
+                _ => (),
+            }
+        }
+        if thawed {
+            commands.entity(freeze.overlay).despawn_recursive();
+            commands.entity(panel).remove::<PanelFreeze>();
+        }
+    }
+}
+fn restart(mut commands: Commands, panels: Query<(Entity, &PanelFreeze)>) {
+    for (entity, freeze) in &panels {
+        commands.entity(freeze.overlay).despawn_recursive();
+        commands.entity(entity).remove::<PanelFreeze>();
+    }
+}
@@ -0,0 +1,461 @@
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    worker_ball_fits_between, PanelRootSide, TriggerType, ARENA_HEIGHT_FRAC_2, ARENA_WIDTH_FRAC_10,
+    ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_5, CIRCLE_DIAMETER, CIRCLE_GRID_BOTTOM_Y,
+    CIRCLE_GRID_HORIZONTAL_GAP, CIRCLE_GRID_HORIZONTAL_HALF_COUNT_EVEN_ROW,
+    CIRCLE_GRID_HORIZONTAL_HALF_COUNT_ODD_ROW, CIRCLE_GRID_VERTICAL_COUNT,
+    CIRCLE_GRID_VERTICAL_GAP, CIRCLE_GRID_VERTICAL_OFFSET, CIRCLE_HALF_GAP,
+    CIRCLE_PYRAMID_HORIZONTAL_GAP, CIRCLE_PYRAMID_VERTICAL_COUNT, CIRCLE_PYRAMID_VERTICAL_GAP,
+    CIRCLE_PYRAMID_VERTICAL_OFFSET, CIRCLE_RADIUS, TRIGGER_ZONE_COUNT, TRIGGER_ZONE_DIVIDER_LENGTH,
+    TRIGGER_ZONE_DIVIDER_RADIUS, TRIGGER_ZONE_Y, WORKER_BALL_RADIUS, WORKER_BALL_SPAWN_Y,
+};
+use crate::config::{GameConfig, PhysicsConfig, TriggerZoneLayout};
+
+// Constants {{{
+
+/// The lowest a peg can go while staying clear of the tallest trigger zones.
+pub const PEG_Y_MIN: f32 = CIRCLE_GRID_BOTTOM_Y + CIRCLE_RADIUS;
+/// The highest a peg can go while staying under the worker balls' spawn point.
+pub const PEG_Y_MAX: f32 = WORKER_BALL_SPAWN_Y - WORKER_BALL_RADIUS - CIRCLE_RADIUS;
+/// The most pegs or dividers a panel can have, so they still fit in a board code.
+pub const PANEL_PART_COUNT_MAX: usize = u8::MAX as usize;
+/// Bumped whenever the board code format changes, so old codes are turned down instead of misread.
+const BOARD_CODE_VERSION: u8 = 1;
+/// Board codes are written in standard padded base64, but read with or without the padding.
+const BOARD_CODE_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+/// Positions are stored in board codes in steps of this many units.
+const BOARD_CODE_STEP: f32 = 0.5;
+
+/// Rapier's default gravity, which the panels are simulated with.
+const GRAVITY: f32 = 9.81;
+/// The time step of the simulated drops, short enough that a falling ball moves less than its
+/// radius in one.
+const DROP_TIME_STEP: f32 = 1.0 / 120.0;
+/// A simulated ball that hasn't landed by now is stuck, and lands nowhere.
+const DROP_TIME_MAX: f32 = 30.0;
+
+// }}}
+
+/// Where the pegs and the trigger zone dividers go in a panel, relative to its center.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub pegs: Vec<Vec2>,
+    /// The x coordinate of each divider. They all stand between the trigger zones.
+    pub dividers: Vec<f32>,
+}
+impl Default for PanelLayout {
+    /// A pyramid of pegs over a staggered grid, with a divider between each trigger zone.
+    fn default() -> Self {
+        let mut pegs = Vec::new();
+        // Even rows have a peg in the middle, and odd rows are shifted half a gap to the sides.
+        let mut row = |y: f32, i: usize, gap: f32, pairs: usize| {
+            let xs = match i % 2 {
+                0 => {
+                    pegs.push(Vec2::new(0.0, y));
+                    (1..=pairs)
+                        .map(|j| j as f32 * (CIRCLE_DIAMETER + gap))
+                        .collect::<Vec<_>>()
+                }
+                _ => (0..pairs)
+                    .map(|j| j as f32 * (CIRCLE_DIAMETER + gap) + CIRCLE_HALF_GAP + CIRCLE_RADIUS)
+                    .collect(),
+            };
+            for x in xs {
+                pegs.push(Vec2::new(x, y));
+                pegs.push(Vec2::new(-x, y));
+            }
+        };
+        for i in 0..CIRCLE_PYRAMID_VERTICAL_COUNT {
+            let y = -(i as f32) * (CIRCLE_DIAMETER + CIRCLE_PYRAMID_VERTICAL_GAP)
+                + CIRCLE_PYRAMID_VERTICAL_OFFSET;
+            row(y, i, CIRCLE_PYRAMID_HORIZONTAL_GAP, i / 2 + i % 2);
+        }
+        for i in 0..CIRCLE_GRID_VERTICAL_COUNT {
+            let y = -(i as f32) * (CIRCLE_DIAMETER + CIRCLE_GRID_VERTICAL_GAP)
+                + CIRCLE_GRID_VERTICAL_OFFSET;
+            let pairs = match i % 2 {
+                0 => CIRCLE_GRID_HORIZONTAL_HALF_COUNT_EVEN_ROW,
+                _ => CIRCLE_GRID_HORIZONTAL_HALF_COUNT_ODD_ROW,
+            };
+            row(y, i, CIRCLE_GRID_HORIZONTAL_GAP, pairs);
+        }
+        let outer = ARENA_WIDTH_FRAC_5 + ARENA_WIDTH_FRAC_10;
+        Self {
+            pegs,
+            dividers: vec![-ARENA_WIDTH_FRAC_10, -outer, ARENA_WIDTH_FRAC_10, outer],
+        }
+    }
+}
+impl PanelLayout {
+    /// Move the pegs and dividers back inside the panel, above the trigger zones and below the
+    /// worker balls' spawn point, and drop any past [`PANEL_PART_COUNT_MAX`]. Warns about any
+    /// [`Self::narrow_gaps`] left.
+    pub fn contain(&mut self) {
+        self.pegs.truncate(PANEL_PART_COUNT_MAX);
+        self.dividers.truncate(PANEL_PART_COUNT_MAX);
+        for peg in &mut self.pegs {
+            *peg = contain_peg(*peg);
+        }
+        for x in &mut self.dividers {
+            *x = contain_divider(*x);
+        }
+        let narrow_gaps = self.narrow_gaps();
+        if narrow_gaps > 0 {
+            warn!(
+                "The panel layout has {} gaps between pegs too narrow for a worker ball to pass, \
+                 where the balls can get stuck",
+                narrow_gaps
+            );
+        }
+    }
+    /// The number of pairs of pegs with a gap between them that a worker ball can get wedged in,
+    /// too narrow for it to pass. Pegs that touch or overlap leave no gap, and act as one wider
+    /// peg instead.
+    pub fn narrow_gaps(&self) -> usize {
+        let mut count = 0;
+        for (i, &a) in self.pegs.iter().enumerate() {
+            for &b in &self.pegs[i + 1..] {
+                let offset = b - a;
+                if offset.length() > CIRCLE_DIAMETER
+                    && !worker_ball_fits_between(offset.x, offset.y)
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    /// The share of worker balls dropped into the panel that land in each trigger zone, counting
+    /// from the left, estimated by simulating `drops` of them from spots picked by an RNG seeded
+    /// with `seed`. Balls that get stuck count for none of the zones.
+    ///
+    /// The simulation is simpler than Rapier's, without friction or the balls hitting each other,
+    /// so it's only a rough estimate, but layouts that send most balls one way still stand out.
+    pub fn landing_odds(
+        &self,
+        physics: &PhysicsConfig,
+        drops: usize,
+        seed: u64,
+    ) -> [f32; TRIGGER_ZONE_COUNT] {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut landed = [0; TRIGGER_ZONE_COUNT];
+        let spawn_x = ARENA_WIDTH_FRAC_2 - WORKER_BALL_RADIUS;
+        for _ in 0..drops {
+            let start = Vec2::new(rng.gen_range(-spawn_x..spawn_x), WORKER_BALL_SPAWN_Y);
+            if let Some(slot) = self.drop(start, physics) {
+                landed[slot] += 1;
+            }
+        }
+        landed.map(|x| x as f32 / drops.max(1) as f32)
+    }
+    /// The trigger zone a worker ball dropped from `position` lands in.
+    fn drop(&self, mut position: Vec2, physics: &PhysicsConfig) -> Option<usize> {
+        let gravity = Vec2::NEG_Y * GRAVITY * physics.worker_ball_gravity_scale;
+        let restitution = physics.worker_ball_restitution;
+        let zone_top = TRIGGER_ZONE_Y + physics.trigger_zone_height / 2.0;
+        let divider_half_length = TRIGGER_ZONE_DIVIDER_LENGTH / 2.0;
+        let mut velocity = Vec2::ZERO;
+        // Push the ball out of whatever it's sunk into, bouncing it off along `normal`.
+        let collide = |position: &mut Vec2, velocity: &mut Vec2, normal: Vec2, depth: f32| {
+            *position += normal * depth;
+            let speed = velocity.dot(normal);
+            if speed < 0.0 {
+                *velocity -= (1.0 + restitution) * speed * normal;
+            }
+        };
+        for _ in 0..(DROP_TIME_MAX / DROP_TIME_STEP) as usize {
+            velocity += gravity * DROP_TIME_STEP;
+            position += velocity * DROP_TIME_STEP;
+            if position.y - WORKER_BALL_RADIUS <= zone_top {
+                let slot = ((position.x + ARENA_WIDTH_FRAC_2) / ARENA_WIDTH_FRAC_5).max(0.0);
+                return Some((slot as usize).min(TRIGGER_ZONE_COUNT - 1));
+            }
+            for &peg in &self.pegs {
+                let offset = position - peg;
+                let depth = CIRCLE_RADIUS + WORKER_BALL_RADIUS - offset.length();
+                if depth > 0.0 {
+                    let normal = offset.try_normalize().unwrap_or(Vec2::Y);
+                    collide(&mut position, &mut velocity, normal, depth);
+                }
+            }
+            for &x in &self.dividers {
+                let nearest = Vec2::new(
+                    x,
+                    position.y.clamp(
+                        TRIGGER_ZONE_Y - divider_half_length,
+                        TRIGGER_ZONE_Y + divider_half_length,
+                    ),
+                );
+                let offset = position - nearest;
+                let depth = TRIGGER_ZONE_DIVIDER_RADIUS + WORKER_BALL_RADIUS - offset.length();
+                if depth > 0.0 {
+                    let normal = offset.try_normalize().unwrap_or(Vec2::Y);
+                    collide(&mut position, &mut velocity, normal, depth);
+                }
+            }
+            let wall = ARENA_WIDTH_FRAC_2 - WORKER_BALL_RADIUS;
+            let depth = position.x.abs() - wall;
+            if depth > 0.0 {
+                let normal = Vec2::new(-position.x.signum(), 0.0);
+                collide(&mut position, &mut velocity, normal, depth);
+            }
+            // Only reachable with trigger zones sunk below the arena.
+            if position.y < -ARENA_HEIGHT_FRAC_2 {
+                return None;
+            }
+        }
+        None
+    }
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.pegs.len() as u8);
+        for peg in &self.pegs {
+            write_position(bytes, peg.x);
+            write_position(bytes, peg.y);
+        }
+        bytes.push(self.dividers.len() as u8);
+        for &x in &self.dividers {
+            write_position(bytes, x);
+        }
+    }
+    fn read(bytes: &mut impl Iterator<Item = u8>) -> Option<Self> {
+        let mut layout = Self {
+            pegs: Vec::new(),
+            dividers: Vec::new(),
+        };
+        for _ in 0..bytes.next()? {
+            let x = read_position(bytes)?;
+            layout.pegs.push(Vec2::new(x, read_position(bytes)?));
+        }
+        for _ in 0..bytes.next()? {
+            layout.dividers.push(read_position(bytes)?);
+        }
+        layout.contain();
+        Some(layout)
+    }
+}
+/// `position` moved back inside the area pegs can go in.
+pub fn contain_peg(position: Vec2) -> Vec2 {
+    position.clamp(
+        Vec2::new(-ARENA_WIDTH_FRAC_2, PEG_Y_MIN),
+        Vec2::new(ARENA_WIDTH_FRAC_2, PEG_Y_MAX),
+    )
+}
+/// `x` moved back inside the panel.
+pub fn contain_divider(x: f32) -> f32 {
+    x.clamp(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2)
+}
+fn write_position(bytes: &mut Vec<u8>, x: f32) {
+    bytes.extend(((x / BOARD_CODE_STEP).round() as i16).to_le_bytes());
+}
+fn read_position(bytes: &mut impl Iterator<Item = u8>) -> Option<f32> {
+    let x = i16::from_le_bytes([bytes.next()?, bytes.next()?]);
+    Some(x as f32 * BOARD_CODE_STEP)
+}
+
+/// The layouts of both panels. As a resource, the layouts the panels have been built from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Reflect, Resource)]
+#[serde(default)]
+pub struct PanelLayouts {
+    pub left: PanelLayout,
+    pub right: PanelLayout,
+}
+impl PanelLayouts {
+    /// The layouts of the custom map picked in `config`, or the default ones.
+    pub fn for_config(config: &GameConfig) -> Self {
+        config
+            .map_file
+            .as_ref()
+            .and_then(|x| x.panels.clone())
+            .unwrap_or_default()
+    }
+    pub fn get(&self, side: PanelRootSide) -> &PanelLayout {
+        match side {
+            PanelRootSide::Left => &self.left,
+            PanelRootSide::Right => &self.right,
+        }
+    }
+    pub fn get_mut(&mut self, side: PanelRootSide) -> &mut PanelLayout {
+        match side {
+            PanelRootSide::Left => &mut self.left,
+            PanelRootSide::Right => &mut self.right,
+        }
+    }
+    pub fn contain(&mut self) {
+        self.left.contain();
+        self.right.contain();
+    }
+    /// A short code standing for these layouts and `zones`, to share boards as text. Positions
+    /// are rounded to the nearest half unit.
+    pub fn board_code(&self, zones: &TriggerZoneLayout) -> String {
+        let mut bytes = vec![BOARD_CODE_VERSION];
+        for trigger_type in zones.0 {
+            bytes.extend(match trigger_type {
+                TriggerType::Multiply(factor) => [0, factor],
+                TriggerType::BurstShot => [1, 0],
+                TriggerType::ChargedShot => [2, 0],
+                TriggerType::Gamble => [3, 0],
+                TriggerType::Swap => [4, 0],
+                TriggerType::Freeze => [5, 0],
+            });
+        }
+        self.left.write(&mut bytes);
+        self.right.write(&mut bytes);
+        BOARD_CODE_BASE64.encode(&bytes)
+    }
+    /// The layouts and trigger zones a [`Self::board_code`] stands for.
+    pub fn from_board_code(code: &str) -> Result<(Self, TriggerZoneLayout), String> {
+        let bytes = BOARD_CODE_BASE64
+            .decode(code.trim())
+            .map_err(|_| "The board code isn't valid base64")?;
+        let mut bytes = bytes.into_iter();
+        match bytes.next() {
+            Some(BOARD_CODE_VERSION) => (),
+            Some(version) => return Err(format!("Unknown board code version {}", version)),
+            None => return Err("The board code is empty".to_string()),
+        }
+        let truncated = || "The board code is cut short".to_string();
+        let mut zones = TriggerZoneLayout::default();
+        for slot in 0..TRIGGER_ZONE_COUNT {
+            let (tag, factor) = (bytes.next(), bytes.next());
+            zones.0[slot] = match tag.zip(factor).ok_or_else(truncated)? {
+                (0, 0) => {
+                    return Err("A trigger zone in the board code multiplies by 0".to_string())
+                }
+                (0, factor) => TriggerType::Multiply(factor),
+                (1, _) => TriggerType::BurstShot,
+                (2, _) => TriggerType::ChargedShot,
+                (3, _) => TriggerType::Gamble,
+                (4, _) => TriggerType::Swap,
+                (5, _) => TriggerType::Freeze,
+                (tag, _) => return Err(format!("Unknown trigger zone {} in the board code", tag)),
+            };
+        }
+        let left = PanelLayout::read(&mut bytes).ok_or_else(truncated)?;
+        let right = PanelLayout::read(&mut bytes).ok_or_else(truncated)?;
+        if bytes.next().is_some() {
+            return Err("The board code has extra data at the end".to_string());
+        }
+        Ok((Self { left, right }, zones))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layout_matches_the_classic_pegs() {
+        let layout = PanelLayout::default();
+        // 15 in the pyramid and 44 in the grid.
+        assert_eq!(layout.pegs.len(), 59);
+        assert_eq!(layout.dividers.len(), TRIGGER_ZONE_COUNT - 1);
+        let mut contained = layout.clone();
+        contained.contain();
+        assert_eq!(contained, layout);
+        assert_eq!(layout.narrow_gaps(), 0);
+    }
+
+    #[test]
+    fn narrow_gaps_are_counted_but_touching_pegs_are_not() {
+        let peg = |x: f32| Vec2::new(x, 0.0);
+        let layout = PanelLayout {
+            pegs: vec![
+                peg(0.0),
+                // Touching the first one, so there's no gap to get stuck in.
+                peg(CIRCLE_DIAMETER),
+                // Half a worker ball away from the second one.
+                peg(2.0 * CIRCLE_DIAMETER + WORKER_BALL_RADIUS),
+            ],
+            dividers: Vec::new(),
+        };
+        assert_eq!(layout.narrow_gaps(), 1);
+    }
+
+    #[test]
+    fn board_codes_round_trip() {
+        let mut layouts = PanelLayouts::default();
+        layouts.right.pegs.truncate(10);
+        layouts.right.pegs.push(Vec2::new(-12.5, 100.0));
+        layouts.right.dividers = vec![0.0];
+        let zones = TriggerZoneLayout([
+            TriggerType::Freeze,
+            TriggerType::Multiply(3),
+            TriggerType::Gamble,
+            TriggerType::Swap,
+            TriggerType::ChargedShot,
+        ]);
+        let code = layouts.board_code(&zones);
+        assert_eq!(
+            PanelLayouts::from_board_code(&code),
+            Ok((layouts.clone(), zones.clone()))
+        );
+        // Chat apps and URLs tend to eat the padding.
+        assert_eq!(
+            PanelLayouts::from_board_code(code.trim_end_matches('=')),
+            Ok((layouts, zones.clone()))
+        );
+        let mut cut = BOARD_CODE_BASE64.decode(&code).unwrap();
+        cut.pop();
+        assert!(PanelLayouts::from_board_code(&BOARD_CODE_BASE64.encode(&cut)).is_err());
+        assert!(PanelLayouts::from_board_code("not a code!").is_err());
+
+        let mut zero = BOARD_CODE_BASE64.decode(&code).unwrap();
+        // The factor of the second trigger zone, after the version and the first zone.
+        zero[4] = 0;
+        assert!(PanelLayouts::from_board_code(&BOARD_CODE_BASE64.encode(&zero)).is_err());
+    }
+
+    #[test]
+    fn landing_odds_follow_the_layout() {
+        let physics = PhysicsConfig::default();
+        let odds = PanelLayout::default().landing_odds(&physics, 200, 0);
+        // The classic layout is symmetric, so the odds should be roughly too.
+        assert!((odds.iter().sum::<f32>() - 1.0).abs() < 0.05, "{:?}", odds);
+        assert!((odds[0] - odds[4]).abs() < 0.1, "{:?}", odds);
+        assert!((odds[1] - odds[3]).abs() < 0.1, "{:?}", odds);
+        // A slanted row of pegs across most of the panel rolls everything off to the right.
+        let ramp = PanelLayout {
+            pegs: (0..=22)
+                .map(|i| Vec2::new(-130.0 + i as f32 * 10.0, 100.0 - i as f32 * 5.0))
+                .collect(),
+            dividers: Vec::new(),
+        };
+        let odds = ramp.landing_odds(&physics, 200, 0);
+        assert!(
+            odds[0] + odds[1] == 0.0 && odds[3] + odds[4] > 0.9,
+            "{:?}",
+            odds
+        );
+        assert_eq!(odds, ramp.landing_odds(&physics, 200, 0));
+    }
+
+    #[test]
+    fn contain_keeps_pegs_off_the_trigger_zones() {
+        let mut layout = PanelLayout {
+            pegs: vec![Vec2::new(500.0, -300.0), Vec2::new(-500.0, 1000.0)],
+            dividers: vec![-400.0],
+        };
+        layout.contain();
+        assert_eq!(
+            layout.pegs,
+            [
+                Vec2::new(ARENA_WIDTH_FRAC_2, PEG_Y_MIN),
+                Vec2::new(-ARENA_WIDTH_FRAC_2, PEG_Y_MAX)
+            ]
+        );
+        assert_eq!(layout.dividers, [-ARENA_WIDTH_FRAC_2]);
+    }
+}
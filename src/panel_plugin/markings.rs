@@ -0,0 +1,119 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use super::{WorkerBall, WORKER_BALL_RADIUS};
+use crate::{
+    config::{GameConfig, ValidateConfig},
+    utils::{Participant, ParticipantMap, TileColor},
+};
+
+// Constants {{{
+
+/// The icons are regular polygons, with this many sides for each participant.
+const ICON_SIDES: ParticipantMap<u32> = ParticipantMap::new(3, 4, 5, 6, 7, 8, 9, 10);
+/// Circumradius of the icons as a fraction of the worker ball's radius.
+const ICON_SCALE: f32 = 0.7;
+const ICON_COLOR: Color = Color::WHITE;
+
+// Z-index, relative to the worker ball
+const OUTLINE_Z: f32 = -0.1;
+const ICON_Z: f32 = 0.1;
+
+// }}}
+
+/// Markings on the worker balls that make it easier to tell who they belong to, for when the balls
+/// are too small or the colors too similar.
+pub struct MarkingsPlugin;
+impl Plugin for MarkingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                update_outline_mesh
+                    .after(ValidateConfig)
+                    .run_if(resource_changed::<GameConfig>),
+                add_markings,
+            ),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct MarkingAssets {
+    outline: Mesh2dHandle,
+    outline_materials: ParticipantMap<Handle<ColorMaterial>>,
+    icons: ParticipantMap<Mesh2dHandle>,
+    icon_material: Handle<ColorMaterial>,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    colors: Res<ParticipantMap<TileColor>>,
+    config: Res<GameConfig>,
+) {
+    commands.insert_resource(MarkingAssets {
+        outline: meshes.add(outline_mesh(&config)).into(),
+        outline_materials: colors.map(|TileColor(color)| materials.add(color)),
+        icons: ICON_SIDES.map(|sides| {
+            meshes
+                .add(RegularPolygon::new(
+                    WORKER_BALL_RADIUS * ICON_SCALE,
+                    sides as usize,
+                ))
+                .into()
+        }),
+        icon_material: materials.add(ICON_COLOR),
+    });
+}
+fn outline_mesh(config: &GameConfig) -> Annulus {
+    let width = config.accessibility.worker_ball_outline.max(0.0);
+    Annulus::new(WORKER_BALL_RADIUS, WORKER_BALL_RADIUS + width)
+}
+/// Resize the outline mesh in place, which all the outlines already spawned share.
+fn update_outline_mesh(
+    mut meshes: ResMut<Assets<Mesh>>,
+    assets: Res<MarkingAssets>,
+    config: Res<GameConfig>,
+) {
+    meshes.insert(&assets.outline.0, outline_mesh(&config).into());
+}
+fn add_markings(
+    mut commands: Commands,
+    assets: Res<MarkingAssets>,
+    config: Res<GameConfig>,
+    balls: Query<(Entity, &Participant), Added<WorkerBall>>,
+) {
+    let config = &config.accessibility;
+    for (entity, &participant) in &balls {
+        if config.worker_ball_outline > 0.0 {
+            commands
+                .spawn((
+                    Name::new("Worker Ball Outline"),
+                    MaterialMesh2dBundle {
+                        mesh: assets.outline.clone(),
+                        material: assets.outline_materials[participant].clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, OUTLINE_Z),
+                        ..default()
+                    },
+                ))
+                .set_parent(entity);
+        }
+        if config.colorblind_icons {
+            commands
+                .spawn((
+                    Name::new("Worker Ball Icon"),
+                    MaterialMesh2dBundle {
+                        mesh: assets.icons[participant].clone(),
+                        material: assets.icon_material.clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, ICON_Z),
+                        ..default()
+                    },
+                ))
+                .set_parent(entity);
+        }
+    }
+}
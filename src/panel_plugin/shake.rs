@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::{PanelRoot, PanelRootSide, PanelVisuals, TriggerEvent, TriggerType};
+use crate::{
+    battlefield::RestartEvent,
+    config::GameConfig,
+    utils::{MatchId, Matches, Roster},
+};
+
+// Constants {{{
+
+const SHAKE_DURATION_SECS: f32 = 0.3;
+/// How far the panel moves at the start of the shake, in world units.
+const SHAKE_AMPLITUDE: f32 = 6.0;
+/// How fast the panel moves back and forth, in radians per second. The two axes use different
+/// frequencies so the panel doesn't just move along a line.
+const SHAKE_FREQUENCY: Vec2 = Vec2::new(90.0, 70.0);
+
+// }}}
+
+pub struct ShakePlugin;
+impl Plugin for ShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_shake.run_if(on_event::<TriggerEvent>()),
+                shake_panels.after(start_shake),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// Component for panel visuals that are being shaken.
+#[derive(Component)]
+struct PanelShake(Timer);
+
+/// Shake the panel a charged shot was triggered from.
+fn start_shake(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    visuals: Query<(Entity, &Parent), With<PanelVisuals>>,
+    roots: Query<(&PanelRoot, &MatchId)>,
+) {
+    let sides = events
+        .read()
+        .filter(|event| event.trigger_type == TriggerType::ChargedShot && event.ball.is_some())
+        .map(|event| PanelRootSide::for_participant_in(event.participant, &roster, &matches))
+        .collect::<Vec<_>>();
+    if sides.is_empty() || !config.accessibility.panel_shake {
+        return;
+    }
+    for (entity, parent) in &visuals {
+        let Ok((&PanelRoot(side), &match_id)) = roots.get(parent.get()) else {
+            continue;
+        };
+        if sides.contains(&(match_id, side)) {
+            commands
+                .entity(entity)
+                .insert(PanelShake(Timer::from_seconds(
+                    SHAKE_DURATION_SECS,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+/// Move the shaking panels' visuals around their resting place, with the motion dying down over the
+/// course of the shake. Only the visuals move, the colliders stay where they are.
+fn shake_panels(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut panels: Query<(Entity, &mut Transform, &mut PanelShake)>,
+) {
+    for (entity, mut transform, mut shake) in &mut panels {
+        shake.0.tick(time.delta());
+        if shake.0.finished() {
+            transform.translation = Vec3::ZERO;
+            commands.entity(entity).remove::<PanelShake>();
+            continue;
+        }
+        let elapsed = shake.0.elapsed_secs();
+        let strength = SHAKE_AMPLITUDE * shake.0.fraction_remaining();
+        let offset = Vec2::new(
+            (elapsed * SHAKE_FREQUENCY.x).sin(),
+            (elapsed * SHAKE_FREQUENCY.y).cos(),
+        ) * strength;
+        transform.translation = offset.extend(0.0);
+    }
+}
+fn restart(mut commands: Commands, mut panels: Query<(Entity, &mut Transform), With<PanelShake>>) {
+    for (entity, mut transform) in &mut panels {
+        transform.translation = Vec3::ZERO;
+        commands.entity(entity).remove::<PanelShake>();
+    }
+}
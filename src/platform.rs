@@ -0,0 +1,124 @@
+//! Integration with the window and the OS around it.
+//!
+//! Showing the round's progress on the taskbar button isn't supported by winit on any platform yet,
+//! and would need platform APIs of its own (`ITaskbarList3` on Windows, the Unity launcher API over
+//! D-Bus on Linux), so it's left out.
+
+use std::{f32::consts::TAU, time::Duration};
+
+use bevy::{
+    prelude::*, time::common_conditions::on_timer, window::PrimaryWindow, winit::WinitWindows,
+};
+use winit::window::Icon;
+
+use crate::{
+    battlefield::{RoundNumber, Tile},
+    utils::{BallColor, DisplayName, Participant, ParticipantMap, Roster},
+    WINDOW_TITLE,
+};
+
+// Constants {{{
+
+/// Width and height of the generated window icon in pixels.
+const ICON_SIZE: u32 = 64;
+/// Time in seconds between window title updates. Counting tiles every frame isn't worth it for
+/// something this rarely looked at.
+const TITLE_UPDATE_INTERVAL: f32 = 0.5;
+
+// }}}
+
+pub struct PlatformPlugin;
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                set_window_icon,
+                update_window_title
+                    .run_if(on_timer(Duration::from_secs_f32(TITLE_UPDATE_INTERVAL))),
+            ),
+        );
+    }
+}
+
+/// Set the window icon to a circle split into one slice per participant in the roster, drawn again
+/// whenever the roster or the colors change. MacOS ignores it in favor of the icon in the app
+/// bundle.
+fn set_window_icon(
+    mut done: Local<bool>,
+    windows: Option<NonSend<WinitWindows>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    roster: Res<Roster>,
+) {
+    // There are no windows at all when running headless, and otherwise they're created by the
+    // event loop, which might not have happened yet.
+    let Some(windows) = windows.filter(|x| !x.windows.is_empty()) else {
+        return;
+    };
+    if *done && !roster.is_changed() && !colors.is_changed() {
+        return;
+    }
+    *done = true;
+
+    let participants = Participant::ALL
+        .into_iter()
+        .filter(|&p| roster[p])
+        .collect::<Vec<_>>();
+    let half = ICON_SIZE as f32 / 2.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let offset = Vec2::new(x as f32 + 0.5 - half, half - y as f32 - 0.5);
+            if offset.length() > half {
+                rgba.extend([0; 4]);
+                continue;
+            }
+            // Clockwise from the top.
+            let turn = offset.x.atan2(offset.y).rem_euclid(TAU) / TAU;
+            let slice = (turn * participants.len() as f32) as usize;
+            let participant = participants[slice.min(participants.len() - 1)];
+            rgba.extend(colors[participant].0.to_srgba().to_u8_array());
+        }
+    }
+    let icon = match Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE) {
+        Ok(icon) => icon,
+        Err(e) => {
+            error!("Failed to create window icon: {}", e);
+            return;
+        }
+    };
+    for window in windows.windows.values() {
+        window.set_window_icon(Some(icon.clone()));
+    }
+}
+/// Show the round number and the participant holding the most tiles in the window title.
+fn update_window_title(
+    round: Res<RoundNumber>,
+    survivors: Res<ParticipantMap<bool>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    tiles: Query<&Participant, With<Tile>>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    let mut counts = ParticipantMap::splat(0);
+    for &participant in &tiles {
+        counts[participant] += 1;
+    }
+    let title = match Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .max_by_key(|&p| counts[p])
+    {
+        Some(leader) => format!(
+            "{} - Round {} - {} Leads",
+            WINDOW_TITLE, round.0, names[leader]
+        ),
+        None => format!("{} - Round {}", WINDOW_TITLE, round.0),
+    };
+    // Avoid triggering change detection, which would push the title to the OS every time.
+    if window.title != title {
+        window.title = title;
+    }
+}
@@ -0,0 +1,120 @@
+//! Presentation mode, for leaving the game running on a live stream without anyone at the keyboard.
+//!
+//! While it's locked, the mouse cursor is hidden over the window and every key is ignored, so a
+//! stray keypress can't pause the game, open the console or restart the round. Pressing the unlock
+//! chord set in the game config unlocks it to take over, and pressing it again locks it back. The
+//! debug hotkeys stay off the whole time, locked or not.
+
+use bevy::{
+    input::{keyboard::KeyboardInput, InputSystem},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::config::GameConfig;
+
+// Constants {{{
+
+/// Keys for poking at the game's internals, which have no place on a stream: the game snapshot, the
+/// aim path preview, the tuning export, stopping a demo recording and the console.
+const DEBUG_KEYS: [KeyCode; 5] = [
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F5,
+    KeyCode::F9,
+    KeyCode::Backquote,
+];
+
+// }}}
+
+pub struct PresentationPlugin;
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PresentationLock>().add_systems(
+            PreUpdate,
+            (
+                reset_lock.run_if(resource_changed::<GameConfig>),
+                filter_input,
+                show_cursor.run_if(resource_changed::<PresentationLock>),
+            )
+                .chain()
+                .after(InputSystem),
+        );
+    }
+}
+
+/// Whether presentation mode is keeping the keyboard out.
+#[derive(Resource, Default)]
+struct PresentationLock(bool);
+
+/// Lock up when presentation mode is turned on, and let go when it's turned off.
+fn reset_lock(
+    mut lock: ResMut<PresentationLock>,
+    mut was_enabled: Local<bool>,
+    config: Res<GameConfig>,
+) {
+    let enabled = config.presentation.enabled;
+    if enabled != *was_enabled {
+        *was_enabled = enabled;
+        lock.0 = enabled;
+    }
+}
+/// Take away the keys from everything else, before anything gets to see them.
+fn filter_input(
+    mut lock: ResMut<PresentationLock>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut events: ResMut<Events<KeyboardInput>>,
+    config: Res<GameConfig>,
+) {
+    if !config.presentation.enabled {
+        return;
+    }
+    let chord = &config.presentation.unlock_chord;
+    if !chord.is_empty()
+        && keys.all_pressed(chord.iter().copied())
+        && keys.any_just_pressed(chord.iter().copied())
+    {
+        lock.0 = !lock.0;
+        info!(
+            "Presentation mode {}.",
+            if lock.0 { "locked" } else { "unlocked" }
+        );
+        // The chord itself shouldn't do anything else.
+        keys.reset_all();
+        events.clear();
+        return;
+    }
+    if lock.0 {
+        // The chord keys are left alone, or they could never be held down together.
+        let others = keys
+            .get_pressed()
+            .chain(keys.get_just_released())
+            .filter(|x| !chord.contains(x))
+            .copied()
+            .collect::<Vec<_>>();
+        for key in others {
+            keys.reset(key);
+        }
+        events.clear();
+        return;
+    }
+    for key in DEBUG_KEYS {
+        keys.reset(key);
+    }
+    if events
+        .iter_current_update_events()
+        .any(|x| DEBUG_KEYS.contains(&x.key_code))
+    {
+        // Events can't be taken out one by one, so the rest are sent again in the same order.
+        let kept = events
+            .drain()
+            .filter(|x| !DEBUG_KEYS.contains(&x.key_code))
+            .collect::<Vec<_>>();
+        events.send_batch(kept);
+    }
+}
+fn show_cursor(lock: Res<PresentationLock>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    for mut window in &mut windows {
+        window.cursor.visible = !lock.0;
+    }
+}
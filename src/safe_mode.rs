@@ -0,0 +1,78 @@
+//! A stripped down way to run the game for when something in the full setup takes it down, such as
+//! a broken GPU driver or audio stack.
+//!
+//! A lock file is kept for as long as the game runs and removed once it exits cleanly, so finding
+//! one at launch means the previous session crashed. The game then starts in safe mode by itself,
+//! with the particle effects, sound and window integrations turned off, so the config can still be
+//! adjusted from inside the game. `--safe-mode` and `--no-safe-mode` override the check.
+
+use std::{fs, io::ErrorKind, path::Path};
+
+use bevy::prelude::*;
+
+use crate::utils::GameAssets;
+
+// Constants {{{
+
+/// Where the lock file is kept while the game runs, relative to the working directory.
+const LOCK_PATH: &str = "running.lock";
+const NOTICE_FONT_SIZE: f32 = 16.0;
+const NOTICE_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+// }}}
+
+/// Whether the game is running in safe mode.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct SafeMode(pub bool);
+
+/// Whether the previous session exited without removing its lock file.
+pub fn previous_session_crashed() -> bool {
+    Path::new(LOCK_PATH).exists()
+}
+/// Create the lock file, to be removed with [`release_lock`] on a clean exit.
+pub fn acquire_lock() {
+    if let Err(e) = fs::write(LOCK_PATH, std::process::id().to_string()) {
+        warn!("Failed to create {}: {}", LOCK_PATH, e);
+    }
+}
+pub fn release_lock() {
+    match fs::remove_file(LOCK_PATH) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            warn!("Failed to remove {}: {}", LOCK_PATH, e);
+        }
+        _ => (),
+    }
+}
+
+pub struct SafeModePlugin;
+impl Plugin for SafeModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Startup,
+            setup.run_if(|safe_mode: Res<SafeMode>| safe_mode.0),
+        );
+    }
+}
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    warn!("Running in safe mode. Launch with --no-safe-mode to turn everything back on.");
+    commands.spawn((
+        Name::new("Safe Mode Notice"),
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                bottom: Val::Px(8.0),
+                ..default()
+            },
+            ..TextBundle::from_section(
+                "Safe mode: particles, sound and window integrations are off",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: NOTICE_FONT_SIZE,
+                    color: NOTICE_COLOR,
+                },
+            )
+        },
+    ));
+}
@@ -0,0 +1,57 @@
+//! Signing files meant to be shared, like demo scripts and daily results, so they can be checked
+//! for having been tampered with before they're trusted.
+//!
+//! The signatures are keyed BLAKE3 hashes under a key kept in `signing.key`, which is made up at
+//! random the first time the game runs. Only whoever holds the key can sign a file or check one,
+//! so a leaderboard host keeps theirs to themselves and hands out copies only to the machines it
+//! trusts to record, such as its own streaming setup. A file signed anywhere else doesn't check
+//! out, no matter how carefully it was put together.
+
+use std::{fs, io::ErrorKind};
+
+use bevy::prelude::*;
+use rand::random;
+
+// Constants {{{
+
+/// Where the signing key is kept, relative to the working directory.
+const SIGNING_KEY_PATH: &str = "signing.key";
+
+// }}}
+
+/// The key shared files are signed and checked with.
+#[derive(Clone, Resource)]
+pub struct SigningKey(pub [u8; blake3::KEY_LEN]);
+impl SigningKey {
+    /// Read the key from [`SIGNING_KEY_PATH`], or make one up and save it there if there isn't
+    /// one yet. If it can't be read or saved, a key that only lasts for this session is used, and
+    /// nothing signed with it can be checked later.
+    pub fn load() -> Self {
+        match fs::read_to_string(SIGNING_KEY_PATH) {
+            Ok(text) => match blake3::Hash::from_hex(text.trim()) {
+                Ok(key) => return Self(*key.as_bytes()),
+                Err(e) => error!("Failed to read the key in {}: {}", SIGNING_KEY_PATH, e),
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let key = Self(random());
+                let hex = blake3::Hash::from_bytes(key.0).to_hex();
+                match fs::write(SIGNING_KEY_PATH, hex.as_str()) {
+                    Ok(()) => info!("Made up a new signing key in {}", SIGNING_KEY_PATH),
+                    Err(e) => error!("Failed to save {}: {}", SIGNING_KEY_PATH, e),
+                }
+                return key;
+            }
+            Err(e) => error!("Failed to read {}: {}", SIGNING_KEY_PATH, e),
+        }
+        warn!("Signing with a key that only lasts for this session.");
+        Self(random())
+    }
+    pub fn sign(&self, data: &[u8]) -> String {
+        blake3::keyed_hash(&self.0, data).to_hex().to_string()
+    }
+    /// Whether `signature` is what [`Self::sign`] makes of `data`. The comparison takes the same
+    /// time however much of it matches.
+    pub fn verify(&self, data: &[u8], signature: &str) -> bool {
+        blake3::Hash::from_hex(signature).is_ok_and(|x| x == blake3::keyed_hash(&self.0, data))
+    }
+}
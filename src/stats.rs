@@ -0,0 +1,169 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{MatchWinners, RoundWinner, ShotFiredEvent},
+    config::GameConfig,
+    cosmetics::TurretSkin,
+    demo::UnverifiedReplay,
+    utils::{Matches, Participant, ParticipantMap, Roster, Seed},
+};
+
+mod aggregator;
+mod bracket;
+mod daily;
+mod export;
+mod series;
+mod session;
+
+pub use aggregator::{ParticipantStats, RoundStats, StatsAggregator};
+pub use bracket::Bracket;
+pub use daily::verify_results;
+pub use series::Series;
+pub use session::SessionStats;
+
+// Constants {{{
+
+/// Where the stats are kept between sessions, relative to the working directory.
+const STATS_PATH: &str = "stats.ron";
+/// The number of shots kept on the all-time leaderboard.
+const RECORD_COUNT: usize = 10;
+
+// }}}
+
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            aggregator::StatsAggregatorPlugin,
+            bracket::BracketPlugin,
+            daily::DailyResultsPlugin,
+            export::StatsExportPlugin,
+            series::SeriesPlugin,
+            session::SessionStatsPlugin,
+        ))
+        .insert_resource(PersistentStats::load())
+        .add_systems(
+            Update,
+            (
+                record_win
+                    .in_set(StatsAggregator)
+                    .run_if(resource_changed::<RoundWinner>),
+                record_shots
+                    .in_set(StatsAggregator)
+                    .run_if(on_event::<ShotFiredEvent>()),
+            )
+                // A replay that doesn't check out could have been doctored to get on the board.
+                .run_if(not(resource_exists::<UnverifiedReplay>)),
+        )
+        .add_systems(
+            Update,
+            save_stats
+                .after(StatsAggregator)
+                .run_if(resource_changed::<PersistentStats>),
+        );
+    }
+}
+
+/// Stats that carry over between sessions.
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistentStats {
+    /// The number of rounds each color has won.
+    pub wins: ParticipantMap<u32>,
+    /// The skin each color has picked.
+    pub skins: ParticipantMap<TurretSkin>,
+    /// The biggest shots ever fired, biggest first.
+    pub biggest_shots: Vec<ShotRecord>,
+}
+/// A shot on the all-time leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShotRecord {
+    pub charge: u64,
+    pub participant: Participant,
+    /// The seed of the session the shot was fired in.
+    pub seed: u64,
+    /// When the shot was fired, in seconds since the Unix epoch.
+    pub fired_at: u64,
+}
+impl PersistentStats {
+    fn load() -> Self {
+        let result = fs::read_to_string(STATS_PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|x| ron::from_str(&x).map_err(|e| e.to_string()));
+        match result {
+            Ok(stats) => stats,
+            Err(e) => {
+                // A missing file just means this is the first session.
+                if fs::metadata(STATS_PATH).is_err_and(|e| e.kind() == ErrorKind::NotFound) {
+                    return Self::default();
+                }
+                error!("Failed to load stats from {}: {}", STATS_PATH, e);
+                Self::default()
+            }
+        }
+    }
+}
+
+fn record_win(
+    (winner, match_winners): (Res<RoundWinner>, Res<MatchWinners>),
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    mut stats: ResMut<PersistentStats>,
+) {
+    if winner.0.is_none() {
+        return;
+    }
+    for participant in match_winners.credited(&config, &roster, &matches) {
+        stats.wins[participant] += 1;
+    }
+}
+/// Put the shots big enough for the leaderboard on it. Only the shots that make it in change the
+/// stats, so the file isn't saved for every shot.
+fn record_shots(
+    mut events: EventReader<ShotFiredEvent>,
+    mut stats: ResMut<PersistentStats>,
+    seed: Res<Seed>,
+) {
+    for event in events.read() {
+        let records = &stats.biggest_shots;
+        if records.len() >= RECORD_COUNT && records.last().is_some_and(|x| x.charge >= event.charge)
+        {
+            continue;
+        }
+        // Ties go to the shot that got there first.
+        let index = records.partition_point(|x| x.charge >= event.charge);
+        let fired_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+        let records = &mut stats.biggest_shots;
+        records.insert(
+            index,
+            ShotRecord {
+                charge: event.charge,
+                participant: event.participant,
+                seed: seed.0,
+                fired_at,
+            },
+        );
+        records.truncate(RECORD_COUNT);
+    }
+}
+fn save_stats(stats: Res<PersistentStats>) {
+    // Nothing's changed yet if it was only just loaded.
+    if stats.is_added() {
+        return;
+    }
+    let result = ron::ser::to_string_pretty(&*stats, default())
+        .map_err(|e| e.to_string())
+        .and_then(|x| fs::write(STATS_PATH, x).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        error!("Failed to save stats to {}: {}", STATS_PATH, e);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, time::Stopwatch};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    battlefield::{
+        Charge, ChargeGeneratedEvent, EliminationEvent, RestartEvent, ShotFiredEvent,
+        TileConvertedEvent, Turret,
+    },
+    config::GameConfig,
+    panel_plugin::TriggerEvent,
+    utils::{Backlog, Participant, ParticipantMap},
+};
+
+pub struct StatsAggregatorPlugin;
+impl Plugin for StatsAggregatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RoundStats>()
+            .init_resource::<Backlog<TileConvertedEvent>>()
+            .init_resource::<Backlog<TriggerEvent>>()
+            .init_resource::<Backlog<ShotFiredEvent>>()
+            .init_resource::<Backlog<ChargeGeneratedEvent>>()
+            .add_systems(
+                Update,
+                (
+                    tick_round_clock.run_if(in_state(AppState::Playing)),
+                    (
+                        queue_events::<TileConvertedEvent>,
+                        queue_events::<TriggerEvent>,
+                        queue_events::<ShotFiredEvent>,
+                        queue_events::<ChargeGeneratedEvent>,
+                    )
+                        .before(count_conversions)
+                        .before(count_triggers)
+                        .before(count_shots)
+                        .before(count_generated_charge),
+                    count_conversions,
+                    count_triggers,
+                    count_shots,
+                    count_generated_charge,
+                    count_eliminations.run_if(on_event::<EliminationEvent>()),
+                    track_turret_charges.run_if(in_state(AppState::Playing)),
+                    restart.run_if(on_event::<RestartEvent>()),
+                )
+                    .in_set(StatsAggregator),
+            );
+    }
+}
+
+/// The systems that turn gameplay events into [`RoundStats`]. Anything reading the stats in the
+/// same frame should run after this set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct StatsAggregator;
+
+/// How much charge a single worker ball has generated for its turret over the round.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BallContribution {
+    pub participant: Participant,
+    pub number: usize,
+    pub generated: u64,
+}
+/// Running totals for a single participant over the current round.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantStats {
+    pub tiles_captured: u32,
+    pub tiles_lost: u32,
+    /// The number of times the participant's marbles landed in a trigger zone.
+    pub triggers: u32,
+    pub shots_fired: u32,
+    /// The total charge of every bullet the participant fired.
+    pub charge_fired: u64,
+    /// The total charge the participant's worker balls generated.
+    pub charge_generated: u64,
+    /// Time in seconds since the round started when the participant was eliminated.
+    pub eliminated_at: Option<f32>,
+    /// The charge the participant's turret is holding right now, or 0 once it's gone.
+    pub turret_charge: u64,
+}
+/// Stats for the current round, aggregated from gameplay events by the [`StatsAggregator`] set so
+/// that features don't have to keep their own counters.
+#[derive(Resource, Default)]
+pub struct RoundStats {
+    /// Time since the round started, stopped once it's over.
+    clock: Stopwatch,
+    participants: ParticipantMap<ParticipantStats>,
+    /// Worker balls are reused after they land in a trigger zone, so each entry covers every trip
+    /// the ball made down the panel.
+    balls: HashMap<Entity, BallContribution>,
+}
+impl RoundStats {
+    pub fn elapsed_secs(&self) -> f32 {
+        self.clock.elapsed_secs()
+    }
+    pub fn get(&self, participant: Participant) -> &ParticipantStats {
+        self.participants.get(participant)
+    }
+    /// The participant whose turret is holding the most charge, along with the charge.
+    pub fn leading_charge(&self) -> Option<(Participant, u64)> {
+        Participant::ALL
+            .into_iter()
+            .map(|p| (p, self.participants[p].turret_charge))
+            .filter(|&(_, charge)| charge > 0)
+            .max_by_key(|&(_, charge)| charge)
+    }
+    /// The ball that generated the most charge this round, if any generated anything at all.
+    pub fn mvp(&self) -> Option<&BallContribution> {
+        self.balls
+            .values()
+            .filter(|x| x.generated > 0)
+            .max_by_key(|x| x.generated)
+    }
+}
+
+fn tick_round_clock(mut stats: ResMut<RoundStats>, time: Res<Time>) {
+    stats.clock.tick(time.delta());
+}
+/// The number of queued events of each kind to count this frame. Everything is counted once the
+/// round is over, so the stats are complete for the game over screen.
+fn stats_budget(config: &GameConfig, state: &State<AppState>) -> usize {
+    if *state.get() == AppState::Playing {
+        config.performance.stats_events_per_frame as usize
+    } else {
+        usize::MAX
+    }
+}
+fn queue_events<E: Event + Clone>(mut backlog: ResMut<Backlog<E>>, mut events: EventReader<E>) {
+    backlog.extend(events.read().cloned());
+}
+fn count_conversions(
+    mut stats: ResMut<RoundStats>,
+    mut backlog: ResMut<Backlog<TileConvertedEvent>>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+) {
+    for event in backlog.take(stats_budget(&config, &state)) {
+        stats.participants[event.to].tiles_captured += 1;
+        stats.participants[event.from].tiles_lost += 1;
+    }
+}
+fn count_triggers(
+    mut stats: ResMut<RoundStats>,
+    mut backlog: ResMut<Backlog<TriggerEvent>>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+) {
+    for event in backlog.take(stats_budget(&config, &state)) {
+        stats.participants[event.participant].triggers += 1;
+    }
+}
+fn count_shots(
+    mut stats: ResMut<RoundStats>,
+    mut backlog: ResMut<Backlog<ShotFiredEvent>>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+) {
+    for event in backlog.take(stats_budget(&config, &state)) {
+        let participant = &mut stats.participants[event.participant];
+        participant.shots_fired += 1;
+        participant.charge_fired = participant.charge_fired.saturating_add(event.charge);
+    }
+}
+fn count_generated_charge(
+    mut stats: ResMut<RoundStats>,
+    mut backlog: ResMut<Backlog<ChargeGeneratedEvent>>,
+    config: Res<GameConfig>,
+    state: Res<State<AppState>>,
+) {
+    for event in backlog.take(stats_budget(&config, &state)) {
+        let participant = &mut stats.participants[event.participant];
+        participant.charge_generated = participant.charge_generated.saturating_add(event.generated);
+        let contribution = stats.balls.entry(event.ball).or_insert(BallContribution {
+            participant: event.owner,
+            number: event.number,
+            generated: 0,
+        });
+        contribution.generated = contribution.generated.saturating_add(event.generated);
+    }
+}
+fn count_eliminations(mut stats: ResMut<RoundStats>, mut events: EventReader<EliminationEvent>) {
+    let now = stats.elapsed_secs();
+    for event in events.read() {
+        stats.participants[event.participant]
+            .eliminated_at
+            .get_or_insert(now);
+    }
+}
+fn track_turret_charges(
+    mut stats: ResMut<RoundStats>,
+    turrets: Query<(&Participant, &Charge), With<Turret>>,
+) {
+    let mut charges = ParticipantMap::splat(0);
+    for (&participant, charge) in &turrets {
+        charges[participant] = charge.value();
+    }
+    for participant in Participant::ALL {
+        // Avoid triggering change detection when nothing moved.
+        if stats.participants[participant].turret_charge != charges[participant] {
+            stats.participants[participant].turret_charge = charges[participant];
+        }
+    }
+}
+fn restart(
+    mut stats: ResMut<RoundStats>,
+    mut conversions: ResMut<Backlog<TileConvertedEvent>>,
+    mut triggers: ResMut<Backlog<TriggerEvent>>,
+    mut shots: ResMut<Backlog<ShotFiredEvent>>,
+    mut charges: ResMut<Backlog<ChargeGeneratedEvent>>,
+) {
+    *stats = RoundStats::default();
+    conversions.clear();
+    triggers.clear();
+    shots.clear();
+    charges.clear();
+}
@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+
+use super::StatsAggregator;
+use crate::{
+    battlefield::{EliminationEvent, MatchWinners, RoundWinner},
+    config::GameConfig,
+    game_command::{execute_commands, GameCommand},
+    utils::{MatchId, Matches, Participant, ParticipantMap, Roster},
+};
+
+// Constants {{{
+
+/// The number of participants it takes to fill the bracket.
+const BRACKET_SIZE: usize = 8;
+/// The number of participants in each heat.
+const HEAT_SIZE: usize = 4;
+/// The number of places in each opening heat that go through to the final.
+const ADVANCING_PER_HEAT: usize = 2;
+
+// }}}
+
+pub struct BracketPlugin;
+impl Plugin for BracketPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bracket>().add_systems(
+            Update,
+            (
+                prepare_heat
+                    .before(execute_commands)
+                    .run_if(on_event::<GameCommand>()),
+                track_eliminations.run_if(on_event::<EliminationEvent>()),
+                record_heat
+                    .after(track_eliminations)
+                    .in_set(StatsAggregator)
+                    .run_if(resource_changed::<RoundWinner>),
+            ),
+        );
+    }
+}
+
+/// A knockout bracket for 8 participants, played 4 at a time. The two opening heats each send their
+/// top two through to the final, which decides the champion. The opening heats can be played side
+/// by side, each as a match of its own. See [`crate::config::BracketConfig`].
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Bracket {
+    /// Everyone in the bracket, so they can all be brought back for the next one.
+    field: Roster,
+    /// The matches everyone played in before the bracket, brought back along with them.
+    field_matches: Matches,
+    /// The opening heats, followed by the final. The final's entrants are filled in as the opening
+    /// heats are played. Empty while no bracket is being played.
+    pub heats: Vec<Heat>,
+    /// The heat being played, as an index into `heats`.
+    pub current: usize,
+    /// The number of heats being played at once, starting from `current`.
+    concurrent: usize,
+    /// Who won the final, once it's been played.
+    pub champion: Option<Participant>,
+    /// The entrants of the current heat in the order they were knocked out.
+    eliminated: Vec<Participant>,
+}
+impl Bracket {
+    fn new(field: Roster, field_matches: Matches) -> Self {
+        let entrants = Participant::ALL
+            .into_iter()
+            .filter(|&x| field[x])
+            .collect::<Vec<_>>();
+        let mut heats = entrants
+            .chunks(HEAT_SIZE)
+            .map(|x| Heat {
+                entrants: x.to_vec(),
+                standings: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        heats.push(Heat::default());
+        Self {
+            field,
+            field_matches,
+            heats,
+            ..default()
+        }
+    }
+    pub fn is_running(&self) -> bool {
+        !self.heats.is_empty()
+    }
+    /// Whether `heat` is being played, or is up next between rounds.
+    pub fn is_current(&self, heat: usize) -> bool {
+        (self.current..self.current + self.concurrent).contains(&heat)
+    }
+    /// Whether `heat` is the final rather than one of the opening heats.
+    pub fn is_final(&self, heat: usize) -> bool {
+        heat + 1 == self.heats.len()
+    }
+}
+/// One round of the bracket.
+#[derive(Debug, Clone, Default)]
+pub struct Heat {
+    pub entrants: Vec<Participant>,
+    /// The entrants from first place to last, once the heat has been played.
+    pub standings: Vec<Participant>,
+}
+impl Heat {
+    /// The entrants going through to the final, once the heat has been played.
+    pub fn advancing(&self) -> &[Participant] {
+        &self.standings[..self.standings.len().min(ADVANCING_PER_HEAT)]
+    }
+}
+
+/// Line up the participants of the next heat as the round restarts, starting a new bracket if there
+/// isn't one going. This overrides the roster and the matches picked on the game over screen, and is
+/// done before the restart command is carried out so the whole round is set up with the heat's
+/// lineup. Opening heats played side by side each get a match of their own.
+fn prepare_heat(
+    mut events: EventReader<GameCommand>,
+    config: Res<GameConfig>,
+    mut bracket: ResMut<Bracket>,
+    (mut roster, mut matches): (ResMut<Roster>, ResMut<Matches>),
+) {
+    if !events.read().any(|&x| x == GameCommand::Restart) {
+        return;
+    }
+    if !config.bracket.enabled {
+        if bracket.is_running() {
+            *roster = bracket.field;
+            *matches = bracket.field_matches;
+            *bracket = Bracket::default();
+        }
+        return;
+    }
+    if bracket.champion.is_some() {
+        *roster = bracket.field;
+        *matches = bracket.field_matches;
+        *bracket = Bracket::default();
+    }
+    if !bracket.is_running() {
+        if roster.count() as usize != BRACKET_SIZE {
+            warn!(
+                "The bracket takes {} participants, but there are {}. Playing a regular round.",
+                BRACKET_SIZE,
+                roster.count()
+            );
+            return;
+        }
+        *bracket = Bracket::new(*roster, *matches);
+    }
+    bracket.concurrent = if config.bracket.side_by_side && !bracket.is_final(bracket.current) {
+        // The rest of the opening heats, leaving out the final.
+        bracket.heats.len() - 1 - bracket.current
+    } else {
+        1
+    };
+    let mut lineup = Roster(ParticipantMap::splat(false));
+    let mut heat_matches = Matches::default();
+    let heats = bracket.current..bracket.current + bracket.concurrent;
+    for (i, heat) in bracket.heats[heats].iter().enumerate() {
+        for &participant in &heat.entrants {
+            lineup[participant] = true;
+            heat_matches.0[participant] = MatchId(i as u8);
+        }
+    }
+    *roster = lineup;
+    *matches = heat_matches;
+    bracket.eliminated.clear();
+}
+fn track_eliminations(mut bracket: ResMut<Bracket>, mut events: EventReader<EliminationEvent>) {
+    for event in events.read() {
+        // A turret can come back and be knocked out again, and only the last time counts.
+        bracket.eliminated.retain(|&x| x != event.participant);
+        bracket.eliminated.push(event.participant);
+    }
+}
+/// Rank the entrants of the heats that were just won and move on to the next one. The winner of
+/// each comes first, then anyone else still standing, then the rest in reverse order of
+/// elimination.
+fn record_heat(
+    (winner, match_winners): (Res<RoundWinner>, Res<MatchWinners>),
+    survivors: Res<ParticipantMap<bool>>,
+    mut bracket: ResMut<Bracket>,
+) {
+    if winner.0.is_none() {
+        return;
+    }
+    if !bracket.is_running() || bracket.champion.is_some() {
+        return;
+    }
+    // The heats played side by side are in matches numbered in the same order.
+    for (i, heat) in (bracket.current..bracket.current + bracket.concurrent).enumerate() {
+        let Some(&winner) = match_winners.0.get(MatchId(i as u8)) else {
+            continue;
+        };
+        let entrants = bracket.heats[heat].entrants.clone();
+        let still_standing = entrants.iter().filter(|&&x| survivors[x]);
+        let knocked_out = bracket.eliminated.iter().rev();
+        let mut standings = vec![winner];
+        // Anyone who wasn't seen leaving goes last.
+        for &participant in still_standing.chain(knocked_out).chain(&entrants) {
+            if entrants.contains(&participant) && !standings.contains(&participant) {
+                standings.push(participant);
+            }
+        }
+        bracket.heats[heat].standings = standings;
+        if bracket.is_final(heat) {
+            bracket.champion = Some(winner);
+            return;
+        }
+        let advancing = bracket.heats[heat].advancing().to_vec();
+        let last = bracket.heats.len() - 1;
+        bracket.heats[last].entrants.extend(advancing);
+    }
+    bracket.current += bracket.concurrent;
+}
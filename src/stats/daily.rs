@@ -0,0 +1,188 @@
+//! A signed record of how every round played on a day ended, for a leaderboard to take in.
+//!
+//! With `daily_results` turned on in the game config, each round is added to
+//! `<directory>/<date>.ron` as it's won, along with the seed and the hash of the game config it
+//! was played with, and the file is signed again with the game's [`SigningKey`]. Launch with
+//! `--verify-results <path>` to check a results file against the key before putting it up. A file
+//! that was edited since it was signed, or signed with another key, is turned down.
+
+use std::{
+    fs,
+    io::ErrorKind,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::StatsAggregator;
+use crate::{
+    battlefield::{RoundNumber, RoundWinner, Tile},
+    config::GameConfig,
+    demo::{config_hash, RoundOutcome, UnverifiedReplay},
+    locale::Locale,
+    signing::SigningKey,
+    utils::{Participant, Seed},
+};
+
+pub struct DailyResultsPlugin;
+impl Plugin for DailyResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            record_round
+                .after(StatsAggregator)
+                .run_if(|config: Res<GameConfig>| config.daily_results.enabled)
+                .run_if(resource_changed::<RoundWinner>)
+                .run_if(not(resource_exists::<UnverifiedReplay>)),
+        );
+    }
+}
+
+/// Every round played on a day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DailyResults {
+    /// The UTC date the rounds were played on, e.g. 2026-10-15.
+    date: String,
+    rounds: Vec<DailyRound>,
+    /// The signature over everything above, to tell whether the results were edited after they
+    /// were saved.
+    #[serde(default)]
+    signature: String,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DailyRound {
+    /// The seed of the session the round was played in.
+    seed: u64,
+    /// The hash of the game config the round was played with.
+    config_hash: String,
+    outcome: RoundOutcome,
+}
+impl DailyResults {
+    /// Everything the signature covers, written out the way it's saved.
+    fn signed_contents(&self) -> Result<String, ron::Error> {
+        ron::to_string(&(&self.date, &self.rounds))
+    }
+    fn sign(&mut self, key: &SigningKey) -> Result<(), ron::Error> {
+        self.signature = key.sign(self.signed_contents()?.as_bytes());
+        Ok(())
+    }
+    /// Check that the results were signed with `key` and haven't been edited since.
+    fn verify(&self, key: &SigningKey) -> Result<(), String> {
+        if self.signature.is_empty() {
+            return Err("they aren't signed".to_string());
+        }
+        let contents = self.signed_contents().map_err(|e| e.to_string())?;
+        if !key.verify(contents.as_bytes(), &self.signature) {
+            return Err(
+                "their signature doesn't match their contents under the key in signing.key, so \
+                 they were either edited after they were saved or signed with another key"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+    fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&text).map_err(|e| e.to_string())
+    }
+}
+
+/// Check the results file at `path` against `key` for `--verify-results`, and say whether they
+/// check out.
+pub fn verify_results(path: &Path, key: &SigningKey) -> AppExit {
+    match DailyResults::load(path).and_then(|x| x.verify(key).map(|_| x)) {
+        Ok(results) => {
+            println!(
+                "{} checks out: {} rounds played on {}",
+                path.display(),
+                results.rounds.len(),
+                results.date
+            );
+            AppExit::Success
+        }
+        Err(e) => {
+            eprintln!("{} doesn't check out: {}", path.display(), e);
+            AppExit::from_code(1)
+        }
+    }
+}
+
+/// Add the round that was just won to today's results and sign them again. Results that don't check
+/// out are left alone, so an edited file can't be signed over.
+fn record_round(
+    config: Res<GameConfig>,
+    registry: Res<AppTypeRegistry>,
+    key: Res<SigningKey>,
+    seed: Res<Seed>,
+    winner: Res<RoundWinner>,
+    round: Res<RoundNumber>,
+    tiles: Query<&Participant, With<Tile>>,
+) {
+    let Some(winner) = winner.0 else {
+        return;
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+    // Read by other programs, so it's written the same whatever the locale.
+    let date = Locale::Iso.format_date(now);
+    let directory = &config.daily_results.directory;
+    let path = Path::new(directory).join(format!("{}.ron", date));
+    let result = match DailyResults::load(&path) {
+        Ok(results) => results.verify(&key).map(|_| results),
+        Err(_) if fs::metadata(&path).is_err_and(|e| e.kind() == ErrorKind::NotFound) => {
+            Ok(DailyResults { date, ..default() })
+        }
+        Err(e) => Err(e),
+    }
+    .and_then(|mut results| {
+        let config_hash = config_hash(&config, &registry.read()).map_err(|e| e.to_string())?;
+        results.rounds.push(DailyRound {
+            seed: seed.0,
+            config_hash,
+            outcome: RoundOutcome::count(round.0, winner, &tiles),
+        });
+        results.sign(&key).map_err(|e| e.to_string())?;
+        let text = ron::ser::to_string_pretty(&results, default()).map_err(|e| e.to_string())?;
+        fs::create_dir_all(directory)
+            .and_then(|_| fs::write(&path, text))
+            .map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(()) => info!("Added round {} to {}", round.0, path.display()),
+        Err(e) => error!(
+            "Failed to add round {} to {}: {}",
+            round.0,
+            path.display(),
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_results_are_turned_down() {
+        let key = SigningKey([1; blake3::KEY_LEN]);
+        let mut results = DailyResults {
+            date: "2026-10-15".to_string(),
+            rounds: vec![DailyRound {
+                seed: 42,
+                config_hash: String::new(),
+                outcome: RoundOutcome::count(1, Participant::A, &[Participant::A]),
+            }],
+            ..default()
+        };
+        assert!(results.verify(&key).is_err());
+        results.sign(&key).unwrap();
+        assert!(results.verify(&key).is_ok());
+        assert!(results.verify(&SigningKey([2; blake3::KEY_LEN])).is_err());
+
+        results.rounds[0].outcome.winner = Participant::B;
+        assert!(results.verify(&key).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+
+use super::{RoundStats, StatsAggregator};
+use crate::{
+    app_state::AppState,
+    battlefield::RoundNumber,
+    config::GameConfig,
+    utils::{DisplayName, Participant, ParticipantMap, Roster},
+};
+
+pub struct StatsExportPlugin;
+impl Plugin for StatsExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            export_stats
+                .after(StatsAggregator)
+                .run_if(in_state(AppState::GameOver)),
+        );
+    }
+}
+
+/// Save the stats of the round that just ended as a CSV file, written for the configured locale so
+/// it opens straight into a spreadsheet. Waits for the game over screen, when everything has been
+/// counted.
+fn export_stats(
+    mut exported: Local<Option<u32>>,
+    config: Res<GameConfig>,
+    round: Res<RoundNumber>,
+    stats: Res<RoundStats>,
+    names: Res<ParticipantMap<DisplayName>>,
+    roster: Res<Roster>,
+) {
+    if !config.stats_export.enabled || exported.replace(round.0) == Some(round.0) {
+        return;
+    }
+    let locale = config.locale;
+    let ended_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+    let ended_at = locale.format_date_time(ended_at);
+    let length = locale.format_decimal(stats.elapsed_secs() as f64, 1);
+    let mut csv = locale.csv_row(&[
+        "Round",
+        "Ended at (UTC)",
+        "Round length (s)",
+        "Participant",
+        "Tiles captured",
+        "Tiles lost",
+        "Triggers",
+        "Shots fired",
+        "Charge fired",
+        "Charge generated",
+        "Eliminated at (s)",
+        "Turret charge",
+    ]);
+    for participant in Participant::ALL.into_iter().filter(|&x| roster[x]) {
+        let x = stats.get(participant);
+        csv.push_str(
+            &locale.csv_row(&[
+                round.0.to_string(),
+                ended_at.clone(),
+                length.clone(),
+                names[participant].to_string(),
+                x.tiles_captured.to_string(),
+                x.tiles_lost.to_string(),
+                x.triggers.to_string(),
+                x.shots_fired.to_string(),
+                x.charge_fired.to_string(),
+                x.charge_generated.to_string(),
+                x.eliminated_at
+                    .map_or(String::new(), |x| locale.format_decimal(x as f64, 1)),
+                x.turret_charge.to_string(),
+            ]),
+        );
+    }
+    let directory = &config.stats_export.directory;
+    let path = Path::new(directory).join(format!("round-{}.csv", round.0));
+    let result = fs::create_dir_all(directory).and_then(|_| fs::write(&path, csv));
+    match result {
+        Ok(()) => info!("Saved the round stats to {}", path.display()),
+        Err(e) => error!(
+            "Failed to save the round stats to {}: {}",
+            path.display(),
+            e
+        ),
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use super::StatsAggregator;
+use crate::{
+    battlefield::{MatchWinners, RestartEvent, RoundWinner},
+    config::GameConfig,
+    utils::{Matches, Participant, ParticipantMap, Roster},
+};
+
+pub struct SeriesPlugin;
+impl Plugin for SeriesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Series>().add_systems(
+            Update,
+            (
+                record_series_win
+                    .in_set(StatsAggregator)
+                    .run_if(resource_changed::<RoundWinner>),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The rounds won so far in the current series, kept across restarts until someone has won enough
+/// of them. See [`crate::config::SeriesConfig`].
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Series {
+    pub wins: ParticipantMap<u32>,
+    /// Who won the series, once it's over.
+    pub champion: Option<Participant>,
+}
+
+fn record_series_win(
+    (winner, match_winners): (Res<RoundWinner>, Res<MatchWinners>),
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    mut series: ResMut<Series>,
+) {
+    if winner.0.is_none() {
+        return;
+    }
+    if !config.series.enabled || series.champion.is_some() {
+        return;
+    }
+    for participant in match_winners.credited(&config, &roster, &matches) {
+        series.wins[participant] += 1;
+    }
+    // With more than one match, the first winner to get there takes the series.
+    series.champion = match_winners
+        .0
+        .iter()
+        .map(|(_, &x)| x)
+        .find(|&x| series.wins[x] >= config.series.wins_needed);
+}
+/// Start a new series once the last one has been won.
+fn restart(mut series: ResMut<Series>) {
+    if series.champion.is_some() {
+        *series = Series::default();
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use super::StatsAggregator;
+use crate::{
+    battlefield::{MatchWinners, RoundWinner, ShotFiredEvent},
+    config::GameConfig,
+    utils::{Matches, Participant, ParticipantMap, Roster},
+};
+
+pub struct SessionStatsPlugin;
+impl Plugin for SessionStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SessionStats>().add_systems(
+            Update,
+            (
+                count_rounds.run_if(resource_changed::<RoundWinner>),
+                track_biggest_shot.run_if(on_event::<ShotFiredEvent>()),
+            )
+                .in_set(StatsAggregator),
+        );
+    }
+}
+
+/// Stats for everything played since launch, for the summary shown on the way out.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct SessionStats {
+    pub rounds_played: u32,
+    pub wins: ParticipantMap<u32>,
+    /// Who fired the biggest shot, and its charge.
+    pub biggest_shot: Option<(Participant, u64)>,
+}
+
+fn count_rounds(
+    (winner, match_winners): (Res<RoundWinner>, Res<MatchWinners>),
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
+    mut session: ResMut<SessionStats>,
+) {
+    if winner.0.is_none() {
+        return;
+    }
+    session.rounds_played += 1;
+    for participant in match_winners.credited(&config, &roster, &matches) {
+        session.wins[participant] += 1;
+    }
+}
+fn track_biggest_shot(mut session: ResMut<SessionStats>, mut events: EventReader<ShotFiredEvent>) {
+    for event in events.read() {
+        if session
+            .biggest_shot
+            .is_none_or(|(_, charge)| event.charge > charge)
+        {
+            session.biggest_shot = Some((event.participant, event.charge));
+        }
+    }
+}
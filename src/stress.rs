@@ -0,0 +1,101 @@
+//! A stress test started with `--stress`. The panels are scaled up and keep dropping worker balls
+//! until there are hundreds of them, with an overlay of every diagnostic the game records, so the
+//! pooling and per-frame budgets can be checked under far more load than a real round puts on them.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use std::time::Duration;
+
+use crate::{cli::LaunchOptions, panel_plugin::WorkerBallNumber, utils::GameAssets};
+
+// Constants {{{
+
+/// How much bigger the panels are, along with how far the camera zooms out to fit them.
+pub const STRESS_PANEL_SCALE: f32 = 1.75;
+/// The number of worker balls each participant gets, in place of
+/// [`crate::panel_plugin::WORKER_BALL_COUNT_MAX`].
+pub const STRESS_WORKER_BALL_COUNT_MAX: usize = 300;
+/// Time in seconds between worker ball drops, in place of the preset's.
+pub const STRESS_WORKER_BALL_SPAWN_SECS: f32 = 0.25;
+const OVERLAY_FONT_SIZE: f32 = 18.0;
+const OVERLAY_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+/// Time in seconds between updates of the overlay, so it can be read.
+const OVERLAY_REFRESH_SECS: f32 = 0.25;
+
+// }}}
+
+pub struct StressPlugin;
+impl Plugin for StressPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().resource::<LaunchOptions>().stress {
+            return;
+        }
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+            .add_systems(Startup, setup)
+            .add_systems(PostStartup, zoom_out)
+            .add_systems(
+                Update,
+                update_overlay.run_if(on_timer(Duration::from_secs_f32(OVERLAY_REFRESH_SECS))),
+            );
+    }
+}
+
+#[derive(Component)]
+struct StressOverlay;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    warn!("Running the stress test. Launch without --stress to play normally.");
+    commands.spawn((
+        StressOverlay,
+        Name::new("Stress Test Overlay"),
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            background_color: OVERLAY_BACKGROUND.into(),
+            z_index: ZIndex::Global(2),
+            ..TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: OVERLAY_FONT_SIZE,
+                    color: OVERLAY_TEXT_COLOR,
+                },
+            )
+        },
+    ));
+}
+/// Fit the scaled up panels on screen.
+fn zoom_out(mut projections: Query<&mut OrthographicProjection>) {
+    for mut projection in &mut projections {
+        projection.scale *= STRESS_PANEL_SCALE;
+    }
+}
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    balls: Query<(), With<WorkerBallNumber>>,
+    mut overlays: Query<&mut Text, With<StressOverlay>>,
+) {
+    let mut value = format!("Stress test\nworker balls: {}", balls.iter().count());
+    for diagnostic in diagnostics.iter() {
+        if let Some(measurement) = diagnostic.smoothed() {
+            value.push_str(&format!(
+                "\n{}: {:.1}{}",
+                diagnostic.path(),
+                measurement,
+                diagnostic.suffix
+            ));
+        }
+    }
+    for mut text in &mut overlays {
+        text.sections[0].value.clone_from(&value);
+    }
+}
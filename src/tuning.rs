@@ -0,0 +1,106 @@
+//! Sharing a balanced set of rules as a RON file.
+//!
+//! Press `F5` to save the current [`GamePreset`] and [`GameConfig`], after tweaking them in the
+//! inspector for example, to `tuning.ron`. Launch with `--tuning <path>` to play with a saved file,
+//! which takes precedence over `--preset`.
+
+use std::{fs, path::Path};
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        TypeRegistry,
+    },
+};
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeSeed;
+
+use crate::{
+    cli::LaunchOptions,
+    config::{GameConfig, GamePreset},
+    ui::AnnouncementEvent,
+};
+
+// Constants {{{
+
+const EXPORT_KEY: KeyCode = KeyCode::F5;
+const EXPORT_PATH: &str = "tuning.ron";
+const EXPORT_ANNOUNCEMENT_COLOR: Color = Color::WHITE;
+
+// }}}
+
+pub struct TuningPlugin;
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Tuning>()
+            .add_systems(Update, export_tuning.run_if(input_just_pressed(EXPORT_KEY)));
+
+        let Some(path) = app.world().resource::<LaunchOptions>().tuning.clone() else {
+            return;
+        };
+        let registry = app.world().resource::<AppTypeRegistry>().clone();
+        let tuning = Tuning::load(&path, &registry.read());
+        match tuning {
+            Ok(tuning) => {
+                info!("Loaded tuning from {}.", path.display());
+                app.insert_resource(tuning.preset)
+                    .insert_resource(tuning.config);
+            }
+            Err(e) => error!("Failed to load tuning from {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Everything that's saved to a tuning file.
+#[derive(Reflect)]
+struct Tuning {
+    preset: GamePreset,
+    config: GameConfig,
+}
+impl Tuning {
+    fn load(path: &Path, registry: &TypeRegistry) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let registration = registry
+            .get(std::any::TypeId::of::<Self>())
+            .ok_or("the tuning type isn't registered")?;
+        let mut deserializer = ron::Deserializer::from_str(&text).map_err(|e| e.to_string())?;
+        let reflected = TypedReflectDeserializer::new(registration, registry)
+            .deserialize(&mut deserializer)
+            .map_err(|e| e.to_string())?;
+        Self::from_reflect(&*reflected).ok_or_else(|| "unexpected tuning format".to_string())
+    }
+    fn save(&self, path: &Path, registry: &TypeRegistry) -> Result<(), String> {
+        let serializer = TypedReflectSerializer::new(self, registry);
+        let text = ron::ser::to_string_pretty(&serializer, PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+fn export_tuning(
+    registry: Res<AppTypeRegistry>,
+    preset: Res<GamePreset>,
+    config: Res<GameConfig>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+) {
+    let tuning = Tuning {
+        preset: *preset,
+        config: config.clone(),
+    };
+    let message = match tuning.save(Path::new(EXPORT_PATH), &registry.read()) {
+        Ok(()) => {
+            info!("Saved tuning to {}.", EXPORT_PATH);
+            format!("Tuning Saved to {}", EXPORT_PATH)
+        }
+        Err(e) => {
+            error!("Failed to save tuning to {}: {}", EXPORT_PATH, e);
+            "Failed to Save Tuning".to_string()
+        }
+    };
+    announcements.send(AnnouncementEvent {
+        message,
+        color: EXPORT_ANNOUNCEMENT_COLOR,
+    });
+}
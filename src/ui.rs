@@ -1,22 +1,67 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use crate::{
-    battlefield::{game_is_going, EliminationEvent, RestartEvent},
-    utils::{BallColor, ParticipantMap},
+    app_state::AppState,
+    battlefield::{EliminationEvent, MatchWinners, RestartEvent},
+    config::GameConfig,
+    game_command::GameCommand,
+    locale::Locale,
+    stats::{Bracket, PersistentStats, RoundStats, Series, StatsAggregator},
+    utils::{
+        Avatar, BallColor, DisplayName, GameAssets, Matches, Participant, ParticipantMap, Roster,
+        TileColor,
+    },
 };
-use bevy::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+mod banner;
+mod bracket;
+mod draft;
+mod match_clock;
+mod menu;
+mod pause;
+mod records;
+mod rules;
+mod scoreboard;
+mod summary;
+mod vote;
 
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup).add_systems(
+        app.add_plugins((
+            banner::BannerPlugin,
+            bracket::BracketScreenPlugin,
+            draft::DraftPlugin,
+            match_clock::MatchClockPlugin,
+            menu::MenuPlugin,
+            pause::PausePlugin,
+            records::RecordsPlugin,
+            rules::RulesPlugin,
+            scoreboard::ScoreboardPlugin,
+            summary::SummaryPlugin,
+            vote::VotePlugin,
+        ))
+        .add_event::<AnnouncementEvent>()
+        .add_systems(Startup, setup)
+        .add_systems(
             Update,
             (
-                button_system.run_if(not(game_is_going)),
+                button_system.run_if(in_state(AppState::GameOver)),
+                roster_toggle_system.run_if(in_state(AppState::GameOver)),
+                update_roster_toggles.run_if(resource_changed::<Roster>),
+                skin_button_system.run_if(in_state(AppState::GameOver)),
+                update_skin_buttons.run_if(resource_changed::<PersistentStats>),
                 restart.run_if(on_event::<RestartEvent>()),
                 add_elimination_text.run_if(on_event::<EliminationEvent>()),
-                remove_elimination_text.run_if(any_with_component::<EliminationTextTimer>),
-                add_game_over_text.run_if(not(game_is_going)),
+                add_announcement_text.run_if(on_event::<AnnouncementEvent>()),
+                remove_announcement_text.run_if(any_with_component::<AnnouncementTimer>),
+                add_game_over_text
+                    .after(StatsAggregator)
+                    .run_if(in_state(AppState::GameOver)),
+                play_again_hotkey.run_if(
+                    in_state(AppState::GameOver).and_then(input_just_pressed(PLAY_AGAIN_KEY)),
+                ),
             ),
         );
     }
@@ -24,12 +69,27 @@ impl Plugin for UIPlugin {
 
 // CONSTANTS {{{
 
-const ELIMINATION_TEXT_DURATION: f32 = 4.0;
+const ANNOUNCEMENT_DURATION: f32 = 4.0;
+const ANNOUNCEMENT_FONT_SIZE: f32 = 32.0;
 const ELIMINATION_TEXT_FONT_SIZE: f32 = 48.0;
+const ELIMINATION_AVATAR_SIZE: f32 = 48.0;
+const WINNER_AVATAR_SIZE: f32 = 160.0;
+/// Font size of the initials standing in for an avatar, as a fraction of the avatar's size.
+const AVATAR_INITIALS_SCALE: f32 = 0.45;
+const AVATAR_INITIALS_COLOR: Color = Color::BLACK;
+/// Space between an avatar and the name or the other avatars next to it.
+const AVATAR_GAP: f32 = 12.0;
 const GAME_OVER_TEXT_FONT_SIZE: f32 = 72.0;
+const MVP_TEXT_FONT_SIZE: f32 = 32.0;
+const PLAY_AGAIN_PROMPT_FONT_SIZE: f32 = 28.0;
+const PLAY_AGAIN_KEY: KeyCode = KeyCode::Enter;
 
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+/// Background color of a roster toggle whose participant is sitting out the next round.
+const INACTIVE_ROSTER_TOGGLE: Color = Color::srgb(0.4, 0.4, 0.4);
+const ROSTER_TOGGLE_FONT_SIZE: f32 = 24.0;
+const SKIN_BUTTON_FONT_SIZE: f32 = 18.0;
 // const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
 
 // }}}
@@ -38,33 +98,102 @@ const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 struct UIRoot;
 #[derive(Clone, Copy, Component)]
 struct RestartButton;
+/// Container of the [`RosterToggle`] buttons.
+#[derive(Clone, Copy, Component)]
+struct RosterPanel;
+/// Button that adds or removes a participant from the next round.
+#[derive(Clone, Copy, Component)]
+struct RosterToggle(Participant);
+/// Button that cycles through the turret skins a participant has unlocked.
+#[derive(Clone, Copy, Component)]
+struct SkinButton(Participant);
+/// Text showing the skin picked by a participant.
+#[derive(Clone, Copy, Component)]
+struct SkinButtonText(Participant);
+/// Event to display a short message at the top of the screen for a few seconds.
+#[derive(Event)]
+pub struct AnnouncementEvent {
+    pub message: String,
+    pub color: Color,
+}
 #[derive(Component)]
-struct EliminationTextTimer(Timer);
+struct AnnouncementTimer(Timer);
 #[derive(Bundle)]
-struct EliminationTextBundle {
+struct AnnouncementBundle {
     text_bundle: TextBundle,
-    timer: EliminationTextTimer,
+    timer: AnnouncementTimer,
 }
-impl EliminationTextBundle {
-    fn new(participant: impl std::fmt::Display, color: Color) -> Self {
-        EliminationTextBundle {
+impl AnnouncementBundle {
+    fn new(message: impl Into<String>, font_size: f32, color: Color, font: Handle<Font>) -> Self {
+        AnnouncementBundle {
             text_bundle: TextBundle::from_section(
-                format!("{} Eliminated", participant),
+                message,
                 TextStyle {
-                    font: default(),
-                    font_size: ELIMINATION_TEXT_FONT_SIZE,
+                    font,
+                    font_size,
                     color,
                 },
             ),
-            timer: EliminationTextTimer(Timer::from_seconds(
-                ELIMINATION_TEXT_DURATION,
-                TimerMode::Once,
-            )),
+            timer: AnnouncementTimer(Timer::from_seconds(ANNOUNCEMENT_DURATION, TimerMode::Once)),
         }
     }
 }
 
-fn setup(mut commands: Commands) {
+/// Spawn a square picture of a participant `size` pixels wide: their avatar, or their initials over
+/// `color` if they don't have one.
+fn spawn_avatar(
+    commands: &mut Commands,
+    avatar: &Avatar,
+    color: Color,
+    size: f32,
+    font: Handle<Font>,
+) -> Entity {
+    let style = Style {
+        width: Val::Px(size),
+        height: Val::Px(size),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_shrink: 0.0,
+        ..default()
+    };
+    match avatar {
+        Avatar::Image(image) => commands
+            .spawn(ImageBundle {
+                style,
+                image: UiImage::new(image.clone()),
+                ..default()
+            })
+            .id(),
+        Avatar::Initials(initials) => {
+            let node = commands
+                .spawn(NodeBundle {
+                    style,
+                    background_color: color.into(),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                })
+                .id();
+            commands
+                .spawn(TextBundle::from_section(
+                    initials.clone(),
+                    TextStyle {
+                        font,
+                        font_size: size * AVATAR_INITIALS_SCALE,
+                        color: AVATAR_INITIALS_COLOR,
+                    },
+                ))
+                .set_parent(node);
+            node
+        }
+    }
+}
+fn setup(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    colors: Res<ParticipantMap<TileColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    stats: Res<PersistentStats>,
+) {
     commands.spawn((
         UIRoot,
         NodeBundle {
@@ -85,7 +214,7 @@ fn setup(mut commands: Commands) {
             RestartButton,
             ButtonBundle {
                 style: Style {
-                    width: Val::Px(200.0),
+                    width: Val::Px(240.0),
                     height: Val::Px(65.0),
                     border: UiRect::all(Val::Px(5.0)),
                     justify_self: JustifySelf::Center,
@@ -106,26 +235,123 @@ fn setup(mut commands: Commands) {
         .id();
     commands
         .spawn(TextBundle::from_section(
-            "Restart",
+            "Play Again",
             TextStyle {
-                font: default(),
+                font: assets.font.clone(),
                 font_size: 40.0,
                 color: Color::srgb(0.9, 0.9, 0.9),
             },
         ))
         .set_parent(button);
+    let roster_panel = commands
+        .spawn((
+            RosterPanel,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Percent(25.0),
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ))
+        .id();
+    for participant in Participant::ALL {
+        let column = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(roster_panel)
+            .id();
+        let toggle = commands
+            .spawn((
+                RosterToggle(participant),
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(120.0),
+                        height: Val::Px(40.0),
+                        border: UiRect::all(Val::Px(3.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    border_radius: BorderRadius::MAX,
+                    background_color: colors.get(participant).0.into(),
+                    ..default()
+                },
+            ))
+            .set_parent(column)
+            .id();
+        commands
+            .spawn(TextBundle::from_section(
+                names[participant].0.clone(),
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: ROSTER_TOGGLE_FONT_SIZE,
+                    color: Color::srgb(0.9, 0.9, 0.9),
+                },
+            ))
+            .set_parent(toggle);
+        let skin_button = commands
+            .spawn((
+                SkinButton(participant),
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(120.0),
+                        height: Val::Px(28.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    border_radius: BorderRadius::MAX,
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+            ))
+            .set_parent(column)
+            .id();
+        commands
+            .spawn((
+                SkinButtonText(participant),
+                TextBundle::from_section(
+                    skin_label(&stats, participant),
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: SKIN_BUTTON_FONT_SIZE,
+                        color: Color::srgb(0.9, 0.9, 0.9),
+                    },
+                ),
+            ))
+            .set_parent(skin_button);
+    }
+}
+fn play_again_hotkey(mut events: EventWriter<GameCommand>) {
+    events.send(GameCommand::Restart);
 }
 fn button_system(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<Button>),
+        (Changed<Interaction>, With<RestartButton>),
     >,
-    mut events: EventWriter<RestartEvent>,
+    mut events: EventWriter<GameCommand>,
 ) {
     for (interaction, mut color, mut border_color) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
-                events.send_default();
+                events.send(GameCommand::Restart);
                 // *color = PRESSED_BUTTON.into();
                 // border_color.0 = RED.into();
             }
@@ -140,24 +366,133 @@ fn button_system(
         }
     }
 }
+fn roster_toggle_system(
+    mut interaction_query: Query<
+        (&Interaction, &RosterToggle, &mut BorderColor),
+        Changed<Interaction>,
+    >,
+    mut roster: ResMut<Roster>,
+) {
+    for (interaction, &RosterToggle(participant), mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => roster[participant] = !roster[participant],
+            Interaction::Hovered => border_color.0 = Color::WHITE,
+            Interaction::None => border_color.0 = Color::BLACK,
+        }
+    }
+}
+fn update_roster_toggles(
+    roster: Res<Roster>,
+    colors: Res<ParticipantMap<TileColor>>,
+    mut toggles: Query<(&RosterToggle, &mut BackgroundColor)>,
+) {
+    for (&RosterToggle(participant), mut color) in &mut toggles {
+        *color = if roster[participant] {
+            colors.get(participant).0
+        } else {
+            INACTIVE_ROSTER_TOGGLE
+        }
+        .into();
+    }
+}
+fn skin_label(stats: &PersistentStats, participant: Participant) -> String {
+    format!(
+        "{} ({} wins)",
+        stats.skins[participant].name(),
+        stats.wins[participant]
+    )
+}
+fn skin_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &SkinButton, &mut BorderColor),
+        Changed<Interaction>,
+    >,
+    mut stats: ResMut<PersistentStats>,
+) {
+    for (interaction, &SkinButton(participant), mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                let wins = stats.wins[participant];
+                stats.skins[participant] = stats.skins[participant].next_unlocked(wins);
+            }
+            Interaction::Hovered => border_color.0 = Color::WHITE,
+            Interaction::None => border_color.0 = Color::BLACK,
+        }
+    }
+}
+fn update_skin_buttons(
+    stats: Res<PersistentStats>,
+    mut texts: Query<(&SkinButtonText, &mut Text)>,
+) {
+    for (&SkinButtonText(participant), mut text) in &mut texts {
+        text.sections[0].value = skin_label(&stats, participant);
+    }
+}
 fn add_elimination_text(
     mut commands: Commands,
     mut events: EventReader<EliminationEvent>,
     colors: Res<ParticipantMap<BallColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    avatars: Res<ParticipantMap<Avatar>>,
+    assets: Res<GameAssets>,
+    ui_root: Query<Entity, With<UIRoot>>,
+) {
+    for event in events.read() {
+        let participant = event.participant;
+        let row = commands
+            .spawn((
+                AnnouncementTimer(Timer::from_seconds(ANNOUNCEMENT_DURATION, TimerMode::Once)),
+                NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(AVATAR_GAP),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .set_parent(ui_root.single())
+            .id();
+        let avatar = spawn_avatar(
+            &mut commands,
+            &avatars[participant],
+            colors[participant].0,
+            ELIMINATION_AVATAR_SIZE,
+            assets.font.clone(),
+        );
+        let text = commands
+            .spawn(TextBundle::from_section(
+                format!("{} Eliminated", names[participant]),
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: ELIMINATION_TEXT_FONT_SIZE,
+                    color: colors[participant].0,
+                },
+            ))
+            .id();
+        commands.entity(row).push_children(&[avatar, text]);
+    }
+}
+fn add_announcement_text(
+    mut commands: Commands,
+    mut events: EventReader<AnnouncementEvent>,
+    assets: Res<GameAssets>,
     ui_root: Query<Entity, With<UIRoot>>,
 ) {
     for event in events.read() {
         commands
-            .spawn(EliminationTextBundle::new(
-                event.participant,
-                colors.get(event.participant).0,
+            .spawn(AnnouncementBundle::new(
+                event.message.clone(),
+                ANNOUNCEMENT_FONT_SIZE,
+                event.color,
+                assets.font.clone(),
             ))
             .set_parent(ui_root.single());
     }
 }
-fn remove_elimination_text(
+fn remove_announcement_text(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut EliminationTextTimer)>,
+    mut query: Query<(Entity, &mut AnnouncementTimer)>,
     time: Res<Time>,
 ) {
     for (text_id, mut timer) in &mut query {
@@ -169,34 +504,160 @@ fn remove_elimination_text(
 }
 fn add_game_over_text(
     mut commands: Commands,
+    assets: Res<GameAssets>,
+    stats: Res<RoundStats>,
+    (series, bracket): (Res<Series>, Res<Bracket>),
+    match_winners: Res<MatchWinners>,
+    colors: Res<ParticipantMap<BallColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    avatars: Res<ParticipantMap<Avatar>>,
+    config: Res<GameConfig>,
+    (roster, matches): (Res<Roster>, Res<Matches>),
     ui_root: Query<Entity, With<UIRoot>>,
     mut restart_button: Query<&mut Visibility, With<RestartButton>>,
+    mut roster_panel: Query<&mut Visibility, (With<RosterPanel>, Without<RestartButton>)>,
 ) {
     if restart_button.single() == Visibility::Visible {
         return;
     }
     *restart_button.single_mut() = Visibility::Visible;
+    *roster_panel.single_mut() = Visibility::Visible;
+    let style = |color| TextStyle {
+        font: assets.font.clone(),
+        font_size: GAME_OVER_TEXT_FONT_SIZE,
+        color,
+    };
+    let name = |participant: Participant| {
+        TextSection::new(names[participant].0.clone(), style(colors[participant].0))
+    };
+    let mut sections = vec![TextSection::new("Game Over", style(Color::BLACK))];
+    let winners = match_winners.credited(&config, &roster, &matches);
+    // One line for each match, which is only the one in most rounds.
+    for (match_id, &winner) in match_winners.0.iter() {
+        let roster = matches.roster(&roster, match_id);
+        if config.teams.enabled && roster[winner.teammate()] {
+            sections.extend([
+                TextSection::new("\n", style(Color::BLACK)),
+                name(winner),
+                TextSection::new(" and ", style(Color::BLACK)),
+                name(winner.teammate()),
+                TextSection::new(" Win", style(Color::BLACK)),
+            ]);
+        } else {
+            sections.extend([
+                TextSection::new("\n", style(Color::BLACK)),
+                name(winner),
+                TextSection::new(" Wins", style(Color::BLACK)),
+            ]);
+        }
+    }
+    if let Some(champion) = series.champion {
+        sections.extend([
+            TextSection::new("\n", style(Color::BLACK)),
+            name(champion),
+            TextSection::new(" Takes the Series", style(Color::BLACK)),
+        ]);
+    }
+    if let Some(champion) = bracket.champion {
+        sections.extend([
+            TextSection::new("\n", style(Color::BLACK)),
+            name(champion),
+            TextSection::new(" Wins the Bracket", style(Color::BLACK)),
+        ]);
+    }
+    sections.push(TextSection::new(
+        format!("\nPress {:?} to play again", PLAY_AGAIN_KEY),
+        TextStyle {
+            font_size: PLAY_AGAIN_PROMPT_FONT_SIZE,
+            ..style(Color::BLACK)
+        },
+    ));
     let text_id = commands
-        .spawn(TextBundle::from_section(
-            "Game Over",
-            TextStyle {
-                font: default(),
-                font_size: GAME_OVER_TEXT_FONT_SIZE,
-                color: Color::BLACK,
-            },
-        ))
+        .spawn(TextBundle::from_sections(sections).with_text_justify(JustifyText::Center))
         .id();
     commands
         .entity(ui_root.single())
         .insert_children(0, &[text_id]);
+    let mut next_index = 1;
+    if !winners.is_empty() {
+        let avatar_row = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(AVATAR_GAP),
+                    margin: UiRect::vertical(Val::Px(AVATAR_GAP)),
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        for participant in winners {
+            let avatar = spawn_avatar(
+                &mut commands,
+                &avatars[participant],
+                colors[participant].0,
+                WINNER_AVATAR_SIZE,
+                assets.font.clone(),
+            );
+            commands.entity(avatar).set_parent(avatar_row);
+        }
+        // Above the game over text, so the winner's face is the first thing seen.
+        commands
+            .entity(ui_root.single())
+            .insert_children(0, &[avatar_row]);
+        next_index += 1;
+    }
+    if let Some(mvp) = stats.mvp() {
+        let mvp_text_id = commands
+            .spawn(TextBundle::from_section(
+                format!(
+                    "MVP: {}'s ball #{}, {} generated",
+                    names[mvp.participant],
+                    mvp.number,
+                    abbreviate(mvp.generated, config.locale)
+                ),
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: MVP_TEXT_FONT_SIZE,
+                    color: colors.get(mvp.participant).0,
+                },
+            ))
+            .id();
+        commands
+            .entity(ui_root.single())
+            .insert_children(next_index, &[mvp_text_id]);
+    }
+}
+/// Format big numbers with a metric suffix, e.g. 2100000 becomes "2.1M", or "2,1M" in locales with
+/// a decimal comma.
+fn abbreviate(value: u64, locale: Locale) -> String {
+    const SUFFIXES: [(u64, &str); 6] = [
+        (1_000_000_000_000_000_000, "E"),
+        (1_000_000_000_000_000, "P"),
+        (1_000_000_000_000, "T"),
+        (1_000_000_000, "G"),
+        (1_000_000, "M"),
+        (1_000, "K"),
+    ];
+    for (threshold, suffix) in SUFFIXES {
+        if value >= threshold {
+            return format!(
+                "{}{}",
+                locale.format_decimal(value as f64 / threshold as f64, 1),
+                suffix
+            );
+        }
+    }
+    value.to_string()
 }
 fn restart(
     mut commands: Commands,
     query: Query<&Children, With<UIRoot>>,
     mut button_visibility: Query<&mut Visibility, With<RestartButton>>,
+    mut roster_panel: Query<&mut Visibility, (With<RosterPanel>, Without<RestartButton>)>,
 ) {
     for &child in query.single().iter() {
         commands.entity(child).despawn_recursive();
         *button_visibility.single_mut() = Visibility::Hidden;
     }
+    *roster_panel.single_mut() = Visibility::Hidden;
 }
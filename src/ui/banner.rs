@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    battlefield::RoundNumber,
+    config::GameConfig,
+    mutators::ActiveMutators,
+    stats::Series,
+    utils::{BallColor, DisplayName, GameAssets, Participant, ParticipantMap, Roster},
+};
+
+// CONSTANTS {{{
+
+const BANNER_FONT_SIZE: f32 = 36.0;
+const BANNER_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const BANNER_BACKGROUND: Color = Color::srgba(0.1, 0.1, 0.1, 0.85);
+/// Where the banner rests once it's slid in, as a percentage of the screen height from the top.
+const BANNER_TOP: f32 = 4.0;
+/// Where the banner slides in from and back out to, above the top of the screen.
+const BANNER_HIDDEN_TOP: f32 = -20.0;
+/// Time in seconds the banner takes to slide in, and again to slide out.
+const BANNER_SLIDE_SECS: f32 = 0.4;
+/// Time in seconds the banner stays in place between sliding in and out.
+const BANNER_HOLD_SECS: f32 = 2.2;
+
+// }}}
+
+/// A banner sliding in at the start of each round with the matchup and the mutators in play.
+pub struct BannerPlugin;
+impl Plugin for BannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Countdown), spawn_banner)
+            .add_systems(Update, slide_banner);
+    }
+}
+
+#[derive(Component)]
+struct RoundBanner {
+    /// Time since the banner was spawned. It goes by real time, since the countdown it shows up
+    /// during has the virtual clock frozen.
+    elapsed: f32,
+}
+
+fn spawn_banner(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    round: Res<RoundNumber>,
+    roster: Res<Roster>,
+    series: Res<Series>,
+    (config, mutators): (Res<GameConfig>, Res<ActiveMutators>),
+    colors: Res<ParticipantMap<BallColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+    banners: Query<Entity, With<RoundBanner>>,
+) {
+    for entity in &banners {
+        commands.entity(entity).despawn_recursive();
+    }
+    let style = |color| TextStyle {
+        font: assets.font.clone(),
+        font_size: BANNER_FONT_SIZE,
+        color,
+    };
+    let mut sections = vec![TextSection::new(
+        format!("Round {} \u{2014} ", round.0),
+        style(BANNER_TEXT_COLOR),
+    )];
+    let participants = Participant::ALL.into_iter().filter(|&p| roster[p]);
+    for (i, participant) in participants.enumerate() {
+        if i > 0 {
+            sections.push(TextSection::new(" vs ", style(BANNER_TEXT_COLOR)));
+        }
+        let name = if config.series.enabled {
+            format!("{} ({})", names[participant], series.wins[participant])
+        } else {
+            names[participant].0.clone()
+        };
+        sections.push(TextSection::new(name, style(colors[participant].0)));
+    }
+    if !mutators.0.is_empty() {
+        let names: Vec<_> = mutators.0.iter().map(|x| x.name()).collect();
+        sections.push(TextSection::new(
+            format!(" \u{2014} Mutator: {}", names.join(", ")),
+            style(BANNER_TEXT_COLOR),
+        ));
+    }
+
+    let banner = commands
+        .spawn((
+            RoundBanner { elapsed: 0.0 },
+            Name::new("Round Banner"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(BANNER_HIDDEN_TOP),
+                    justify_self: JustifySelf::Center,
+                    padding: UiRect::axes(Val::Px(24.0), Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: BANNER_BACKGROUND.into(),
+                border_radius: BorderRadius::MAX,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn(TextBundle::from_sections(sections))
+        .set_parent(banner);
+}
+/// Ease the banner in from above the screen, hold it there, then ease it back out and despawn it.
+fn slide_banner(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut banners: Query<(Entity, &mut RoundBanner, &mut Style)>,
+) {
+    for (entity, mut banner, mut style) in &mut banners {
+        banner.elapsed += time.delta_seconds();
+        let slide_out_at = BANNER_SLIDE_SECS + BANNER_HOLD_SECS;
+        let shown = if banner.elapsed < BANNER_SLIDE_SECS {
+            banner.elapsed / BANNER_SLIDE_SECS
+        } else if banner.elapsed < slide_out_at {
+            1.0
+        } else {
+            1.0 - (banner.elapsed - slide_out_at) / BANNER_SLIDE_SECS
+        };
+        if banner.elapsed >= slide_out_at + BANNER_SLIDE_SECS {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        // Smoothstep, so the banner slows down as it comes to a stop.
+        let t = shown * shown * (3.0 - 2.0 * shown);
+        style.top = Val::Percent(BANNER_HIDDEN_TOP + (BANNER_TOP - BANNER_HIDDEN_TOP) * t);
+    }
+}
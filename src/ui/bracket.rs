@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use super::{spawn_avatar, AVATAR_GAP};
+use crate::{
+    app_state::AppState,
+    stats::Bracket,
+    utils::{Avatar, BallColor, DisplayName, GameAssets, ParticipantMap},
+};
+
+// CONSTANTS {{{
+
+const BRACKET_TITLE_FONT_SIZE: f32 = 28.0;
+const BRACKET_HEAT_FONT_SIZE: f32 = 22.0;
+const BRACKET_FONT_SIZE: f32 = 20.0;
+const BRACKET_AVATAR_SIZE: f32 = 24.0;
+const BRACKET_ROW_GAP: f32 = 4.0;
+/// Space above each heat, setting it apart from the one before.
+const BRACKET_HEAT_GAP: f32 = 12.0;
+/// Alpha of the names of entrants knocked out of the bracket.
+const KNOCKED_OUT_ALPHA: f32 = 0.35;
+const PLACEHOLDER_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+
+// }}}
+
+/// The knockout bracket, shown on the right between the heats.
+pub struct BracketScreenPlugin;
+impl Plugin for BracketScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            update_bracket_screen
+                .run_if(resource_changed::<Bracket>.or_else(state_changed::<AppState>)),
+        );
+    }
+}
+
+#[derive(Component)]
+struct BracketScreen;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        BracketScreen,
+        Name::new("Bracket Screen"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Percent(2.0),
+                top: Val::Percent(20.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(BRACKET_ROW_GAP),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+/// Show each heat with its entrants, dimming those knocked out of the bracket, while the arena isn't
+/// in play.
+fn update_bracket_screen(
+    mut commands: Commands,
+    bracket: Res<Bracket>,
+    state: Res<State<AppState>>,
+    assets: Res<GameAssets>,
+    colors: Res<ParticipantMap<BallColor>>,
+    (names, avatars): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<Avatar>>,
+    ),
+    mut screens: Query<(Entity, &mut Visibility), With<BracketScreen>>,
+) {
+    let style = |font_size, color| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color,
+    };
+    let shown =
+        bracket.is_running() && matches!(state.get(), AppState::Countdown | AppState::GameOver);
+    for (screen, mut visibility) in &mut screens {
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        commands.entity(screen).despawn_descendants();
+        if !shown {
+            continue;
+        }
+        commands
+            .spawn(TextBundle::from_section(
+                "Knockout Bracket",
+                style(BRACKET_TITLE_FONT_SIZE, Color::BLACK),
+            ))
+            .set_parent(screen);
+        for (i, heat) in bracket.heats.iter().enumerate() {
+            let title = if bracket.is_final(i) {
+                "Final".to_string()
+            } else {
+                format!("Heat {}", i + 1)
+            };
+            let status = match (bracket.champion, bracket.is_current(i), state.get()) {
+                (None, true, AppState::GameOver) => " (up next)",
+                (None, true, _) => " (now playing)",
+                _ => "",
+            };
+            commands
+                .spawn(TextBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(BRACKET_HEAT_GAP)),
+                        ..default()
+                    },
+                    ..TextBundle::from_section(
+                        format!("{}{}", title, status),
+                        style(BRACKET_HEAT_FONT_SIZE, Color::BLACK),
+                    )
+                })
+                .set_parent(screen);
+            let played = !heat.standings.is_empty();
+            let entrants = if played {
+                &heat.standings
+            } else {
+                &heat.entrants
+            };
+            for (place, &participant) in entrants.iter().enumerate() {
+                let knocked_out = played
+                    && (bracket.is_final(i) && place > 0
+                        || !heat.advancing().contains(&participant));
+                let alpha = if knocked_out { KNOCKED_OUT_ALPHA } else { 1.0 };
+                let row = commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(AVATAR_GAP),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .set_parent(screen)
+                    .id();
+                let avatar = spawn_avatar(
+                    &mut commands,
+                    &avatars[participant],
+                    colors[participant].0,
+                    BRACKET_AVATAR_SIZE,
+                    assets.font.clone(),
+                );
+                let label = if played {
+                    format!("{}. {}", place + 1, names[participant])
+                } else {
+                    names[participant].to_string()
+                };
+                let text = commands
+                    .spawn(TextBundle::from_section(
+                        label,
+                        style(BRACKET_FONT_SIZE, colors[participant].0.with_alpha(alpha)),
+                    ))
+                    .id();
+                commands.entity(row).push_children(&[avatar, text]);
+            }
+            // The final fills up as the opening heats are played. Every heat is the same size.
+            for _ in entrants.len()..bracket.heats[0].entrants.len() {
+                commands
+                    .spawn(TextBundle::from_section(
+                        "To be decided",
+                        style(BRACKET_FONT_SIZE, PLACEHOLDER_COLOR),
+                    ))
+                    .set_parent(screen);
+            }
+        }
+        if let Some(champion) = bracket.champion {
+            commands
+                .spawn(TextBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(BRACKET_HEAT_GAP)),
+                        ..default()
+                    },
+                    ..TextBundle::from_section(
+                        format!("Champion: {}", names[champion]),
+                        style(BRACKET_HEAT_FONT_SIZE, colors[champion].0),
+                    )
+                })
+                .set_parent(screen);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+
+use super::{HOVERED_BUTTON, NORMAL_BUTTON};
+use crate::{
+    app_state::AppState,
+    battlefield::RestartEvent,
+    config::GameConfig,
+    draft::{DraftedUpgrades, Upgrade},
+    utils::{DisplayName, GameAssets, Participant, ParticipantMap, TileColor},
+};
+
+// CONSTANTS {{{
+
+const DRAFT_TITLE_FONT_SIZE: f32 = 28.0;
+const DRAFT_NAME_FONT_SIZE: f32 = 20.0;
+const DRAFT_BUTTON_FONT_SIZE: f32 = 16.0;
+const DRAFT_BUTTON_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+// }}}
+
+pub struct DraftPlugin;
+impl Plugin for DraftPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_draft
+                    .run_if(in_state(AppState::GameOver).and_then(not(resource_exists::<Draft>))),
+                (
+                    draft_button_system,
+                    update_draft_labels
+                        .after(draft_button_system)
+                        .run_if(resource_changed::<DraftedUpgrades>),
+                )
+                    .run_if(resource_exists::<Draft>),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The draft for the next round's upgrades, held between rounds.
+#[derive(Resource)]
+struct Draft {
+    /// The number of upgrades each participant can pick, fixed for the duration of the draft.
+    budget: usize,
+}
+#[derive(Component)]
+struct DraftPanel;
+/// The text showing how much of a participant's budget is left.
+#[derive(Component)]
+struct DraftBudgetText(Participant);
+/// Button that picks an upgrade for a participant. Once the budget is spent, it takes back every
+/// pick of its upgrade instead.
+#[derive(Component)]
+struct DraftButton(Participant, Upgrade);
+#[derive(Component)]
+struct DraftButtonText(Participant, Upgrade);
+
+fn budget_label(name: &DisplayName, left: usize) -> String {
+    format!("{}: {} left", name, left)
+}
+fn button_label(upgrade: Upgrade, count: usize) -> String {
+    format!("{} x{}", upgrade.name(), count)
+}
+
+/// Start a new draft once the round is over. The last round's upgrades are cleared either way, so
+/// they don't carry over if the draft has since been turned off.
+fn start_draft(
+    mut commands: Commands,
+    mut upgrades: ResMut<DraftedUpgrades>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    colors: Res<ParticipantMap<TileColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+) {
+    *upgrades = DraftedUpgrades::default();
+    let budget = if config.draft.enabled {
+        config.draft.budget as usize
+    } else {
+        0
+    };
+    commands.insert_resource(Draft { budget });
+    if budget == 0 {
+        return;
+    }
+
+    let panel = commands
+        .spawn((
+            DraftPanel,
+            Name::new("Upgrade Draft"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Percent(2.0),
+                    top: Val::Percent(20.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn(TextBundle::from_section(
+            "Draft Upgrades",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: DRAFT_TITLE_FONT_SIZE,
+                color: Color::BLACK,
+            },
+        ))
+        .set_parent(panel);
+    for participant in Participant::ALL {
+        commands
+            .spawn((
+                DraftBudgetText(participant),
+                TextBundle::from_section(
+                    budget_label(&names[participant], budget),
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: DRAFT_NAME_FONT_SIZE,
+                        color: colors[participant].0,
+                    },
+                ),
+            ))
+            .set_parent(panel);
+        let row = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(panel)
+            .id();
+        for upgrade in Upgrade::ALL {
+            let button = commands
+                .spawn((
+                    DraftButton(participant, upgrade),
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(110.0),
+                            height: Val::Px(28.0),
+                            border: UiRect::all(Val::Px(2.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        border_color: BorderColor(Color::BLACK),
+                        border_radius: BorderRadius::MAX,
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                ))
+                .set_parent(row)
+                .id();
+            commands
+                .spawn((
+                    DraftButtonText(participant, upgrade),
+                    TextBundle::from_section(
+                        button_label(upgrade, 0),
+                        TextStyle {
+                            font: assets.font.clone(),
+                            font_size: DRAFT_BUTTON_FONT_SIZE,
+                            color: DRAFT_BUTTON_TEXT_COLOR,
+                        },
+                    ),
+                ))
+                .set_parent(button);
+        }
+    }
+}
+fn draft_button_system(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &DraftButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    draft: Res<Draft>,
+    mut upgrades: ResMut<DraftedUpgrades>,
+) {
+    for (interaction, &DraftButton(participant, upgrade), mut color, mut border_color) in
+        &mut interaction_query
+    {
+        match *interaction {
+            Interaction::Pressed => {
+                let picks = &mut upgrades.0[participant];
+                if picks.len() < draft.budget {
+                    picks.push(upgrade);
+                } else {
+                    picks.retain(|&x| x != upgrade);
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+fn update_draft_labels(
+    draft: Res<Draft>,
+    upgrades: Res<DraftedUpgrades>,
+    names: Res<ParticipantMap<DisplayName>>,
+    mut budget_texts: Query<(&mut Text, &DraftBudgetText), Without<DraftButtonText>>,
+    mut button_texts: Query<(&mut Text, &DraftButtonText)>,
+) {
+    for (mut text, &DraftBudgetText(participant)) in &mut budget_texts {
+        let left = draft.budget.saturating_sub(upgrades.0[participant].len());
+        text.sections[0].value = budget_label(&names[participant], left);
+    }
+    for (mut text, &DraftButtonText(participant, upgrade)) in &mut button_texts {
+        text.sections[0].value = button_label(upgrade, upgrades.count(participant, upgrade));
+    }
+}
+/// Lock in the picks for the next round and close the draft.
+fn restart(mut commands: Commands, panels: Query<Entity, With<DraftPanel>>) {
+    commands.remove_resource::<Draft>();
+    for entity in &panels {
+        commands.entity(entity).despawn_recursive();
+    }
+}
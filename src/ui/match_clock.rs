@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::WinConditions,
+    stats::{RoundStats, StatsAggregator},
+    utils::GameAssets,
+};
+
+// CONSTANTS {{{
+
+const CLOCK_FONT_SIZE: f32 = 32.0;
+const CLOCK_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+/// The clock turns this color for the last stretch of the round.
+const CLOCK_WARNING_COLOR: Color = Color::srgb(0.8, 0.1, 0.1);
+const CLOCK_WARNING_SECS: f32 = 10.0;
+
+// }}}
+
+pub struct MatchClockPlugin;
+impl Plugin for MatchClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_systems(Update, update_clock.after(StatsAggregator));
+    }
+}
+
+/// The time left in the round, shown when one of the win conditions has a time limit.
+#[derive(Component)]
+struct MatchClock;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    let root = commands
+        .spawn((
+            Name::new("Match Clock"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(8.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((
+            MatchClock,
+            TextBundle {
+                visibility: Visibility::Hidden,
+                ..TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: CLOCK_FONT_SIZE,
+                        color: CLOCK_COLOR,
+                    },
+                )
+            },
+        ))
+        .set_parent(root);
+}
+fn update_clock(
+    stats: Res<RoundStats>,
+    win_conditions: Res<WinConditions>,
+    mut clocks: Query<(&mut Text, &mut Visibility), With<MatchClock>>,
+) {
+    let limit = win_conditions
+        .0
+        .iter()
+        .filter_map(|x| x.time_limit_secs())
+        .reduce(f32::min);
+    for (mut text, mut visibility) in &mut clocks {
+        let Some(limit) = limit else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        let secs_left = (limit - stats.elapsed_secs()).max(0.0).ceil() as u32;
+        let value = format!("{}:{:02}", secs_left / 60, secs_left % 60);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+            text.sections[0].style.color = if (secs_left as f32) <= CLOCK_WARNING_SECS {
+                CLOCK_WARNING_COLOR
+            } else {
+                CLOCK_COLOR
+            };
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use bevy::prelude::*;
+
+use super::{HOVERED_BUTTON, NORMAL_BUTTON};
+use crate::{
+    app_state::AppState,
+    battlefield::{MapBalance, MapFiles},
+    cli::LaunchOptions,
+    config::{GameConfig, GamePreset, PanelMap, ValidateConfig},
+    game_command::GameCommand,
+    utils::{GameAssets, Participant, ParticipantMap, Roster},
+};
+
+// CONSTANTS {{{
+
+const MENU_TITLE_FONT_SIZE: f32 = 64.0;
+const MENU_HEADING_FONT_SIZE: f32 = 24.0;
+const MENU_BUTTON_FONT_SIZE: f32 = 20.0;
+const MENU_BUTTON_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const SELECTED_MENU_OPTION: Color = Color::srgb(0.35, 0.55, 0.35);
+/// Color of the territory shares of a lopsided custom map.
+const LOPSIDED_MAP_TEXT_COLOR: Color = Color::srgb(1.0, 0.6, 0.3);
+
+// }}}
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Menu), (setup, update_map_balance).chain())
+            .add_systems(OnExit(AppState::Menu), cleanup)
+            .add_systems(
+                Update,
+                (
+                    menu_option_system.before(ValidateConfig),
+                    menu_button_system,
+                    update_menu_options.after(ValidateConfig).run_if(
+                        resource_changed::<Roster>
+                            .or_else(resource_changed::<GamePreset>)
+                            .or_else(resource_changed::<GameConfig>),
+                    ),
+                    update_map_balance
+                        .after(ValidateConfig)
+                        .run_if(resource_changed::<Roster>.or_else(resource_changed::<GameConfig>)),
+                )
+                    .run_if(in_state(AppState::Menu)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct MainMenu;
+/// Text under the maps showing how the picked custom map shares out the battlefield.
+#[derive(Component)]
+struct MapBalanceText;
+/// Button that picks a setting for the first round.
+#[derive(Debug, Component, Clone, Copy)]
+enum MenuOption {
+    Participants(usize),
+    Preset(GamePreset),
+    Map(PanelMap),
+    /// One of the custom maps, as an index into [`MapFiles`].
+    MapFile(usize),
+}
+impl MenuOption {
+    fn label(self, maps: &MapFiles) -> String {
+        match self {
+            MenuOption::Participants(count) => count.to_string(),
+            MenuOption::Preset(preset) => preset.name().to_string(),
+            MenuOption::Map(map) => map.name().to_string(),
+            MenuOption::MapFile(i) => maps.0[i].name.clone(),
+        }
+    }
+    fn is_selected(
+        self,
+        roster: &Roster,
+        preset: GamePreset,
+        config: &GameConfig,
+        maps: &MapFiles,
+    ) -> bool {
+        match self {
+            MenuOption::Participants(count) => roster.count() as usize == count,
+            MenuOption::Preset(x) => x == preset,
+            MenuOption::Map(map) => {
+                config.map_file.is_none() && map.trigger_zones() == config.trigger_zones
+            }
+            MenuOption::MapFile(i) => config.map_file.as_ref() == Some(&maps.0[i]),
+        }
+    }
+    fn color(
+        self,
+        roster: &Roster,
+        preset: GamePreset,
+        config: &GameConfig,
+        maps: &MapFiles,
+    ) -> Color {
+        if self.is_selected(roster, preset, config, maps) {
+            SELECTED_MENU_OPTION
+        } else {
+            NORMAL_BUTTON
+        }
+    }
+}
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    Start,
+    MapEditor,
+}
+impl MenuButton {
+    fn label(self) -> &'static str {
+        match self {
+            MenuButton::Start => "Start",
+            MenuButton::MapEditor => "Map Editor",
+        }
+    }
+}
+
+fn button_bundle(width: f32, color: Color) -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            width: Val::Px(width),
+            height: Val::Px(36.0),
+            border: UiRect::all(Val::Px(2.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        border_color: BorderColor(Color::BLACK),
+        border_radius: BorderRadius::MAX,
+        background_color: color.into(),
+        ..default()
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    roster: Res<Roster>,
+    preset: Res<GamePreset>,
+    config: Res<GameConfig>,
+    maps: Res<MapFiles>,
+) {
+    let text_style = |font_size| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color: MENU_BUTTON_TEXT_COLOR,
+    };
+    let menu = commands
+        .spawn((
+            MainMenu,
+            Name::new("Main Menu"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn(TextBundle::from_section(
+            "Multiply or Release",
+            text_style(MENU_TITLE_FONT_SIZE),
+        ))
+        .set_parent(menu);
+
+    let participant_counts = (2..=Participant::ALL.len()).map(MenuOption::Participants);
+    let rows: [(&str, Vec<MenuOption>); 3] = [
+        ("Participants", participant_counts.collect()),
+        (
+            "Preset",
+            GamePreset::ALL
+                .into_iter()
+                .map(MenuOption::Preset)
+                .collect(),
+        ),
+        (
+            "Map",
+            PanelMap::ALL
+                .into_iter()
+                .map(MenuOption::Map)
+                .chain((0..maps.0.len()).map(MenuOption::MapFile))
+                .collect(),
+        ),
+    ];
+    for (heading, options) in rows {
+        commands
+            .spawn(TextBundle::from_section(
+                heading,
+                text_style(MENU_HEADING_FONT_SIZE),
+            ))
+            .set_parent(menu);
+        let row = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(menu)
+            .id();
+        for option in options {
+            let color = option.color(&roster, *preset, &config, &maps);
+            let button = commands
+                .spawn((option, button_bundle(140.0, color)))
+                .set_parent(row)
+                .id();
+            commands
+                .spawn(TextBundle::from_section(
+                    option.label(&maps),
+                    text_style(MENU_BUTTON_FONT_SIZE),
+                ))
+                .set_parent(button);
+        }
+    }
+    commands
+        .spawn((
+            MapBalanceText,
+            TextBundle::from_section("", text_style(MENU_BUTTON_FONT_SIZE)),
+        ))
+        .set_parent(menu);
+
+    let row = commands
+        .spawn(NodeBundle {
+            style: Style {
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        })
+        .set_parent(menu)
+        .id();
+    for kind in [MenuButton::Start, MenuButton::MapEditor] {
+        let button = commands
+            .spawn((kind, button_bundle(200.0, NORMAL_BUTTON)))
+            .set_parent(row)
+            .id();
+        commands
+            .spawn(TextBundle::from_section(
+                kind.label(),
+                text_style(MENU_HEADING_FONT_SIZE),
+            ))
+            .set_parent(button);
+    }
+}
+fn cleanup(mut commands: Commands, menus: Query<Entity, With<MainMenu>>) {
+    for entity in &menus {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+fn menu_option_system(
+    mut interaction_query: Query<
+        (&Interaction, &MenuOption, &mut BorderColor),
+        Changed<Interaction>,
+    >,
+    mut roster: ResMut<Roster>,
+    mut preset: ResMut<GamePreset>,
+    mut config: ResMut<GameConfig>,
+    (options, maps): (Res<LaunchOptions>, Res<MapFiles>),
+) {
+    for (interaction, &option, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => match option {
+                MenuOption::Participants(count) => {
+                    let mut new_roster = Roster(ParticipantMap::splat(false));
+                    for &participant in &Participant::ALL[..count] {
+                        new_roster[participant] = true;
+                    }
+                    *roster = new_roster;
+                }
+                // Start over from the preset, which comes with its own map.
+                MenuOption::Preset(new_preset) => {
+                    *preset = new_preset;
+                    *config = GameConfig::from_preset(new_preset);
+                    options.override_config(&mut config);
+                }
+                MenuOption::Map(map) => {
+                    config.trigger_zones = map.trigger_zones();
+                    config.map_file = None;
+                }
+                MenuOption::MapFile(i) => {
+                    let map = &maps.0[i];
+                    if let Some(trigger_zones) = &map.trigger_zones {
+                        config.trigger_zones = trigger_zones.clone();
+                    }
+                    config.map_file = Some(map.clone());
+                }
+            },
+            Interaction::Hovered => border_color.0 = Color::WHITE,
+            Interaction::None => border_color.0 = Color::BLACK,
+        }
+    }
+}
+fn update_menu_options(
+    roster: Res<Roster>,
+    preset: Res<GamePreset>,
+    config: Res<GameConfig>,
+    maps: Res<MapFiles>,
+    mut buttons: Query<(&MenuOption, &mut BackgroundColor)>,
+) {
+    for (&option, mut color) in &mut buttons {
+        *color = option.color(&roster, *preset, &config, &maps).into();
+    }
+}
+/// Show how fairly the picked custom map shares out the battlefield for the participants picked,
+/// warning if it's lopsided.
+fn update_map_balance(
+    roster: Res<Roster>,
+    config: Res<GameConfig>,
+    mut texts: Query<&mut Text, With<MapBalanceText>>,
+) {
+    let balance = config
+        .map_file
+        .as_ref()
+        .map(|map| MapBalance::check(map, &roster, &config));
+    for mut text in &mut texts {
+        let section = &mut text.sections[0];
+        section.value = balance
+            .as_ref()
+            .map_or_else(String::new, |x| x.summary(&config));
+        section.style.color = match balance.as_ref().is_some_and(MapBalance::is_lopsided) {
+            true => LOPSIDED_MAP_TEXT_COLOR,
+            false => MENU_BUTTON_TEXT_COLOR,
+        };
+    }
+}
+/// Start the first round with the settings picked. The round is set up again from scratch, since
+/// the battlefield was built for the settings from launch.
+fn menu_button_system(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &MenuButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut events: EventWriter<GameCommand>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, &kind, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => match kind {
+                MenuButton::Start => {
+                    events.send(GameCommand::Restart);
+                    next_state.set(AppState::Countdown);
+                }
+                MenuButton::MapEditor => next_state.set(AppState::Editor),
+            },
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
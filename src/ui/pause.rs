@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use super::{HOVERED_BUTTON, NORMAL_BUTTON};
+use crate::{app_state::AppState, utils::GameAssets};
+
+// CONSTANTS {{{
+
+const PAUSE_BUTTON_FONT_SIZE: f32 = 18.0;
+const PAUSE_BUTTON_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+// }}}
+
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                pause_button_system
+                    .run_if(in_state(AppState::Playing).or_else(in_state(AppState::Paused))),
+                update_pause_button.run_if(state_changed::<AppState>),
+            ),
+        );
+    }
+}
+
+/// Button in the corner that pauses and resumes the round, for when there's no keyboard at hand.
+#[derive(Component)]
+struct PauseButton;
+#[derive(Component)]
+struct PauseButtonText;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    let button = commands
+        .spawn((
+            PauseButton,
+            Name::new("Pause Button"),
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(12.0),
+                    top: Val::Px(12.0),
+                    width: Val::Px(90.0),
+                    height: Val::Px(28.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                border_color: BorderColor(Color::BLACK),
+                border_radius: BorderRadius::MAX,
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((
+            PauseButtonText,
+            TextBundle::from_section(
+                "Pause",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: PAUSE_BUTTON_FONT_SIZE,
+                    color: PAUSE_BUTTON_TEXT_COLOR,
+                },
+            ),
+        ))
+        .set_parent(button);
+}
+fn pause_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &mut BorderColor),
+        (Changed<Interaction>, With<PauseButton>),
+    >,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => next_state.set(state.get().toggle_pause()),
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+/// Only show the button while there's a round to pause, and label it with what it'll do.
+fn update_pause_button(
+    state: Res<State<AppState>>,
+    mut buttons: Query<&mut Visibility, With<PauseButton>>,
+    mut texts: Query<&mut Text, With<PauseButtonText>>,
+) {
+    let (visibility, label) = match state.get() {
+        AppState::Playing => (Visibility::Inherited, "Pause"),
+        AppState::Paused => (Visibility::Inherited, "Resume"),
+        _ => (Visibility::Hidden, "Pause"),
+    };
+    for mut button_visibility in &mut buttons {
+        *button_visibility = visibility;
+    }
+    for mut text in &mut texts {
+        text.sections[0].value = label.to_string();
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use super::{abbreviate, AnnouncementEvent};
+use crate::{
+    config::GameConfig,
+    stats::{PersistentStats, ShotRecord},
+    utils::{BallColor, DisplayName, GameAssets, ParticipantMap},
+};
+
+// CONSTANTS {{{
+
+const RECORDS_KEY: KeyCode = KeyCode::F4;
+const RECORDS_TITLE_FONT_SIZE: f32 = 24.0;
+const RECORDS_FONT_SIZE: f32 = 18.0;
+const RECORDS_TEXT_COLOR: Color = Color::srgb(0.95, 0.95, 0.95);
+const RECORDS_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+const NEW_RECORD_COLOR: Color = Color::srgb(1.0, 0.84, 0.0);
+
+// }}}
+
+pub struct RecordsPlugin;
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                toggle_records.run_if(input_just_pressed(RECORDS_KEY)),
+                update_records_text.after(toggle_records),
+                announce_new_record.run_if(resource_changed::<PersistentStats>),
+            ),
+        );
+    }
+}
+
+/// The overlay listing the biggest shots of all time.
+#[derive(Component)]
+struct RecordsPanel;
+#[derive(Component)]
+struct RecordsText;
+
+fn setup(mut commands: Commands) {
+    let panel = commands
+        .spawn((
+            RecordsPanel,
+            Name::new("Records Overlay"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(20.0),
+                    top: Val::Px(20.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                background_color: RECORDS_BACKGROUND.into(),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((RecordsText, TextBundle::default()))
+        .set_parent(panel);
+}
+fn toggle_records(mut panels: Query<&mut Visibility, With<RecordsPanel>>) {
+    for mut visibility in &mut panels {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+fn update_records_text(
+    stats: Res<PersistentStats>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    names: Res<ParticipantMap<DisplayName>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    panels: Query<Ref<Visibility>, With<RecordsPanel>>,
+    mut texts: Query<&mut Text, With<RecordsText>>,
+) {
+    let Ok(visibility) = panels.get_single() else {
+        return;
+    };
+    if *visibility == Visibility::Hidden
+        || !(visibility.is_changed() || stats.is_changed() || config.is_changed())
+    {
+        return;
+    }
+    let style = |font_size, color| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color,
+    };
+    let mut sections = vec![TextSection::new(
+        format!("Biggest Shots ({:?} to close)", RECORDS_KEY),
+        style(RECORDS_TITLE_FONT_SIZE, RECORDS_TEXT_COLOR),
+    )];
+    if stats.biggest_shots.is_empty() {
+        sections.push(TextSection::new(
+            "\nNo shots fired yet",
+            style(RECORDS_FONT_SIZE, RECORDS_TEXT_COLOR),
+        ));
+    }
+    for (i, record) in stats.biggest_shots.iter().enumerate() {
+        let ShotRecord {
+            charge,
+            participant,
+            seed,
+            fired_at,
+        } = *record;
+        sections.push(TextSection::new(
+            format!("\n{}. {} ", i + 1, abbreviate(charge, config.locale)),
+            style(RECORDS_FONT_SIZE, RECORDS_TEXT_COLOR),
+        ));
+        sections.push(TextSection::new(
+            names[participant].to_string(),
+            style(RECORDS_FONT_SIZE, colors[participant].0),
+        ));
+        sections.push(TextSection::new(
+            format!(
+                " on {} (seed {})",
+                config.locale.format_date(fired_at),
+                seed
+            ),
+            style(RECORDS_FONT_SIZE, RECORDS_TEXT_COLOR),
+        ));
+    }
+    for mut text in &mut texts {
+        text.sections.clone_from(&sections);
+    }
+}
+/// Announce any shot that beats the biggest one on record. The record loaded at launch isn't new,
+/// and neither is the very first shot, since there was nothing to beat yet.
+fn announce_new_record(
+    mut best: Local<Option<u64>>,
+    stats: Res<PersistentStats>,
+    config: Res<GameConfig>,
+    names: Res<ParticipantMap<DisplayName>>,
+    mut announcements: EventWriter<AnnouncementEvent>,
+) {
+    let Some(record) = stats.biggest_shots.first() else {
+        return;
+    };
+    let previous = best.replace(record.charge);
+    if previous.is_none_or(|x| x >= record.charge) {
+        return;
+    }
+    announcements.send(AnnouncementEvent {
+        message: format!(
+            "New Record! {} by {}",
+            abbreviate(record.charge, config.locale),
+            names[record.participant]
+        ),
+        color: NEW_RECORD_COLOR,
+    });
+}
@@ -0,0 +1,391 @@
+use std::fmt::Write;
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    battlefield::{aim_keys, BattlefieldGeometry, WinConditions},
+    config::{ArenaShape, GameConfig, GamePreset, LaserMode, TileConversionMode, TileShape},
+    mutators::ActiveMutators,
+    utils::{DisplayName, GameAssets, Participant, ParticipantMap, Roster},
+};
+
+// CONSTANTS {{{
+
+const RULES_KEY: KeyCode = KeyCode::F1;
+const RULES_FONT_SIZE: f32 = 18.0;
+const RULES_TEXT_COLOR: Color = Color::srgb(0.95, 0.95, 0.95);
+const RULES_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+
+// }}}
+
+pub struct RulesPlugin;
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            (
+                toggle_rules.run_if(input_just_pressed(RULES_KEY)),
+                update_rules_text.after(toggle_rules),
+            ),
+        );
+    }
+}
+
+/// The overlay summarizing the rules of the current round.
+#[derive(Component)]
+struct RulesPanel;
+#[derive(Component)]
+struct RulesText;
+
+fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+    let panel = commands
+        .spawn((
+            RulesPanel,
+            Name::new("Rules Overlay"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(20.0),
+                    top: Val::Px(20.0),
+                    max_width: Val::Percent(40.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                background_color: RULES_BACKGROUND.into(),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((
+            RulesText,
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: RULES_FONT_SIZE,
+                    color: RULES_TEXT_COLOR,
+                },
+            ),
+        ))
+        .set_parent(panel);
+}
+fn toggle_rules(mut panels: Query<&mut Visibility, With<RulesPanel>>) {
+    for mut visibility in &mut panels {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+/// Regenerate the rules while the overlay is shown, so they're never out of date with the config,
+/// the mutators voted in, or the win conditions picked up on restart.
+fn update_rules_text(
+    config: Res<GameConfig>,
+    preset: Res<GamePreset>,
+    mutators: Res<ActiveMutators>,
+    win_conditions: Res<WinConditions>,
+    names: Res<ParticipantMap<DisplayName>>,
+    roster: Res<Roster>,
+    panels: Query<Ref<Visibility>, With<RulesPanel>>,
+    mut texts: Query<&mut Text, With<RulesText>>,
+) {
+    let Ok(visibility) = panels.get_single() else {
+        return;
+    };
+    if *visibility == Visibility::Hidden
+        || !(visibility.is_changed()
+            || config.is_changed()
+            || mutators.is_changed()
+            || win_conditions.is_changed()
+            || roster.is_changed())
+    {
+        return;
+    }
+    let rules = rules_text(
+        *preset,
+        &config,
+        &mutators,
+        &win_conditions,
+        &names,
+        &roster,
+    );
+    for mut text in &mut texts {
+        text.sections[0].value.clone_from(&rules);
+    }
+}
+
+fn rules_text(
+    preset: GamePreset,
+    config: &GameConfig,
+    mutators: &ActiveMutators,
+    win_conditions: &WinConditions,
+    names: &ParticipantMap<DisplayName>,
+    roster: &Roster,
+) -> String {
+    // Writing to a `String` can't fail.
+    let mut text = format!("Rules ({:?} to close)\n", RULES_KEY);
+    let _ = writeln!(text, "Preset: {}", preset.name());
+    if config.teams.enabled {
+        let teams = Participant::ALL
+            .into_iter()
+            .filter(|&p| roster[p] && (p as usize) < (p.teammate() as usize))
+            .map(|p| match roster[p.teammate()] {
+                true => format!("{} and {}", names[p], names[p.teammate()]),
+                false => names[p].to_string(),
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(text, "Teams: {}", teams.join(" against "));
+    }
+
+    let humans = Participant::ALL
+        .into_iter()
+        .filter(|&x| roster[x] && config.control.human[x])
+        .collect::<Vec<_>>();
+    if !humans.is_empty() {
+        text.push_str("\nControls\n");
+        for participant in humans {
+            let key_name = |key: KeyCode| format!("{:?}", key).replace("Key", "");
+            let (left, right) = aim_keys(participant);
+            let _ = writeln!(
+                text,
+                "- {} turns with {} and {}",
+                names[participant],
+                key_name(left),
+                key_name(right)
+            );
+        }
+    }
+
+    text.push_str("\nWin Conditions\n");
+    for condition in &win_conditions.0 {
+        let _ = writeln!(text, "- {}", condition.describe());
+    }
+
+    text.push_str("\nTrigger Zones\n");
+    for trigger_type in config.trigger_zones.0 {
+        let label = trigger_type.to_string().replace('\n', " ");
+        let _ = writeln!(
+            text,
+            "- {}: {}",
+            label,
+            trigger_type.describe(config, mutators)
+        );
+    }
+
+    text.push_str("\nMutators\n");
+    if mutators.0.is_empty() {
+        text.push_str("- None\n");
+    }
+    for mutator in &mutators.0 {
+        let _ = writeln!(text, "- {}: {}", mutator.name(), mutator.description());
+    }
+
+    text.push_str("\nBattlefield\n");
+    let arena = match BattlefieldGeometry::new(roster, config).shape {
+        ArenaShape::Square => None,
+        ArenaShape::Circle => Some("circle"),
+        ArenaShape::Diamond => Some("diamond"),
+        ArenaShape::Plus => Some("plus"),
+        ArenaShape::Hexagon => Some("hexagon"),
+    };
+    if let Some(arena) = arena {
+        let _ = writeln!(text, "- The arena is shaped like a {}", arena);
+    } else if config.wrap_around {
+        let _ = writeln!(
+            text,
+            "- Bullets going off one edge come back in on the opposite edge"
+        );
+    }
+    if let Some(map) = &config.map_file {
+        let _ = writeln!(text, "- Played on the custom map {}", map.name);
+        if !map.speed_zones.is_empty() {
+            let _ = writeln!(
+                text,
+                "- Bullets slow down in the brown zones and speed up in the blue ones"
+            );
+        }
+        if !map.teleporters.is_empty() {
+            let _ = writeln!(
+                text,
+                "- Bullets that run into a teleporter come out of its pair, still going the same way"
+            );
+        }
+    } else if config.map_generator.enabled {
+        let _ = writeln!(
+            text,
+            "- Obstacles and neutral ground are laid out fresh each round, the same in every corner"
+        );
+    }
+    if config.walls.enabled {
+        let _ = writeln!(
+            text,
+            "- Walls block the way until shots wear them down by {} charge",
+            config.walls.hit_points
+        );
+    }
+    if config.shrink.enabled {
+        let _ = writeln!(
+            text,
+            "- The battlefield starts shrinking {:.0} seconds in",
+            config.shrink.start_secs
+        );
+    }
+    if config.hazard_bar.enabled {
+        let _ = writeln!(
+            text,
+            "- A bar spins in the middle, deflecting bullets and clearing the tiles it sweeps over"
+        );
+    }
+    if config.battle_royale.enabled {
+        let _ = writeln!(
+            text,
+            "- The edges of everyone's territory decay every {:.0} seconds, apart from ground taken in the last {:.0}",
+            config.battle_royale.interval_secs, config.battle_royale.grace_secs
+        );
+    }
+    if config.territory_elimination.enabled {
+        let _ = writeln!(
+            text,
+            "- Holding less than {:.0}% of the battlefield for {:.0} seconds eliminates you",
+            config.territory_elimination.min_fraction * 100.0,
+            config.territory_elimination.grace_secs
+        );
+    }
+    if config.tile_shape == TileShape::Hexagon {
+        let _ = writeln!(text, "- The battlefield is tiled with hexagons");
+    }
+    if config.fortification.radius > 0.0 {
+        let _ = writeln!(
+            text,
+            "- Tiles near a turret take {} hits to convert",
+            config.fortification.hits
+        );
+        if config.fortification.min_charge > 0 {
+            let _ = writeln!(
+                text,
+                "- Only shots with at least {} charge left can hit them",
+                config.fortification.min_charge
+            );
+        }
+    }
+    if config.tile_hit_points.enabled {
+        let _ = writeln!(
+            text,
+            "- Tiles deep in a turret's territory take up to {} hits to convert",
+            config.tile_hit_points.max_hits
+        );
+    }
+    for (name, mode) in [
+        ("Charged shots", config.tile_conversion.charged_shot),
+        ("Burst shots", config.tile_conversion.burst_shot),
+    ] {
+        if mode == TileConversionMode::Splash {
+            let _ = writeln!(text, "- {} convert every tile where they burst", name);
+        }
+    }
+    if config.laser.enabled {
+        let effect = match config.laser.mode {
+            LaserMode::Destroy => "destroys",
+            LaserMode::Halve => "halves the charge of",
+        };
+        let _ = writeln!(text, "- A rotating laser {} the shots it hits", effect);
+    }
+    if config.black_hole.enabled {
+        text.push_str("- Black holes appear now and then and pull shots in\n");
+    }
+    if config.supply_drop.enabled {
+        text.push_str("- Shoot supply crates to get a random trigger\n");
+    }
+    if config.interest.enabled {
+        let _ = writeln!(
+            text,
+            "- Turrets earn {:.0}% interest on their charge per second",
+            config.interest.rate_per_sec * 100.0
+        );
+    }
+    if config.king_of_the_hill.enabled {
+        let _ = writeln!(
+            text,
+            "- Owning most of the circle in the middle earns {:.0} charge per second",
+            config.king_of_the_hill.charge_per_sec
+        );
+    }
+    if config.siphon.enabled {
+        let _ = writeln!(
+            text,
+            "- Shots that stop over their own tiles refund {:.0}% of their charge",
+            config.siphon.refund_fraction * 100.0
+        );
+    }
+    if config.capture_streak.enabled {
+        let _ = writeln!(
+            text,
+            "- Shots that capture {} tiles in a row catch fire and speed up",
+            config.capture_streak.threshold
+        );
+    }
+    if config.streak_bonus.enabled {
+        let _ = writeln!(
+            text,
+            "- Capturing tiles in a row without losing any earns {} bonus charge at each streak milestone",
+            config.streak_bonus.bonus_charge
+        );
+    }
+    if config.comeback.enabled {
+        let _ = writeln!(
+            text,
+            "- Eliminated turrets come back after {:.0} seconds with {:.0}% of the leader's charge",
+            config.comeback.delay_secs,
+            config.comeback.charge_fraction * 100.0
+        );
+    }
+    if config.draft.enabled {
+        let _ = writeln!(
+            text,
+            "- Each participant drafts {} upgrades between rounds",
+            config.draft.budget
+        );
+    }
+    if config.emotes.enabled {
+        let _ = writeln!(
+            text,
+            "- Turrets react to the round with emotes, just for show"
+        );
+    }
+    if config.bracket.enabled {
+        let _ = writeln!(
+            text,
+            "- 8 participants play a knockout bracket 4 at a time, and the top 2 of each heat go \
+             through to the final"
+        );
+        if config.bracket.side_by_side {
+            let _ = writeln!(text, "- The opening heats are played side by side");
+        }
+    }
+    if config.series.enabled {
+        let _ = writeln!(
+            text,
+            "- The first to win {} rounds wins the series",
+            config.series.wins_needed
+        );
+    }
+    if config.speed_ramp.enabled {
+        let _ = writeln!(
+            text,
+            "- The game speeds up after {:.0} minutes",
+            config.speed_ramp.start_secs / 60.0
+        );
+    }
+    if config.stalemate.enabled {
+        let _ = writeln!(
+            text,
+            "- Turrets are forced to fire after {:.0} seconds without progress",
+            config.stalemate.timeout_secs
+        );
+    }
+    text
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use super::{spawn_avatar, AVATAR_GAP};
+use crate::{
+    config::{GameConfig, ValidateConfig},
+    stats::Series,
+    utils::{Avatar, BallColor, DisplayName, GameAssets, Participant, ParticipantMap, Roster},
+};
+
+// CONSTANTS {{{
+
+const SCOREBOARD_TITLE_FONT_SIZE: f32 = 28.0;
+const SCOREBOARD_FONT_SIZE: f32 = 22.0;
+const SCOREBOARD_AVATAR_SIZE: f32 = 28.0;
+const SCOREBOARD_ROW_GAP: f32 = 4.0;
+
+// }}}
+
+/// The series standings, shown on the left for as long as series are enabled.
+pub struct ScoreboardPlugin;
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup).add_systems(
+            Update,
+            update_scoreboard.after(ValidateConfig).run_if(
+                resource_changed::<Series>
+                    .or_else(resource_changed::<GameConfig>)
+                    .or_else(resource_changed::<Roster>),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct Scoreboard;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Scoreboard,
+        Name::new("Series Scoreboard"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(2.0),
+                top: Val::Percent(20.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(SCOREBOARD_ROW_GAP),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+fn update_scoreboard(
+    mut commands: Commands,
+    series: Res<Series>,
+    config: Res<GameConfig>,
+    roster: Res<Roster>,
+    assets: Res<GameAssets>,
+    colors: Res<ParticipantMap<BallColor>>,
+    (names, avatars): (
+        Res<ParticipantMap<DisplayName>>,
+        Res<ParticipantMap<Avatar>>,
+    ),
+    mut scoreboards: Query<(Entity, &mut Visibility), With<Scoreboard>>,
+) {
+    let style = |font_size, color| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color,
+    };
+    for (scoreboard, mut visibility) in &mut scoreboards {
+        *visibility = if config.series.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        commands.entity(scoreboard).despawn_descendants();
+        commands
+            .spawn(TextBundle::from_section(
+                format!("First to {}", config.series.wins_needed),
+                style(SCOREBOARD_TITLE_FONT_SIZE, Color::BLACK),
+            ))
+            .set_parent(scoreboard);
+        // Anyone with wins in the series stays on the board even if they sit out a round.
+        for participant in Participant::ALL {
+            if !(roster[participant] || series.wins[participant] > 0) {
+                continue;
+            }
+            let row = commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(AVATAR_GAP),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .set_parent(scoreboard)
+                .id();
+            let avatar = spawn_avatar(
+                &mut commands,
+                &avatars[participant],
+                colors[participant].0,
+                SCOREBOARD_AVATAR_SIZE,
+                assets.font.clone(),
+            );
+            let text = commands
+                .spawn(TextBundle::from_section(
+                    format!("{}: {}", names[participant], series.wins[participant]),
+                    style(SCOREBOARD_FONT_SIZE, colors[participant].0),
+                ))
+                .id();
+            commands.entity(row).push_children(&[avatar, text]);
+        }
+    }
+}
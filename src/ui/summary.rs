@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use super::abbreviate;
+use crate::{
+    app_state::AppState,
+    config::GameConfig,
+    stats::SessionStats,
+    utils::{BallColor, DisplayName, GameAssets, Participant, ParticipantMap},
+};
+
+// CONSTANTS {{{
+
+const SUMMARY_TITLE_FONT_SIZE: f32 = 56.0;
+const SUMMARY_FONT_SIZE: f32 = 28.0;
+const SUMMARY_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+// }}}
+
+/// The session summary shown on the way out.
+pub struct SummaryPlugin;
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::ShuttingDown), setup);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    session: Res<SessionStats>,
+    config: Res<GameConfig>,
+    assets: Res<GameAssets>,
+    colors: Res<ParticipantMap<BallColor>>,
+    names: Res<ParticipantMap<DisplayName>>,
+) {
+    let style = |font_size, color| TextStyle {
+        font: assets.font.clone(),
+        font_size,
+        color,
+    };
+    let mut sections = vec![
+        TextSection::new(
+            "Session Summary",
+            style(SUMMARY_TITLE_FONT_SIZE, SUMMARY_TEXT_COLOR),
+        ),
+        TextSection::new(
+            format!("\nRounds played: {}", session.rounds_played),
+            style(SUMMARY_FONT_SIZE, SUMMARY_TEXT_COLOR),
+        ),
+    ];
+    if let Some((participant, charge)) = session.biggest_shot {
+        sections.push(TextSection::new(
+            "\nBiggest shot: ",
+            style(SUMMARY_FONT_SIZE, SUMMARY_TEXT_COLOR),
+        ));
+        sections.push(TextSection::new(
+            format!(
+                "{}, {}",
+                names[participant],
+                abbreviate(charge, config.locale)
+            ),
+            style(SUMMARY_FONT_SIZE, colors[participant].0),
+        ));
+    }
+    for participant in Participant::ALL {
+        if session.wins[participant] > 0 {
+            sections.push(TextSection::new(
+                format!(
+                    "\n{} wins: {}",
+                    names[participant], session.wins[participant]
+                ),
+                style(SUMMARY_FONT_SIZE, colors[participant].0),
+            ));
+        }
+    }
+
+    commands
+        .spawn((
+            Name::new("Session Summary"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                z_index: ZIndex::Global(3),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_sections(sections).with_text_justify(JustifyText::Center));
+        });
+}
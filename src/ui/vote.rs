@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    app_state::AppState,
+    battlefield::RestartEvent,
+    config::GameConfig,
+    mutators::{ActiveMutators, Mutator},
+    utils::{GameAssets, GameRng},
+};
+
+// CONSTANTS {{{
+
+/// The number of options on the ballot.
+const VOTE_OPTION_COUNT: usize = 3;
+/// The keys spectators press to vote for each option, in order.
+const VOTE_KEYS: [KeyCode; VOTE_OPTION_COUNT] = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3];
+const VOTE_TITLE_FONT_SIZE: f32 = 28.0;
+const VOTE_OPTION_FONT_SIZE: f32 = 22.0;
+const VOTE_BAR_WIDTH: f32 = 300.0;
+const VOTE_BAR_HEIGHT: f32 = 12.0;
+const VOTE_BAR_COLOR: Color = Color::srgb(0.9, 0.75, 0.2);
+const VOTE_BAR_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.3);
+/// How quickly the bars catch up to the tally, as a fraction of the remaining distance per second.
+const VOTE_BAR_SPEED: f32 = 8.0;
+
+// }}}
+
+pub struct VotePlugin;
+impl Plugin for VotePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_vote.run_if(
+                    in_state(AppState::GameOver)
+                        .and_then(not(resource_exists::<MutatorVote>))
+                        .and_then(|config: Res<GameConfig>| !config.mutator_roll.enabled),
+                ),
+                (cast_votes, animate_vote_bars.after(cast_votes))
+                    .run_if(resource_exists::<MutatorVote>),
+                restart.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// The vote for the next round's mutator, held between rounds.
+#[derive(Resource)]
+struct MutatorVote {
+    /// The mutators on the ballot. `None` stands for playing the next round without one, and
+    /// fills in the ballot when there aren't enough mutators to go around.
+    options: Vec<Option<Mutator>>,
+    tally: Vec<u32>,
+}
+impl MutatorVote {
+    fn winner(&self) -> Option<Mutator> {
+        // Reversed so that ties go to the option listed first.
+        let (winner, _) = self
+            .options
+            .iter()
+            .zip(&self.tally)
+            .rev()
+            .max_by_key(|(_, &votes)| votes)?;
+        *winner
+    }
+    fn share(&self, index: usize) -> f32 {
+        let total = self.tally.iter().sum::<u32>();
+        if total == 0 {
+            0.0
+        } else {
+            self.tally[index] as f32 / total as f32
+        }
+    }
+}
+#[derive(Component)]
+struct VotePanel;
+/// The text showing the name and vote count of a ballot option.
+#[derive(Component)]
+struct VoteText(usize);
+/// The bar showing the share of the votes a ballot option has.
+#[derive(Component)]
+struct VoteBar(usize);
+
+fn option_label(index: usize, option: Option<Mutator>, votes: u32) -> String {
+    let name = option.map_or("No Mutator", |x| x.name());
+    format!("[{}] {}: {}", index + 1, name, votes)
+}
+
+fn start_vote(mut commands: Commands, mut rng: ResMut<GameRng>, assets: Res<GameAssets>) {
+    let mut options = Mutator::ALL
+        .choose_multiple(&mut rng.0, VOTE_OPTION_COUNT)
+        .copied()
+        .map(Some)
+        .collect::<Vec<_>>();
+    options.resize(VOTE_OPTION_COUNT, None);
+
+    let panel = commands
+        .spawn((
+            VotePanel,
+            Name::new("Mutator Vote"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Percent(5.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn(TextBundle::from_section(
+            "Vote for the Next Mutator",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: VOTE_TITLE_FONT_SIZE,
+                color: Color::BLACK,
+            },
+        ))
+        .set_parent(panel);
+    for (i, &option) in options.iter().enumerate() {
+        commands
+            .spawn((
+                VoteText(i),
+                TextBundle::from_section(
+                    option_label(i, option, 0),
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size: VOTE_OPTION_FONT_SIZE,
+                        color: Color::BLACK,
+                    },
+                ),
+            ))
+            .set_parent(panel);
+        let track = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(VOTE_BAR_WIDTH),
+                    height: Val::Px(VOTE_BAR_HEIGHT),
+                    ..default()
+                },
+                background_color: VOTE_BAR_BACKGROUND.into(),
+                ..default()
+            })
+            .set_parent(panel)
+            .id();
+        commands
+            .spawn((
+                VoteBar(i),
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: VOTE_BAR_COLOR.into(),
+                    ..default()
+                },
+            ))
+            .set_parent(track);
+    }
+    commands.insert_resource(MutatorVote {
+        tally: vec![0; options.len()],
+        options,
+    });
+}
+fn cast_votes(
+    mut vote: ResMut<MutatorVote>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut texts: Query<(&mut Text, &VoteText)>,
+) {
+    let mut changed = false;
+    for (i, key) in VOTE_KEYS.into_iter().enumerate() {
+        if keys.just_pressed(key) && i < vote.tally.len() {
+            vote.tally[i] += 1;
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+    for (mut text, &VoteText(i)) in &mut texts {
+        text.sections[0].value = option_label(i, vote.options[i], vote.tally[i]);
+    }
+}
+/// Ease each bar towards its option's share of the votes. This goes by real time, since the arena
+/// and the virtual clock with it are frozen between rounds.
+fn animate_vote_bars(
+    vote: Res<MutatorVote>,
+    time: Res<Time<Real>>,
+    mut bars: Query<(&mut Style, &VoteBar)>,
+) {
+    let t = (VOTE_BAR_SPEED * time.delta_seconds()).min(1.0);
+    for (mut style, &VoteBar(i)) in &mut bars {
+        let Val::Percent(current) = style.width else {
+            continue;
+        };
+        let target = vote.share(i) * 100.0;
+        if (target - current).abs() > f32::EPSILON {
+            style.width = Val::Percent(current + (target - current) * t);
+        }
+    }
+}
+/// Apply the winning mutator to the next round and close the vote.
+fn restart(
+    mut commands: Commands,
+    vote: Option<Res<MutatorVote>>,
+    mut mutators: ResMut<ActiveMutators>,
+    panels: Query<Entity, With<VotePanel>>,
+) {
+    let Some(vote) = vote else {
+        return;
+    };
+    let winner = vote.winner();
+    info!("Mutator vote: {:?} won with {:?}.", winner, vote.tally);
+    mutators.0 = winner.into_iter().collect();
+    commands.remove_resource::<MutatorVote>();
+    for entity in &panels {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,21 +1,39 @@
-use std::ops::{Index, IndexMut};
-
-use bevy::{color::palettes::css, prelude::*};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    ops::{Index, IndexMut},
+    path::Path,
+    time::SystemTime,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{CompressedImageFormats, ImageSampler, ImageType},
+    },
+    sprite::Mesh2dHandle,
+};
 use bevy_hanabi::prelude::*;
-
-use crate::panel_plugin::{WORKER_BALL_COUNT_MAX, WORKER_BALL_RADIUS};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use crate::battlefield::RestartEvent;
+use crate::cli::LaunchOptions;
+use crate::config::{GameConfig, TeamConfig};
+use crate::panel_plugin::{
+    CIRCLE_COLOR, CIRCLE_RADIUS, TRIGGER_ZONE_DIVIDER_COLOR, TRIGGER_ZONE_DIVIDER_LENGTH,
+    TRIGGER_ZONE_DIVIDER_RADIUS, WORKER_BALL_COUNT_MAX, WORKER_BALL_RADIUS,
+};
+use crate::stress::STRESS_WORKER_BALL_COUNT_MAX;
+#[cfg(debug_assertions)]
+use bevy_rapier2d::prelude::*;
 
 // Constants {{{
 
-const PARTICIPANT_COLORS: ParticipantMap<Srgba> = ParticipantMap::new(
-    css::MAROON,
-    css::DARK_GREEN,
-    css::PURPLE,
-    css::DARK_GOLDENROD,
-);
-const BALL_COLORS: ParticipantMap<Srgba> =
-    ParticipantMap::new(css::RED, css::LIMEGREEN, css::VIOLET, css::YELLOW);
-
 const HIT_PARTICLE_LIFETIME: f32 = 2.;
 const HIT_PARTICLE_SIZE: f32 = WORKER_BALL_RADIUS * 2.0;
 const HIT_PARTICLE_COUNT: f32 = 16.0;
@@ -25,39 +43,306 @@ pub const TRAIL_LIFETIME: f32 = 0.5;
 pub const SPAWN_COLOR_PROPERTY: &str = "spawn_color";
 const POSITION_PROPERTY: &str = "position";
 const BULLET_VEL_PROPERTY: &str = "bullet_vel";
+/// The most matches that can run side by side in one window.
+pub const MATCH_COUNT_MAX: usize = 2;
+/// How far apart the matches are laid out in the world, far enough that nothing in one can reach
+/// into the next even with the stress test's giant panels.
+const MATCH_SPACING: f32 = 4000.0;
+/// The number of participants in the default roster, counting from the first.
+const DEFAULT_PARTICIPANT_COUNT: usize = 4;
+const AVATAR_DIR: &str = "mods/avatars";
+/// The width of the hexagon tile texture in pixels. Its height is `2 / sqrt(3)` times this.
+const HEX_TILE_TEXTURE_WIDTH: u32 = 64;
 
 // }}}
 
 pub struct UtilsPlugin;
 impl Plugin for UtilsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            PreStartup,
-            (
-                setup_participant_maps,
-                (setup_tile_hit_effect, setup_trail_effect).after(setup_participant_maps),
-            ),
-        );
+        let seed = app
+            .world()
+            .resource::<LaunchOptions>()
+            .seed
+            .unwrap_or_else(rand::random);
+        app.insert_resource(GameRng::from_seed(seed))
+            .insert_resource(Seed(seed))
+            .add_systems(
+                PreStartup,
+                (
+                    setup_participant_maps,
+                    setup_game_assets.after(setup_participant_maps),
+                ),
+            );
+        // Runs in `Last` so that the commands from every restart system have been applied.
+        #[cfg(debug_assertions)]
+        app.add_systems(Last, verify_round_teardown);
     }
 }
 
+/// The random number generator for everything that affects the outcome of a round, so that rounds
+/// can be reproduced from a seed.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub ChaCha8Rng);
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(ChaCha8Rng::from_entropy())
+    }
+}
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+/// The seed [`GameRng`] started from, taken from `--seed` if given and picked at random otherwise.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct Seed(pub u64);
+/// Marker for physics entities that only live for a single round and have to be despawned on
+/// restart.
+#[derive(Component, Clone, Copy, Default)]
+pub struct RoundScoped;
+/// The match an entity or event belongs to. The round-scoped entities are tagged with it, so the
+/// state kept in [`PerMatch`] can be looked up and restarted for one match without touching any
+/// other running in the same world.
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MatchId(pub u8);
+impl MatchId {
+    /// The match set up at launch, which everyone plays in unless assigned elsewhere.
+    pub const MAIN: Self = Self(0);
+    /// Where the middle of the match's battlefield is in the world. Everything in a match is laid
+    /// out around it, so the matches stay out of each other's way, and the main match is right at
+    /// the origin.
+    pub fn world_offset(self) -> Vec2 {
+        Vec2::new(self.0 as f32 * MATCH_SPACING, 0.0)
+    }
+}
+/// The match each participant plays in.
+#[derive(Debug, Clone, Copy, Default, Resource, Deref)]
+pub struct Matches(pub ParticipantMap<MatchId>);
+impl Matches {
+    /// The participants from `roster` playing in `match_id`.
+    pub fn roster(&self, roster: &Roster, match_id: MatchId) -> Roster {
+        Roster(ParticipantMap::from_fn(|p| {
+            roster[p] && self[p] == match_id
+        }))
+    }
+    /// The matches at least one participant from `roster` plays in, in order.
+    pub fn running(&self, roster: &Roster) -> Vec<MatchId> {
+        let mut running: Vec<_> = Participant::ALL
+            .into_iter()
+            .filter(|&p| roster[p])
+            .map(|p| self[p])
+            .collect();
+        running.sort();
+        running.dedup();
+        running
+    }
+}
+/// One `T` for each match, in place of a single resource shared by every match in the world.
+#[derive(Resource)]
+pub struct PerMatch<T>(BTreeMap<MatchId, T>);
+impl<T> Default for PerMatch<T> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+impl<T> PerMatch<T> {
+    pub fn insert(&mut self, match_id: MatchId, value: T) {
+        self.0.insert(match_id, value);
+    }
+    pub fn remove(&mut self, match_id: MatchId) -> Option<T> {
+        self.0.remove(&match_id)
+    }
+    /// Keep only the matches `f` returns `true` for.
+    pub fn retain(&mut self, mut f: impl FnMut(MatchId) -> bool) {
+        self.0.retain(|&id, _| f(id));
+    }
+    pub fn get(&self, match_id: MatchId) -> Option<&T> {
+        self.0.get(&match_id)
+    }
+    pub fn get_mut(&mut self, match_id: MatchId) -> Option<&mut T> {
+        self.0.get_mut(&match_id)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (MatchId, &T)> {
+        self.0.iter().map(|(&id, x)| (id, x))
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (MatchId, &mut T)> {
+        self.0.iter_mut().map(|(&id, x)| (id, x))
+    }
+}
+impl<T> Index<MatchId> for PerMatch<T> {
+    type Output = T;
+    fn index(&self, index: MatchId) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("{:?} should have been set up", index))
+    }
+}
+impl<T> IndexMut<MatchId> for PerMatch<T> {
+    fn index_mut(&mut self, index: MatchId) -> &mut Self::Output {
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{:?} should have been set up", index))
+    }
+}
+
+/// Work carried over between frames, for handlers that can fall behind during bursts without
+/// affecting the outcome of the round.
+#[derive(Resource)]
+pub struct Backlog<T>(VecDeque<T>);
+impl<T> Default for Backlog<T> {
+    fn default() -> Self {
+        Self(VecDeque::new())
+    }
+}
+impl<T> Backlog<T> {
+    pub fn push(&mut self, item: T) {
+        self.0.push_back(item);
+    }
+    pub fn extend(&mut self, items: impl IntoIterator<Item = T>) {
+        self.0.extend(items);
+    }
+    /// Take up to `budget` of the oldest items, leaving the rest for later frames.
+    pub fn take(&mut self, budget: usize) -> impl Iterator<Item = T> + '_ {
+        let count = budget.min(self.0.len());
+        self.0.drain(..count)
+    }
+    /// Drop the oldest items so that at most `capacity` are left, returning how many were dropped.
+    pub fn discard_oldest(&mut self, capacity: usize) -> usize {
+        let excess = self.0.len().saturating_sub(capacity);
+        self.0.drain(..excess);
+        excess
+    }
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// The participants taking part in the current round. Changes take effect on restart.
+#[derive(Debug, Clone, Copy, Resource, Deref, DerefMut)]
+pub struct Roster(pub ParticipantMap<bool>);
+impl Default for Roster {
+    fn default() -> Self {
+        Self(ParticipantMap::from_fn(|p| {
+            (p as usize) < DEFAULT_PARTICIPANT_COUNT
+        }))
+    }
+}
+impl Roster {
+    pub fn count(&self) -> u8 {
+        Participant::ALL.into_iter().filter(|&p| self[p]).count() as u8
+    }
+}
 #[derive(Debug, Clone, Copy, Default, Resource)]
 pub struct TileColor(pub Color);
 #[derive(Debug, Clone, Copy, Default, Resource)]
 pub struct BallColor(pub Color);
+#[derive(Debug, Clone, Copy, Default, Resource)]
+pub struct TrailColor(pub Color);
+/// The name a participant goes by on screen. The [`Display`](std::fmt::Display) implementation of
+/// [`Participant`] is only a fallback for logs and the command line.
+#[derive(Debug, Clone, Default, Resource, Deref)]
+pub struct DisplayName(pub String);
+impl std::fmt::Display for DisplayName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+/// The picture shown next to a participant's name.
+#[derive(Debug, Clone)]
+pub enum Avatar {
+    Image(Handle<Image>),
+    /// The initials of the participant's name, shown over their ball color when they don't have an
+    /// image or it failed to load.
+    Initials(String),
+}
+impl Avatar {
+    fn load(name: &str, file: Option<&str>, images: &mut Assets<Image>) -> Self {
+        let initials = || {
+            let initials = name
+                .split_whitespace()
+                .filter_map(|word| word.chars().next())
+                .take(2)
+                .flat_map(char::to_uppercase)
+                .collect();
+            Self::Initials(initials)
+        };
+        let Some(file) = file else {
+            return initials();
+        };
+        let path = Path::new(AVATAR_DIR).join(file);
+        let extension = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .unwrap_or_default();
+        let image = fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                Image::from_buffer(
+                    &bytes,
+                    ImageType::Extension(extension),
+                    CompressedImageFormats::NONE,
+                    true,
+                    ImageSampler::Default,
+                    RenderAssetUsages::default(),
+                )
+                .map_err(|e| e.to_string())
+            });
+        match image {
+            Ok(image) => Self::Image(images.add(image)),
+            Err(e) => {
+                warn!(
+                    "Failed to load the avatar for {} from {}: {}",
+                    name,
+                    path.display(),
+                    e
+                );
+                initials()
+            }
+        }
+    }
+}
 
 /// A struct that maps a value to each participant.
-#[derive(Debug, Clone, Copy, Default, Resource)]
+#[derive(Debug, Clone, Copy, Default, Resource, Serialize, Deserialize, Reflect)]
 pub struct ParticipantMap<T> {
     // {{{
     pub a: T,
     pub b: T,
     pub c: T,
     pub d: T,
+    // Files saved back when there were only four participants leave the rest out.
+    #[serde(default)]
+    pub e: T,
+    #[serde(default)]
+    pub f: T,
+    #[serde(default)]
+    pub g: T,
+    #[serde(default)]
+    pub h: T,
 }
 impl<T> ParticipantMap<T> {
-    pub const fn new(a: T, b: T, c: T, d: T) -> Self {
-        Self { a, b, c, d }
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(a: T, b: T, c: T, d: T, e: T, f: T, g: T, h: T) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            g,
+            h,
+        }
+    }
+    pub fn from_fn(mut f: impl FnMut(Participant) -> T) -> Self {
+        Self::new(
+            f(Participant::A),
+            f(Participant::B),
+            f(Participant::C),
+            f(Participant::D),
+            f(Participant::E),
+            f(Participant::F),
+            f(Participant::G),
+            f(Participant::H),
+        )
     }
     pub const fn get(&self, participant: Participant) -> &T {
         match participant {
@@ -65,30 +350,33 @@ impl<T> ParticipantMap<T> {
             Participant::B => &self.b,
             Participant::C => &self.c,
             Participant::D => &self.d,
+            Participant::E => &self.e,
+            Participant::F => &self.f,
+            Participant::G => &self.g,
+            Participant::H => &self.h,
         }
     }
     pub fn set(&mut self, participant: Participant, new_value: T) {
-        match participant {
-            Participant::A => self.a = new_value,
-            Participant::B => self.b = new_value,
-            Participant::C => self.c = new_value,
-            Participant::D => self.d = new_value,
-        }
+        self[participant] = new_value;
     }
     pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> ParticipantMap<U> {
-        ParticipantMap::new(f(self.a), f(self.b), f(self.c), f(self.d))
+        ParticipantMap::new(
+            f(self.a),
+            f(self.b),
+            f(self.c),
+            f(self.d),
+            f(self.e),
+            f(self.f),
+            f(self.g),
+            f(self.h),
+        )
     }
     // }}}
 }
 impl<T> Index<Participant> for ParticipantMap<T> {
     type Output = T;
     fn index(&self, index: Participant) -> &Self::Output {
-        match index {
-            Participant::A => &self.a,
-            Participant::B => &self.b,
-            Participant::C => &self.c,
-            Participant::D => &self.d,
-        }
+        self.get(index)
     }
 }
 impl<T> IndexMut<Participant> for ParticipantMap<T> {
@@ -98,21 +386,20 @@ impl<T> IndexMut<Participant> for ParticipantMap<T> {
             Participant::B => &mut self.b,
             Participant::C => &mut self.c,
             Participant::D => &mut self.d,
+            Participant::E => &mut self.e,
+            Participant::F => &mut self.f,
+            Participant::G => &mut self.g,
+            Participant::H => &mut self.h,
         }
     }
 }
 impl<T: Copy> ParticipantMap<T> {
     pub const fn splat(x: T) -> Self {
-        Self {
-            a: x,
-            b: x,
-            c: x,
-            d: x,
-        }
+        Self::new(x, x, x, x, x, x, x, x)
     }
 }
 
-#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 /// A game participant. It's not called player since the game is not interactive.
 pub enum Participant {
     #[default]
@@ -120,9 +407,49 @@ pub enum Participant {
     B,
     C,
     D,
+    E,
+    F,
+    G,
+    H,
 }
 impl Participant {
-    pub const ALL: [Self; 4] = [Self::A, Self::B, Self::C, Self::D];
+    pub const ALL: [Self; 8] = [
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+    ];
+    /// Pick a random surviving participant that isn't an ally of this one, or this one itself if
+    /// there's nobody else left.
+    pub fn random_opponent(
+        self,
+        survivors: &ParticipantMap<bool>,
+        teams: &TeamConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let opponents = Self::ALL
+            .into_iter()
+            .filter(|&x| !teams.allies(self, x) && survivors[x])
+            .collect::<Vec<_>>();
+        opponents.choose(rng).copied().unwrap_or(self)
+    }
+    /// The participant on the same team when playing in teams.
+    pub const fn teammate(self) -> Self {
+        match self {
+            Self::A => Self::C,
+            Self::B => Self::D,
+            Self::C => Self::A,
+            Self::D => Self::B,
+            Self::E => Self::F,
+            Self::F => Self::E,
+            Self::G => Self::H,
+            Self::H => Self::G,
+        }
+    }
 }
 impl std::fmt::Display for Participant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -131,34 +458,120 @@ impl std::fmt::Display for Participant {
             Participant::B => "GREEN",
             Participant::C => "VIOLET",
             Participant::D => "YELLOW",
+            Participant::E => "BLUE",
+            Participant::F => "ORANGE",
+            Participant::G => "CYAN",
+            Participant::H => "PINK",
         };
         f.write_str(name)
     }
 }
+/// Handles to every asset shared between the plugins.
+///
+/// Populated once at startup so that setup and restart systems can clone handles out of here
+/// instead of adding duplicate assets to `Assets<...>` every time.
 #[derive(Clone, Resource)]
-pub struct TileHitEffect(pub Handle<EffectAsset>);
-#[derive(Clone, Resource)]
-pub struct TrailEffect(pub Handle<EffectAsset>);
-#[derive(Clone, Component, Deref, DerefMut)]
-pub struct EffectLifetimeTimer(Timer);
-impl Default for EffectLifetimeTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(
-            HIT_PARTICLE_LIFETIME + 0.2,
-            TimerMode::Once,
-        ))
-    }
+pub struct GameAssets {
+    /// A circle mesh of radius 1. Scale it with the entity's transform.
+    pub unit_circle: Mesh2dHandle,
+    pub worker_ball: Mesh2dHandle,
+    pub peg: Mesh2dHandle,
+    /// Capsule mesh used by the dividers between trigger zones.
+    pub divider: Mesh2dHandle,
+    pub ball_materials: ParticipantMap<Handle<ColorMaterial>>,
+    pub peg_material: Handle<ColorMaterial>,
+    pub divider_material: Handle<ColorMaterial>,
+    pub font: Handle<Font>,
+    pub tile_hit_effect: Handle<EffectAsset>,
+    pub trail_effect: Handle<EffectAsset>,
+    /// A white hexagon with its points at the top and bottom, tinted to color hexagon tiles.
+    pub hex_tile: Handle<Image>,
 }
 
-fn setup_participant_maps(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
-    commands.insert_resource(ParticipantMap::splat(true));
-    commands.insert_resource(PARTICIPANT_COLORS.map(Color::Srgba).map(TileColor));
-    commands.insert_resource(BALL_COLORS.map(Color::Srgba).map(BallColor));
+fn setup_participant_maps(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    options: Res<LaunchOptions>,
+    config: Res<GameConfig>,
+) {
+    let roster = options.roster.unwrap_or_default();
+    commands.insert_resource(roster.0);
+    commands.insert_resource(roster);
+    commands.insert_resource(options.matches.unwrap_or_default());
+    let identities = &config.participants.0;
+    commands.insert_resource(identities.clone().map(|x| TileColor(x.tile_color)));
+    commands.insert_resource(identities.clone().map(|x| BallColor(x.ball_color)));
+    commands.insert_resource(
+        identities
+            .clone()
+            .map(|x| TrailColor(x.trail_color.unwrap_or(x.tile_color))),
+    );
     commands.insert_resource(
-        BALL_COLORS.map(|srgba| materials.add(ColorMaterial::from(Color::from(srgba)))),
+        identities
+            .clone()
+            .map(|x| Avatar::load(&x.name, x.avatar.as_deref(), &mut images)),
     );
+    commands.insert_resource(identities.clone().map(|x| DisplayName(x.name)));
 }
-fn setup_tile_hit_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
+fn setup_game_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<GameConfig>,
+    options: Res<LaunchOptions>,
+) {
+    let mut mesh = |mesh: Mesh| Mesh2dHandle(meshes.add(mesh));
+    commands.insert_resource(GameAssets {
+        unit_circle: mesh(Circle::new(1.0).into()),
+        worker_ball: mesh(Circle::new(WORKER_BALL_RADIUS).into()),
+        peg: mesh(Circle::new(CIRCLE_RADIUS).into()),
+        divider: mesh(
+            Capsule2d::new(TRIGGER_ZONE_DIVIDER_RADIUS, TRIGGER_ZONE_DIVIDER_LENGTH).into(),
+        ),
+        ball_materials: config
+            .participants
+            .0
+            .clone()
+            .map(|x| materials.add(x.ball_color)),
+        peg_material: materials.add(CIRCLE_COLOR),
+        divider_material: materials.add(TRIGGER_ZONE_DIVIDER_COLOR),
+        font: Handle::default(),
+        tile_hit_effect: effects.add(create_tile_hit_effect()),
+        trail_effect: effects.add(create_trail_effect(&options)),
+        hex_tile: images.add(create_hex_tile_image()),
+    });
+}
+/// Draw a pointy-topped hexagon that fills the image from side to side and top to bottom.
+fn create_hex_tile_image() -> Image {
+    let width = HEX_TILE_TEXTURE_WIDTH;
+    let height = (width as f32 * 2.0 / 3f32.sqrt()).round() as u32;
+    let half_size = Vec2::new(width as f32, height as f32) * 0.5;
+    let radius = half_size.y;
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let offset = (Vec2::new(x as f32, y as f32) + 0.5 - half_size).abs();
+            let inside = offset.x <= half_size.x && offset.x / 3f32.sqrt() + offset.y <= radius;
+            let alpha = if inside { u8::MAX } else { 0 };
+            data.extend_from_slice(&[u8::MAX, u8::MAX, u8::MAX, alpha]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+/// The built-in tile hit effect, used unless it's overridden from [`crate::effects`].
+pub fn create_tile_hit_effect() -> EffectAsset {
     // Set `spawn_immediately` to false to spawn on command with Spawner::reset()
     let spawner = Spawner::once(HIT_PARTICLE_COUNT.into(), true);
 
@@ -211,28 +624,26 @@ fn setup_tile_hit_effect(mut commands: Commands, mut effects: ResMut<Assets<Effe
         );
     let init_vel = SetAttributeModifier::new(Attribute::VELOCITY, vel.expr());
 
-    let effect = effects.add(
-        EffectAsset::new(
-            vec![(HIT_PARTICLE_COUNT * HIT_PARTICLE_MAX_PER_SECOND * HIT_PARTICLE_LIFETIME) as u32],
-            spawner,
-            writer.finish(),
-        )
-        .with_name("tile hit")
-        .init(init_pos)
-        .init(init_vel)
-        .init(init_age)
-        .init(init_lifetime)
-        .init(init_color)
-        .update(update_drag)
-        .render(SizeOverLifetimeModifier {
-            gradient,
-            screen_space_size: false,
-        }),
-    );
-
-    commands.insert_resource(TileHitEffect(effect));
+    EffectAsset::new(
+        vec![(HIT_PARTICLE_COUNT * HIT_PARTICLE_MAX_PER_SECOND * HIT_PARTICLE_LIFETIME) as u32],
+        spawner,
+        writer.finish(),
+    )
+    .with_name("tile hit")
+    .init(init_pos)
+    .init(init_vel)
+    .init(init_age)
+    .init(init_lifetime)
+    .init(init_color)
+    .update(update_drag)
+    .render(SizeOverLifetimeModifier {
+        gradient,
+        screen_space_size: false,
+    })
 }
-fn setup_trail_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
+/// The built-in worker ball trail effect, used unless it's overridden from [`crate::effects`]. It
+/// has room for every worker ball to have a trail, including the hundreds of the stress test.
+pub fn create_trail_effect(options: &LaunchOptions) -> EffectAsset {
     let writer = ExprWriter::default();
 
     let pos = writer.add_property(POSITION_PROPERTY, Vec3::ZERO.into());
@@ -313,11 +724,14 @@ fn setup_trail_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectA
 
     let group0 = ParticleGroupSet::single(0);
     let group12 = ParticleGroupSet::single(1).with_group(2);
-    const TOTAL_BALL_COUNT: u32 = WORKER_BALL_COUNT_MAX as u32 * 4;
-    const PARTICLE_COUNT: u32 =
-        (TOTAL_BALL_COUNT as f32 * TRAIL_SPAWN_RATE * TRAIL_LIFETIME + 1.0) as u32;
-    let effect = EffectAsset::new(
-        vec![TOTAL_BALL_COUNT, PARTICLE_COUNT, PARTICLE_COUNT],
+    let balls_per_participant = match options.stress {
+        true => STRESS_WORKER_BALL_COUNT_MAX,
+        false => WORKER_BALL_COUNT_MAX,
+    };
+    let total_ball_count = (balls_per_participant * Participant::ALL.len()) as u32;
+    let particle_count = (total_ball_count as f32 * TRAIL_SPAWN_RATE * TRAIL_LIFETIME + 1.0) as u32;
+    EffectAsset::new(
+        vec![total_ball_count, particle_count, particle_count],
         Spawner::once(1.0.into(), true),
         writer.finish(),
     )
@@ -340,9 +754,12 @@ fn setup_trail_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectA
     //     screen_space_size: false,
     // })
     .render_groups(round, group0.with_group(1))
-    .render_groups(RibbonModifier, ParticleGroupSet::single(2));
+    .render_groups(RibbonModifier, ParticleGroupSet::single(2))
+}
 
-    commands.insert_resource(TrailEffect(effects.add(effect)));
+/// When the file at `path` was last modified, or `None` if it can't be read.
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|x| x.modified()).ok()
 }
 
 pub trait EffectPropertiesExt: Default {
@@ -370,3 +787,65 @@ impl EffectPropertiesExt for EffectProperties {
         self.set("position", position.into());
     }
 }
+/// Check that every round-scoped physics entity from the previous round is gone after a restart,
+/// and that rapier doesn't hold on to colliders or bodies of despawned entities.
+///
+/// This runs every frame so that [`Ref::is_added`] is only true for the entities spawned this
+/// frame, which belong to the new round.
+#[cfg(debug_assertions)]
+#[allow(clippy::type_complexity)]
+fn verify_round_teardown(
+    mut restart_events: EventReader<RestartEvent>,
+    rapier: Res<RapierContext>,
+    round_scoped: Query<
+        (Entity, Option<&Name>, Ref<RoundScoped>, Option<&MatchId>),
+        Or<(
+            With<Collider>,
+            With<RigidBody>,
+            With<ImpulseJoint>,
+            With<MultibodyJoint>,
+        )>,
+    >,
+    entities: Query<()>,
+) {
+    let restarted: Vec<_> = restart_events.read().map(|x| x.match_id).collect();
+    if restarted.is_empty() {
+        return;
+    }
+    let mut offenders = 0;
+    for (entity, name, marker, match_id) in &round_scoped {
+        // The entities of the matches that weren't restarted are meant to stay.
+        let restarted = match match_id {
+            Some(x) => restarted.contains(x),
+            None => true,
+        };
+        if restarted && !marker.is_added() {
+            error!(
+                "{} {} survived the restart.",
+                entity,
+                name.map_or("", Name::as_str)
+            );
+            offenders += 1;
+        }
+    }
+    let rapier_entities = rapier
+        .colliders
+        .iter()
+        .filter_map(|(handle, _)| rapier.collider_entity(handle))
+        .chain(
+            rapier
+                .bodies
+                .iter()
+                .filter_map(|(handle, _)| rapier.rigid_body_entity(handle)),
+        );
+    for entity in rapier_entities {
+        if !entities.contains(entity) {
+            error!(
+                "Rapier still holds a collider or body for despawned {}.",
+                entity
+            );
+            offenders += 1;
+        }
+    }
+    assert_eq!(offenders, 0, "Physics entities survived the restart.");
+}